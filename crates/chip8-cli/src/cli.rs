@@ -0,0 +1,72 @@
+pub mod attract;
+pub mod chat;
+pub mod cinema;
+pub mod clipboard;
+pub mod compat;
+pub mod discover;
+pub mod explain;
+pub mod headless;
+pub mod heatmap;
+pub mod import_octo;
+pub mod jukebox;
+pub mod latency;
+pub mod options;
+pub mod pane;
+pub mod plugins;
+pub mod postmortem;
+pub mod printer;
+pub mod progress;
+pub mod quirks_report;
+pub mod repl;
+pub mod replay_session;
+pub mod shutdown;
+pub mod stats;
+pub mod teach;
+pub mod timeline;
+pub mod tui;
+pub mod welcome;
+
+use chip8_core::{Display, Emulator, EmulatorBuilder, Quirks};
+use std::str::FromStr;
+
+pub const DEFAULT_WATCHDOG_FRAMES: u32 = 300;
+
+pub fn new_emulator(quirks: Quirks) -> Emulator {
+    EmulatorBuilder::new().quirks(quirks).build().unwrap()
+}
+
+// Batch jobs run one ROM per core, or `--jobs N` at a time. WASI cannot
+// start threads, there everything runs on the calling one.
+pub fn thread_pool(args: &[String]) -> rayon::ThreadPool {
+    let jobs = match cfg!(target_os = "wasi") {
+        true => 1,
+        false => parse_flag(args, "--jobs").unwrap_or(0),
+    };
+    let builder = rayon::ThreadPoolBuilder::new().num_threads(jobs);
+    #[cfg(target_os = "wasi")]
+    let builder = builder.use_current_thread();
+    builder.build().expect("cannot start the worker threads")
+}
+
+// Value following `flag` in the arguments, if the flag is there at all.
+pub fn parse_flag<T: FromStr>(args: &[String], flag: &str) -> Option<T> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    match args.get(index + 1).map(|value| value.parse()) {
+        Some(Ok(value)) => Some(value),
+        _ => panic!("{} expects a value", flag),
+    }
+}
+
+// The screen as text, one character per logical pixel.
+pub fn screen_text(display: &Display) -> String {
+    let mut text = String::new();
+    for row in display.iter_rows() {
+        text.extend(row.iter().map(|on| if on { '#' } else { '.' }));
+        text.push('\n');
+    }
+    text
+}
+
+pub fn print_screen(display: &Display) {
+    print!("{}", screen_text(display));
+}
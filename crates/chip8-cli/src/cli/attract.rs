@@ -0,0 +1,151 @@
+use chip8_core::host::{Files, StdFiles};
+use chip8_core::input::{InputScript, InputSource};
+use chip8_core::playlist::{Playlist, PlaylistEntry, DEFAULT_SECONDS};
+use chip8_core::render::Rotation;
+use chip8_core::title::rom_title;
+use chip8_core::{run_frame, EmulatorBuilder, Palette};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cli::tui::{read_keys, RawMode, Renderer, CTRL_C, ESCAPE};
+use crate::cli::{parse_flag, shutdown};
+
+const USAGE: &str = "usage: attract <playlist> [--seconds N] [--loops N] \
+                     [--tui-renderer auto|ascii|blocks|braille|sixel|kitty] [--scale N]";
+const DEFAULT_SCALE: usize = 4;
+const FRAME: Duration = Duration::from_micros(16_667);
+const FRAMES_PER_SECOND: u32 = 60;
+const TAB: u8 = b'\t';
+// How long an error stays on screen before the next ROM.
+const ERROR_SECONDS: u64 = 3;
+
+enum Next {
+    Continue,
+    Quit,
+}
+
+// `attract <playlist>`: kiosk mode for events and museums. Plays every ROM
+// of the playlist in turn for its `seconds`, driven by its recorded
+// `inputs` if it has any, and starts over at the end, `--loops` times or
+// forever. A ROM that fails is skipped, the show goes on. Tab skips to the
+// next ROM, Esc quits.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let playlist = match Playlist::load_from(&StdFiles, Path::new(path)) {
+        Ok(playlist) => playlist,
+        Err(error) => {
+            eprintln!("error: {}: {}", path, error);
+            return 1;
+        }
+    };
+    let Some(renderer) = Renderer::from_args(args) else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let seconds = parse_flag::<u32>(args, "--seconds").unwrap_or(DEFAULT_SECONDS);
+    let loops = parse_flag::<u32>(args, "--loops").unwrap_or(0);
+    let scale = parse_flag::<usize>(args, "--scale").unwrap_or(DEFAULT_SCALE);
+    let _raw = match RawMode::enable() {
+        Ok(raw) => raw,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+
+    let keys = read_keys();
+    let mut stdout = io::stdout().lock();
+    let _ = write!(stdout, "\x1b[?25l");
+    let mut round = 0;
+    'show: while loops == 0 || round < loops {
+        round += 1;
+        for entry in &playlist.entries {
+            let _ = write!(stdout, "\x1b[2J");
+            let played = play(&mut stdout, &keys, entry, seconds, renderer, scale);
+            match played {
+                Ok(Next::Continue) => {}
+                Ok(Next::Quit) => break 'show,
+                Err(error) => {
+                    let _ = write!(stdout, "\r\n{}: {}\r\n", entry.rom.display(), error);
+                    let _ = stdout.flush();
+                    thread::sleep(Duration::from_secs(ERROR_SECONDS));
+                }
+            }
+            if shutdown::requested() {
+                break 'show;
+            }
+        }
+    }
+    let _ = write!(stdout, "\x1b[?25h\r\n");
+    0
+}
+
+fn play(
+    stdout: &mut impl Write,
+    keys: &Receiver<(u8, Instant)>,
+    entry: &PlaylistEntry,
+    seconds: u32,
+    renderer: Renderer,
+    scale: usize,
+) -> Result<Next, String> {
+    let data = StdFiles
+        .read(&entry.rom)
+        .map_err(|error| format!("cannot read the ROM: {}", error))?;
+    let mut inputs = match &entry.inputs {
+        Some(path) => {
+            let text = StdFiles
+                .read(path)
+                .map_err(|error| format!("cannot read {}: {}", path.display(), error))?;
+            Some(InputScript::parse(&String::from_utf8_lossy(&text))?)
+        }
+        None => None,
+    };
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(entry.quirks.unwrap_or_default())
+        .rom(&data)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let title = rom_title(&data, &entry.rom);
+    let frames = entry.seconds.unwrap_or(seconds) * FRAMES_PER_SECOND;
+
+    for frame in 0..frames {
+        let started = Instant::now();
+        for (byte, _) in keys.try_iter() {
+            match byte {
+                ESCAPE | CTRL_C => return Ok(Next::Quit),
+                TAB => return Ok(Next::Continue),
+                _ => {}
+            }
+        }
+        if shutdown::requested() {
+            return Ok(Next::Quit);
+        }
+        if let Some(inputs) = &mut inputs {
+            inputs.poll(&mut emulator.keypad);
+        }
+        let output = run_frame(&mut emulator);
+        if let Some(error) = output.error {
+            return Err(error.to_string());
+        }
+        // Redrawn every second at least, for the countdown.
+        if output.display_changed || frame % FRAMES_PER_SECOND == 0 {
+            let left = (frames - frame) / FRAMES_PER_SECOND;
+            let text = renderer.render(
+                &emulator.display,
+                &Palette::default(),
+                scale,
+                Rotation::None,
+            );
+            let _ = write!(stdout, "\x1b[H{}\r\n{}  {}s \x1b[K", text, title, left);
+            let _ = stdout.flush();
+        }
+        thread::sleep(FRAME.saturating_sub(started.elapsed()));
+    }
+    Ok(Next::Continue)
+}
@@ -0,0 +1,50 @@
+use chip8_core::input::{ChatVotes, InputSource, VoteConfig};
+use chip8_core::Keypad;
+use std::io::{self, BufRead, BufReader};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+// Chat votes from a bridge connected to `--chat ADDR`. The bridge, e.g. a
+// relay for Twitch IRC or a WebSocket piped through websocat, sends one
+// `user message` line per chat message. Any number of bridges may connect.
+pub struct ChatBridge {
+    votes: ChatVotes,
+    lines: Receiver<String>,
+}
+
+impl ChatBridge {
+    pub fn listen(address: &str, config: VoteConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    for line in BufReader::new(stream).lines() {
+                        let Ok(line) = line else { break };
+                        if sender.send(line).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        Ok(ChatBridge {
+            votes: ChatVotes::new(config),
+            lines,
+        })
+    }
+}
+
+impl InputSource for ChatBridge {
+    fn poll(&mut self, keypad: &mut Keypad) {
+        for line in self.lines.try_iter() {
+            if let Some((user, message)) = line.split_once(' ') {
+                // Chat that is not a vote, or too early a vote, is ignored.
+                let _ = self.votes.vote(user, message);
+            }
+        }
+        self.votes.poll(keypad);
+    }
+}
@@ -0,0 +1,118 @@
+use chip8_core::cast::CastWriter;
+use chip8_core::host::{Clock, SystemClock};
+use chip8_core::terminal::TextStyle;
+use chip8_core::{load_rom_to_memory, run_frame_with, Quirks};
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::{new_emulator, parse_flag};
+use crate::cli::{postmortem, shutdown};
+
+const USAGE: &str = "usage: cinema <rom> [--fps N] [--frames N] [--style ascii|blocks] \
+                     [--quirks PROFILE] [--cast FILE]";
+const DEFAULT_FPS: u32 = 30;
+const DEFAULT_FRAMES: u32 = 600;
+const EMULATOR_FPS: u32 = 60;
+
+// Moves the cursor home and hides it, so frames overwrite each other.
+const HOME: &str = "\x1b[H";
+const START: &str = "\x1b[2J\x1b[?25l";
+const END: &str = "\x1b[?25h";
+
+// `cinema <rom>`: plays a ROM as text on stdout in real time, at a fixed
+// `--fps`, for `asciinema rec -c` or termtosvg. With `--cast FILE` it
+// writes the asciinema recording directly instead, as fast as it can.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let fps = parse_flag::<u32>(args, "--fps")
+        .unwrap_or(DEFAULT_FPS)
+        .clamp(1, EMULATOR_FPS);
+    let frames = parse_flag::<u32>(args, "--frames").unwrap_or(DEFAULT_FRAMES);
+    let style = parse_flag::<TextStyle>(args, "--style").unwrap_or_default();
+    let quirks = parse_flag::<Quirks>(args, "--quirks").unwrap_or_default();
+
+    let mut emulator = new_emulator(quirks);
+    if let Err(error) = load_rom_to_memory(&mut emulator, &data) {
+        eprintln!("error: {}", error);
+        return 1;
+    }
+    let mut cast = match parse_flag::<String>(args, "--cast") {
+        Some(path) => {
+            let (columns, lines) = style.size(&emulator.display);
+            let created = fs::File::create(&path).and_then(|file| {
+                CastWriter::new(
+                    BufWriter::new(file),
+                    columns,
+                    lines,
+                    SystemClock.unix_seconds(),
+                )
+            });
+            match created {
+                Ok(cast) => Some(cast),
+                Err(error) => {
+                    eprintln!("error: cannot write {}: {}", path, error);
+                    return 1;
+                }
+            }
+        }
+        None => None,
+    };
+
+    // Emulator frames per frame shown, rounded down.
+    let every = EMULATOR_FPS / fps;
+    let interval = Duration::from_secs(1) / fps;
+    let mut stdout = io::stdout().lock();
+    let mut show = |seconds: f64, text: &str| match cast.as_mut() {
+        Some(cast) => cast.output(seconds, text),
+        None => {
+            stdout.write_all(text.as_bytes())?;
+            stdout.flush()?;
+            thread::sleep(interval);
+            Ok(())
+        }
+    };
+
+    let mut exit_code = 0;
+    let mut written = show(0.0, START);
+    for frame in 1..=frames {
+        if shutdown::requested() || written.is_err() {
+            exit_code = shutdown::INTERRUPTED;
+            break;
+        }
+        postmortem::snapshot(&emulator);
+        let output = run_frame_with(&mut emulator, |emulator| {
+            postmortem::trace(emulator);
+            chip8_core::step(emulator)
+        });
+        if let Some(error) = output.error {
+            eprintln!("error: frame {}: {}", frame, error);
+            exit_code = 1;
+            break;
+        }
+        if frame % every == 0 {
+            let seconds = frame as f64 / EMULATOR_FPS as f64;
+            let text = format!("{}{}", HOME, style.render(&emulator.display));
+            written = show(seconds, &text);
+        }
+    }
+    let finished = show(frames as f64 / EMULATOR_FPS as f64, END)
+        .and(written)
+        .and_then(|()| cast.map_or(Ok(()), |cast| cast.finish().map(|_| ())));
+    if let Err(error) = finished {
+        eprintln!("error: {}", error);
+        return 1;
+    }
+    exit_code
+}
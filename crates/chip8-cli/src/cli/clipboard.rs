@@ -0,0 +1,25 @@
+// The system clipboard, when built with the `clipboard` feature.
+
+#[cfg(all(feature = "clipboard", not(target_os = "wasi")))]
+pub fn copy(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(all(feature = "clipboard", not(target_os = "wasi")))]
+pub fn paste() -> Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(all(feature = "clipboard", not(target_os = "wasi"))))]
+pub fn copy(_text: &str) -> Result<(), String> {
+    Err("built without the clipboard feature".to_string())
+}
+
+#[cfg(not(all(feature = "clipboard", not(target_os = "wasi"))))]
+pub fn paste() -> Result<String, String> {
+    Err("built without the clipboard feature".to_string())
+}
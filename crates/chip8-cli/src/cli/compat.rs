@@ -0,0 +1,215 @@
+use chip8_core::endstate::EndState;
+use chip8_core::{load_rom_to_memory, step, EmulatorError, Quirks};
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::headless::{self, Outcome, Report};
+use crate::cli::progress::{print_summary, JobProgress};
+use crate::cli::shutdown;
+use crate::cli::{new_emulator, parse_flag, thread_pool, DEFAULT_WATCHDOG_FRAMES};
+
+const USAGE: &str = "usage: compat <rom-dir> [--seconds N] [--jobs N] [--csv FILE] [--html FILE]";
+const DEFAULT_SECONDS: u32 = 5;
+
+struct Entry {
+    rom: String,
+    profile: &'static str,
+    report: Report,
+}
+
+// `compat <rom-dir>`: runs every ROM of a directory under each quirk profile
+// and reports which combinations crash, get stuck or never draw anything.
+pub fn run(args: &[String]) -> i32 {
+    let Some(directory) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let frames = parse_flag::<u32>(args, "--seconds").unwrap_or(DEFAULT_SECONDS) * 60;
+    let roms = match list_roms(Path::new(directory)) {
+        Ok(roms) => roms,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", directory, error);
+            return 1;
+        }
+    };
+
+    let profiles = Quirks::profiles();
+    let total = frames * (roms.len() * profiles.len()) as u32;
+    let progress = JobProgress::new(Some(total));
+    // Every ROM and profile runs on its own emulator, rows come back in
+    // directory order.
+    let rows: Vec<Option<Vec<Entry>>> = thread_pool(args).install(|| {
+        roms.par_iter()
+            .map(|path| {
+                // Whole rows only, so the report stays well formed.
+                if shutdown::requested() {
+                    return None;
+                }
+                let rom = path.file_name().unwrap().to_string_lossy().into_owned();
+                let row = profiles
+                    .par_iter()
+                    .map(|&(profile, quirks)| {
+                        progress.start(&format!("{} [{}]", rom, profile));
+                        Entry {
+                            rom: rom.clone(),
+                            profile,
+                            report: run_rom(path, &rom, quirks, frames, &progress),
+                        }
+                    })
+                    .collect();
+                Some(row)
+            })
+            .collect()
+    });
+    progress.finish();
+    let entries: Vec<Entry> = rows.into_iter().flatten().flatten().collect();
+
+    let csv = parse_flag::<String>(args, "--csv");
+    let html = parse_flag::<String>(args, "--html");
+    if let Some(path) = &csv {
+        write_report(path, &to_csv(&entries));
+    }
+    if let Some(path) = &html {
+        write_report(path, &to_html(&entries, &profiles));
+    }
+    if csv.is_none() && html.is_none() {
+        print!("{}", to_csv(&entries));
+    } else {
+        let reports: Vec<Report> = entries
+            .into_iter()
+            .map(|entry| Report {
+                rom: format!("{} [{}]", entry.rom, entry.profile),
+                ..entry.report
+            })
+            .collect();
+        print_summary(&reports);
+    }
+    0
+}
+
+pub(crate) fn list_roms(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut roms = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "ch8") {
+            roms.push(path);
+        }
+    }
+    roms.sort();
+    Ok(roms)
+}
+
+fn run_rom(path: &Path, rom: &str, quirks: Quirks, frames: u32, progress: &JobProgress) -> Report {
+    let mut emulator = new_emulator(quirks);
+    let loaded = fs::read(path)
+        .map_err(|error| error.to_string())
+        .and_then(|data| load_rom_to_memory(&mut emulator, &data).map_err(|e| e.to_string()));
+    match loaded {
+        Ok(()) => headless::run(
+            rom,
+            &mut emulator,
+            DEFAULT_WATCHDOG_FRAMES,
+            Some(frames),
+            progress,
+            &mut step,
+        ),
+        Err(error) => Report {
+            rom: rom.to_string(),
+            frames: 0,
+            drew_pixels: false,
+            display_frames: 0,
+            end_state: EndState::NeverDrew,
+            outcome: Outcome::NotLoaded(error),
+        },
+    }
+}
+
+fn verdict(report: &Report) -> &'static str {
+    match &report.outcome {
+        Outcome::Crashed(EmulatorError::UnknownOpcode(_)) => "unknown opcode",
+        Outcome::NotLoaded(_) => "not loaded",
+        Outcome::Crashed(_) => "crash",
+        Outcome::Stalled(_) => "stuck",
+        Outcome::Interrupted => "interrupted",
+        Outcome::Completed if !report.drew_pixels => "blank screen",
+        Outcome::Completed => "ok",
+    }
+}
+
+fn to_csv(entries: &[Entry]) -> String {
+    let mut csv = String::from("rom,profile,frames,verdict,end_state,detail\n");
+    for entry in entries {
+        let fields = [
+            entry.rom.clone(),
+            entry.profile.to_string(),
+            entry.report.frames.to_string(),
+            verdict(&entry.report).to_string(),
+            entry.report.end_state.label().to_string(),
+            entry.report.outcome.describe(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// One row per ROM, one column per profile.
+fn to_html(entries: &[Entry], profiles: &[(&'static str, Quirks)]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>CHIP-8 compatibility report</title>\n<style>\n\
+         table { border-collapse: collapse; font-family: sans-serif; }\n\
+         td, th { border: 1px solid #999; padding: 4px 8px; }\n\
+         .ok { background: #bfb; }\n.blank { background: #ffb; }\n.fail { background: #fbb; }\n\
+         </style>\n</head>\n<body>\n<table>\n<tr><th>ROM</th>",
+    );
+    for (profile, _) in profiles {
+        html.push_str(&format!("<th>{}</th>", profile));
+    }
+    html.push_str("</tr>\n");
+
+    for row in entries.chunks(profiles.len()) {
+        html.push_str(&format!("<tr><td>{}</td>", escape_html(&row[0].rom)));
+        for entry in row {
+            let verdict = verdict(&entry.report);
+            let class = match verdict {
+                "ok" => "ok",
+                "blank screen" => "blank",
+                _ => "fail",
+            };
+            html.push_str(&format!(
+                "<td class=\"{}\" title=\"{}\">{}<br><small>{}</small></td>",
+                class,
+                escape_html(&entry.report.outcome.describe()),
+                verdict,
+                entry.report.end_state.label()
+            ));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_report(path: &str, contents: &str) {
+    if let Err(error) = fs::write(path, contents) {
+        eprintln!("error: cannot write {}: {}", path, error);
+    }
+}
@@ -0,0 +1,153 @@
+use chip8_core::config::RomConfig;
+use chip8_core::rom::rom_hash;
+use chip8_core::{load_rom_to_memory, step, Quirks};
+use rayon::prelude::*;
+use std::fs;
+
+use crate::cli::headless::{self, Outcome, Report};
+use crate::cli::progress::JobProgress;
+use crate::cli::shutdown;
+use crate::cli::{new_emulator, parse_flag, thread_pool, DEFAULT_WATCHDOG_FRAMES};
+
+const USAGE: &str = "usage: discover <rom> [--seconds N] [--jobs N] [--save]";
+const DEFAULT_SECONDS: u32 = 10;
+
+// ROMs whose right profile is already known, by `rom_hash`.
+const KNOWN_PROFILES: &[(u64, &str)] = &[(0x9495_733f_6062_4ee6, "chip8")];
+
+struct Candidate {
+    quirks: Quirks,
+    report: Report,
+}
+
+impl Candidate {
+    // Crashing combinations are out, then the more the screen keeps
+    // changing the more likely the game is actually running.
+    fn score(&self) -> Option<u32> {
+        let activity = self.report.display_frames + self.report.drew_pixels as u32;
+        match self.report.outcome {
+            Outcome::Completed => Some(activity * 2),
+            Outcome::Stalled(_) => Some(activity),
+            Outcome::Crashed(_) | Outcome::NotLoaded(_) | Outcome::Interrupted => None,
+        }
+    }
+}
+
+// `discover <rom>`: runs the ROM under every combination of quirks and
+// recommends the one that looks healthiest, optionally saving it to the
+// per-ROM config.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let frames = parse_flag::<u32>(args, "--seconds").unwrap_or(DEFAULT_SECONDS) * 60;
+
+    let matrix = Quirks::matrix();
+    let progress = JobProgress::new(Some(frames * matrix.len() as u32));
+    let mut emulator = new_emulator(Quirks::default());
+    if let Err(error) = load_rom_to_memory(&mut emulator, &data) {
+        eprintln!("error: {}", error);
+        return 1;
+    }
+    let mut candidates: Vec<Candidate> = thread_pool(args).install(|| {
+        matrix
+            .par_iter()
+            .map(|&quirks| {
+                let mut emulator = emulator.clone();
+                emulator.quirks = quirks;
+                progress.start(&quirks.to_string());
+                let report = headless::run(
+                    path,
+                    &mut emulator,
+                    DEFAULT_WATCHDOG_FRAMES,
+                    Some(frames),
+                    &progress,
+                    &mut step,
+                );
+                Candidate { quirks, report }
+            })
+            .collect()
+    });
+    progress.finish();
+    // A partial search would recommend, and maybe save, the wrong profile.
+    if shutdown::requested() {
+        return shutdown::INTERRUPTED;
+    }
+
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.score()));
+    let quirks_width = candidates
+        .iter()
+        .map(|candidate| candidate.quirks.to_string().len())
+        .max()
+        .unwrap_or_default();
+    for candidate in candidates.iter() {
+        let score = candidate
+            .score()
+            .map_or("-".to_string(), |score| score.to_string());
+        println!(
+            "{:>6}  {:<quirks_width$}  {}",
+            score,
+            candidate.quirks.to_string(),
+            candidate.report.outcome.describe()
+        );
+    }
+
+    let Some(quirks) = recommend(&data, &candidates) else {
+        println!("no quirk combination runs this ROM without crashing");
+        return 2;
+    };
+    println!("recommended: {}", quirks);
+
+    if args.iter().any(|arg| arg == "--save") {
+        let mut config = match RomConfig::load(&data) {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!("error: cannot read the ROM config: {}", error);
+                return 1;
+            }
+        };
+        config.quirks = Some(quirks);
+        if let Err(error) = config.save(&data) {
+            eprintln!("error: cannot save the ROM config: {}", error);
+            return 1;
+        }
+        println!("saved to {}", RomConfig::path(&data).display());
+    }
+    0
+}
+
+// Known ROMs win outright. Otherwise among the best scoring combinations
+// prefer a named profile, then the one closest to plain CHIP-8.
+fn recommend(data: &[u8], candidates: &[Candidate]) -> Option<Quirks> {
+    let hash = rom_hash(data);
+    if let Some((_, profile)) = KNOWN_PROFILES.iter().find(|(known, _)| *known == hash) {
+        return profile.parse().ok();
+    }
+
+    let best = candidates.iter().filter_map(Candidate::score).max()?;
+    let distance = |quirks: &Quirks| {
+        let named = Quirks::profiles()
+            .iter()
+            .any(|(_, profile)| profile == quirks);
+        let differences = quirks
+            .flags()
+            .iter()
+            .zip(Quirks::chip8().flags().iter())
+            .filter(|(a, b)| a.1 != b.1)
+            .count();
+        (!named, differences)
+    };
+    candidates
+        .iter()
+        .filter(|candidate| candidate.score() == Some(best))
+        .map(|candidate| candidate.quirks)
+        .min_by_key(distance)
+}
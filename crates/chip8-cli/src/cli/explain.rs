@@ -0,0 +1,60 @@
+use chip8_core::instruction::{self, InstructionInfo, INSTRUCTIONS};
+use chip8_core::Instruction;
+
+const USAGE: &str = "usage: explain [opcode or pattern, e.g. 8016 or 8XY6]";
+
+// `explain <opcode>`: prints what an instruction does. Takes either a
+// concrete opcode or a pattern with X, Y, N and K placeholders, without an
+// argument it lists them all.
+pub fn run(args: &[String]) -> i32 {
+    let Some(query) = args.first() else {
+        for info in INSTRUCTIONS {
+            println!(
+                "{}  {:<20} {}",
+                info.pattern, info.mnemonic, info.description
+            );
+        }
+        return 0;
+    };
+
+    if let Some(info) = instruction::find(query) {
+        print_info(info);
+        return 0;
+    }
+    let digits = query.trim_start_matches("0x").trim_start_matches("0X");
+    let concrete = digits.len() == 4 && digits.chars().all(|c| c.is_ascii_hexdigit());
+    // Placeholders decode as 0, any value picks the same instruction.
+    let placeholders_to_zero: String = digits
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'X' | 'Y' | 'N' | 'K' => '0',
+            c => c,
+        })
+        .collect();
+    let op_code = match u16::from_str_radix(&placeholders_to_zero, 16) {
+        Ok(op_code) if digits.len() == 4 => op_code,
+        _ => {
+            eprintln!("error: {:?} is not an opcode\n{}", query, USAGE);
+            return 1;
+        }
+    };
+
+    let instruction = Instruction::decode(op_code);
+    if let Instruction::Unknown(_) = instruction {
+        eprintln!("error: {:#06x} is not a known instruction", op_code);
+        return 1;
+    }
+    if concrete {
+        println!("{:04X}: {}", op_code, instruction);
+    }
+    print_info(instruction.describe());
+    0
+}
+
+fn print_info(info: &InstructionInfo) {
+    println!("{}  {}  ({})", info.pattern, info.mnemonic, info.variant);
+    println!("  {}", info.description);
+    if !info.quirks.is_empty() {
+        println!("  affected by quirks: {}", info.quirks.join(", "));
+    }
+}
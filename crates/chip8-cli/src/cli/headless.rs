@@ -0,0 +1,101 @@
+use chip8_core::endstate::{Classifier, EndState};
+use chip8_core::watchdog::{Stall, Watchdog};
+use chip8_core::{run_frame_with, Emulator, EmulatorError};
+
+use crate::cli::postmortem;
+use crate::cli::progress::JobProgress;
+use crate::cli::shutdown;
+
+pub enum Outcome {
+    NotLoaded(String),
+    // Ran for the whole frame budget.
+    Completed,
+    Crashed(EmulatorError),
+    Stalled(Stall),
+    // Stopped by Ctrl-C or SIGTERM.
+    Interrupted,
+}
+
+impl Outcome {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Outcome::Completed => 0,
+            Outcome::NotLoaded(_) | Outcome::Crashed(_) => 1,
+            Outcome::Stalled(_) => 2,
+            Outcome::Interrupted => shutdown::INTERRUPTED,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Outcome::NotLoaded(error) => format!("cannot load: {}", error),
+            Outcome::Completed => "ok".to_string(),
+            Outcome::Crashed(error) => format!("error: {}", error),
+            Outcome::Stalled(stall) => format!("watchdog: {}", stall),
+            Outcome::Interrupted => "interrupted".to_string(),
+        }
+    }
+}
+
+pub struct Report {
+    pub rom: String,
+    pub frames: u32,
+    // Whether a single pixel was ever lit, a never-drawing ROM usually
+    // means the interpreter is missing something.
+    pub drew_pixels: bool,
+    // Frames in which the screen changed.
+    pub display_frames: u32,
+    pub outcome: Outcome,
+    // Where the run seemed to end up, see `Classifier`.
+    pub end_state: EndState,
+}
+
+// Runs without any frontend until the frame budget runs out, the ROM fails
+// or the watchdog decides it is stuck. Every instruction goes through
+// `step`, normally `chip8_core::step`.
+pub fn run(
+    rom: &str,
+    emulator: &mut Emulator,
+    watchdog_frames: u32,
+    max_frames: Option<u32>,
+    progress: &JobProgress,
+    step: &mut dyn FnMut(&mut Emulator) -> Result<(), EmulatorError>,
+) -> Report {
+    let mut watchdog = Watchdog::new(watchdog_frames);
+    let mut classifier = Classifier::new();
+    let mut frames = 0;
+    let mut drew_pixels = false;
+    let mut display_frames = 0;
+    let outcome = loop {
+        if max_frames == Some(frames) {
+            break Outcome::Completed;
+        }
+        if shutdown::requested() {
+            break Outcome::Interrupted;
+        }
+        postmortem::snapshot(emulator);
+        let output = run_frame_with(emulator, |emulator| {
+            postmortem::trace(emulator);
+            step(emulator)
+        });
+        frames += 1;
+        progress.advance();
+        drew_pixels |= !emulator.display.is_blank();
+        display_frames += output.display_changed as u32;
+        classifier.observe(emulator, &output);
+        if let Some(error) = output.error {
+            break Outcome::Crashed(error);
+        }
+        if let Err(stall) = watchdog.observe(emulator) {
+            break Outcome::Stalled(stall);
+        }
+    };
+    Report {
+        rom: rom.to_string(),
+        frames,
+        drew_pixels,
+        display_frames,
+        outcome,
+        end_state: classifier.classify(),
+    }
+}
@@ -0,0 +1,108 @@
+use chip8_core::heatmap::{Heatmap, HEATMAP_SIDE};
+use chip8_core::{load_rom_to_memory, run_frame_with, Quirks};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::{new_emulator, parse_flag};
+use crate::cli::{postmortem, shutdown};
+
+const USAGE: &str = "usage: heatmap <rom> [--frames N] [--every N] [--quirks PROFILE] [--ppm FILE]";
+const DEFAULT_FRAMES: u32 = 600;
+const DEFAULT_EVERY: u32 = 6;
+const FRAME: Duration = Duration::from_micros(16_667);
+
+// `heatmap <rom>`: runs a ROM and shows how often each byte of RAM is read
+// (green), written (red) and executed (blue), one cell per byte with 0x000
+// at the top left and 64 bytes per row. On a terminal the map is redrawn
+// live every few frames.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let frames = parse_flag::<u32>(args, "--frames").unwrap_or(DEFAULT_FRAMES);
+    let every = parse_flag::<u32>(args, "--every")
+        .unwrap_or(DEFAULT_EVERY)
+        .max(1);
+    let quirks = parse_flag::<Quirks>(args, "--quirks").unwrap_or_default();
+    let live = io::stdout().is_terminal();
+
+    let mut emulator = new_emulator(quirks);
+    if let Err(error) = load_rom_to_memory(&mut emulator, &data) {
+        eprintln!("error: {}", error);
+        return 1;
+    }
+
+    let mut heatmap = Heatmap::new();
+    let mut exit_code = 0;
+    for frame in 1..=frames {
+        if shutdown::requested() {
+            exit_code = shutdown::INTERRUPTED;
+            break;
+        }
+        postmortem::snapshot(&emulator);
+        let output = run_frame_with(&mut emulator, |emulator| {
+            postmortem::trace(emulator);
+            heatmap.step(emulator)
+        });
+        if let Some(error) = output.error {
+            eprintln!("error: frame {}: {}", frame, error);
+            exit_code = 1;
+            break;
+        }
+        if live && frame % every == 0 {
+            print!("\x1b[H\x1b[2J{}", to_ansi(&heatmap));
+            io::stdout().flush().ok();
+            thread::sleep(FRAME * every);
+        }
+    }
+    print!("{}", to_ansi(&heatmap));
+
+    if let Some(path) = parse_flag::<String>(args, "--ppm") {
+        if let Err(error) = fs::write(&path, to_ppm(&heatmap)) {
+            eprintln!("error: cannot write {}: {}", path, error);
+            return 1;
+        }
+    }
+    exit_code
+}
+
+// Two rows of cells per line of text, with the upper half block in the
+// colour of the top cell over a background of the bottom one.
+fn to_ansi(heatmap: &Heatmap) -> String {
+    let pixels = heatmap.to_rgba();
+    let cell = |x: usize, y: usize| {
+        let index = (y * HEATMAP_SIDE + x) * 4;
+        (pixels[index], pixels[index + 1], pixels[index + 2])
+    };
+    let mut text = String::new();
+    for y in (0..HEATMAP_SIDE).step_by(2) {
+        text.push_str(&format!("{:#05x} ", y * HEATMAP_SIDE));
+        for x in 0..HEATMAP_SIDE {
+            let (top, bottom) = (cell(x, y), cell(x, y + 1));
+            text.push_str(&format!(
+                "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}",
+                top.0, top.1, top.2, bottom.0, bottom.1, bottom.2
+            ));
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+fn to_ppm(heatmap: &Heatmap) -> Vec<u8> {
+    let mut ppm = format!("P6\n{} {}\n255\n", HEATMAP_SIDE, HEATMAP_SIDE).into_bytes();
+    for pixel in heatmap.to_rgba().chunks(4) {
+        ppm.extend_from_slice(&pixel[..3]);
+    }
+    ppm
+}
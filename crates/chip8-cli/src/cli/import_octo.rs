@@ -0,0 +1,56 @@
+use chip8_core::octo::OctoState;
+use chip8_core::savestate;
+use std::fs;
+
+const USAGE: &str = "usage: import-octo <options.json> <rom> <state>";
+
+// `import-octo <options.json> <rom> <state>`: turns what Octo kept of a
+// program in the browser, its options and flag registers, into a savestate
+// of the ROM at power on that `tui --state` starts from.
+pub fn run(args: &[String]) -> i32 {
+    let [json_path, rom_path, state_path] = args else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let octo = match fs::read_to_string(json_path)
+        .map_err(|error| format!("cannot read {}: {}", json_path, error))
+        .and_then(|json| OctoState::parse(&json).map_err(|e| format!("{}: {}", json_path, e)))
+    {
+        Ok(octo) => octo,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+    let data = match fs::read(rom_path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", rom_path, error);
+            return 1;
+        }
+    };
+    let emulator = match octo.emulator(&data) {
+        Ok(emulator) => emulator,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+    for option in &octo.unsupported {
+        eprintln!("warning: {} is not supported and left out", option);
+    }
+    if let Err(error) = fs::write(state_path, savestate::save(&emulator)) {
+        eprintln!("error: cannot write {}: {}", state_path, error);
+        return 1;
+    }
+    println!(
+        "quirks {}, {} instructions per frame, {}",
+        octo.quirks,
+        octo.cycles_per_frame,
+        match octo.flags {
+            Some(_) => "flags imported",
+            None => "no flags",
+        }
+    );
+    0
+}
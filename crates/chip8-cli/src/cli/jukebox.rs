@@ -0,0 +1,161 @@
+use chip8_core::audio::{write_samples, write_wav, write_wav_header};
+use chip8_core::instruction::Variant;
+use chip8_core::jukebox::{Jukebox, TrackEnd, DEFAULT_SILENCE_SECONDS, SAMPLE_RATE};
+use chip8_core::title::rom_title;
+use chip8_core::{EmulatorBuilder, Quirks};
+#[cfg(not(target_os = "wasi"))]
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "wasi")]
+use crate::cli::progress::no_terminal::{ProgressBar, ProgressStyle};
+use crate::cli::{parse_flag, shutdown};
+
+const USAGE: &str = "usage: jukebox <rom> [--seconds N] [--silence SECONDS] [--volume 0-1] \
+                     [--quirks PROFILE] [--wav FILE|-]";
+// Longer than any track, short enough that a ROM that never stops makes
+// a file of a sensible size.
+const DEFAULT_SECONDS: u32 = 600;
+const FRAMES_PER_SECOND: u32 = 60;
+
+// `jukebox <rom>`: plays an XO-CHIP music ROM without a window. It runs
+// until the track goes silent, loops or `--seconds` pass, showing how far
+// it got on stderr. `--wav FILE` renders the track, trailing silence cut,
+// as fast as it can; `--wav -` streams it to stdout in real time, for
+// `chip8 jukebox song.ch8 --wav - | aplay`.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let seconds = parse_flag::<u32>(args, "--seconds").unwrap_or(DEFAULT_SECONDS);
+    let silence = parse_flag::<f32>(args, "--silence").unwrap_or(DEFAULT_SILENCE_SECONDS);
+    let volume = parse_flag::<f32>(args, "--volume");
+    let quirks = parse_flag::<Quirks>(args, "--quirks").unwrap_or_else(Quirks::xo_chip);
+    let wav = parse_flag::<String>(args, "--wav");
+
+    let emulator = EmulatorBuilder::new()
+        .variant(Variant::XoChip)
+        .quirks(quirks)
+        .rom(&data)
+        .build();
+    let emulator = match emulator {
+        Ok(emulator) => emulator,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+    let mut jukebox = Jukebox::new(emulator, SAMPLE_RATE, silence);
+    if let Some(volume) = volume {
+        jukebox.set_volume(volume);
+    }
+
+    let streaming = wav.as_deref() == Some("-");
+    let mut stdout = io::stdout().lock();
+    if streaming {
+        if let Err(error) = write_wav_header(&mut stdout, SAMPLE_RATE, None) {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    }
+
+    let title = rom_title(&data, Path::new(path));
+    let frames = seconds as u64 * FRAMES_PER_SECOND as u64;
+    let progress = progress_bar(frames);
+    let started = Instant::now();
+    let mut samples = Vec::new();
+    let mut end = None;
+    let mut exit_code = 0;
+    while jukebox.frame() < frames {
+        if shutdown::requested() {
+            exit_code = shutdown::INTERRUPTED;
+            break;
+        }
+        let start = samples.len();
+        end = jukebox.play_frame(&mut samples);
+        if wav.is_none() {
+            samples.clear();
+        }
+        if streaming {
+            let written = write_samples(&mut stdout, &samples[start..]);
+            samples.clear();
+            if let Err(error) = written {
+                // The player went away.
+                if error.kind() != io::ErrorKind::BrokenPipe {
+                    eprintln!("error: {}", error);
+                    exit_code = 1;
+                }
+                break;
+            }
+            // Keep pace with the player instead of filling its pipe.
+            let due = Duration::from_secs(jukebox.frame()) / FRAMES_PER_SECOND;
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                thread::sleep(wait);
+            }
+        }
+        let state = if jukebox.is_sounding() {
+            "playing"
+        } else {
+            "silent"
+        };
+        progress.set_message(format!("{} {} {}", title, clock(jukebox.seconds()), state));
+        progress.set_position(jukebox.frame());
+        if end.is_some() {
+            break;
+        }
+    }
+    progress.finish_and_clear();
+
+    let length = match &end {
+        Some(TrackEnd::Silence) => jukebox.track_seconds(),
+        _ => jukebox.seconds(),
+    };
+    match &end {
+        Some(end) => eprintln!("{}: {}, {}", title, clock(length), end),
+        None => eprintln!("{}: still playing after {}", title, clock(length)),
+    }
+    if let Some(TrackEnd::Crashed(_)) = end {
+        exit_code = 1;
+    }
+
+    if let Some(path) = wav.filter(|_| !streaming) {
+        samples.truncate((length * SAMPLE_RATE as f32) as usize);
+        let written = fs::File::create(&path).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            write_wav(&mut writer, SAMPLE_RATE, &samples)?;
+            writer.flush()
+        });
+        if let Err(error) = written {
+            eprintln!("error: cannot write {}: {}", path, error);
+            return 1;
+        }
+    }
+    exit_code
+}
+
+// m:ss.s
+fn clock(seconds: f32) -> String {
+    format!("{}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0)
+}
+
+fn progress_bar(frames: u64) -> ProgressBar {
+    let bar = ProgressBar::new(frames);
+    bar.set_style(
+        ProgressStyle::with_template("{msg} [{bar:40}] {elapsed}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    bar
+}
@@ -0,0 +1,106 @@
+use chip8_core::latency::{probe_emulator, LatencyProbe};
+use chip8_core::render::Rotation;
+use chip8_core::{run_frame, Palette};
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cli::tui::{keypad_key, read_keys, RawMode, Renderer, CTRL_C, ESCAPE, HOLD_FRAMES};
+use crate::cli::{parse_flag, shutdown};
+
+const USAGE: &str = "usage: latency [--presses N] [--synthetic] \
+                     [--tui-renderer auto|ascii|blocks|braille|sixel|kitty] [--scale N]";
+const DEFAULT_PRESSES: usize = 20;
+const DEFAULT_SCALE: usize = 4;
+const FRAME: Duration = Duration::from_micros(16_667);
+// Frames between the presses `--synthetic` makes up.
+const SYNTHETIC_INTERVAL: u32 = 30;
+
+// `latency`: runs the probe ROM, which flashes the screen while a key is
+// down, and measures how long each press takes from the terminal to the
+// flash being written out, split into input, emulation and render. Press
+// keypad keys until `--presses` are measured, or let `--synthetic` press
+// them, leaving out the terminal's own input delay. Esc stops early.
+pub fn run(args: &[String]) -> i32 {
+    let Some(renderer) = Renderer::from_args(args) else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let presses = parse_flag::<usize>(args, "--presses").unwrap_or(DEFAULT_PRESSES);
+    let synthetic = args.iter().any(|arg| arg == "--synthetic");
+    let scale = parse_flag::<usize>(args, "--scale").unwrap_or(DEFAULT_SCALE);
+    let mut emulator = probe_emulator().unwrap();
+    let raw = match RawMode::enable() {
+        Ok(raw) => raw,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+
+    let keys = read_keys();
+    let mut stdout = io::stdout().lock();
+    let _ = write!(stdout, "\x1b[2J\x1b[?25l");
+    let mut probe = LatencyProbe::new();
+    let mut held: Option<(u8, u32)> = None;
+    let mut frame = 0u32;
+    'frames: while probe.samples().len() < presses && !shutdown::requested() {
+        let started = Instant::now();
+        frame += 1;
+        let mut pressed = None;
+        for (byte, at) in keys.try_iter() {
+            if byte == ESCAPE || byte == CTRL_C {
+                break 'frames;
+            }
+            if let Some(key) = keypad_key(byte).filter(|_| !synthetic) {
+                pressed = Some((key, at));
+            }
+        }
+        if synthetic && frame.is_multiple_of(SYNTHETIC_INTERVAL) {
+            pressed = Some((0, Instant::now()));
+        }
+
+        if let Some((key, at)) = pressed {
+            match held {
+                // A repeat only keeps the key down.
+                Some((held_key, _)) if held_key == key => {}
+                _ => {
+                    if let Some((held_key, _)) = held {
+                        emulator.keypad.release(held_key);
+                    }
+                    emulator.keypad.press(key);
+                    probe.key_event(at);
+                }
+            }
+            held = Some((key, HOLD_FRAMES));
+        }
+        if let Some((key, frames)) = &mut held {
+            *frames -= 1;
+            if *frames == 0 {
+                emulator.keypad.release(*key);
+                held = None;
+            }
+        }
+
+        probe.polled(Instant::now());
+        let output = run_frame(&mut emulator);
+        probe.emulated(Instant::now(), output.display_changed);
+        if output.display_changed || frame == 1 {
+            let text = renderer.render(
+                &emulator.display,
+                &Palette::default(),
+                scale,
+                Rotation::None,
+            );
+            let status = format!("{}/{} presses", probe.samples().len(), presses);
+            let _ = write!(stdout, "\x1b[H{}\r\n{}\x1b[K", text, status);
+            let _ = stdout.flush();
+        }
+        probe.presented(Instant::now());
+        thread::sleep(FRAME.saturating_sub(started.elapsed()));
+    }
+    let _ = write!(stdout, "\x1b[?25h\r\n");
+    drop(raw);
+    let _ = write!(stdout, "{}", probe.report());
+    0
+}
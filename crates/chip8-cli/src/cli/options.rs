@@ -0,0 +1,120 @@
+// Options of `chip8 [rom]`, the real-time player and `--headless` runs.
+// Commands like `chip8 tui` are dispatched before these are parsed, their
+// options are read by each command.
+use chip8_core::breakpoint::AddressRange;
+use chip8_core::instruction::Variant;
+use chip8_core::{Palette, Quirks};
+use clap::{value_parser, Arg, ArgAction, Command};
+
+const COMMANDS: &str = "\
+Commands, each reading its own options:
+  attract, cinema, compat, discover, explain, heatmap, import-octo,
+  jukebox, latency, pane, printer, quirks-report, repl, replay-session,
+  stats, teach, timeline, tui, welcome";
+
+pub fn command() -> Command {
+    let value = |name: &'static str, value_name: &'static str, help: &'static str| {
+        Arg::new(name).long(name).value_name(value_name).help(help)
+    };
+    let switch = |name: &'static str, help: &'static str| {
+        Arg::new(name)
+            .long(name)
+            .help(help)
+            .action(ArgAction::SetTrue)
+    };
+    let headless = "Headless runs";
+    Command::new("chip8")
+        .about("Plays a CHIP-8 ROM in the terminal")
+        .after_help(COMMANDS)
+        .arg(
+            Arg::new("rom")
+                .value_name("ROM")
+                .help("The ROM file to play, the demo without one"),
+        )
+        .arg(
+            value(
+                "scale",
+                "N",
+                "Characters per pixel on the screen, 1 by default",
+            )
+            .value_parser(value_parser!(u16).range(1..=8)),
+        )
+        .arg(
+            value("cpu-hz", "N", "Instructions a second, 600 by default")
+                .value_parser(value_parser!(u32).range(1..)),
+        )
+        .arg(
+            value("quirks", "PROFILE", "A quirks profile, or quirks by name")
+                .value_parser(|text: &str| text.parse::<Quirks>()),
+        )
+        .arg(
+            value(
+                "palette",
+                "\"FG BG\"",
+                "Colours as RRGGBB, like \"33ff66 002200\"",
+            )
+            .value_parser(|text: &str| text.parse::<Palette>()),
+        )
+        .arg(
+            value("variant", "NAME", "The machine, chip8 by default")
+                .value_parser(|text: &str| text.parse::<Variant>()),
+        )
+        .arg(switch(
+            "headless",
+            "Runs without a screen and reports how it went",
+        ))
+        .next_help_heading(headless)
+        .arg(value("frames", "N", "Stops after N frames").value_parser(value_parser!(u32)))
+        .arg(
+            value(
+                "watchdog-frames",
+                "N",
+                "Frames without progress before the run counts as hung",
+            )
+            .value_parser(value_parser!(u32)),
+        )
+        .arg(value(
+            "font",
+            "NAME|FILE",
+            "A built-in font by name, or a font file",
+        ))
+        .arg(value(
+            "narrate",
+            "FILE",
+            "Writes every instruction run to FILE",
+        ))
+        .arg(switch(
+            "strict",
+            "Lists what interpreters disagree on once the run is over",
+        ))
+        .arg(switch("strict-trap", "Stops at the first of those"))
+        .arg(
+            value(
+                "watch-access",
+                "RANGE",
+                "Lists instructions reading or writing RANGE",
+            )
+            .value_parser(|text: &str| text.parse::<AddressRange>()),
+        )
+        .arg(
+            value("break-access", "RANGE", "Stops at the first of those")
+                .value_parser(|text: &str| text.parse::<AddressRange>()),
+        )
+        .arg(value("chat", "ADDR", "Lets chat play, listening on ADDR"))
+        .arg(
+            value("chat-window", "FRAMES", "Frames each vote stays open")
+                .value_parser(value_parser!(u32)),
+        )
+        .arg(
+            value("chat-hold", "FRAMES", "Frames the winning key stays down")
+                .value_parser(value_parser!(u32)),
+        )
+        .arg(
+            value("chat-cooldown", "FRAMES", "Frames between votes")
+                .value_parser(value_parser!(u32)),
+        )
+        .arg(
+            value("plugin", "PATH", "Loads a hook plugin, any number of times")
+                .action(ArgAction::Append),
+        )
+}
@@ -0,0 +1,101 @@
+use chip8_core::branches::Branches;
+use chip8_core::dump::{debug_pane_labeled, PANES};
+use chip8_core::labels::Labels;
+use chip8_core::watch::Watches;
+use chip8_core::Emulator;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::shutdown;
+
+const USAGE: &str = "usage: pane <dir> registers|disassembly|memory|watches|branches";
+// The `--watch` expressions and how execution got to PC, besides the
+// core's `PANES`.
+const WATCHES: &str = "watches";
+const BRANCHES: &str = "branches";
+// Jumps, calls, returns and skips in the branches pane.
+pub(crate) const BRANCH_HISTORY: usize = 32;
+const POLL: Duration = Duration::from_millis(50);
+
+// Keeps the debugger panes of a running game up to date as files in a
+// directory, for `pane` to show in other terminal windows.
+pub(crate) struct PaneWriter {
+    dir: PathBuf,
+    watches: Watches,
+    branches: Branches,
+    labels: Labels,
+    last: Vec<String>,
+}
+
+impl PaneWriter {
+    pub(crate) fn new(
+        dir: &Path,
+        watches: Watches,
+        branches: Branches,
+        labels: Labels,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(dir)
+            .map_err(|error| format!("cannot create {}: {}", dir.display(), error))?;
+        Ok(PaneWriter {
+            dir: dir.to_path_buf(),
+            watches,
+            branches,
+            labels,
+            last: vec![String::new(); PANES.len() + 2],
+        })
+    }
+
+    // Rewrites the panes that changed. Each goes to a temporary file first
+    // and is renamed over the old one, so `pane` never reads half of it.
+    pub(crate) fn update(&mut self, emulator: &Emulator) -> Result<(), String> {
+        let names = PANES.iter().copied().chain([WATCHES, BRANCHES]);
+        for (name, last) in names.zip(&mut self.last) {
+            let text = match name {
+                WATCHES => self.watches.panel(emulator),
+                BRANCHES => self.branches.panel(&self.labels),
+                _ => debug_pane_labeled(emulator, name, &self.labels).unwrap_or_default(),
+            };
+            if text == *last {
+                continue;
+            }
+            let path = self.dir.join(name);
+            let temporary = self.dir.join(format!(".{}.tmp", name));
+            fs::write(&temporary, &text)
+                .and_then(|_| fs::rename(&temporary, &path))
+                .map_err(|error| format!("cannot write {}: {}", path.display(), error))?;
+            *last = text;
+        }
+        Ok(())
+    }
+}
+
+// `pane <dir> <name>`: shows one debugger pane of a game started with
+// `tui --debug-panes <dir>`, redrawn as it changes. Open one terminal
+// window per pane and arrange them around the game's however suits.
+pub fn run(args: &[String]) -> i32 {
+    let (Some(dir), Some(name)) = (args.first(), args.get(1)) else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    if !PANES.contains(&name.as_str()) && name != WATCHES && name != BRANCHES {
+        eprintln!("{}", USAGE);
+        return 1;
+    }
+    let path = Path::new(dir).join(name);
+    let mut stdout = io::stdout().lock();
+    let mut shown = None;
+    while !shutdown::requested() {
+        // Missing until the game starts, and between its runs.
+        let text = fs::read_to_string(&path).unwrap_or_else(|_| "waiting for the game\n".into());
+        if shown.as_ref() != Some(&text) {
+            let _ = write!(stdout, "\x1b[H\x1b[2J{}", text);
+            let _ = stdout.flush();
+            shown = Some(text);
+        }
+        thread::sleep(POLL);
+    }
+    0
+}
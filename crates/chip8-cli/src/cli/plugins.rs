@@ -0,0 +1,28 @@
+// Hook plugins from shared libraries, `--plugin PATH` any number of times,
+// when built with the `plugins` feature.
+use chip8_core::Emulator;
+
+// Values of every `--plugin` in the arguments.
+fn paths(args: &[String]) -> Vec<&str> {
+    args.windows(2)
+        .filter(|pair| pair[0] == "--plugin")
+        .map(|pair| pair[1].as_str())
+        .collect()
+}
+
+#[cfg(feature = "plugins")]
+pub fn add(emulator: &mut Emulator, args: &[String]) -> Result<(), String> {
+    for path in paths(args) {
+        let plugin = chip8_core::plugin::Plugin::load(std::path::Path::new(path))?;
+        emulator.hooks.add(plugin);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "plugins"))]
+pub fn add(_emulator: &mut Emulator, args: &[String]) -> Result<(), String> {
+    match paths(args).is_empty() {
+        true => Ok(()),
+        false => Err("built without the plugins feature".to_string()),
+    }
+}
@@ -0,0 +1,100 @@
+use chip8_core::config::config_dir;
+use chip8_core::dump;
+use chip8_core::host::{Clock, Files};
+use chip8_core::trace::Trace;
+use chip8_core::{Emulator, EmulatorError};
+use std::cell::RefCell;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+
+use crate::cli::screen_text;
+
+const TRACE_LEN: usize = 64;
+
+#[derive(Default)]
+struct Watched {
+    snapshot: Option<Emulator>,
+    trace: Option<Trace>,
+}
+
+thread_local! {
+    static WATCHED: RefCell<Watched> = RefCell::new(Watched::default());
+}
+
+// Replaces the panic hook with one that writes the last snapshot and the
+// instructions executed since to a crash file before the usual message, so
+// users have something to attach to bug reports.
+pub fn install(files: Box<dyn Files>, clock: Box<dyn Clock>) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        match write_crash_file(info, files.as_ref(), clock.as_ref()) {
+            Some(Ok(path)) => eprintln!(
+                "the emulator crashed, its state was saved to {}\n\
+                 please attach that file when reporting the bug",
+                path.display()
+            ),
+            Some(Err(error)) => eprintln!("error: cannot save the crash state: {}", error),
+            None => (),
+        }
+        default_hook(info);
+    }));
+}
+
+// Keeps a copy of the machine, call once per frame.
+pub fn snapshot(emulator: &Emulator) {
+    WATCHED.with(|watched| {
+        let mut watched = watched.borrow_mut();
+        match &mut watched.snapshot {
+            Some(snapshot) => snapshot.clone_from(emulator),
+            None => watched.snapshot = Some(emulator.clone()),
+        }
+        if let Some(trace) = &mut watched.trace {
+            trace.clear();
+        }
+    });
+}
+
+// `chip8_core::step` that also remembers what it executed.
+pub fn step(emulator: &mut Emulator) -> Result<(), EmulatorError> {
+    trace(emulator);
+    chip8_core::step(emulator)
+}
+
+pub fn trace(emulator: &Emulator) {
+    WATCHED.with(|watched| {
+        watched
+            .borrow_mut()
+            .trace
+            .get_or_insert_with(|| Trace::new(TRACE_LEN))
+            .record(emulator);
+    });
+}
+
+// Nothing is written when the panic happened outside emulation.
+fn write_crash_file(
+    info: &PanicHookInfo,
+    files: &dyn Files,
+    clock: &dyn Clock,
+) -> Option<std::io::Result<PathBuf>> {
+    let text = WATCHED.with(|watched| {
+        let watched = watched.try_borrow().ok()?;
+        let snapshot = watched.snapshot.as_ref()?;
+        let mut text = format!("panic: {}\n\n", info);
+        text.push_str("last snapshot:\n");
+        text.push_str(&dump::registers(snapshot));
+        if let Some(trace) = &watched.trace {
+            text.push_str("\nexecuted since:\n");
+            text.push_str(&trace.to_text());
+        }
+        text.push_str("\nscreen:\n");
+        text.push_str(&screen_text(&snapshot.display));
+        text.push_str("\nmemory:\n");
+        text.push_str(&dump::hexdump(&snapshot.ram, 0, snapshot.ram.len()));
+        Some(text)
+    })?;
+
+    let path = config_dir()
+        .join("crashes")
+        .join(format!("crash-{}.txt", clock.unix_seconds()));
+    Some(files.write(&path, text.as_bytes()).map(|()| path))
+}
@@ -0,0 +1,80 @@
+use chip8_core::instruction::Variant;
+use chip8_core::{get_op_code, run_frame, EmulatorBuilder, Instruction, Quirks};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::cli::{parse_flag, shutdown};
+
+const USAGE: &str =
+    "usage: printer <rom> [--frames N] [--output FILE] [--variant NAME] [--quirks PROFILE]";
+// A minute of emulated time.
+const DEFAULT_FRAMES: u32 = 3600;
+
+// `printer <rom>`: runs a ROM without a screen and with the FxF0 printer
+// on, writing what it prints to stdout or `--output` as it goes. Stops when
+// the ROM halts with a jump to itself or after `--frames`, and fails when
+// it crashes, so test ROMs that print their results work as CI steps.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let frames = parse_flag::<u32>(args, "--frames").unwrap_or(DEFAULT_FRAMES);
+    let variant = parse_flag::<Variant>(args, "--variant").unwrap_or(Variant::Chip8);
+    let mut builder = EmulatorBuilder::new()
+        .variant(variant)
+        .printer(true)
+        .seed(0)
+        .rom(&data);
+    if let Some(quirks) = parse_flag::<Quirks>(args, "--quirks") {
+        builder = builder.quirks(quirks);
+    }
+    let mut emulator = match builder.build() {
+        Ok(emulator) => emulator,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+    let mut output: Box<dyn Write> = match parse_flag::<PathBuf>(args, "--output") {
+        Some(path) => match fs::File::create(&path) {
+            Ok(file) => Box::new(file),
+            Err(error) => {
+                eprintln!("error: cannot create {}: {}", path.display(), error);
+                return 1;
+            }
+        },
+        None => Box::new(io::stdout()),
+    };
+
+    for frame in 0..frames {
+        if shutdown::requested() {
+            return shutdown::INTERRUPTED;
+        }
+        let result = run_frame(&mut emulator);
+        if let Some(printer) = &mut emulator.printer {
+            let printed = printer.take();
+            if let Err(error) = output.write_all(&printed).and_then(|_| output.flush()) {
+                eprintln!("error: cannot write the output: {}", error);
+                return 1;
+            }
+        }
+        if let Some(error) = result.error {
+            eprintln!("error: frame {}: {}", frame, error);
+            return 1;
+        }
+        let pc = emulator.program_counter;
+        if Instruction::decode(get_op_code(&emulator)) == (Instruction::Jp { nnn: pc }) {
+            break;
+        }
+    }
+    0
+}
@@ -0,0 +1,109 @@
+#[cfg(not(target_os = "wasi"))]
+use indicatif::{ProgressBar, ProgressStyle};
+#[cfg(target_os = "wasi")]
+use no_terminal::{ProgressBar, ProgressStyle};
+
+use crate::cli::headless::Report;
+
+// Progress of a headless job on stderr. indicatif hides it by itself when
+// stderr is not a terminal, so CI logs stay clean.
+pub struct JobProgress {
+    bar: ProgressBar,
+}
+
+impl JobProgress {
+    // `frames` is the total frame budget, if there is one.
+    pub fn new(frames: Option<u32>) -> Self {
+        let bar = match frames {
+            Some(frames) => {
+                let bar = ProgressBar::new(frames as u64);
+                bar.set_style(
+                    ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len} frames ({eta})")
+                        .unwrap()
+                        .progress_chars("=> "),
+                );
+                bar
+            }
+            None => {
+                let bar = ProgressBar::new_spinner();
+                bar.set_style(
+                    ProgressStyle::with_template("{spinner} {msg} {pos} frames").unwrap(),
+                );
+                bar
+            }
+        };
+        JobProgress { bar }
+    }
+
+    pub fn start(&self, rom: &str) {
+        self.bar.set_message(rom.to_string());
+    }
+
+    pub fn advance(&self) {
+        self.bar.inc(1);
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+pub fn print_summary(reports: &[Report]) {
+    let rom_width = reports
+        .iter()
+        .map(|report| report.rom.len())
+        .chain(Some("ROM".len()))
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:<rom_width$}  {:>8}  {:<16}  RESULT",
+        "ROM", "FRAMES", "END STATE"
+    );
+    for report in reports {
+        println!(
+            "{:<rom_width$}  {:>8}  {:<16}  {}",
+            report.rom,
+            report.frames,
+            report.end_state.label(),
+            report.outcome.describe()
+        );
+    }
+}
+
+// The part of indicatif used above, doing nothing, for WASI where there is
+// no terminal to draw on.
+#[cfg(target_os = "wasi")]
+pub(crate) mod no_terminal {
+    pub struct ProgressBar;
+    pub struct ProgressStyle;
+
+    impl ProgressBar {
+        pub fn new(_len: u64) -> Self {
+            ProgressBar
+        }
+
+        pub fn new_spinner() -> Self {
+            ProgressBar
+        }
+
+        pub fn set_style(&self, _style: ProgressStyle) {}
+
+        pub fn set_message(&self, _message: String) {}
+
+        pub fn inc(&self, _delta: u64) {}
+
+        pub fn set_position(&self, _position: u64) {}
+
+        pub fn finish_and_clear(&self) {}
+    }
+
+    impl ProgressStyle {
+        pub fn with_template(_template: &str) -> Result<Self, String> {
+            Ok(ProgressStyle)
+        }
+
+        pub fn progress_chars(self, _chars: &str) -> Self {
+            self
+        }
+    }
+}
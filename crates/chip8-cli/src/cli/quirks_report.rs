@@ -0,0 +1,46 @@
+use chip8_core::instruction::Variant;
+use chip8_core::quirkstest::{format_report, quirks_report, DEFAULT_FRAMES};
+use chip8_core::Quirks;
+use std::fs;
+
+use crate::cli::parse_flag;
+
+const USAGE: &str =
+    "usage: quirks-report <5-quirks.ch8> [--platform chip8|schip|xochip] [--frames N] [--quirks PROFILE]";
+
+// `quirks-report <rom>`: runs Timendus' quirks test headlessly, once per
+// quirk profile and for `--quirks` if given, and prints which quirks each
+// profile gets right for `--platform`, CHIP-8 by default.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let variant = parse_flag::<Variant>(args, "--platform").unwrap_or(Variant::Chip8);
+    let frames = parse_flag::<u32>(args, "--frames").unwrap_or(DEFAULT_FRAMES);
+    let mut profiles: Vec<(String, Quirks)> = Quirks::profiles()
+        .iter()
+        .map(|(name, quirks)| (name.to_string(), *quirks))
+        .collect();
+    if let Some(quirks) = parse_flag::<Quirks>(args, "--quirks") {
+        profiles.push((quirks.to_string(), quirks));
+    }
+    match quirks_report(&data, variant, &profiles, frames) {
+        Ok(rows) => {
+            println!("{} quirks", variant);
+            print!("{}", format_report(&rows));
+            0
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            1
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use chip8_core::config::RomConfig;
+use chip8_core::dump;
+use chip8_core::labels::Labels;
+use chip8_core::watch::Watches;
+use chip8_core::{execute, Emulator, EmulatorBuilder, Instruction, Quirks, RAM_SIZE};
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use crate::cli::shutdown;
+use crate::cli::{clipboard, new_emulator, parse_flag, print_screen};
+
+const HELP: &str = "\
+Type an opcode (8014, 0x8014) or an instruction (ADD V0, V1) to run it.
+:regs                 show the registers
+:mem ADDR [LEN]       hexdump LEN bytes of memory (16 by default)
+:dis [ADDR] [COUNT]   disassemble COUNT instructions (8 from PC by default)
+:copy regs|mem|dis    copy one of the above to the clipboard, same arguments
+:paste ADDR           write the hex bytes on the clipboard to memory
+:screen               show the screen
+:watch [EXPR]         show EXPR, e.g. RAM[0x3A0] or V3 - V2, after each instruction, or list them
+:unwatch N            stop showing watch N
+:label ADDR NAME      name ADDR, for use instead of it anywhere above
+:unlabel NAME         forget the name
+:labels               list the names
+:history              list what was run, !N runs entry N again and !! the last one
+:reset                start over with a fresh machine
+:quit                 leave";
+const DEFAULT_DUMP_LEN: usize = 16;
+const DEFAULT_DISASSEMBLY: usize = 8;
+
+// `repl [--quirks PROFILE] [--rom FILE]`: runs instructions one at a time
+// as they are typed, printing the registers after each one. `--rom` loads
+// a ROM to poke at first, and keeps the labels given to its addresses in
+// its config for next time.
+pub fn run(args: &[String]) -> i32 {
+    let quirks = parse_flag::<Quirks>(args, "--quirks").unwrap_or_default();
+    let rom = match parse_flag::<PathBuf>(args, "--rom") {
+        Some(path) => match fs::read(&path) {
+            Ok(data) => Some(data),
+            Err(error) => {
+                eprintln!("error: cannot read {}: {}", path.display(), error);
+                return 1;
+            }
+        },
+        None => None,
+    };
+    let fresh = || match &rom {
+        Some(data) => EmulatorBuilder::new()
+            .quirks(quirks)
+            .rom(data)
+            .build()
+            .map_err(|error| error.to_string()),
+        None => Ok(new_emulator(quirks)),
+    };
+    let mut emulator = match fresh() {
+        Ok(emulator) => emulator,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+    let mut labels = match rom.as_deref().map(|data| RomConfig::load(data)?.labels()) {
+        Some(Ok(labels)) => labels,
+        Some(Err(error)) => {
+            eprintln!("error: cannot read the ROM config: {}", error);
+            return 1;
+        }
+        None => Labels::new(),
+    };
+    let mut history: Vec<String> = Vec::new();
+    let mut watches = Watches::new();
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        println!(
+            "chip8-rs repl, {} quirks. :help lists the commands.",
+            quirks
+        );
+    }
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout().flush().ok();
+        }
+        let Some(Ok(line)) = lines.next() else {
+            return 0;
+        };
+        if shutdown::requested() {
+            return shutdown::INTERRUPTED;
+        }
+        let mut line = line.trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(entry) = line.strip_prefix('!') {
+            let index = if entry == "!" {
+                history.len().checked_sub(1)
+            } else {
+                entry.parse::<usize>().ok().and_then(|n| n.checked_sub(1))
+            };
+            match index.and_then(|index| history.get(index)) {
+                Some(previous) => {
+                    println!("{}", previous);
+                    line = previous.clone();
+                }
+                None => {
+                    eprintln!("error: no history entry {:?}", entry);
+                    continue;
+                }
+            }
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[0] {
+            ":quit" | ":q" => return 0,
+            ":help" => println!("{}", HELP),
+            ":screen" => print_screen(&emulator.display),
+            ":history" => {
+                for (number, entry) in history.iter().enumerate() {
+                    println!("{:>4}  {}", number + 1, entry);
+                }
+            }
+            ":reset" => {
+                emulator = fresh().unwrap();
+                history.clear();
+                print!("{}", dump::registers(&emulator));
+            }
+            ":regs" | ":mem" | ":dis" => match view(&emulator, &labels, words[0], &words[1..]) {
+                Ok(text) => print!("{}", text),
+                Err(error) => eprintln!("error: {}", error),
+            },
+            ":copy" => {
+                let copied = match words.get(1) {
+                    Some(what) => view(&emulator, &labels, &format!(":{}", what), &words[2..]),
+                    None => Err("copy what? regs, mem or dis".to_string()),
+                };
+                match copied.and_then(|text| clipboard::copy(&text)) {
+                    Ok(()) => println!("copied"),
+                    Err(error) => eprintln!("error: {}", error),
+                }
+            }
+            ":watch" if words.len() == 1 => {
+                for (number, expression) in watches.iter().enumerate() {
+                    println!("{:>4}  {}", number + 1, expression);
+                }
+            }
+            ":watch" => match watches.add_with(line[":watch".len()..].trim(), &labels) {
+                Ok(()) => print!("{}", watches.panel(&emulator)),
+                Err(error) => eprintln!("error: {}", error),
+            },
+            ":unwatch" => {
+                let number = words.get(1).and_then(|number| number.parse::<usize>().ok());
+                match number.and_then(|number| watches.remove(number.checked_sub(1)?)) {
+                    Some(expression) => println!("stopped watching {}", expression),
+                    None => eprintln!("error: no watch {:?}", words.get(1).unwrap_or(&"")),
+                }
+            }
+            ":label" => {
+                let labeled = match words[1..] {
+                    [address, name] => parse_address(address, &labels)
+                        .and_then(|address| labels.add(address, name))
+                        .and_then(|()| save_labels(rom.as_deref(), &labels)),
+                    _ => Err(":label needs an address and a name".to_string()),
+                };
+                if let Err(error) = labeled {
+                    eprintln!("error: {}", error);
+                }
+            }
+            ":unlabel" => {
+                let name = words.get(1).copied().unwrap_or_default();
+                match labels.remove(name) {
+                    Some(_) => {
+                        if let Err(error) = save_labels(rom.as_deref(), &labels) {
+                            eprintln!("error: {}", error);
+                        }
+                    }
+                    None => eprintln!("error: no label {:?}", name),
+                }
+            }
+            ":labels" => {
+                for (address, name) in labels.iter() {
+                    println!("{:#05x}  {}", address, name);
+                }
+            }
+            ":paste" => match paste(&mut emulator, &labels, &words[1..]) {
+                Ok(count) => println!("wrote {} bytes", count),
+                Err(error) => eprintln!("error: {}", error),
+            },
+            _ if line.starts_with(':') => eprintln!("error: unknown command {}", line),
+            _ => match parse(&line) {
+                Ok(instruction) => {
+                    history.push(line);
+                    run_instruction(&mut emulator, &labels, instruction);
+                    print!("{}", watches.panel(&emulator));
+                }
+                Err(error) => eprintln!("error: {}", error),
+            },
+        }
+    }
+}
+
+fn parse(line: &str) -> Result<Instruction, String> {
+    let digits = line.trim_start_matches("0x").trim_start_matches("0X");
+    if digits.len() == 4 {
+        if let Ok(op_code) = u16::from_str_radix(digits, 16) {
+            return Ok(Instruction::decode(op_code));
+        }
+    }
+    line.parse()
+}
+
+// The instruction is written at PC and executed from there, as if the
+// machine had fetched it, so jumps and skips move PC like they would in a
+// ROM.
+fn run_instruction(emulator: &mut Emulator, labels: &Labels, instruction: Instruction) {
+    let pc = emulator.program_counter as usize;
+    let [high, low] = instruction.encode().to_be_bytes();
+    let size = emulator.ram.len();
+    emulator.ram[pc % size] = high;
+    emulator.ram[(pc + 1) % size] = low;
+    emulator.program_counter += 2;
+
+    println!(
+        "{:04X}  {}{}",
+        instruction.encode(),
+        instruction,
+        labels.annotate(pc as u16, instruction)
+    );
+    if let Err(error) = execute(emulator, instruction) {
+        eprintln!("error: {}", error);
+    }
+    if emulator.display.take_dirty() {
+        print_screen(&emulator.display);
+    }
+    print!("{}", dump::registers(emulator));
+}
+
+// Text for :regs, :mem and :dis, shared with :copy.
+fn view(
+    emulator: &Emulator,
+    labels: &Labels,
+    command: &str,
+    args: &[&str],
+) -> Result<String, String> {
+    match command {
+        ":regs" => Ok(dump::registers(emulator)),
+        ":mem" => {
+            let address = args.first().ok_or(":mem needs an address")?;
+            let len = number(args.get(1), DEFAULT_DUMP_LEN)?;
+            Ok(dump::hexdump(
+                &emulator.ram,
+                parse_address(address, labels)?,
+                len,
+            ))
+        }
+        ":dis" => {
+            let address = match args.first() {
+                Some(address) => parse_address(address, labels)?,
+                None => emulator.program_counter,
+            };
+            let count = number(args.get(1), DEFAULT_DISASSEMBLY)?;
+            Ok(dump::disassemble_labeled(
+                &emulator.ram,
+                address,
+                count,
+                labels,
+            ))
+        }
+        _ => Err(format!("cannot copy {:?}", &command[1..])),
+    }
+}
+
+fn paste(emulator: &mut Emulator, labels: &Labels, args: &[&str]) -> Result<usize, String> {
+    let address = parse_address(args.first().ok_or(":paste needs an address")?, labels)?;
+    let bytes = dump::parse_hex_bytes(&clipboard::paste()?)?;
+    dump::poke(&mut emulator.ram, address, &bytes);
+    Ok(bytes.len())
+}
+
+// Hex, or a label.
+fn parse_address(text: &str, labels: &Labels) -> Result<u16, String> {
+    if let Some(address) = labels.address(text) {
+        return Ok(address);
+    }
+    let digits = text.trim_start_matches("0x").trim_start_matches("0X");
+    match u16::from_str_radix(digits, 16) {
+        Ok(address) if (address as usize) < RAM_SIZE => Ok(address),
+        _ => Err(format!("{:?} is not an address", text)),
+    }
+}
+
+// Only with a ROM, into its config.
+fn save_labels(rom: Option<&[u8]>, labels: &Labels) -> Result<(), String> {
+    let Some(rom) = rom else {
+        return Ok(());
+    };
+    let mut config = RomConfig::load(rom)?;
+    config.set_labels(labels);
+    config
+        .save(rom)
+        .map_err(|error| format!("cannot save the ROM config: {}", error))
+}
+
+fn number(text: Option<&&str>, default: usize) -> Result<usize, String> {
+    match text {
+        Some(text) => text
+            .parse()
+            .map_err(|_| format!("{:?} is not a number", text)),
+        None => Ok(default),
+    }
+}
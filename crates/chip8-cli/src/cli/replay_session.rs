@@ -0,0 +1,64 @@
+use chip8_core::checksum::FrameChain;
+use chip8_core::session::SessionLog;
+use std::fs;
+
+use crate::cli::print_screen;
+
+const USAGE: &str = "usage: replay-session <log> <rom> [--screen] [--overlay]";
+
+// `replay-session <log> <rom>`: plays a session recorded with
+// `tui --session-log` again with the same ROM, quirks, speed, seed and key
+// presses, checking it goes exactly the same way, and says how it ended.
+// `--screen` prints the screen as it was at the end. `--overlay` prints the
+// frame counter and code `tui --checksum-overlay` showed on every frame,
+// to check a video of the run against.
+pub fn run(args: &[String]) -> i32 {
+    let (Some(log_path), Some(rom_path)) = (args.first(), args.get(1)) else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let log = match fs::read_to_string(log_path)
+        .map_err(|error| format!("cannot read {}: {}", log_path, error))
+        .and_then(|text| SessionLog::parse(&text).map_err(|e| format!("{}: {}", log_path, e)))
+    {
+        Ok(log) => log,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+    let data = match fs::read(rom_path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", rom_path, error);
+            return 1;
+        }
+    };
+    println!(
+        "{}, recorded with chip8 {}: quirks {}, {} instructions per frame, {} frames",
+        log.rom_name, log.version, log.quirks, log.cycles_per_frame, log.frames
+    );
+    let overlay = args.iter().any(|arg| arg == "--overlay");
+    let mut chain = FrameChain::new();
+    let replayed = log.replay_with(&data, &mut |_, emulator| {
+        chain.advance(emulator);
+        if overlay {
+            println!("{}", chain);
+        }
+    });
+    let emulator = match replayed {
+        Ok(emulator) => emulator,
+        Err(error) => {
+            eprintln!("error: the session did not reproduce: {}", error);
+            return 1;
+        }
+    };
+    match &log.error {
+        Some((frame, error)) => println!("reproduced, stopped at frame {}: {}", frame, error),
+        None => println!("reproduced, no errors"),
+    }
+    if args.iter().any(|arg| arg == "--screen") {
+        print_screen(&emulator.display);
+    }
+    0
+}
@@ -0,0 +1,33 @@
+#[cfg(not(target_os = "wasi"))]
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// Exit code of a run cut short by Ctrl-C or SIGTERM, 128 + SIGINT.
+pub const INTERRUPTED: i32 = 130;
+
+// Turns SIGINT and SIGTERM into a request that the running command notices
+// between frames, so it can still flush what it was writing, e.g. a
+// narration, and print its report. A second signal quits right away, for
+// commands blocked on input.
+#[cfg(not(target_os = "wasi"))]
+pub fn install() {
+    let installed = ctrlc::set_handler(|| {
+        if REQUESTED.swap(true, Ordering::SeqCst) {
+            process::exit(INTERRUPTED);
+        }
+        eprintln!("\ninterrupted, finishing up. Press Ctrl-C again to quit without saving.");
+    });
+    if let Err(error) = installed {
+        eprintln!("warning: cannot handle Ctrl-C: {}", error);
+    }
+}
+
+// WASI has no signals, the runtime just kills the module.
+#[cfg(target_os = "wasi")]
+pub fn install() {}
+
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,43 @@
+use chip8_core::stats::PlayStatsStore;
+
+// `stats`: lists every ROM played so far, most recent first.
+pub fn run(_args: &[String]) -> i32 {
+    let store = match PlayStatsStore::load() {
+        Ok(store) => store,
+        Err(error) => {
+            eprintln!(
+                "error: cannot read {}: {}",
+                PlayStatsStore::path().display(),
+                error
+            );
+            return 1;
+        }
+    };
+    let roms: Vec<_> = store.iter().collect();
+    if roms.is_empty() {
+        println!("no ROM played yet");
+        return 0;
+    }
+    let width = roms
+        .iter()
+        .map(|(_, stats)| stats.name.len())
+        .chain(Some("ROM".len()))
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:<width$}  LAUNCHES  PLAYTIME  LAST PLAYED",
+        "ROM",
+        width = width
+    );
+    for (_, stats) in roms {
+        println!(
+            "{:<width$}  {:>8}  {:>8}  {}",
+            stats.name,
+            stats.launches,
+            stats.playtime(),
+            stats.last_played_date().unwrap_or_else(|| "-".to_string()),
+            width = width
+        );
+    }
+    0
+}
@@ -0,0 +1,128 @@
+use chip8_core::delta::{changes, Change};
+use chip8_core::narration::Narrator;
+use chip8_core::{get_op_code, load_rom_to_memory, step, tick_timers, Instruction, Quirks};
+use std::fs;
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::{new_emulator, parse_flag, print_screen};
+use crate::cli::{postmortem, shutdown};
+
+const USAGE: &str = "usage: teach <rom> [--ips N] [--steps N] [--quirks PROFILE] [--narrate FILE]";
+const DEFAULT_IPS: f64 = 2.0;
+const LEFT_WIDTH: usize = 44;
+
+// `teach <rom>`: runs a ROM a few instructions per second, showing each
+// instruction next to its explanation and what it changed, for people
+// learning how an interpreter works. Timers tick every `cycles_per_frame`
+// instructions, so games see time pass at their usual pace per
+// instruction.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let ips = parse_flag::<f64>(args, "--ips").unwrap_or(DEFAULT_IPS);
+    let max_steps = parse_flag::<u64>(args, "--steps");
+    let quirks = parse_flag::<Quirks>(args, "--quirks").unwrap_or_default();
+    let delay = if ips > 0.0 {
+        Duration::from_secs_f64(1.0 / ips)
+    } else {
+        Duration::ZERO
+    };
+
+    let mut narrator = match parse_flag::<String>(args, "--narrate") {
+        Some(path) => match fs::File::create(&path) {
+            Ok(file) => Some(Narrator::new(io::BufWriter::new(file))),
+            Err(error) => {
+                eprintln!("error: cannot create {}: {}", path, error);
+                return 1;
+            }
+        },
+        None => None,
+    };
+
+    let mut emulator = new_emulator(quirks);
+    if let Err(error) = load_rom_to_memory(&mut emulator, &data) {
+        eprintln!("error: {}", error);
+        return 1;
+    }
+
+    let mut steps = 0;
+    while max_steps.is_none_or(|max| steps < max) && !shutdown::requested() {
+        let before = emulator.clone();
+        let op_code = get_op_code(&emulator);
+        let instruction = Instruction::decode(op_code);
+        postmortem::snapshot(&emulator);
+        postmortem::trace(&emulator);
+        let result = match narrator.as_mut() {
+            Some(narrator) => narrator.step(&mut emulator),
+            None => step(&mut emulator),
+        };
+        steps += 1;
+        if steps.is_multiple_of(emulator.cycles_per_frame as u64) {
+            tick_timers(&mut emulator);
+        }
+
+        let left = explain(before.program_counter, op_code, instruction);
+        let right: Vec<String> = changes(&before, &emulator)
+            .iter()
+            .map(Change::to_string)
+            .collect();
+        println!("-- step {} {}", steps, "-".repeat(LEFT_WIDTH + 26));
+        for row in 0..left.len().max(right.len()) {
+            let left = left.get(row).map(String::as_str).unwrap_or("");
+            let right = right.get(row).map(String::as_str).unwrap_or("");
+            let line = format!("{:<width$} | {}", left, right, width = LEFT_WIDTH);
+            println!("{}", line.trim_end());
+        }
+        if let Err(error) = result {
+            eprintln!("error: {}", error);
+            break;
+        }
+        if emulator.display.take_dirty() {
+            print_screen(&emulator.display);
+        }
+        thread::sleep(delay);
+    }
+    if let Some(Err(error)) = narrator.map(Narrator::finish) {
+        eprintln!("error: cannot write the narration: {}", error);
+        return 1;
+    }
+    if shutdown::requested() {
+        return shutdown::INTERRUPTED;
+    }
+    0
+}
+
+// The instruction and its documentation, wrapped to the left column.
+fn explain(address: u16, op_code: u16, instruction: Instruction) -> Vec<String> {
+    let info = instruction.describe();
+    let mut lines = vec![format!(
+        "{:#05x}  {:04X}  {}",
+        address, op_code, instruction
+    )];
+    let mut line = String::new();
+    for word in info.description.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > LEFT_WIDTH {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    if !info.quirks.is_empty() {
+        lines.push(format!("quirks: {}", info.quirks.join(", ")));
+    }
+    lines
+}
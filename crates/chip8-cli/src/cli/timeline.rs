@@ -0,0 +1,140 @@
+use chip8_core::timeline::{Thumbnail, Timeline, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH};
+use chip8_core::{load_rom_to_memory, run_frame_with, Emulator, Quirks};
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::cli::{new_emulator, parse_flag, print_screen};
+use crate::cli::{postmortem, shutdown};
+
+const USAGE: &str = "usage: timeline <rom> [--frames N] [--every N] [--keep N] [--quirks PROFILE]";
+const HELP: &str = "\
+n, p          next or previous snapshot
+g FRAME       go to the snapshot at or before FRAME
+s             show the full screen of the current snapshot
+r             rewind the emulator here and play --frames more frames
+q             leave";
+const DEFAULT_FRAMES: u64 = 600;
+const DEFAULT_EVERY: u64 = 30;
+const DEFAULT_KEEP: usize = 600;
+
+// `timeline <rom>`: plays a ROM while keeping a snapshot every few frames,
+// then lets you scrub back and forth through the thumbnails and rewind the
+// emulator to any of them.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let frames = parse_flag::<u64>(args, "--frames").unwrap_or(DEFAULT_FRAMES);
+    let every = parse_flag::<u64>(args, "--every").unwrap_or(DEFAULT_EVERY);
+    let keep = parse_flag::<usize>(args, "--keep").unwrap_or(DEFAULT_KEEP);
+    let quirks = parse_flag::<Quirks>(args, "--quirks").unwrap_or_default();
+
+    let mut emulator = new_emulator(quirks);
+    if let Err(error) = load_rom_to_memory(&mut emulator, &data) {
+        eprintln!("error: {}", error);
+        return 1;
+    }
+    let mut timeline = Timeline::new(every, keep);
+    timeline.record(0, &emulator);
+    let mut frame = play(&mut emulator, &mut timeline, 0, frames);
+    if shutdown::requested() {
+        return shutdown::INTERRUPTED;
+    }
+
+    let interactive = io::stdin().is_terminal();
+    let mut current = timeline.len() - 1;
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        show(&timeline, current, frame);
+        if interactive {
+            print!("> ");
+            io::stdout().flush().ok();
+        }
+        let Some(Ok(line)) = lines.next() else {
+            return 0;
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.first().copied() {
+            Some("n") => current = (current + 1).min(timeline.len() - 1),
+            Some("p") => current = current.saturating_sub(1),
+            Some("g") => match words.get(1).and_then(|frame| frame.parse().ok()) {
+                Some(target) => current = timeline.seek(target).unwrap_or(0),
+                None => eprintln!("error: g needs a frame number"),
+            },
+            Some("s") => print_screen(&timeline.state(current).unwrap().display),
+            Some("r") => {
+                let start = timeline.rewind(current, &mut emulator).unwrap();
+                frame = play(&mut emulator, &mut timeline, start, frames);
+                current = timeline.len() - 1;
+            }
+            Some("q") => return 0,
+            Some("help") => println!("{}", HELP),
+            _ => eprintln!("error: unknown command, help lists them"),
+        }
+    }
+}
+
+// Runs `frames` frames from `start`, returns the frame it stopped at.
+fn play(emulator: &mut Emulator, timeline: &mut Timeline, start: u64, frames: u64) -> u64 {
+    let mut frame = start;
+    while frame < start + frames && !shutdown::requested() {
+        postmortem::snapshot(emulator);
+        let output = run_frame_with(emulator, postmortem::step);
+        frame += 1;
+        timeline.record(frame, emulator);
+        if let Some(error) = output.error {
+            eprintln!("error: frame {}: {}", frame, error);
+            break;
+        }
+    }
+    frame
+}
+
+fn show(timeline: &Timeline, current: usize, frame: u64) {
+    let moment = timeline.get(current).unwrap();
+    println!(
+        "snapshot {}/{}, frame {} of {}",
+        current + 1,
+        timeline.len(),
+        moment.frame,
+        frame
+    );
+    print!("{}", to_text(&moment.thumbnail));
+    // Where the snapshot sits on the whole session.
+    let width = THUMBNAIL_WIDTH + 2;
+    let position = match timeline.len() {
+        1 => 0,
+        len => current * (width - 1) / (len - 1),
+    };
+    let bar: String = (0..width)
+        .map(|column| if column == position { '|' } else { '-' })
+        .collect();
+    println!("{}", bar);
+}
+
+// Two thumbnail rows per line of text, with half blocks.
+fn to_text(thumbnail: &Thumbnail) -> String {
+    let mut text = format!("+{}+\n", "-".repeat(THUMBNAIL_WIDTH));
+    for y in (0..THUMBNAIL_HEIGHT).step_by(2) {
+        text.push('|');
+        for x in 0..THUMBNAIL_WIDTH {
+            text.push(match (thumbnail.pixel(x, y), thumbnail.pixel(x, y + 1)) {
+                (true, true) => '\u{2588}',
+                (true, false) => '\u{2580}',
+                (false, true) => '\u{2584}',
+                (false, false) => ' ',
+            });
+        }
+        text.push_str("|\n");
+    }
+    text.push_str(&format!("+{}+\n", "-".repeat(THUMBNAIL_WIDTH)));
+    text
+}
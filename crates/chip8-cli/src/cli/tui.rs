@@ -0,0 +1,717 @@
+use chip8_core::branches::BranchHistory;
+use chip8_core::breakpoint::AccessBreakpoint;
+use chip8_core::calibration::Calibration;
+use chip8_core::checksum::FrameChain;
+use chip8_core::clock::Clock;
+use chip8_core::config::{RomConfig, Settings};
+use chip8_core::demo;
+use chip8_core::diagnosis::Diagnosis;
+use chip8_core::discord;
+use chip8_core::disk::Disk;
+use chip8_core::events::Event;
+use chip8_core::host::{Clock as _, StdFiles, SystemClock};
+use chip8_core::input::InputSource;
+use chip8_core::instruction::Variant;
+use chip8_core::macros::{MacroRecorder, Macros};
+use chip8_core::perftrace::{PerfTrace, Track};
+use chip8_core::reload::{ConfigWatcher, Reload};
+use chip8_core::render::Rotation;
+use chip8_core::savestate;
+use chip8_core::session::SessionLog;
+use chip8_core::strict::Strict;
+use chip8_core::sync::{Correction, Resync, SyncMonitor};
+use chip8_core::terminal::{Graphics, TextStyle};
+use chip8_core::title;
+use chip8_core::watch::Watches;
+use chip8_core::{run_frame, Display, EmulatorBuilder, FrameOutput, Palette, Quirks};
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cli::pane::{PaneWriter, BRANCH_HISTORY};
+use crate::cli::parse_flag;
+
+const USAGE: &str = "usage: tui [rom] [--tui-renderer auto|ascii|blocks|braille|sixel|kitty] \
+                     [--scale N] [--rotate 90|180|270 [--keep-keys]] [--variant NAME] [--quirks PROFILE] \
+                     [--cpu-hz N] [--sync-stats] [--checksum-overlay] \
+                     [--debug-panes DIR] [--session-log FILE] \
+                     [--state FILE] [--perf-trace FILE] [--wall-clock UTC_OFFSET_MINUTES] [--disk] \
+                     [--watch EXPR]... [--calibrate] [--strict] [--strict-trap] \
+                     [--watch-access RANGE] [--break-access RANGE] \
+                     [--record-macro NAME [--macro-hotkey CHORD] [--autoplay]]";
+const DEFAULT_SCALE: usize = 4;
+const FRAME: Duration = Duration::from_micros(16_667);
+// Terminals only report key presses, a key is let go this many frames
+// after its last repeat.
+pub(crate) const HOLD_FRAMES: u32 = 6;
+pub(crate) const ESCAPE: u8 = 0x1B;
+pub(crate) const CTRL_C: u8 = 0x03;
+const CTRL_R: u8 = 0x12;
+// How often the ROM's config is read again, and how long a reload shows.
+const RELOAD_FRAMES: u32 = 60;
+const NOTICE_FRAMES: u32 = 180;
+
+// `tui [rom]`: plays a ROM in the terminal, or the demo without one,
+// drawn with `--tui-renderer`. The default, auto, draws the bitmap
+// `--scale`d up when the terminal speaks Sixel or the Kitty protocol and
+// falls back to blocks, braille suits SUPER-CHIP's hires screen best.
+// `--rotate` turns the picture clockwise for a screen stood on its side,
+// and the arrow keys 2, 4, 6 and 8 with it unless `--keep-keys` is given.
+// The keypad is the 4x4 block from 1 to V, Esc quits. Sleeping between frames always runs a little long,
+// frames are skipped or repeated to keep the game at 60 Hz, with
+// `--sync-stats` printing how that went. `--checksum-overlay` shows the
+// frame number and a code chained from every frame's state under the
+// screen, for streams of a run to be checked against its session log with
+// `replay-session --overlay`. `--debug-panes` keeps the
+// registers, disassembly, memory and the last branches taken in DIR for
+// `pane` to show in windows of their own. `--session-log` writes what `replay-session` needs to play
+// the session again, for bug reports. `--state` starts from a savestate of
+// the ROM instead of power on, e.g. one made by `import-octo`.
+// `--perf-trace` writes where the host time went, frame by frame, for
+// chrome://tracing or Perfetto. `--wall-clock` lets clock ROMs read the
+// time of day with FxF1, in the time zone that many minutes ahead of UTC.
+// `--disk` attaches the ROM's disk for FxF2 and FxF3, saved after every
+// frame that wrote to it. Each `--watch` adds an expression such as
+// "RAM[0x3A0]" or "V3 - V2" to the watches pane, updated every frame.
+// `--cpu-hz` runs that many instructions a second, whatever speed the ROM's
+// config sets. `--calibrate` shows the
+// speed under the screen, + and - change it while playing and quitting
+// saves it to the ROM's config, which sets the speed from then on.
+// `--record-macro` records the keys pressed as a macro of the ROM until
+// Ctrl-R or quitting, `--macro-hotkey ctrl+t` plays it on
+// Ctrl-T and `--autoplay` whenever the ROM starts, see `macros`.
+// `--strict` lists what the ROM does that interpreters disagree on once
+// the game is over, `--strict-trap` stops it there like a crash, see
+// `strict`. `--watch-access 0x300-0x30F` lists every instruction reading or
+// writing those addresses through I, `--break-access` stops at the first,
+// see `breakpoint`. Edits to the ROM's config apply while playing, see `reload` for which
+// wait for the ROM to start again. The game shows on Discord when the
+// settings say so, paused once it has stopped.
+// When the ROM or savestate does not load, or the game stops, the reason
+// and what to try, such as another `--variant`, show on screen until a
+// key is pressed.
+pub fn run(args: &[String]) -> i32 {
+    let path = args.first().filter(|arg| !arg.starts_with("--"));
+    let (name, data) = match path {
+        None => (demo::NAME.to_string(), demo::ROM.to_vec()),
+        Some(path) => match fs::read(path) {
+            Ok(data) => (title::rom_name(Path::new(path)), data),
+            Err(error) => {
+                return fail(
+                    &Diagnosis::new(&format!("Cannot open {}", path), error)
+                        .fix("Check the path, and that the file can be read."),
+                );
+            }
+        },
+    };
+    let Some(renderer) = Renderer::from_args(args) else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let scale = parse_flag::<usize>(args, "--scale").unwrap_or(DEFAULT_SCALE);
+    let rotation = parse_flag::<Rotation>(args, "--rotate").unwrap_or_default();
+    let keys_rotation = match args.iter().any(|arg| arg == "--keep-keys") {
+        true => Rotation::None,
+        false => rotation,
+    };
+    let variant = parse_flag::<Variant>(args, "--variant").unwrap_or(Variant::Chip8);
+    let utc_offset = parse_flag::<i32>(args, "--wall-clock");
+    let mut config = match RomConfig::load(&data) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("error: cannot read the ROM config: {}", error);
+            return 1;
+        }
+    };
+    let built = match parse_flag::<PathBuf>(args, "--state") {
+        Some(state) => {
+            let state_name = state.display().to_string();
+            match fs::read(&state) {
+                Ok(saved) => savestate::load(&saved)
+                    .map_err(|error| Diagnosis::savestate(&state_name, &error)),
+                Err(error) => Err(
+                    Diagnosis::new(&format!("Cannot open {}", state_name), error)
+                        .fix("Check the path, and that the file can be read."),
+                ),
+            }
+        }
+        None => {
+            let mut builder = EmulatorBuilder::new().variant(variant).rom(&data);
+            if let Some(quirks) = parse_flag::<Quirks>(args, "--quirks") {
+                builder = builder.quirks(quirks);
+            }
+            if let Some(cycles) = config.cycles_per_frame {
+                builder = builder.cycles_per_frame(cycles);
+            }
+            if let Some(hz) = parse_flag(args, "--cpu-hz") {
+                builder = builder.clock_hz(hz);
+            }
+            if let Some(minutes) = utc_offset {
+                builder = builder.wall_clock(minutes.saturating_mul(60));
+            }
+            if args.iter().any(|arg| arg == "--disk") {
+                match Disk::load(&data) {
+                    Ok(disk) => builder = builder.disk(disk),
+                    Err(error) => {
+                        let path = Disk::path(&data);
+                        return fail(&Diagnosis::new("Cannot load the disk", error).fix(&format!(
+                            "Delete {} to start from a blank disk.",
+                            path.display()
+                        )));
+                    }
+                }
+            }
+            builder
+                .build()
+                .map_err(|error| Diagnosis::rom(&name, &data, &error))
+        }
+    };
+    let mut emulator = match built {
+        Ok(emulator) => emulator,
+        Err(diagnosis) => return fail(&diagnosis),
+    };
+    let trap = args.iter().any(|arg| arg == "--strict-trap");
+    let findings = (trap || args.iter().any(|arg| arg == "--strict")).then(|| {
+        let strict = Strict::new(&emulator, data.len());
+        let findings = strict.findings();
+        emulator.hooks.add(strict);
+        findings
+    });
+    let break_access = parse_flag(args, "--break-access");
+    let hits = break_access
+        .or_else(|| parse_flag(args, "--watch-access"))
+        .map(|range| {
+            let breakpoint = AccessBreakpoint::new(range);
+            let hits = breakpoint.hits();
+            emulator.hooks.add(breakpoint);
+            hits
+        });
+    // The ROM's labels, named in the repl, show in the panes.
+    let labels = match config.labels() {
+        Ok(labels) => labels,
+        Err(error) => {
+            eprintln!("error: cannot read the ROM config: {}", error);
+            return 1;
+        }
+    };
+    let mut macros = match config.macros() {
+        Ok(macros) => macros,
+        Err(error) => {
+            eprintln!("error: cannot read the ROM config: {}", error);
+            return 1;
+        }
+    };
+    let mut watches = Watches::new();
+    for pair in args.windows(2).filter(|pair| pair[0] == "--watch") {
+        if let Err(error) = watches.add_with(&pair[1], &labels) {
+            eprintln!("error: --watch {:?}: {}", pair[1], error);
+            return 1;
+        }
+    }
+    let mut panes = match parse_flag::<PathBuf>(args, "--debug-panes") {
+        Some(dir) => {
+            let history = BranchHistory::new(BRANCH_HISTORY);
+            let branches = history.branches();
+            emulator.hooks.add(history);
+            match PaneWriter::new(&dir, watches, branches, labels) {
+                Ok(panes) => Some(panes),
+                Err(error) => {
+                    eprintln!("error: {}", error);
+                    return 1;
+                }
+            }
+        }
+        None if !watches.is_empty() => {
+            eprintln!("error: --watch shows in the watches pane, it needs --debug-panes");
+            return 1;
+        }
+        None => None,
+    };
+    let log_path = parse_flag::<PathBuf>(args, "--session-log");
+    let mut session = log_path.as_ref().map(|_| {
+        let file = path.and_then(|path| Path::new(path).file_name());
+        let file = file.map_or(name.clone(), |file| file.to_string_lossy().into_owned());
+        SessionLog::start(&file, &data, &emulator)
+    });
+    let trace_path = parse_flag::<PathBuf>(args, "--perf-trace");
+    let mut perf = trace_path.as_ref().map(|_| PerfTrace::new());
+    let settings = Settings::load_from(&StdFiles).unwrap_or_else(|error| {
+        eprintln!("error: cannot read the settings: {}", error);
+        Default::default()
+    });
+    let rom_title = title::rom_title(&data, Path::new(path.map_or(demo::NAME, String::as_str)));
+    let mut discord = discord::publish(&settings, &rom_title, SystemClock.unix_seconds());
+    let _raw = match RawMode::enable() {
+        Ok(raw) => raw,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+
+    let keys = read_keys();
+    let mut stdout = io::stdout().lock();
+    let _ = write!(stdout, "\x1b[2J\x1b[?25l");
+    let mut held = [0u32; 16];
+    let mut redraw = true;
+    let mut exit_code = 0;
+    // There is no audio, only the render clock counts.
+    let mut sync = SyncMonitor::new(1, Resync::DuplicateFrames);
+    let mut first_frame = None;
+    let mut calibration = args
+        .iter()
+        .any(|arg| arg == "--calibrate")
+        .then(|| Calibration::new(emulator.cycles_per_frame));
+    let macro_name = parse_flag::<String>(args, "--record-macro");
+    let mut recorder = macro_name
+        .as_ref()
+        .map(|_| MacroRecorder::start(&emulator.keypad));
+    let mut chain = args
+        .iter()
+        .any(|arg| arg == "--checksum-overlay")
+        .then(FrameChain::new);
+    let mut status_line = calibration.is_some() || recorder.is_some() || chain.is_some();
+    let mut palette = config.palette.unwrap_or_default();
+    let mut watcher = ConfigWatcher::new(&StdFiles, &data, config.clone());
+    let mut notice: Option<(String, u32)> = None;
+    let mut ticks = 0u32;
+    macros.autoplay();
+    'frames: loop {
+        let started = Instant::now();
+        ticks = ticks.wrapping_add(1);
+        if ticks.is_multiple_of(RELOAD_FRAMES) {
+            match watcher.poll(&StdFiles, &mut emulator) {
+                Some(Ok(reload)) if !reload.is_empty() => {
+                    let reloaded = watcher.config();
+                    palette = reloaded.palette.unwrap_or_default();
+                    if reload.applied.contains(&"macros") {
+                        macros = reloaded.macros().unwrap_or_default();
+                    }
+                    if reload.applied.contains(&"speed") {
+                        calibration =
+                            calibration.map(|_| Calibration::new(emulator.cycles_per_frame));
+                    }
+                    config = reloaded.clone();
+                    notice = Some((reload_notice(&reload), NOTICE_FRAMES));
+                }
+                Some(Err(error)) => {
+                    notice = Some((format!("config not reloaded: {}", error), NOTICE_FRAMES));
+                }
+                _ => {}
+            }
+            if notice.is_some() {
+                status_line = true;
+                redraw = true;
+            }
+        }
+        if let Some((_, frames)) = &mut notice {
+            *frames -= 1;
+            if *frames == 0 {
+                notice = None;
+                redraw = true;
+            }
+        }
+        for (byte, _) in keys.try_iter() {
+            if byte == ESCAPE || byte == CTRL_C {
+                break 'frames;
+            }
+            if byte == CTRL_R {
+                if let (Some(recording), Some(name)) = (recorder.take(), &macro_name) {
+                    save_macro(&mut config, &data, &mut macros, name, recording, args);
+                    redraw = true;
+                }
+                continue;
+            }
+            if let Some(name) = ctrl_chord(byte).and_then(|chord| macros.for_hotkey(&chord)) {
+                let name = name.to_string();
+                macros.play(&name);
+                continue;
+            }
+            if let Some(calibration) = &mut calibration {
+                let cycles = match byte {
+                    b'+' | b'=' => Some(calibration.faster()),
+                    b'-' | b'_' => Some(calibration.slower()),
+                    _ => None,
+                };
+                if let Some(cycles) = cycles {
+                    emulator.cycles_per_frame = cycles;
+                    emulator.clock = Clock::default();
+                    redraw = true;
+                    continue;
+                }
+            }
+            if let Some(key) = keypad_key(byte).map(|key| keys_rotation.key(key)) {
+                if held[key as usize] == 0 {
+                    emulator.keypad.press(key);
+                }
+                held[key as usize] = HOLD_FRAMES;
+            }
+        }
+        for (key, frames) in held.iter_mut().enumerate() {
+            if *frames > 0 {
+                *frames -= 1;
+                if *frames == 0 {
+                    emulator.keypad.release(key as u8);
+                }
+            }
+        }
+        let frames = match sync.correction() {
+            Correction::SkipFrame => 2,
+            Correction::DuplicateFrame => 0,
+            _ => 1,
+        };
+        let mut output = FrameOutput::default();
+        for _ in 0..frames {
+            macros.poll(&mut emulator.keypad);
+            if let Some(recorder) = &mut recorder {
+                recorder.before_frame(&emulator.keypad);
+            }
+            if let Some(session) = &mut session {
+                session.before_frame(&emulator.keypad);
+            }
+            if let Some(clock) = &mut emulator.wall_clock {
+                clock.sync(&SystemClock);
+            }
+            let mut frame = match &mut perf {
+                Some(perf) => perf.run_frame(&mut emulator),
+                None => run_frame(&mut emulator),
+            };
+            if let Some(error) = findings.as_ref().filter(|_| trap).and_then(|f| f.trap()) {
+                frame.error.get_or_insert(error);
+            }
+            let breaks = hits.as_ref().filter(|_| break_access.is_some());
+            if let Some(error) = breaks.and_then(|hits| hits.trap()) {
+                frame.error.get_or_insert(error);
+            }
+            if let Some(recorder) = &mut recorder {
+                recorder.after_frame(&emulator.keypad);
+            }
+            if let Some(chain) = &mut chain {
+                chain.advance(&emulator);
+                redraw = true;
+            }
+            if let Some(session) = &mut session {
+                session.after_frame(&emulator, &frame);
+            }
+            sync.frame_emulated();
+            output.display_changed |= frame.display_changed;
+            if frame.error.is_some() {
+                output.error = frame.error;
+                break;
+            }
+        }
+        if let Some(disk) = emulator.disk.as_mut().filter(|disk| disk.is_dirty()) {
+            if let Err(error) = disk.save(&data) {
+                let _ = write!(stdout, "\r\nerror: cannot save the disk: {}\r\n", error);
+                exit_code = 1;
+                break;
+            }
+        }
+        if output.display_changed || redraw {
+            let presenting = Instant::now();
+            let text = renderer.render(&emulator.display, &palette, scale, rotation);
+            let _ = write!(stdout, "\x1b[H{}", text);
+            if status_line {
+                let mut status = Vec::new();
+                if let Some(chain) = &chain {
+                    status.push(chain.to_string());
+                }
+                if let Some(calibration) = &calibration {
+                    status.push(format!("{}, + and - to change", calibration));
+                }
+                if let (Some(_), Some(name)) = (&recorder, &macro_name) {
+                    status.push(format!("recording {}, Ctrl-R stops", name));
+                }
+                if let Some((text, _)) = &notice {
+                    status.push(text.clone());
+                }
+                let _ = write!(stdout, "\r\n{}\x1b[K", status.join(" | "));
+            }
+            let _ = stdout.flush();
+            redraw = false;
+            if let Some(perf) = &mut perf {
+                perf.span(Track::Frontend, "present", presenting);
+            }
+        }
+        sync.presented(first_frame.get_or_insert(started).elapsed());
+        if let Some(writer) = &mut panes {
+            if let Err(error) = writer.update(&emulator) {
+                let _ = write!(stdout, "\r\nerror: {}\r\n", error);
+                exit_code = 1;
+                break;
+            }
+        }
+        if let Some(error) = output.error {
+            if let Some(discord) = &mut discord {
+                discord.handle(&Event::Paused, SystemClock.unix_seconds());
+            }
+            show(&mut stdout, &Diagnosis::crash(&name, &data, &error));
+            let _ = keys.recv();
+            let _ = write!(stdout, "\x1b[2J\x1b[Herror: {}\r\n", error);
+            exit_code = 1;
+            break;
+        }
+        thread::sleep(FRAME.saturating_sub(started.elapsed()));
+    }
+    let _ = write!(stdout, "\x1b[?25h\r\n");
+    if let (Some(recording), Some(name)) = (recorder.take(), &macro_name) {
+        if !save_macro(&mut config, &data, &mut macros, name, recording, args) {
+            exit_code = 1;
+        }
+    }
+    for finding in findings.iter().flat_map(|findings| findings.list()) {
+        let _ = write!(stdout, "strict: {}\r\n", finding);
+    }
+    for hit in hits.iter().flat_map(|hits| hits.list()) {
+        let _ = write!(stdout, "access: {}\r\n", hit);
+    }
+    if args.iter().any(|arg| arg == "--sync-stats") {
+        let _ = write!(stdout, "sync: {}\r\n", sync.stats());
+    }
+    if let Some(calibration) = calibration.filter(Calibration::changed) {
+        config.cycles_per_frame = Some(calibration.cycles_per_frame());
+        match config.save(&data) {
+            Ok(()) => {
+                let _ = write!(
+                    stdout,
+                    "saved {} to {}\r\n",
+                    calibration,
+                    RomConfig::path(&data).display()
+                );
+            }
+            Err(error) => {
+                let _ = write!(stdout, "error: cannot save the ROM config: {}\r\n", error);
+                exit_code = 1;
+            }
+        }
+    }
+    if let (Some(perf), Some(trace_path)) = (&perf, &trace_path) {
+        if let Err(error) = fs::write(trace_path, perf.to_json()) {
+            let _ = write!(
+                stdout,
+                "error: cannot write {}: {}\r\n",
+                trace_path.display(),
+                error
+            );
+            exit_code = 1;
+        }
+    }
+    if let (Some(session), Some(log_path)) = (&session, &log_path) {
+        if let Err(error) = fs::write(log_path, session.to_text()) {
+            let _ = write!(
+                stdout,
+                "error: cannot write {}: {}\r\n",
+                log_path.display(),
+                error
+            );
+            exit_code = 1;
+        }
+    }
+    exit_code
+}
+
+const DIALOG_WIDTH: usize = 60;
+
+fn reload_notice(reload: &Reload) -> String {
+    let mut parts = Vec::new();
+    if !reload.applied.is_empty() {
+        parts.push(format!("reloaded {}", reload.applied.join(", ")));
+    }
+    if !reload.on_reset.is_empty() {
+        parts.push(format!(
+            "{} from the next start",
+            reload.on_reset.join(", ")
+        ));
+    }
+    parts.join(", ")
+}
+
+// Ctrl+A to Ctrl+Z arrive as 1 to 26, the chord a macro is bound to.
+fn ctrl_chord(byte: u8) -> Option<String> {
+    (1..=26)
+        .contains(&byte)
+        .then(|| format!("ctrl+{}", (b'a' + byte - 1) as char))
+}
+
+// Saves what `recording` caught as the macro `name` in the ROM's config,
+// with `--macro-hotkey` and `--autoplay`. Says how that went below the
+// screen, false if it did not.
+fn save_macro(
+    config: &mut RomConfig,
+    data: &[u8],
+    macros: &mut Macros,
+    name: &str,
+    recording: MacroRecorder,
+    args: &[String],
+) -> bool {
+    let script = recording.finish();
+    let events = script.events().len();
+    let saved = macros.add(name, script).and_then(|()| {
+        let found = macros.get_mut(name).unwrap();
+        if let Some(chord) = parse_flag::<String>(args, "--macro-hotkey") {
+            found.hotkey = Some(chord);
+        }
+        if args.iter().any(|arg| arg == "--autoplay") {
+            found.autoplay = true;
+        }
+        config.set_macros(macros);
+        config
+            .save(data)
+            .map_err(|error| format!("cannot save the ROM config: {}", error))
+    });
+    let mut stdout = io::stdout().lock();
+    let _ = match &saved {
+        Ok(()) => write!(
+            stdout,
+            "\r\nsaved macro {}, {} key events, to {}\x1b[K\r\n",
+            name,
+            events,
+            RomConfig::path(data).display()
+        ),
+        Err(error) => write!(stdout, "\r\nerror: macro {}: {}\x1b[K\r\n", name, error),
+    };
+    saved.is_ok()
+}
+
+// Shows `diagnosis` until a key is pressed, or prints it when there is no
+// terminal to show it on. The exit code, always 1.
+fn fail(diagnosis: &Diagnosis) -> i32 {
+    match RawMode::enable() {
+        Ok(_raw) => {
+            let keys = read_keys();
+            let mut stdout = io::stdout().lock();
+            let _ = write!(stdout, "\x1b[2J");
+            show(&mut stdout, diagnosis);
+            let _ = keys.recv();
+            let _ = write!(stdout, "\x1b[2J\x1b[Herror: {}\r\n", diagnosis.error);
+        }
+        Err(_) => {
+            for line in diagnosis.dialog(DIALOG_WIDTH) {
+                eprintln!("{}", line);
+            }
+        }
+    }
+    1
+}
+
+// Draws `diagnosis` over the top left of the screen.
+fn show(stdout: &mut impl Write, diagnosis: &Diagnosis) {
+    let _ = write!(stdout, "\x1b[H");
+    for line in diagnosis.dialog(DIALOG_WIDTH) {
+        let _ = write!(stdout, "{}\x1b[K\r\n", line);
+    }
+    let _ = write!(stdout, "Press any key to close.\x1b[K\r\n");
+    let _ = stdout.flush();
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Renderer {
+    Text(TextStyle),
+    Graphics(Graphics),
+}
+
+impl Renderer {
+    // From `--tui-renderer`, None if it names no renderer.
+    pub(crate) fn from_args(args: &[String]) -> Option<Renderer> {
+        match parse_flag::<String>(args, "--tui-renderer").as_deref() {
+            None | Some("auto") => Some(
+                Graphics::detect(|name| env::var(name).ok())
+                    .map_or(Renderer::Text(TextStyle::Blocks), Renderer::Graphics),
+            ),
+            Some(name) => name
+                .parse()
+                .map(Renderer::Graphics)
+                .or_else(|_| name.parse().map(Renderer::Text))
+                .ok(),
+        }
+    }
+
+    pub(crate) fn render(
+        &self,
+        display: &Display,
+        palette: &Palette,
+        scale: usize,
+        rotation: Rotation,
+    ) -> String {
+        match self {
+            // Raw mode does not turn \n into \r\n.
+            Renderer::Text(style) => style
+                .render_rotated(display, rotation)
+                .replace('\n', "\r\n"),
+            Renderer::Graphics(graphics) => {
+                graphics.render_rotated(display, palette, scale, rotation)
+            }
+        }
+    }
+}
+
+// Bytes typed on stdin and when they arrived, read on a thread of their
+// own so frames never wait for the keyboard.
+pub(crate) fn read_keys() -> mpsc::Receiver<(u8, Instant)> {
+    let (sender, keys) = mpsc::channel();
+    thread::spawn(move || {
+        for byte in io::stdin().lock().bytes() {
+            let Ok(byte) = byte else { break };
+            if sender.send((byte, Instant::now())).is_err() {
+                break;
+            }
+        }
+    });
+    keys
+}
+
+// The host key of each keypad key: 1234 / QWER / ASDF / ZXCV on a QWERTY
+// keyboard, laid out like the COSMAC VIP keypad.
+pub(crate) const KEY_LAYOUT: &[u8; 16] = b"x123qweasdzc4rfv";
+
+pub(crate) fn keypad_key(byte: u8) -> Option<u8> {
+    KEY_LAYOUT
+        .iter()
+        .position(|&c| c == byte.to_ascii_lowercase())
+        .map(|key| key as u8)
+}
+
+// Unbuffered input without echo until dropped.
+#[cfg(unix)]
+pub(crate) struct RawMode(libc::termios);
+
+#[cfg(unix)]
+impl RawMode {
+    pub(crate) fn enable() -> Result<Self, String> {
+        unsafe {
+            let mut original = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err("the tui needs a terminal".to_string());
+            }
+            let mut raw = original;
+            libc::cfmakeraw(&mut raw);
+            // Keep output processing, only input goes raw.
+            raw.c_oflag = original.c_oflag;
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw);
+            Ok(RawMode(original))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) struct RawMode;
+
+#[cfg(not(unix))]
+impl RawMode {
+    pub(crate) fn enable() -> Result<Self, String> {
+        Err("the tui only runs on unix terminals for now".to_string())
+    }
+}
@@ -0,0 +1,141 @@
+use chip8_core::config::Settings;
+use chip8_core::demo;
+use chip8_core::host::StdFiles;
+use chip8_core::hotkeys::Hotkeys;
+use chip8_core::title::rom_title;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cli::compat::list_roms;
+use crate::cli::parse_flag;
+use crate::cli::tui::{self, KEY_LAYOUT};
+
+const USAGE: &str = "usage: welcome [--roms DIR]";
+const DEFAULT_ROMS: &str = "roms";
+// The COSMAC VIP keypad, as laid out on the machine.
+const KEYPAD: [[u8; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xC],
+    [0x4, 0x5, 0x6, 0xD],
+    [0x7, 0x8, 0x9, 0xE],
+    [0xA, 0x0, 0xB, 0xF],
+];
+
+// What running `chip8` without arguments does: the first time, how to
+// load ROMs, the keypad layout and the hotkeys, then every time the ROM
+// browser. Seeing the introduction is kept in the settings, `welcome`
+// shows it again.
+pub fn first_launch() -> i32 {
+    match Settings::load_from(&StdFiles) {
+        Ok(settings) if settings.welcomed => {
+            println!("`chip8 welcome` shows the introduction again.\n");
+        }
+        Ok(mut settings) => {
+            print!("{}", introduction());
+            settings.welcomed = true;
+            if let Err(error) = settings.save_to(&StdFiles) {
+                eprintln!("error: cannot save the settings: {}", error);
+            }
+        }
+        // Left alone, saving now would lose what is in there.
+        Err(error) => {
+            eprintln!("error: cannot read the settings: {}", error);
+            print!("{}", introduction());
+        }
+    }
+    browse(Path::new(DEFAULT_ROMS))
+}
+
+// `welcome`: the introduction and the ROM browser, over the ROMs in
+// `--roms`, ./roms by default.
+pub fn run(args: &[String]) -> i32 {
+    if args.first().is_some_and(|arg| arg != "--roms") {
+        eprintln!("{}", USAGE);
+        return 1;
+    }
+    let roms = parse_flag::<PathBuf>(args, "--roms").unwrap_or(PathBuf::from(DEFAULT_ROMS));
+    print!("{}", introduction());
+    browse(&roms)
+}
+
+fn introduction() -> String {
+    let mut text = String::from(
+        "Welcome to chip8-rs, an emulator for CHIP-8 and its descendants.\n\
+         \n\
+         Play a ROM in the terminal with `chip8 tui game.ch8`, or pick one\n\
+         below, the demo shows the keys work. SUPER-CHIP and XO-CHIP games\n\
+         need `--variant schip` or `--variant xochip`, and\n\
+         `chip8 discover game.ch8` finds the right quirks for a game that\n\
+         misbehaves.\n\
+         \n\
+         The keypad is the block of keys from 1 to V:\n\
+         \n\
+         \x20   keypad      keyboard\n",
+    );
+    for row in KEYPAD {
+        let keypad: Vec<String> = row.iter().map(|key| format!("{:X}", key)).collect();
+        let keyboard: Vec<String> = row
+            .iter()
+            .map(|&key| {
+                (KEY_LAYOUT[key as usize] as char)
+                    .to_ascii_uppercase()
+                    .to_string()
+            })
+            .collect();
+        text.push_str(&format!(
+            "    {}     {}\n",
+            keypad.join(" "),
+            keyboard.join(" ")
+        ));
+    }
+    text.push_str("\nEsc quits the tui. Frontends with a window also take these hotkeys:\n\n");
+    for line in Hotkeys::default().to_config().lines() {
+        let (chord, action) = line.split_once(" = ").unwrap_or((line, ""));
+        text.push_str(&format!("    {:<10}{}\n", chord, action.replace('_', " ")));
+    }
+    text.push('\n');
+    text
+}
+
+// Lists the ROMs in `directory`, and the demo, and plays the one picked
+// in the tui.
+fn browse(directory: &Path) -> i32 {
+    // roms/ has the demo's source and ROM, it is listed once.
+    let mut roms = list_roms(directory).unwrap_or_default();
+    roms.retain(|path| fs::read(path).map_or(true, |data| data != demo::ROM));
+    if roms.is_empty() {
+        println!(
+            "No ROMs in {}/ yet, put some .ch8 files there.\n",
+            directory.display()
+        );
+    } else {
+        println!("ROMs in {}/:\n", directory.display());
+    }
+    println!("     0  the {}", demo::NAME);
+    for (number, path) in roms.iter().enumerate() {
+        let title = fs::read(path)
+            .map(|data| rom_title(&data, path))
+            .unwrap_or_else(|_| path.display().to_string());
+        println!("    {:>2}  {}", number + 1, title);
+    }
+    loop {
+        print!("\nPlay which one? (0-{}, Enter quits) ", roms.len());
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer).unwrap_or(0) == 0 {
+            return 0;
+        }
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return 0;
+        }
+        match answer.parse::<usize>() {
+            Ok(0) => return tui::run(&[]),
+            Ok(number) if number <= roms.len() => {
+                let path = roms[number - 1].to_string_lossy().into_owned();
+                return tui::run(&[path]);
+            }
+            _ => println!("{:?} is not one of the numbers above", answer),
+        }
+    }
+}
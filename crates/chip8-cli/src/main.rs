@@ -0,0 +1,323 @@
+mod cli;
+
+use chip8_core::branches::BranchHistory;
+use chip8_core::breakpoint::{AccessBreakpoint, AddressRange};
+use chip8_core::config::{RomConfig, Settings};
+use chip8_core::font::Font;
+use chip8_core::host::{Clock, Files, StdFiles, SystemClock};
+use chip8_core::input::{InputSource, VoteConfig};
+use chip8_core::instruction::Variant;
+use chip8_core::narration::Narrator;
+use chip8_core::render::Rotation;
+use chip8_core::strict::Strict;
+use chip8_core::terminal::TextStyle;
+use chip8_core::title::{rom_name, rom_title};
+use chip8_core::{demo, run_frame, step, EmulatorBuilder, Palette, Quirks};
+use cli::chat::ChatBridge;
+use cli::headless;
+use cli::progress::{print_summary, JobProgress};
+use cli::tui::{keypad_key, read_keys, RawMode, CTRL_C, ESCAPE, HOLD_FRAMES};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Jumps, calls, returns and skips shown when a headless run crashes.
+const BRANCH_HISTORY: usize = 16;
+
+// The name and bytes of the ROM at `path`, or of the demo without one.
+fn read_rom(files: &dyn Files, path: Option<&Path>) -> (String, Vec<u8>) {
+    let Some(path) = path else {
+        return (demo::NAME.to_string(), demo::ROM.to_vec());
+    };
+    match files.read(path) {
+        Ok(content) => (rom_name(path), content),
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path.display(), error);
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    cli::shutdown::install();
+    cli::postmortem::install(Box::new(StdFiles), Box::new(SystemClock));
+    match args.get(1).map(String::as_str) {
+        Some("compat") => process::exit(cli::compat::run(&args[2..])),
+        Some("quirks-report") => process::exit(cli::quirks_report::run(&args[2..])),
+        Some("discover") => process::exit(cli::discover::run(&args[2..])),
+        Some("explain") => process::exit(cli::explain::run(&args[2..])),
+        Some("repl") => process::exit(cli::repl::run(&args[2..])),
+        Some("replay-session") => process::exit(cli::replay_session::run(&args[2..])),
+        Some("teach") => process::exit(cli::teach::run(&args[2..])),
+        Some("heatmap") => process::exit(cli::heatmap::run(&args[2..])),
+        Some("timeline") => process::exit(cli::timeline::run(&args[2..])),
+        Some("attract") => process::exit(cli::attract::run(&args[2..])),
+        Some("tui") => process::exit(cli::tui::run(&args[2..])),
+        Some("import-octo") => process::exit(cli::import_octo::run(&args[2..])),
+        Some("printer") => process::exit(cli::printer::run(&args[2..])),
+        Some("pane") => process::exit(cli::pane::run(&args[2..])),
+        Some("cinema") => process::exit(cli::cinema::run(&args[2..])),
+        Some("latency") => process::exit(cli::latency::run(&args[2..])),
+        Some("jukebox") => process::exit(cli::jukebox::run(&args[2..])),
+        Some("stats") => process::exit(cli::stats::run(&args[2..])),
+        Some("welcome") => process::exit(cli::welcome::run(&args[2..])),
+        None => process::exit(cli::welcome::first_launch()),
+        _ => (),
+    }
+
+    let options = cli::options::command().get_matches_from(&args);
+    let path = options.get_one::<String>("rom").map(Path::new);
+    let quirks = options.get_one::<Quirks>("quirks").copied();
+    let hz = options.get_one::<u32>("cpu-hz").copied();
+    if options.get_flag("headless") {
+        // Everything the run reads goes through `files`, which is all a
+        // WASI runtime has to provide.
+        let files = StdFiles;
+        let (name, data) = read_rom(&files, path);
+        let variant = options
+            .get_one::<Variant>("variant")
+            .copied()
+            .unwrap_or(Variant::Chip8);
+        let config = RomConfig::load_from(&files, &data).unwrap_or_else(|error| {
+            eprintln!("error: cannot read the ROM config: {}", error);
+            Default::default()
+        });
+        // `--font NAME|FILE` wins over the ROM's own `font = ...`.
+        let font = options
+            .get_one::<String>("font")
+            .cloned()
+            .or(config.font.clone())
+            .map(|font| {
+                Font::open(&files, &font).unwrap_or_else(|error| {
+                    eprintln!("error: {}", error);
+                    process::exit(1);
+                })
+            })
+            .unwrap_or_default();
+        let mut builder = EmulatorBuilder::new()
+            .variant(variant)
+            .font(font)
+            .rom(&data);
+        if let Some(cycles) = config.cycles_per_frame {
+            builder = builder.cycles_per_frame(cycles);
+        }
+        if let Some(quirks) = quirks {
+            builder = builder.quirks(quirks);
+        }
+        if let Some(hz) = hz {
+            builder = builder.clock_hz(hz);
+        }
+        let mut emulator = builder.build().unwrap_or_else(|error| {
+            eprintln!("error: cannot load {}: {}", name, error);
+            process::exit(1);
+        });
+        if let Err(error) = cli::plugins::add(&mut emulator, &args) {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+        // `--strict` lists what the ROM does that interpreters disagree on
+        // once the run is over, `--strict-trap` stops at the first of them.
+        let trap = options.get_flag("strict-trap");
+        let findings = (trap || options.get_flag("strict")).then(|| {
+            let strict = Strict::new(&emulator, data.len());
+            let findings = strict.findings();
+            emulator.hooks.add(strict);
+            findings
+        });
+        // `--watch-access RANGE` lists every instruction reading or writing
+        // the range through I, `--break-access RANGE` stops at the first.
+        let break_access = options.get_one::<AddressRange>("break-access").copied();
+        let hits = break_access
+            .or_else(|| options.get_one::<AddressRange>("watch-access").copied())
+            .map(|range| {
+                let breakpoint = AccessBreakpoint::new(range);
+                let hits = breakpoint.hits();
+                emulator.hooks.add(breakpoint);
+                hits
+            });
+        // How the run got to where it crashed, printed with the error.
+        let history = BranchHistory::new(BRANCH_HISTORY);
+        let branches = history.branches();
+        emulator.hooks.add(history);
+        let watchdog_frames = options
+            .get_one::<u32>("watchdog-frames")
+            .copied()
+            .unwrap_or(cli::DEFAULT_WATCHDOG_FRAMES);
+        let max_frames = options.get_one::<u32>("frames").copied();
+
+        let mut narrator = options.get_one::<String>("narrate").map(|path| {
+            let file = fs::File::create(path).unwrap_or_else(|error| {
+                eprintln!("error: cannot create {}: {}", path, error);
+                process::exit(1);
+            });
+            Narrator::new(io::BufWriter::new(file))
+        });
+
+        // Triggers are only logged for now, there is no on-screen display
+        // in headless runs.
+        let mut triggers = config.triggers().unwrap_or_else(|error| {
+            eprintln!("error: cannot read the ROM config: {}", error);
+            Default::default()
+        });
+
+        let settings = Settings::load_from(&files).unwrap_or_else(|error| {
+            eprintln!("error: cannot read the settings: {}", error);
+            Default::default()
+        });
+        let title = rom_title(&data, path.unwrap_or(Path::new(demo::NAME)));
+        let _discord = chip8_core::discord::publish(&settings, &title, SystemClock.unix_seconds());
+
+        // `--chat ADDR` lets chat play, see `ChatBridge`.
+        let mut chat = options.get_one::<String>("chat").map(|address| {
+            let defaults = VoteConfig::default();
+            let frames =
+                |name: &str, default: u32| options.get_one::<u32>(name).copied().unwrap_or(default);
+            let config = VoteConfig {
+                window_frames: frames("chat-window", defaults.window_frames),
+                hold_frames: frames("chat-hold", defaults.hold_frames),
+                cooldown_frames: frames("chat-cooldown", defaults.cooldown_frames),
+            };
+            ChatBridge::listen(address, config).unwrap_or_else(|error| {
+                eprintln!("error: cannot listen on {}: {}", address, error);
+                process::exit(1);
+            })
+        });
+
+        let progress = JobProgress::new(max_frames);
+        progress.start(&name);
+        let report = headless::run(
+            &name,
+            &mut emulator,
+            watchdog_frames,
+            max_frames,
+            &progress,
+            &mut |emulator| {
+                if let (Some(chat), true) = (chat.as_mut(), emulator.vblank) {
+                    chat.poll(&mut emulator.keypad);
+                }
+                let result = match narrator.as_mut() {
+                    Some(narrator) => narrator.step(emulator),
+                    None => step(emulator),
+                };
+                for trigger in triggers.check(emulator) {
+                    eprintln!("trigger {}: {}", trigger.name, trigger.message);
+                }
+                let result = match &findings {
+                    Some(findings) if trap && result.is_ok() => findings.trap().map_or(result, Err),
+                    _ => result,
+                };
+                match &hits {
+                    Some(hits) if break_access.is_some() && result.is_ok() => {
+                        hits.trap().map_or(result, Err)
+                    }
+                    _ => result,
+                }
+            },
+        );
+        progress.finish();
+        if let Some(Err(error)) = narrator.map(Narrator::finish) {
+            eprintln!("error: cannot write the narration: {}", error);
+        }
+
+        for finding in findings.iter().flat_map(|findings| findings.list()) {
+            eprintln!("strict: {}", finding);
+        }
+        for hit in hits.iter().flat_map(|hits| hits.list()) {
+            eprintln!("access: {}", hit);
+        }
+        let crashed = matches!(report.outcome, headless::Outcome::Crashed(_));
+        if crashed && !branches.is_empty() {
+            eprintln!("branches before the crash, the latest last:");
+            eprint!("{}", branches.panel(&Default::default()));
+        }
+        let exit_code = report.outcome.exit_code();
+        print_summary(&[report]);
+        process::exit(exit_code);
+    }
+    // Without a command, plays the ROM, the demo without one, in real time:
+    // `--cpu-hz` instructions a second, 600 by default, and the timers at
+    // 60 Hz whatever that is. The screen is printed as text whenever it
+    // changes, each pixel `--scale` characters across, in the `--palette`
+    // colours when given. Keys go to the keypad laid out like in `tui`, Esc
+    // or Ctrl-C quits. The game shows on Discord when the settings say so.
+    let (name, data) = read_rom(&StdFiles, path);
+    let mut builder = EmulatorBuilder::new().rom(&data);
+    if let Some(variant) = options.get_one::<Variant>("variant") {
+        builder = builder.variant(*variant);
+    }
+    if let Some(quirks) = quirks {
+        builder = builder.quirks(quirks);
+    }
+    if let Some(hz) = hz {
+        builder = builder.clock_hz(hz);
+    }
+    let mut emulator = builder.build().unwrap_or_else(|error| {
+        eprintln!("error: cannot load {}: {}", name, error);
+        process::exit(1);
+    });
+    let scale = options
+        .get_one::<u16>("scale")
+        .map_or(1, |&scale| scale as usize);
+    let colors = options.get_one::<Palette>("palette").map(|palette| {
+        let [r, g, b, _] = palette.foreground;
+        let [br, bg, bb, _] = palette.background;
+        format!(
+            "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
+            r, g, b, br, bg, bb
+        )
+    });
+    let settings = Settings::load_from(&StdFiles).unwrap_or_else(|error| {
+        eprintln!("error: cannot read the settings: {}", error);
+        Default::default()
+    });
+    let title = rom_title(&data, path.unwrap_or(Path::new(demo::NAME)));
+    let _discord = chip8_core::discord::publish(&settings, &title, SystemClock.unix_seconds());
+    // Without a terminal to read keys from the ROM plays on without them.
+    let raw = RawMode::enable().ok();
+    let keys = raw.as_ref().map(|_| read_keys());
+    let mut held = [0u32; 16];
+    let frame = Duration::from_secs(1) / 60;
+    let mut next_frame = Instant::now();
+    while !cli::shutdown::requested() {
+        for (byte, _) in keys.iter().flat_map(|keys| keys.try_iter()) {
+            if byte == ESCAPE || byte == CTRL_C {
+                return;
+            }
+            if let Some(key) = keypad_key(byte) {
+                if held[key as usize] == 0 {
+                    emulator.keypad.press(key);
+                }
+                held[key as usize] = HOLD_FRAMES;
+            }
+        }
+        for (key, frames) in held.iter_mut().enumerate() {
+            if *frames > 0 {
+                *frames -= 1;
+                if *frames == 0 {
+                    emulator.keypad.release(key as u8);
+                }
+            }
+        }
+        let output = run_frame(&mut emulator);
+        if let Some(error) = output.error {
+            drop(raw);
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+        if output.display_changed {
+            let screen = TextStyle::Blocks.render_scaled(&emulator.display, Rotation::None, scale);
+            match &colors {
+                Some(colors) => print!("\x1b[H{}{}\x1b[0m", colors, screen),
+                None => print!("\x1b[H{}", screen),
+            }
+            let _ = io::stdout().flush();
+        }
+        next_frame += frame;
+        thread::sleep(next_frame.saturating_duration_since(Instant::now()));
+    }
+}
@@ -0,0 +1,66 @@
+use chip8_core::assembler::assemble;
+use std::path::PathBuf;
+use std::process::Command;
+use std::{env, fs, process};
+
+// An empty directory of its own under the system's temporary one.
+fn scratch(name: &str) -> PathBuf {
+    let directory = env::temp_dir().join(format!("chip8-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+fn write_rom(path: PathBuf, source: &str) {
+    fs::write(path, assemble(source).unwrap()).unwrap();
+}
+
+#[test]
+fn reports_every_rom_under_every_profile() {
+    let directory = scratch("compat");
+    // Draws a 5 and idles.
+    write_rom(
+        directory.join("digit.ch8"),
+        "LD V0, 5\nLD F, V0\nDRW V1, V1, 5\nJP 0x206",
+    );
+    write_rom(directory.join("idle.ch8"), "JP 0x200");
+    write_rom(directory.join("junk.ch8"), "DW 0xFFFF");
+    fs::write(directory.join("notes.txt"), "not a ROM").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_chip8"))
+        .args(["compat", directory.to_str().unwrap(), "--seconds", "1"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("rom,profile,frames,verdict,end_state,detail")
+    );
+    // rom, profile and verdict of every row.
+    let rows: Vec<(String, String, String)> = lines
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            (fields[0].into(), fields[1].into(), fields[3].into())
+        })
+        .collect();
+    let profiles = chip8_core::Quirks::profiles();
+    assert_eq!(rows.len(), 3 * profiles.len());
+    assert!(rows.iter().all(|(rom, _, _)| rom != "notes.txt"));
+    for (rom, verdict) in [
+        ("digit.ch8", "ok"),
+        ("idle.ch8", "blank screen"),
+        ("junk.ch8", "unknown opcode"),
+    ] {
+        let names: Vec<&str> = rows
+            .iter()
+            .filter(|row| row.0 == rom)
+            .inspect(|row| assert_eq!(row.2, verdict, "{} [{}]", rom, row.1))
+            .map(|row| row.1.as_str())
+            .collect();
+        let expected: Vec<&str> = profiles.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, expected);
+    }
+    fs::remove_dir_all(directory).unwrap();
+}
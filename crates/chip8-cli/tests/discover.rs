@@ -0,0 +1,70 @@
+use chip8_core::assembler::assemble;
+use chip8_core::rom::rom_hash;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::{env, fs, process};
+
+// An empty directory of its own under the system's temporary one.
+fn scratch(name: &str) -> PathBuf {
+    let directory = env::temp_dir().join(format!("chip8-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+// `chip8 discover` with its configs kept in `home`.
+fn discover(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_chip8"))
+        .arg("discover")
+        .args(args)
+        .args(["--seconds", "1"])
+        .env("CHIP8_RS_HOME", home)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn recommends_and_saves_a_profile() {
+    let directory = scratch("discover");
+    // Draws a 5 and idles.
+    let rom = assemble("LD V0, 5\nLD F, V0\nDRW V1, V1, 5\nJP 0x206").unwrap();
+    let path = directory.join("digit.ch8");
+    fs::write(&path, &rom).unwrap();
+
+    let output = discover(&directory, &[path.to_str().unwrap(), "--save"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let recommended = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("recommended: "))
+        .expect("no recommendation");
+    let config = directory
+        .join("roms")
+        .join(format!("{:016x}.cfg", rom_hash(&rom)));
+    let saved = fs::read_to_string(config).unwrap();
+    assert_eq!(saved, format!("quirks = {}\n", recommended));
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn files_that_are_not_roms_are_refused() {
+    let directory = scratch("discover-refused");
+    let big = directory.join("notes.txt");
+    fs::write(&big, vec![b'x'; 5000]).unwrap();
+    assert_eq!(
+        discover(&directory, &[big.to_str().unwrap()]).status.code(),
+        Some(1)
+    );
+    let missing = directory.join("missing.ch8");
+    let output = discover(&directory, &[missing.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot read"));
+
+    // Every combination crashes on it.
+    let junk = directory.join("junk.ch8");
+    fs::write(&junk, [0xFF, 0xFF]).unwrap();
+    let output = discover(&directory, &[junk.to_str().unwrap(), "--save"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!directory.join("roms").exists());
+    fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,127 @@
+use chip8_core::display::{Framebuffer, Resolution, MAX_HEIGHT, MAX_WIDTH};
+use chip8_core::{
+    execute, load_rom_to_memory, run_frame, Display, Emulator, EmulatorBuilder, Instruction,
+    Quirks, INITIAL_ADDRESS,
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+const PONG: &[u8] = include_bytes!("../../../roms/pong.ch8");
+
+fn new_emulator() -> Emulator {
+    EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .build()
+        .unwrap()
+}
+
+fn decode(c: &mut Criterion) {
+    c.bench_function("decode", |b| {
+        b.iter(|| {
+            for op_code in (0..=u16::MAX).step_by(257) {
+                black_box(Instruction::decode(black_box(op_code)));
+            }
+        })
+    });
+}
+
+fn arithmetic(c: &mut Criterion) {
+    let op_codes: Vec<_> = [
+        0x8010, 0x8011, 0x8012, 0x8013, 0x8014, 0x8015, 0x8016, 0x8017, 0x801E,
+    ]
+    .iter()
+    .map(|&op_code| Instruction::decode(op_code))
+    .collect();
+    let mut emulator = new_emulator();
+    emulator.v_registers[0] = 0xA5;
+    emulator.v_registers[1] = 0x3C;
+
+    c.bench_function("8xy_ group", |b| {
+        b.iter(|| {
+            for &instruction in op_codes.iter() {
+                execute(&mut emulator, black_box(instruction)).unwrap();
+            }
+        })
+    });
+}
+
+fn draw(c: &mut Criterion) {
+    let mut group = c.benchmark_group("DRW");
+    for height in [1, 5, 15] {
+        let mut emulator = new_emulator();
+        emulator.i_register = INITIAL_ADDRESS;
+        emulator.ram[INITIAL_ADDRESS as usize..INITIAL_ADDRESS as usize + 15].fill(0xAA);
+        emulator.v_registers[0] = 60;
+        emulator.v_registers[1] = 10;
+        let instruction = Instruction::decode(0xD010 | height);
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(height),
+            &instruction,
+            |b, &op| b.iter(|| execute(&mut emulator, black_box(op)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+// The per-pixel loop the packed blit replaced, kept as a baseline.
+fn draw_sprite_naive(
+    plane: &mut Framebuffer<MAX_WIDTH, MAX_HEIGHT>,
+    x: usize,
+    y: usize,
+    sprite: &[u8],
+) -> bool {
+    let mut collision = false;
+    for (row, byte) in sprite.iter().enumerate() {
+        let pixel_y = y % MAX_HEIGHT + row;
+        if pixel_y >= MAX_HEIGHT {
+            break;
+        }
+        for bit in 0..8 {
+            let pixel_x = x % MAX_WIDTH + bit;
+            if pixel_x >= MAX_WIDTH {
+                break;
+            }
+            if byte & (0x80 >> bit) != 0 {
+                let was_on = plane.get(pixel_x, pixel_y);
+                plane.set(pixel_x, pixel_y, !was_on);
+                collision |= was_on;
+            }
+        }
+    }
+    collision
+}
+
+fn sprite_blit(c: &mut Criterion) {
+    let sprite = [0xAA; 15];
+    let mut group = c.benchmark_group("sprite blit");
+    for height in [1, 5, 15] {
+        let mut plane = Framebuffer::new();
+        group.bench_with_input(BenchmarkId::new("naive", height), &height, |b, &height| {
+            b.iter(|| draw_sprite_naive(&mut plane, black_box(124), 10, &sprite[..height]))
+        });
+
+        let mut display = Display::new();
+        display.set_resolution(Resolution::Hires);
+        group.bench_with_input(BenchmarkId::new("packed", height), &height, |b, &height| {
+            b.iter(|| display.draw_sprite(black_box(124), 10, &sprite[..height], true))
+        });
+    }
+    group.finish();
+}
+
+fn pong_frame(c: &mut Criterion) {
+    let mut emulator = new_emulator();
+    load_rom_to_memory(&mut emulator, PONG).unwrap();
+
+    c.bench_function("pong first frame", |b| {
+        b.iter_batched(
+            || emulator.clone(),
+            |mut emulator| run_frame(&mut emulator),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, decode, arithmetic, draw, sprite_blit, pong_frame);
+criterion_main!(benches);
@@ -0,0 +1,123 @@
+use std::collections::BTreeSet;
+
+use crate::{Instruction, INITIAL_ADDRESS};
+
+// What static analysis of a ROM found out without running it: which
+// addresses hold reachable code, which ones are pointed at by `LD I` and
+// which jumps could not be followed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Analysis {
+    code: BTreeSet<u16>,
+    data: BTreeSet<u16>,
+    // Addresses of `JP V0, nnn`, whose targets depend on V0.
+    indirect_jumps: BTreeSet<u16>,
+}
+
+impl Analysis {
+    pub fn is_code(&self, address: u16) -> bool {
+        self.code.contains(&address)
+    }
+
+    // Start addresses of reachable instructions, in address order.
+    pub fn code(&self) -> impl Iterator<Item = u16> + '_ {
+        self.code.iter().copied()
+    }
+
+    pub(crate) fn code_set(&self) -> &BTreeSet<u16> {
+        &self.code
+    }
+
+    // Targets of `LD I, nnn` inside the ROM, usually sprites or tables.
+    pub fn data_references(&self) -> impl Iterator<Item = u16> + '_ {
+        self.data.iter().copied()
+    }
+
+    pub fn indirect_jumps(&self) -> impl Iterator<Item = u16> + '_ {
+        self.indirect_jumps.iter().copied()
+    }
+}
+
+// Follows every path from the entry point: jumps and calls to their
+// targets, skips to both of the next instructions, and stops at returns,
+// computed jumps, unknown opcodes and the end of the ROM. `rom` is the ROM
+// image, loaded at `INITIAL_ADDRESS`.
+pub fn analyze(rom: &[u8]) -> Analysis {
+    let (start, end) = bounds(rom);
+    let mut analysis = Analysis::default();
+    let mut pending = vec![INITIAL_ADDRESS];
+    while let Some(address) = pending.pop() {
+        if analysis.code.contains(&address) {
+            continue;
+        }
+        let Some(instruction) = decode(rom, address) else {
+            continue;
+        };
+        analysis.code.insert(address);
+        match instruction {
+            Instruction::JpV0 { .. } => {
+                analysis.indirect_jumps.insert(address);
+            }
+            Instruction::LdI { nnn } if (start..end).contains(&(nnn as usize)) => {
+                analysis.data.insert(nnn);
+            }
+            Instruction::LongI => {
+                if let Some(nnnn) = fetch(rom, address.wrapping_add(2))
+                    .filter(|&nnnn| (start..end).contains(&(nnnn as usize)))
+                {
+                    analysis.data.insert(nnnn);
+                }
+            }
+            _ => {}
+        }
+        pending.extend(successors(rom, address, instruction));
+    }
+    analysis
+}
+
+// Where the ROM sits in memory, start inclusive and end exclusive.
+fn bounds(rom: &[u8]) -> (usize, usize) {
+    let start = INITIAL_ADDRESS as usize;
+    (start, start + rom.len())
+}
+
+// The opcode at `address`, None outside the ROM.
+pub(crate) fn fetch(rom: &[u8], address: u16) -> Option<u16> {
+    let (start, end) = bounds(rom);
+    let offset = address as usize;
+    (offset >= start && offset + 1 < end).then(|| {
+        let offset = offset - start;
+        u16::from_be_bytes([rom[offset], rom[offset + 1]])
+    })
+}
+
+// The instruction at `address`, None outside the ROM or if unknown.
+pub(crate) fn decode(rom: &[u8], address: u16) -> Option<Instruction> {
+    match Instruction::decode(fetch(rom, address)?) {
+        Instruction::Unknown(_) => None,
+        instruction => Some(instruction),
+    }
+}
+
+// Where execution can go after `instruction` at `address`: both ways of a
+// skip, a call's target and the instruction it returns to, nowhere after a
+// return or a computed jump.
+pub(crate) fn successors(rom: &[u8], address: u16, instruction: Instruction) -> Vec<u16> {
+    let next = address.wrapping_add(2);
+    match instruction {
+        Instruction::Ret | Instruction::JpV0 { .. } => vec![],
+        Instruction::Jp { nnn } => vec![nnn],
+        Instruction::Call { nnn } => vec![nnn, next],
+        Instruction::SeByte { .. }
+        | Instruction::SneByte { .. }
+        | Instruction::SeReg { .. }
+        | Instruction::SneReg { .. }
+        | Instruction::Skp { .. }
+        | Instruction::Sknp { .. } => {
+            let skipped = fetch(rom, next).map_or(2, |op_code| Instruction::decode(op_code).size());
+            vec![next, next.wrapping_add(skipped)]
+        }
+        // The address after it is data.
+        Instruction::LongI => vec![address.wrapping_add(instruction.size())],
+        _ => vec![next],
+    }
+}
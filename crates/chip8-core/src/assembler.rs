@@ -0,0 +1,199 @@
+use std::fmt;
+
+use crate::dump::parse_hex_bytes;
+use crate::instruction::{self, Blame, INSTRUCTIONS};
+
+// The syntax `Instruction` parses, plus `DB` lines of bytes and `;`
+// comments. Leading addresses and op codes are skipped, so the output of
+// `disassemble` assembles back into the same ROM.
+//
+// A line that does not assemble does not stop the ones after it, the error
+// is a diagnostic for every such line, in order.
+pub fn assemble(source: &str) -> Result<Vec<u8>, Vec<Diagnostic>> {
+    let mut rom = Vec::new();
+    let mut diagnostics = Vec::new();
+    for (number, text) in source.lines().enumerate() {
+        match assemble_line(number + 1, text) {
+            Ok(bytes) => rom.extend(bytes),
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+    }
+    if diagnostics.is_empty() {
+        Ok(rom)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+// What is wrong with one line, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    // Counted from 1, columns in characters.
+    pub line: usize,
+    pub column: usize,
+    // The characters at fault, at least one.
+    pub len: usize,
+    pub message: String,
+    // A likely fix, such as the mnemonic a misspelt one is closest to.
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    // The diagnostic as a compiler prints it, the line of `source` under a
+    // `path:line:column` header with the span marked:
+    //
+    //     error: unknown mnemonic "LDD"
+    //      --> game.asm:3:5
+    //       |
+    //     3 |     LDD V0, 1
+    //       |     ^^^
+    //       = help: did you mean LD?
+    pub fn render(&self, path: &str, source: &str) -> String {
+        let text = source.lines().nth(self.line - 1).unwrap_or_default();
+        // Tabs before the span stay tabs, so the carets line up under it.
+        let indent: String = text
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let gutter = " ".repeat(self.line.to_string().len());
+        let mut out = format!(
+            "error: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self.message,
+            gutter,
+            path,
+            self.line,
+            self.column,
+            gutter,
+            self.line,
+            text,
+            gutter,
+            indent,
+            "^".repeat(self.len),
+        );
+        if let Some(help) = &self.help {
+            out.push_str(&format!("{} = help: {}\n", gutter, help));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if let Some(help) = &self.help {
+            write!(f, " ({})", help)?;
+        }
+        Ok(())
+    }
+}
+
+fn assemble_line(number: usize, text: &str) -> Result<Vec<u8>, Diagnostic> {
+    let code = text.split(';').next().unwrap_or_default();
+    let code = skip_listing_columns(code.trim());
+    if code.is_empty() {
+        return Ok(Vec::new());
+    }
+    // Every part is a slice of `text`, found again by where it starts.
+    let error = |part: &str, message: String, help: Option<String>| {
+        let start = part.as_ptr() as usize - text.as_ptr() as usize;
+        Diagnostic {
+            line: number,
+            column: text[..start].chars().count() + 1,
+            len: part.chars().count().max(1),
+            message,
+            help,
+        }
+    };
+    if let Some(bytes) = code.strip_prefix("DB ") {
+        let mut rom = Vec::new();
+        for token in bytes.split(|c: char| c.is_whitespace() || c == ',') {
+            let parsed = parse_hex_bytes(token).map_err(|message| {
+                let help = "DB takes hex bytes, such as DB F0 90 or DB F090";
+                error(token, message, Some(help.to_string()))
+            })?;
+            rom.extend(parsed);
+        }
+        return Ok(rom);
+    }
+    let parse_error = match instruction::parse(code) {
+        Ok(instruction) => return Ok(instruction.encode().to_be_bytes().to_vec()),
+        Err(parse_error) => parse_error,
+    };
+    let (mnemonic, rest) = code.split_once(char::is_whitespace).unwrap_or((code, ""));
+    let upper = mnemonic.to_ascii_uppercase();
+    Err(match parse_error.blame {
+        Blame::Mnemonic => error(
+            mnemonic,
+            parse_error.message,
+            suggest(&upper).map(|known| format!("did you mean {}?", known)),
+        ),
+        Blame::Operands => {
+            let forms: Vec<&str> = instruction::forms(&upper).collect();
+            let help = format!("{} takes {}", upper, forms.join("; or "));
+            let operands = rest.trim();
+            let part = if operands.is_empty() {
+                mnemonic
+            } else {
+                operands
+            };
+            error(part, parse_error.message, Some(help))
+        }
+        Blame::Operand(index) => {
+            let operand = rest.split(',').nth(index).unwrap_or(rest).trim();
+            let register = operand.len() > 1 && operand.starts_with(['V', 'v']);
+            let help = register.then(|| "registers are V0 to VF".to_string());
+            error(operand, parse_error.message, help)
+        }
+    })
+}
+
+// The known mnemonic closest to `mnemonic`, if one is close enough to be a
+// typo of it.
+fn suggest(mnemonic: &str) -> Option<&'static str> {
+    let known = INSTRUCTIONS
+        .iter()
+        .filter_map(|info| info.mnemonic.split(' ').next())
+        .chain(["DB", "DW"]);
+    let (distance, closest) = known
+        .map(|known| (edit_distance(mnemonic, known), known))
+        .min_by_key(|&(distance, _)| distance)?;
+    // One mistake in three letters, two in six.
+    (distance * 3 <= mnemonic.len().max(3)).then_some(closest)
+}
+
+// Levenshtein distance, the characters inserted, deleted or replaced to
+// turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let replaced = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn skip_listing_columns(line: &str) -> &str {
+    let mut rest = line;
+    if let Some((first, after)) = rest.split_once(char::is_whitespace) {
+        if first.starts_with("0x") {
+            rest = after.trim_start();
+        }
+    }
+    if let Some((first, after)) = rest.split_once(char::is_whitespace) {
+        if first.len() == 4 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            rest = after.trim_start();
+        }
+    }
+    rest
+}
@@ -0,0 +1,281 @@
+use std::io::{self, Write};
+
+use crate::events::Event;
+use crate::xochip::{pattern_bit, playback_rate, PATTERN_BITS, PATTERN_SIZE};
+
+pub const DEFAULT_FREQUENCY: f32 = 440.0;
+pub const DEFAULT_VOLUME: f32 = 0.25;
+// Long enough to avoid clicks, short enough to still sound like a beep.
+const RAMP_SECONDS: f32 = 0.005;
+// A frame long beep, from the sound timer set to 2, is barely more than a
+// click. Frontends can stretch beeps to this with `set_min_duration`.
+pub const AUDIBLE_BEEP_SECONDS: f32 = 0.05;
+
+// What the beeper does while the game runs below full speed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowMotion {
+    // The tone keeps its pitch, beeps last as long as the slowed down game
+    // makes them.
+    #[default]
+    KeepPitch,
+    // Beeps fade out until the game is back to full speed.
+    Mute,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeeperState {
+    Silent,
+    FadingIn,
+    Sounding,
+    FadingOut,
+}
+
+// Square wave generator driven by the sound timer. Instead of switching the
+// tone on and off, the gain ramps towards its target, so starting, stopping,
+// pausing and single stepping never cut the waveform mid-cycle.
+#[derive(Debug, Clone)]
+pub struct Beeper {
+    sample_rate: f32,
+    // Samples generated per sample of emulated time, see `set_resample`.
+    resample: f32,
+    // Emulated seconds per second, see `set_speed`.
+    speed: f32,
+    slow_motion: SlowMotion,
+    frequency: f32,
+    volume: f32,
+    phase: f32,
+    gain: f32,
+    beeping: bool,
+    suspended: bool,
+    min_samples: u32,
+    // Samples left before a short beep may stop.
+    hold: u32,
+    // Samples left before the beep stops by itself, from `set_time_left`.
+    stop_in: Option<u32>,
+}
+
+impl Beeper {
+    pub fn new(sample_rate: u32) -> Self {
+        Beeper {
+            sample_rate: sample_rate as f32,
+            resample: 1.0,
+            speed: 1.0,
+            slow_motion: SlowMotion::default(),
+            frequency: DEFAULT_FREQUENCY,
+            volume: DEFAULT_VOLUME,
+            phase: 0.0,
+            gain: 0.0,
+            beeping: false,
+            suspended: false,
+            min_samples: 0,
+            hold: 0,
+            stop_in: None,
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    // Stretches (above 1) or squeezes the audio, for `sync::Correction`.
+    // The tone is synthesized, so no actual resampling is needed: it is
+    // generated for a slightly different sample rate. That shifts the
+    // pitch too, which is inaudible for the small corrections of `sync`
+    // but not for slow motion, that is `set_speed`.
+    pub fn set_resample(&mut self, ratio: f32) {
+        self.resample = ratio;
+    }
+
+    // The game's speed, 50 for half speed. Sound timer time becomes that
+    // much more or less of real time, the pitch stays, or below 100 the
+    // beeper goes quiet with `SlowMotion::Mute`.
+    pub fn set_speed(&mut self, percent: u32) {
+        self.speed = percent.max(1) as f32 / 100.0;
+    }
+
+    pub fn set_slow_motion(&mut self, slow_motion: SlowMotion) {
+        self.slow_motion = slow_motion;
+    }
+
+    // Follows `Event::SpeedChanged`, so whoever changes the speed only has
+    // to say so on the event bus.
+    pub fn handle(&mut self, event: &Event) {
+        if let Event::SpeedChanged { percent } = event {
+            self.set_speed(*percent);
+        }
+    }
+
+    // Every beep lasts at least this long, even when the sound timer runs
+    // out sooner. 0 plays beeps exactly as long as the timer.
+    pub fn set_min_duration(&mut self, seconds: f32) {
+        self.min_samples = (seconds.max(0.0) * self.rate()) as u32;
+    }
+
+    // Mirrors the sound timer, call it once per frame.
+    pub fn set_beeping(&mut self, beeping: bool) {
+        if beeping && !self.beeping {
+            self.hold = self.min_samples;
+        }
+        self.beeping = beeping;
+        self.stop_in = None;
+    }
+
+    // Like `set_beeping`, from `emulator::sound_time_left`: the beep stops
+    // on its own after that many seconds of emulated time, so the release
+    // starts at the right sample instead of at the next frame.
+    pub fn set_time_left(&mut self, seconds: f32) {
+        let samples = (seconds.max(0.0) / self.speed * self.rate()).round() as u32;
+        self.set_beeping(samples > 0);
+        if samples > 0 {
+            self.stop_in = Some(samples);
+        }
+    }
+
+    // While paused, single stepping or rewinding the tone fades out and
+    // keeps its phase, so `resume` picks it up without a pop.
+    pub fn suspend(&mut self) {
+        self.suspended = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.suspended = false;
+    }
+
+    pub fn state(&self) -> BeeperState {
+        let target = self.target_gain();
+        if self.gain == target {
+            if target == 0.0 {
+                BeeperState::Silent
+            } else {
+                BeeperState::Sounding
+            }
+        } else if self.gain < target {
+            BeeperState::FadingIn
+        } else {
+            BeeperState::FadingOut
+        }
+    }
+
+    // Fills an audio callback buffer with mono samples in [-1, 1].
+    pub fn fill(&mut self, samples: &mut [f32]) {
+        let ramp_step = 1.0 / (RAMP_SECONDS * self.rate());
+        let phase_step = self.frequency / self.rate();
+        for sample in samples.iter_mut() {
+            if !self.suspended {
+                self.hold = self.hold.saturating_sub(1);
+                if let Some(left) = &mut self.stop_in {
+                    *left -= 1;
+                    if *left == 0 {
+                        self.beeping = false;
+                        self.stop_in = None;
+                    }
+                }
+            }
+            let target = self.target_gain();
+            if self.gain < target {
+                self.gain = (self.gain + ramp_step * self.volume).min(target);
+            } else if self.gain > target {
+                self.gain = (self.gain - ramp_step * self.volume).max(target);
+            }
+
+            if self.gain == 0.0 {
+                // Start the next beep on a wave edge.
+                self.phase = 0.0;
+                *sample = 0.0;
+                continue;
+            }
+            let level = if self.phase < 0.5 { 1.0 } else { -1.0 };
+            *sample = level * self.gain;
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+
+    fn rate(&self) -> f32 {
+        self.sample_rate * self.resample
+    }
+
+    fn target_gain(&self) -> f32 {
+        let muted = self.slow_motion == SlowMotion::Mute && self.speed < 1.0;
+        if (self.beeping || self.hold > 0) && !self.suspended && !muted {
+            self.volume
+        } else {
+            0.0
+        }
+    }
+}
+
+// XO-CHIP's pattern playback: the 128 bit pattern looped at the pitch's
+// bit rate, a set bit is +volume and a clear one -volume.
+#[derive(Debug, Clone)]
+pub struct PatternVoice {
+    sample_rate: f32,
+    volume: f32,
+    // Position in the pattern, in bits.
+    position: f32,
+}
+
+impl PatternVoice {
+    pub fn new(sample_rate: u32) -> Self {
+        PatternVoice {
+            sample_rate: sample_rate as f32,
+            volume: DEFAULT_VOLUME,
+            position: 0.0,
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn fill(&mut self, samples: &mut [f32], pattern: &[u8; PATTERN_SIZE], pitch: u8) {
+        let step = playback_rate(pitch) / self.sample_rate;
+        for sample in samples.iter_mut() {
+            let on = pattern_bit(pattern, self.position as usize);
+            *sample = if on { self.volume } else { -self.volume };
+            self.position = (self.position + step) % PATTERN_BITS as f32;
+        }
+    }
+}
+
+// A mono 16 bit PCM WAV file of samples in [-1, 1].
+pub fn write_wav(writer: &mut impl Write, sample_rate: u32, samples: &[f32]) -> io::Result<()> {
+    write_wav_header(writer, sample_rate, Some(samples.len()))?;
+    write_samples(writer, samples)
+}
+
+// The header alone, for streaming the samples after it. Without a length
+// the sizes are left at their maximum, which players read as "until the
+// end of the stream".
+pub fn write_wav_header(
+    writer: &mut impl Write,
+    sample_rate: u32,
+    samples: Option<usize>,
+) -> io::Result<()> {
+    let data_len = samples.map_or(u32::MAX - 36, |samples| samples as u32 * 2);
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_len).to_le_bytes())?;
+    writer.write_all(b"WAVEfmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    // PCM, one channel.
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * 2).to_le_bytes())?;
+    // Bytes per frame and bits per sample.
+    writer.write_all(&2u16.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+    writer.write_all(b"data")?;
+    writer.write_all(&data_len.to_le_bytes())
+}
+
+pub fn write_samples(writer: &mut impl Write, samples: &[f32]) -> io::Result<()> {
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::hooks::ExecutionHook;
+use crate::labels::Labels;
+use crate::{Emulator, EmulatorError, Instruction};
+
+// How execution got where it is: the last jumps, calls, returns and skips
+// taken, for a debugger to show next to a crash. Everything else falls
+// through to the next instruction and is left out.
+
+// One instruction that sent PC somewhere other than the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Branch {
+    pub from: u16,
+    pub to: u16,
+    pub op_code: u16,
+}
+
+// "0x21a  2360  CALL 0x360  -> 0x360"
+impl fmt::Display for Branch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:#05x}  {:04X}  {:<15} -> {:#05x}",
+            self.from,
+            self.op_code,
+            Instruction::decode(self.op_code).to_string(),
+            self.to
+        )
+    }
+}
+
+// What a `BranchHistory` hook saw, readable while the hook is on the
+// emulator.
+#[derive(Debug, Clone, Default)]
+pub struct Branches(Arc<Mutex<VecDeque<Branch>>>);
+
+impl Branches {
+    // Oldest first.
+    pub fn list(&self) -> Vec<Branch> {
+        self.0.lock().unwrap().iter().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    // One line per branch, the latest at the bottom, with the labels of
+    // the addresses that have one.
+    pub fn panel(&self, labels: &Labels) -> String {
+        let mut text = String::new();
+        for branch in self.list() {
+            let names: Vec<String> = [("from", branch.from), ("to", branch.to)]
+                .iter()
+                .filter_map(|&(way, address)| Some(format!("{} {}", way, labels.name(address)?)))
+                .collect();
+            match names.is_empty() {
+                true => text.push_str(&format!("{}\n", branch)),
+                false => text.push_str(&format!("{}  ; {}\n", branch, names.join(", "))),
+            }
+        }
+        text
+    }
+}
+
+// An execution hook keeping the last `capacity` branches:
+//
+//     let history = BranchHistory::new(32);
+//     let branches = history.branches();
+//     emulator.hooks.add(history);
+pub struct BranchHistory {
+    capacity: usize,
+    branches: Branches,
+    // The instruction being executed, by its address.
+    from: u16,
+}
+
+impl BranchHistory {
+    pub fn new(capacity: usize) -> Self {
+        BranchHistory {
+            capacity,
+            branches: Branches::default(),
+            from: 0,
+        }
+    }
+
+    pub fn branches(&self) -> Branches {
+        self.branches.clone()
+    }
+}
+
+impl ExecutionHook for BranchHistory {
+    fn before_step(&mut self, emulator: &mut Emulator, _instruction: Instruction) {
+        self.from = emulator.program_counter.wrapping_sub(2);
+    }
+
+    fn after_step(
+        &mut self,
+        emulator: &mut Emulator,
+        instruction: Instruction,
+        _result: &Result<(), EmulatorError>,
+    ) {
+        let to = emulator.program_counter;
+        // Running again, as Fx0A waiting for a key does, is not a branch
+        // either.
+        let next = self.from.wrapping_add(instruction.size());
+        if to == self.from || to == next || self.capacity == 0 {
+            return;
+        }
+        let mut branches = self.branches.0.lock().unwrap();
+        if branches.len() == self.capacity {
+            branches.pop_front();
+        }
+        branches.push_back(Branch {
+            from: self.from,
+            to,
+            op_code: instruction.encode(),
+        });
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::heatmap::{memory_access, Access, AccessKind};
+use crate::hooks::ExecutionHook;
+use crate::{Emulator, EmulatorError, Instruction};
+
+// Breaking on a range of memory rather than on one address: every
+// instruction reading or writing any of it through I, Dxyn, Fx33, Fx55 and
+// Fx65, is a hit. Pointed at a data table it finds all the code using it,
+// however I got there.
+
+// Addresses from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl AddressRange {
+    pub fn contains(&self, address: usize) -> bool {
+        (self.start as usize..=self.end as usize).contains(&address)
+    }
+}
+
+impl fmt::Display for AddressRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#05x}-{:#05x}", self.start, self.end)
+    }
+}
+
+// `0x300-0x30F`, or `0x300` for the one address.
+impl FromStr for AddressRange {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let address = |text: &str| {
+            let digits = text
+                .trim()
+                .trim_start_matches("0x")
+                .trim_start_matches("0X");
+            u16::from_str_radix(digits, 16)
+                .map_err(|_| format!("{:?} is not a hex address", text.trim()))
+        };
+        let (start, end) = match text.split_once('-') {
+            Some((start, end)) => (address(start)?, address(end)?),
+            None => (address(text)?, address(text)?),
+        };
+        if end < start {
+            return Err(format!("{} ends before it starts", text.trim()));
+        }
+        Ok(AddressRange { start, end })
+    }
+}
+
+// One instruction that touched the range, by its address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    pub address: u16,
+    pub op_code: u16,
+    // What it touched the first time, the whole access and not only the
+    // part in the range.
+    pub access: Access,
+    // Times it did.
+    pub count: u32,
+}
+
+impl fmt::Display for Hit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let verb = match self.access.kind {
+            AccessKind::Read => "reads",
+            AccessKind::Write => "writes",
+        };
+        let last = self.access.start.wrapping_add(self.access.len.max(1) - 1);
+        write!(
+            f,
+            "{:#05x} ({:04X}): {} {:#05x}-{:#05x}",
+            self.address, self.op_code, verb, self.access.start, last
+        )?;
+        if self.count > 1 {
+            write!(f, ", {} times", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+// What an `AccessBreakpoint` hit, readable while the hook is on the
+// emulator.
+#[derive(Debug, Clone, Default)]
+pub struct Hits(Arc<Mutex<Vec<Hit>>>);
+
+impl Hits {
+    // In the order first hit.
+    pub fn list(&self) -> Vec<Hit> {
+        self.0.lock().unwrap().clone()
+    }
+
+    // Instructions hit so far, each counted once however often.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // The latest hit, as the error that stops a run breaking on them.
+    pub fn trap(&self) -> Option<EmulatorError> {
+        let last = *self.0.lock().unwrap().last()?;
+        Some(EmulatorError::Breakpoint(last.to_string()))
+    }
+
+    fn record(&self, address: u16, op_code: u16, access: Access) {
+        let mut hits = self.0.lock().unwrap();
+        match hits.iter_mut().find(|hit| hit.address == address) {
+            Some(hit) => hit.count += 1,
+            None => hits.push(Hit {
+                address,
+                op_code,
+                access,
+                count: 1,
+            }),
+        }
+    }
+}
+
+// An execution hook noting every instruction that accesses `range`:
+//
+//     let breakpoint = AccessBreakpoint::new("0x300-0x30F".parse()?);
+//     let hits = breakpoint.hits();
+//     emulator.hooks.add(breakpoint);
+pub struct AccessBreakpoint {
+    range: AddressRange,
+    hits: Hits,
+}
+
+impl AccessBreakpoint {
+    pub fn new(range: AddressRange) -> Self {
+        AccessBreakpoint {
+            range,
+            hits: Hits::default(),
+        }
+    }
+
+    pub fn hits(&self) -> Hits {
+        self.hits.clone()
+    }
+}
+
+impl ExecutionHook for AccessBreakpoint {
+    fn before_step(&mut self, emulator: &mut Emulator, instruction: Instruction) {
+        let Some(access) = memory_access(emulator, instruction) else {
+            return;
+        };
+        if access
+            .addresses()
+            .any(|address| self.range.contains(address))
+        {
+            let address = emulator.program_counter.wrapping_sub(2);
+            self.hits.record(address, instruction.encode(), access);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use crate::chip8x::Chip8x;
+use crate::clock::{Clock, FRAMES_PER_SECOND};
+use crate::disk::Disk;
+use crate::display::Display;
+use crate::font::Font;
+use crate::hooks::Hooks;
+use crate::instruction::Variant;
+use crate::keypad::Keypad;
+use crate::printer::Printer;
+use crate::wallclock::WallClock;
+use crate::xochip::XoChip;
+use crate::{
+    load_rom_at, Emulator, EmulatorError, Quirks, Rng, CYCLES_PER_FRAME, RAM_SIZE, STACK_SIZE,
+    V_REGISTERS_NUMBER, XOCHIP_RAM_SIZE,
+};
+
+// Builds an `Emulator` with everything checked up front, instead of a struct
+// literal that has to list every register:
+//
+//     let emulator = EmulatorBuilder::new()
+//         .variant(Variant::Schip)
+//         .seed(42)
+//         .rom(&data)
+//         .build()?;
+#[derive(Debug, Clone)]
+pub struct EmulatorBuilder {
+    variant: Variant,
+    quirks: Option<Quirks>,
+    seed: Option<u64>,
+    cycles_per_frame: usize,
+    clock_hz: Option<u32>,
+    stack_limit: usize,
+    ram_size: Option<usize>,
+    rom: Option<Vec<u8>>,
+    memory: Vec<(u16, Vec<u8>)>,
+    registers: Vec<(usize, u8)>,
+    i_register: u16,
+    program_counter: Option<u16>,
+    font: Font,
+    printer: bool,
+    wall_clock: Option<i32>,
+    disk: Option<Disk>,
+}
+
+impl Default for EmulatorBuilder {
+    fn default() -> Self {
+        EmulatorBuilder {
+            variant: Variant::Chip8,
+            quirks: None,
+            seed: None,
+            cycles_per_frame: CYCLES_PER_FRAME,
+            clock_hz: None,
+            stack_limit: STACK_SIZE,
+            ram_size: None,
+            rom: None,
+            memory: Vec::new(),
+            registers: Vec::new(),
+            i_register: 0,
+            program_counter: None,
+            font: Font::default(),
+            printer: false,
+            wall_clock: None,
+            disk: None,
+        }
+    }
+}
+
+impl EmulatorBuilder {
+    pub fn new() -> Self {
+        EmulatorBuilder::default()
+    }
+
+    // Picks the quirks of the variant, unless `quirks` is also given, its
+    // screen and where the ROM goes. The ETI-660 interpreter is a port of
+    // the COSMAC VIP one and shares its quirks, so does CHIP-8X.
+    pub fn variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn quirks(mut self, quirks: Quirks) -> Self {
+        self.quirks = Some(quirks);
+        self
+    }
+
+    // Makes Cxkk repeatable. Without a seed it is random with the `rand`
+    // feature and seed 0 without.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn cycles_per_frame(mut self, cycles: usize) -> Self {
+        self.cycles_per_frame = cycles;
+        self.clock_hz = None;
+        self
+    }
+
+    // Instructions per second, exactly, spread over the frames by the
+    // emulator's `Clock`. `cycles_per_frame` is the nearest whole number,
+    // at least 1, e.g. for how long keys stay down.
+    pub fn clock_hz(mut self, hz: u32) -> Self {
+        self.cycles_per_frame = ((hz + FRAMES_PER_SECOND / 2) / FRAMES_PER_SECOND).max(1) as usize;
+        self.clock_hz = Some(hz);
+        self
+    }
+
+    // Nesting depth of subroutine calls before a stack overflow, at most
+    // `STACK_SIZE`. The COSMAC VIP only had room for 12.
+    pub fn stack_limit(mut self, limit: usize) -> Self {
+        self.stack_limit = limit;
+        self
+    }
+
+    // Bytes of memory instead of the variant's, a power of two from
+    // `RAM_SIZE` to `XOCHIP_RAM_SIZE` so addresses wrap by masking. E.g. a
+    // CHIP-8 machine with XO-CHIP's 64K for a ROM too big for 4K.
+    pub fn ram_size(mut self, bytes: usize) -> Self {
+        self.ram_size = Some(bytes);
+        self
+    }
+
+    // Replaces the CHIP-48 font in the interpreter area.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    // Enables FxF0, see `printer`, on any variant.
+    pub fn printer(mut self, enabled: bool) -> Self {
+        self.printer = enabled;
+        self
+    }
+
+    // Enables FxF1, see `wallclock`, with the time of day that many
+    // seconds ahead of UTC. It reads midnight 1970 until synced.
+    pub fn wall_clock(mut self, utc_offset: i32) -> Self {
+        self.wall_clock = Some(utc_offset);
+        self
+    }
+
+    // Enables FxF2 and FxF3, see `disk`, on this image, e.g. the one
+    // `Disk::load` found for the ROM.
+    pub fn disk(mut self, disk: Disk) -> Self {
+        self.disk = Some(disk);
+        self
+    }
+
+    // Loaded at the variant's start address, `INITIAL_ADDRESS` for all but
+    // the ETI-660.
+    pub fn rom(mut self, data: &[u8]) -> Self {
+        self.rom = Some(data.to_vec());
+        self
+    }
+
+    // Written after the ROM, so it can patch it.
+    pub fn memory(mut self, address: u16, bytes: &[u8]) -> Self {
+        self.memory.push((address, bytes.to_vec()));
+        self
+    }
+
+    pub fn register(mut self, x: usize, value: u8) -> Self {
+        self.registers.push((x, value));
+        self
+    }
+
+    pub fn i_register(mut self, i: u16) -> Self {
+        self.i_register = i;
+        self
+    }
+
+    pub fn program_counter(mut self, address: u16) -> Self {
+        self.program_counter = Some(address);
+        self
+    }
+
+    pub fn build(self) -> Result<Emulator, EmulatorError> {
+        let invalid = |message: String| Err(EmulatorError::InvalidConfig(message));
+        if self.cycles_per_frame == 0 {
+            return invalid("the clock must run at least one instruction per frame".to_string());
+        }
+        if self.clock_hz == Some(0) {
+            return invalid("the clock must run at least one instruction a second".to_string());
+        }
+        if !(1..=STACK_SIZE).contains(&self.stack_limit) {
+            return invalid(format!("stack limit must be between 1 and {}", STACK_SIZE));
+        }
+        let ram_size = self.ram_size.unwrap_or(self.variant.ram_size());
+        if !ram_size.is_power_of_two() || !(RAM_SIZE..=XOCHIP_RAM_SIZE).contains(&ram_size) {
+            return invalid(format!(
+                "memory must be a power of two from {} to {} bytes",
+                RAM_SIZE, XOCHIP_RAM_SIZE
+            ));
+        }
+        let program_counter = self.program_counter.unwrap_or(self.variant.start_address());
+        if program_counter as usize >= ram_size {
+            return invalid(format!("PC {:#x} is outside memory", program_counter));
+        }
+        if self.i_register as usize >= ram_size {
+            return invalid(format!("I {:#x} is outside memory", self.i_register));
+        }
+
+        let quirks = self.quirks.unwrap_or(match self.variant {
+            Variant::Chip8 | Variant::Eti660 | Variant::Chip8x => Quirks::chip8(),
+            Variant::Schip => Quirks::schip(),
+            Variant::XoChip => Quirks::xo_chip(),
+        });
+        let rng = match self.seed {
+            Some(seed) => Rng::new(seed),
+            None => default_rng(),
+        };
+        let mut emulator = Emulator {
+            v_registers: [0; V_REGISTERS_NUMBER],
+            i_register: self.i_register,
+            program_counter,
+            stack_pointer: 0,
+            stack: [0; STACK_SIZE],
+            stack_limit: self.stack_limit,
+            delay_timer_registry: 0,
+            sound_timer_registry: 0,
+            ram: vec![0; ram_size],
+            display: Display::with_resolution(self.variant.resolution()),
+            keypad: Keypad::new(),
+            quirks,
+            rng,
+            cycles_per_frame: self.cycles_per_frame,
+            clock: self.clock_hz.map_or_else(Clock::default, Clock::new),
+            vblank: false,
+            timer_cycles: 0,
+            chip8x: (self.variant == Variant::Chip8x).then(Chip8x::new),
+            xochip: (self.variant == Variant::XoChip).then(XoChip::new),
+            printer: self.printer.then(Printer::new),
+            wall_clock: self.wall_clock.map(WallClock::new),
+            disk: self.disk,
+            hooks: Hooks::default(),
+        };
+        self.font.write_to(&mut emulator.ram);
+        if let Some(rom) = &self.rom {
+            load_rom_at(&mut emulator, self.variant.start_address(), rom)?;
+        }
+        for (address, bytes) in &self.memory {
+            let start = *address as usize;
+            if start + bytes.len() > ram_size {
+                return invalid(format!(
+                    "{} bytes at {:#x} do not fit in memory",
+                    bytes.len(),
+                    address
+                ));
+            }
+            emulator.ram[start..start + bytes.len()].copy_from_slice(bytes);
+        }
+        for &(x, value) in &self.registers {
+            if x >= V_REGISTERS_NUMBER {
+                return invalid(format!("there is no register V{:X}", x));
+            }
+            emulator.v_registers[x] = value;
+        }
+        Ok(emulator)
+    }
+}
+
+#[cfg(feature = "rand")]
+fn default_rng() -> Rng {
+    Rng::from_entropy()
+}
+
+#[cfg(not(feature = "rand"))]
+fn default_rng() -> Rng {
+    Rng::default()
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+// Anything faster is far past every real interpreter.
+pub const MAX_CYCLES_PER_FRAME: usize = 1000;
+const FRAMES_PER_SECOND: usize = 60;
+
+// Finding a game's speed by playing it: the instructions per frame go up
+// or down a step at a time, about a tenth, until it feels right, then the
+// frontend saves the result as the ROM's `cycles_per_frame`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    start: usize,
+    cycles: usize,
+}
+
+impl Calibration {
+    pub fn new(cycles_per_frame: usize) -> Self {
+        let cycles = cycles_per_frame.clamp(1, MAX_CYCLES_PER_FRAME);
+        Calibration {
+            start: cycles,
+            cycles,
+        }
+    }
+
+    pub fn cycles_per_frame(&self) -> usize {
+        self.cycles
+    }
+
+    pub fn faster(&mut self) -> usize {
+        self.cycles = next(self.cycles);
+        self.cycles
+    }
+
+    // Back to the value `faster` would have come from, so the two undo
+    // each other.
+    pub fn slower(&mut self) -> usize {
+        self.cycles = (1..self.cycles)
+            .rev()
+            .find(|&value| next(value) <= self.cycles)
+            .unwrap_or(1);
+        self.cycles
+    }
+
+    // Whether there is anything to save.
+    pub fn changed(&self) -> bool {
+        self.cycles != self.start
+    }
+}
+
+fn next(cycles: usize) -> usize {
+    (cycles + (cycles / 10).max(1)).min(MAX_CYCLES_PER_FRAME)
+}
+
+// "15 instructions per frame (900 Hz)".
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} instructions per frame ({} Hz)",
+            self.cycles,
+            self.cycles * FRAMES_PER_SECOND
+        )
+    }
+}
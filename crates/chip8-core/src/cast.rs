@@ -0,0 +1,32 @@
+use std::io::{self, Write};
+
+use crate::narration::json_string;
+
+// Writes an asciinema v2 recording: a JSON header line, then one
+// `[seconds, "o", text]` line per chunk of terminal output. `asciinema
+// play`, termtosvg and the web player all take these.
+pub struct CastWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CastWriter<W> {
+    // `timestamp` is when it was recorded, in Unix seconds.
+    pub fn new(mut writer: W, columns: usize, lines: usize, timestamp: u64) -> io::Result<Self> {
+        writeln!(
+            writer,
+            "{{\"version\":2,\"width\":{},\"height\":{},\"timestamp\":{},\"env\":{{\"TERM\":\"xterm-256color\"}}}}",
+            columns, lines, timestamp
+        )?;
+        Ok(CastWriter { writer })
+    }
+
+    // Output `seconds` after the start of the recording.
+    pub fn output(&mut self, seconds: f64, text: &str) -> io::Result<()> {
+        writeln!(self.writer, "[{:.6},\"o\",{}]", seconds, json_string(text))
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::Emulator;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+// FNV-1a as a `Hasher`. Integers are fed little endian and `usize` as 64
+// bits, so the same state hashes the same on every host and every build.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    pub fn new() -> Self {
+        StableHasher(FNV_OFFSET)
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+// Checksum of the whole machine: registers, stack, timers, memory, screen
+// and quirks.
+pub fn state_checksum(emulator: &Emulator) -> u64 {
+    let mut hasher = StableHasher::new();
+    emulator.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Desync {
+    pub frame: u64,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl fmt::Display for Desync {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "state desync at frame {}: expected checksum {:016x}, found {:016x}",
+            self.frame, self.expected, self.actual
+        )
+    }
+}
+
+// Checksums taken every `interval` frames while recording a replay, or
+// received from a netplay peer. Checking a frame against it pinpoints where
+// two runs stopped matching instead of letting them drift apart silently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumLog {
+    interval: u64,
+    checksums: BTreeMap<u64, u64>,
+}
+
+impl ChecksumLog {
+    pub fn new(interval: u64) -> Self {
+        ChecksumLog {
+            interval: interval.max(1),
+            checksums: BTreeMap::new(),
+        }
+    }
+
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    pub fn len(&self) -> usize {
+        self.checksums.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checksums.is_empty()
+    }
+
+    // Call after every frame, only frames on the interval are kept.
+    pub fn record(&mut self, frame: u64, emulator: &Emulator) {
+        if frame.is_multiple_of(self.interval) {
+            self.insert(frame, state_checksum(emulator));
+        }
+    }
+
+    // For checksums computed elsewhere, e.g. sent by a peer.
+    pub fn insert(&mut self, frame: u64, checksum: u64) {
+        self.checksums.insert(frame, checksum);
+    }
+
+    pub fn get(&self, frame: u64) -> Option<u64> {
+        self.checksums.get(&frame).copied()
+    }
+
+    // Compares this frame with the recorded checksum, frames without one
+    // always pass.
+    pub fn check(&self, frame: u64, emulator: &Emulator) -> Result<(), Desync> {
+        self.check_checksum(frame, state_checksum(emulator))
+    }
+
+    pub fn check_checksum(&self, frame: u64, actual: u64) -> Result<(), Desync> {
+        match self.get(frame) {
+            Some(expected) if expected != actual => Err(Desync {
+                frame,
+                expected,
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    // "interval N" followed by one "frame checksum" line per entry.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("interval {}\n", self.interval);
+        for (frame, checksum) in &self.checksums {
+            text.push_str(&format!("{} {:016x}\n", frame, checksum));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let interval = match lines.next() {
+            Some((_, line)) => line
+                .strip_prefix("interval ")
+                .and_then(|value| value.trim().parse().ok())
+                .ok_or_else(|| format!("line 1: expected \"interval N\", found {:?}", line))?,
+            None => return Err("empty checksum log".to_string()),
+        };
+        let mut log = ChecksumLog::new(interval);
+        for (number, line) in lines {
+            let entry = line.split_once(' ').and_then(|(frame, checksum)| {
+                Some((
+                    frame.parse().ok()?,
+                    u64::from_str_radix(checksum.trim(), 16).ok()?,
+                ))
+            });
+            let (frame, checksum) = entry.ok_or_else(|| {
+                format!(
+                    "line {}: expected \"frame checksum\", found {:?}",
+                    number, line
+                )
+            })?;
+            log.insert(frame, checksum);
+        }
+        Ok(log)
+    }
+}
+
+// The frame counter and code of a verification overlay, like speedrun
+// streams show. Each frame's code is the previous one hashed with the
+// machine's state, so it depends on every frame before it: replaying the
+// run's inputs shows the same codes frame for frame, and a video spliced
+// from two runs shows codes no replay can produce past the splice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameChain {
+    frame: u64,
+    code: u64,
+}
+
+impl FrameChain {
+    pub fn new() -> Self {
+        FrameChain::default()
+    }
+
+    // Call after every frame.
+    pub fn advance(&mut self, emulator: &Emulator) {
+        let mut hasher = StableHasher::new();
+        hasher.write_u64(self.code);
+        hasher.write_u64(state_checksum(emulator));
+        self.code = hasher.finish();
+        self.frame += 1;
+    }
+
+    // Frames since power on, or since the run started from a savestate.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    // Short enough to read off a video, folded to 32 bits.
+    pub fn code(&self) -> u32 {
+        (self.code ^ self.code >> 32) as u32
+    }
+}
+
+// "frame 1234 a1b2c3d4"
+impl fmt::Display for FrameChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "frame {} {:08x}", self.frame, self.code())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{step, tick_timers, Display, Emulator, EmulatorBuilder, EmulatorError};
+
+// A machine for frontends embedding the core that only need to load ROMs,
+// run them and show the screen. It wraps `EmulatorBuilder` and the `step`
+// and `tick_timers` functions, `emulator` reaches everything else.
+#[derive(Debug, Clone)]
+pub struct Chip8 {
+    builder: EmulatorBuilder,
+    emulator: Emulator,
+}
+
+impl Chip8 {
+    // The builder picks the machine, kept for every ROM loaded after.
+    pub fn new(builder: EmulatorBuilder) -> Result<Self, EmulatorError> {
+        let emulator = builder.clone().build()?;
+        Ok(Chip8 { builder, emulator })
+    }
+
+    // Starts the ROM on a fresh machine, the current one is left as it was
+    // if the ROM does not fit.
+    pub fn load_rom(&mut self, rom: &[u8]) -> Result<(), EmulatorError> {
+        self.emulator = self.builder.clone().rom(rom).build()?;
+        Ok(())
+    }
+
+    pub fn step(&mut self) -> Result<(), EmulatorError> {
+        step(&mut self.emulator)
+    }
+
+    // Once every 60th of a second.
+    pub fn tick_timers(&mut self) {
+        tick_timers(&mut self.emulator)
+    }
+
+    pub fn framebuffer(&self) -> &Display {
+        &self.emulator.display
+    }
+
+    // Queued like `Keypad::press` and `release`, applied as `step` runs.
+    pub fn set_key(&mut self, key: u8, pressed: bool) {
+        if pressed {
+            self.emulator.keypad.press(key);
+        } else {
+            self.emulator.keypad.release(key);
+        }
+    }
+
+    pub fn emulator(&self) -> &Emulator {
+        &self.emulator
+    }
+
+    pub fn emulator_mut(&mut self) -> &mut Emulator {
+        &mut self.emulator
+    }
+}
@@ -0,0 +1,133 @@
+use crate::display::{Display, Palette};
+use crate::keypad::Keypad;
+
+// The VP-590 colour board splits the 64x32 screen into 8 pixel wide
+// columns, with a colour per column of every row.
+pub const ZONE_COLUMNS: usize = 8;
+pub const ZONE_ROWS: usize = 32;
+
+// Foreground colours by number: black, red, blue, violet, green, yellow,
+// aqua and white.
+pub const COLORS: [[u8; 4]; 8] = [
+    [0x00, 0x00, 0x00, 0xFF],
+    [0xFF, 0x00, 0x00, 0xFF],
+    [0x00, 0x00, 0xFF, 0xFF],
+    [0xFF, 0x00, 0xFF, 0xFF],
+    [0x00, 0xFF, 0x00, 0xFF],
+    [0xFF, 0xFF, 0x00, 0xFF],
+    [0x00, 0xFF, 0xFF, 0xFF],
+    [0xFF, 0xFF, 0xFF, 0xFF],
+];
+
+// 02A0 cycles through these: dark blue, black, green and red.
+pub const BACKGROUNDS: [[u8; 4]; 4] = [
+    [0x00, 0x00, 0x80, 0xFF],
+    [0x00, 0x00, 0x00, 0xFF],
+    [0x00, 0x80, 0x00, 0xFF],
+    [0x80, 0x00, 0x00, 0xFF],
+];
+
+const DEFAULT_COLOR: u8 = 1;
+
+// The hardware CHIP-8X adds to a COSMAC VIP: the colour board, a second
+// hex keypad and the VP-595 sound board's I/O port. `Emulator::chip8x` is
+// only set for the CHIP-8X variant, its opcodes fail without it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chip8x {
+    pub background: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::big_array"))]
+    pub zones: [u8; ZONE_COLUMNS * ZONE_ROWS],
+    pub keypad: Keypad,
+    // Last byte written with FXF8, the tone of the sound board.
+    pub tone: u8,
+    // What FXFB reads, set by the frontend.
+    pub input: u8,
+}
+
+impl Default for Chip8x {
+    fn default() -> Self {
+        Chip8x {
+            background: 0,
+            zones: [DEFAULT_COLOR; ZONE_COLUMNS * ZONE_ROWS],
+            keypad: Keypad::new(),
+            tone: 0,
+            input: 0,
+        }
+    }
+}
+
+impl Chip8x {
+    pub fn new() -> Self {
+        Chip8x::default()
+    }
+
+    pub fn next_background(&mut self) {
+        self.background = (self.background + 1) % BACKGROUNDS.len() as u8;
+    }
+
+    // BXY0: `horizontal` and `vertical` hold the first zone in their low
+    // nibble and how many more to colour in their high nibble. Zones are 8x4
+    // pixels here, four rows of the colour board.
+    pub fn color_zones(&mut self, horizontal: u8, vertical: u8, color: u8) {
+        let columns =
+            (horizontal & 0xF) as usize..=((horizontal & 0xF) + (horizontal >> 4)) as usize;
+        let zones = (vertical & 0xF) as usize..=((vertical & 0xF) + (vertical >> 4)) as usize;
+        for zone in zones {
+            for row in zone * 4..zone * 4 + 4 {
+                for column in columns.clone() {
+                    self.set_color(column, row, color);
+                }
+            }
+        }
+    }
+
+    // BXYN: colours `rows` rows of the column holding pixel `x`, from row `y`.
+    pub fn color_rows(&mut self, x: u8, y: u8, rows: u8, color: u8) {
+        for row in y as usize..y as usize + rows as usize {
+            self.set_color(x as usize / 8, row, color);
+        }
+    }
+
+    pub fn color(&self, x: usize, y: usize) -> u8 {
+        self.zones[(y % ZONE_ROWS) * ZONE_COLUMNS + (x / 8) % ZONE_COLUMNS]
+    }
+
+    // The screen in colour: lit pixels take their zone's colour, the rest
+    // the background.
+    pub fn to_rgba(&self, display: &Display) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(display.width() * display.height() * 4);
+        for y in 0..display.height() {
+            for x in 0..display.width() {
+                let palette = self.palette(x, y);
+                rgba.extend(if display.pixel(x, y) {
+                    palette.foreground
+                } else {
+                    palette.background
+                });
+            }
+        }
+        rgba
+    }
+
+    fn palette(&self, x: usize, y: usize) -> Palette {
+        Palette {
+            background: BACKGROUNDS[self.background as usize % BACKGROUNDS.len()],
+            foreground: COLORS[self.color(x, y) as usize % COLORS.len()],
+        }
+    }
+
+    fn set_color(&mut self, column: usize, row: usize, color: u8) {
+        if column < ZONE_COLUMNS && row < ZONE_ROWS {
+            self.zones[row * ZONE_COLUMNS + column] = color & 7;
+        }
+    }
+}
+
+// 5XY1: adds each nibble on its own, wrapping at 8, since both usually hold
+// a pair of zone coordinates.
+pub fn add_bcd(vx: u8, vy: u8) -> u8 {
+    let high = ((vx >> 4) + (vy >> 4)) & 0x7;
+    let low = ((vx & 0xF) + (vy & 0xF)) & 0x7;
+    (high << 4) | low
+}
@@ -0,0 +1,50 @@
+use std::hash::{Hash, Hasher};
+
+pub const FRAMES_PER_SECOND: u32 = 60;
+
+// An instruction rate that is not a whole number per frame, set with
+// `EmulatorBuilder::clock_hz`. Each frame runs the instructions that came
+// due and carries the rest over in `phase`, in sixtieths of an
+// instruction, so 1000 Hz runs 16 or 17 a frame and exactly 1000 a second,
+// and 1 Hz one every 60 frames. Without `hz` every frame runs
+// `cycles_per_frame`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Clock {
+    pub hz: Option<u32>,
+    pub phase: u32,
+}
+
+// Hashes nothing for whole instructions per frame, so those machines have
+// the checksums they had before the clock could run fractions: golden runs
+// and recorded replays still match.
+impl Hash for Clock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if let Some(hz) = self.hz {
+            hz.hash(state);
+            self.phase.hash(state);
+        }
+    }
+}
+
+impl Clock {
+    // Whole instructions per frame when `hz` divides into them.
+    pub fn new(hz: u32) -> Self {
+        Clock {
+            hz: (!hz.is_multiple_of(FRAMES_PER_SECOND)).then_some(hz),
+            phase: 0,
+        }
+    }
+
+    // Instructions to run this frame, `cycles_per_frame` without `hz`.
+    pub fn next_frame(&mut self, cycles_per_frame: usize) -> usize {
+        match self.hz {
+            Some(hz) => {
+                let due = hz as u64 + self.phase as u64;
+                self.phase = (due % FRAMES_PER_SECOND as u64) as u32;
+                (due / FRAMES_PER_SECOND as u64) as usize
+            }
+            None => cycles_per_frame,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// Codecs for savestates and the rewind timeline, which are mostly runs:
+// memory the ROM never touches, a blank screen, and from one snapshot to
+// the next nearly everything alike. With the `compression` feature they are
+// packed with zstd, without it with the small run-length codec below, which
+// needs nothing from the target and is what embedded builds use. Every
+// build reads run-length packed states.
+//
+// Packed bytes are a varint of the unpacked length, then tokens: the
+// varint `n << 1 | 1` and a byte repeated n times, or `n << 1` and n bytes
+// as they are.
+
+// Long enough that a run token, a varint and a byte, is shorter.
+const MIN_RUN: usize = 3;
+// Nothing packed here unpacks larger, a length past it is corrupt data.
+const MAX_UNPACKED: usize = 1 << 26;
+
+pub fn pack(data: &[u8]) -> Vec<u8> {
+    let mut packed = Vec::with_capacity(data.len() / 4 + 8);
+    varint(&mut packed, data.len());
+    let mut literal = 0;
+    let mut index = 0;
+    while index < data.len() {
+        let byte = data[index];
+        let run = data[index..].iter().take_while(|&&b| b == byte).count();
+        if run < MIN_RUN {
+            index += run;
+            continue;
+        }
+        literals(&mut packed, &data[literal..index]);
+        varint(&mut packed, run << 1 | 1);
+        packed.push(byte);
+        index += run;
+        literal = index;
+    }
+    literals(&mut packed, &data[literal..]);
+    packed
+}
+
+pub fn unpack(packed: &[u8]) -> Result<Vec<u8>, String> {
+    let mut rest = packed;
+    let len = read_varint(&mut rest)?;
+    if len > MAX_UNPACKED {
+        return Err("corrupt packed data".to_string());
+    }
+    let mut data = Vec::with_capacity(len);
+    while data.len() < len {
+        let token = read_varint(&mut rest)?;
+        let count = token >> 1;
+        if count > len - data.len() {
+            return Err("corrupt packed data".to_string());
+        }
+        if token & 1 == 1 {
+            let (&byte, after) = rest.split_first().ok_or("truncated")?;
+            data.resize(data.len() + count, byte);
+            rest = after;
+        } else {
+            let bytes = rest.get(..count).ok_or("truncated")?;
+            data.extend_from_slice(bytes);
+            rest = &rest[count..];
+        }
+    }
+    if !rest.is_empty() {
+        return Err("trailing bytes".to_string());
+    }
+    Ok(data)
+}
+
+// `data` packed as its difference from `base`, XORed so what did not
+// change is runs of zeros. Unpacks only with the same `base`.
+pub fn pack_delta(base: &[u8], data: &[u8]) -> Vec<u8> {
+    pack(&xor(base, data))
+}
+
+pub fn unpack_delta(base: &[u8], packed: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(xor(base, &unpack(packed)?))
+}
+
+// Past the end of `base`, `data` is kept as it is.
+fn xor(base: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    for (byte, &other) in out.iter_mut().zip(base) {
+        *byte ^= other;
+    }
+    out
+}
+
+fn literals(packed: &mut Vec<u8>, bytes: &[u8]) {
+    if !bytes.is_empty() {
+        varint(packed, bytes.len() << 1);
+        packed.extend_from_slice(bytes);
+    }
+}
+
+// Seven bits a byte, low first, the top bit set on all but the last.
+fn varint(packed: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        packed.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    packed.push(value as u8);
+}
+
+fn read_varint(rest: &mut &[u8]) -> Result<usize, String> {
+    let mut value = 0usize;
+    for shift in (0..usize::BITS).step_by(7) {
+        let (&byte, after) = rest.split_first().ok_or("truncated")?;
+        *rest = after;
+        value |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("corrupt packed data".to_string())
+}
+
+// zstd at its default level, what the `compression` feature packs with.
+#[cfg(feature = "compression")]
+pub fn zstd_pack(data: &[u8]) -> Vec<u8> {
+    zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL).expect("packing into memory")
+}
+
+#[cfg(feature = "compression")]
+pub fn zstd_unpack(packed: &[u8]) -> Result<Vec<u8>, String> {
+    zstd::bulk::decompress(packed, MAX_UNPACKED).map_err(|error| error.to_string())
+}
+
+// Like `pack_delta`, with zstd.
+#[cfg(feature = "compression")]
+pub fn zstd_pack_delta(base: &[u8], data: &[u8]) -> Vec<u8> {
+    zstd_pack(&xor(base, data))
+}
+
+#[cfg(feature = "compression")]
+pub fn zstd_unpack_delta(base: &[u8], packed: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(xor(base, &zstd_unpack(packed)?))
+}
@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::calibration::MAX_CYCLES_PER_FRAME;
+use crate::display::Palette;
+use crate::host::{Files, StdFiles};
+use crate::labels::Labels;
+use crate::macros::Macros;
+use crate::quirks::Quirks;
+use crate::rom::rom_hash;
+use crate::triggers::Triggers;
+
+// Per-user data lives in $CHIP8_RS_HOME, or ~/.config/chip8-rs by default.
+pub fn config_dir() -> PathBuf {
+    if let Some(home) = env::var_os("CHIP8_RS_HOME") {
+        return PathBuf::from(home);
+    }
+    let home = env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+    home.join(".config").join("chip8-rs")
+}
+
+// Settings remembered for one ROM, stored as `key = value` lines in a file
+// named after the ROM hash, so renaming the file keeps them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RomConfig {
+    pub quirks: Option<Quirks>,
+    // A built-in font name or a font file, see `Font::open`.
+    pub font: Option<String>,
+    // The game's speed, as found with `calibration`.
+    pub cycles_per_frame: Option<usize>,
+    // The colors frontends that draw pixels use, `palette = 33ff66 002200`.
+    pub palette: Option<Palette>,
+    // Keys this version does not know about, kept so saving does not drop
+    // settings written by other tools.
+    other: BTreeMap<String, String>,
+}
+
+impl RomConfig {
+    pub fn path(rom: &[u8]) -> PathBuf {
+        config_dir()
+            .join("roms")
+            .join(format!("{:016x}.cfg", rom_hash(rom)))
+    }
+
+    // A missing file is just an empty config.
+    pub fn load(rom: &[u8]) -> Result<Self, String> {
+        Self::load_from(&StdFiles, rom)
+    }
+
+    pub fn load_from(files: &dyn Files, rom: &[u8]) -> Result<Self, String> {
+        match files.read(&Self::path(rom)) {
+            Ok(data) => Self::parse(&String::from_utf8_lossy(&data)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(RomConfig::default()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    pub fn save(&self, rom: &[u8]) -> io::Result<()> {
+        let path = Self::path(rom);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_text())
+    }
+
+    // The ROM's `trigger.<name> = <condition> | <message>` entries, which
+    // can use its labels.
+    pub fn triggers(&self) -> Result<Triggers, String> {
+        Triggers::from_config(
+            self.other
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+            &self.labels()?,
+        )
+    }
+
+    // The ROM's `label.<name> = <address>` entries.
+    pub fn labels(&self) -> Result<Labels, String> {
+        Labels::from_config(
+            self.other
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        )
+    }
+
+    // Replaces the ROM's labels with these.
+    pub fn set_labels(&mut self, labels: &Labels) {
+        self.other.retain(|key, _| !key.starts_with("label."));
+        self.other.extend(labels.to_config());
+    }
+
+    // The ROM's `macro.<name>` entries.
+    pub fn macros(&self) -> Result<Macros, String> {
+        Macros::from_config(
+            self.other
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        )
+    }
+
+    // Replaces the ROM's macros with these.
+    pub fn set_macros(&mut self, macros: &Macros) {
+        self.other.retain(|key, _| !key.starts_with("macro."));
+        self.other.extend(macros.to_config());
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = RomConfig::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `key = value`", number + 1))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "quirks" => {
+                    let quirks = value
+                        .parse()
+                        .map_err(|error| format!("line {}: {}", number + 1, error))?;
+                    config.quirks = Some(quirks);
+                }
+                "font" => config.font = Some(value.to_string()),
+                "cycles_per_frame" => {
+                    let cycles = value
+                        .parse()
+                        .ok()
+                        .filter(|cycles| (1..=MAX_CYCLES_PER_FRAME).contains(cycles))
+                        .ok_or_else(|| {
+                            format!(
+                                "line {}: cycles_per_frame is from 1 to {}",
+                                number + 1,
+                                MAX_CYCLES_PER_FRAME
+                            )
+                        })?;
+                    config.cycles_per_frame = Some(cycles);
+                }
+                "palette" => {
+                    let palette = value
+                        .parse()
+                        .map_err(|error| format!("line {}: {}", number + 1, error))?;
+                    config.palette = Some(palette);
+                }
+                _ => {
+                    config.other.insert(key.to_string(), value.to_string());
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if let Some(quirks) = self.quirks {
+            text.push_str(&format!("quirks = {}\n", quirks));
+        }
+        if let Some(font) = &self.font {
+            text.push_str(&format!("font = {}\n", font));
+        }
+        if let Some(cycles) = self.cycles_per_frame {
+            text.push_str(&format!("cycles_per_frame = {}\n", cycles));
+        }
+        if let Some(palette) = self.palette {
+            text.push_str(&format!("palette = {}\n", palette));
+        }
+        for (key, value) in self.other.iter() {
+            text.push_str(&format!("{} = {}\n", key, value));
+        }
+        text
+    }
+}
+
+// Settings for every ROM, `key = value` lines in settings.cfg.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Settings {
+    // Show what is being played on Discord, `discord = on`.
+    pub discord: bool,
+    // The Discord application the presence is published as.
+    pub discord_client_id: Option<u64>,
+    // The introduction of a launch without arguments was shown,
+    // `welcomed = on`.
+    pub welcomed: bool,
+    other: BTreeMap<String, String>,
+}
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        config_dir().join("settings.cfg")
+    }
+
+    // A missing file means the defaults.
+    pub fn load_from(files: &dyn Files) -> Result<Self, String> {
+        match files.read(&Self::path()) {
+            Ok(data) => Self::parse(&String::from_utf8_lossy(&data)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    pub fn save_to(&self, files: &dyn Files) -> io::Result<()> {
+        files.write(&Self::path(), self.to_text().as_bytes())
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut settings = Settings::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| format!("line {}: {}", number + 1, message);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected `key = value`"))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "discord" => {
+                    settings.discord = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(error("discord is either on or off")),
+                    }
+                }
+                "welcomed" => {
+                    settings.welcomed = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(error("welcomed is either on or off")),
+                    }
+                }
+                "discord_client_id" => {
+                    let id = value.parse().map_err(|_| error("invalid client id"))?;
+                    settings.discord_client_id = Some(id);
+                }
+                _ => {
+                    settings.other.insert(key.to_string(), value.to_string());
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("discord = {}\n", if self.discord { "on" } else { "off" });
+        if let Some(id) = self.discord_client_id {
+            text.push_str(&format!("discord_client_id = {}\n", id));
+        }
+        if self.welcomed {
+            text.push_str("welcomed = on\n");
+        }
+        for (key, value) in self.other.iter() {
+            text.push_str(&format!("{} = {}\n", key, value));
+        }
+        text
+    }
+}
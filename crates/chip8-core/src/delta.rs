@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::Emulator;
+
+// One thing an instruction changed, with the values before and after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Register(usize, u8, u8),
+    I(u16, u16),
+    ProgramCounter(u16, u16),
+    // Return addresses pushed by CALL and popped by RET.
+    Pushed(u16),
+    Popped(u16),
+    DelayTimer(usize, usize),
+    SoundTimer(usize, usize),
+    Memory(u16, u8, u8),
+    Screen,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Change::Register(index, before, after) => {
+                write!(f, "V{:X}: {:#04x} -> {:#04x}", index, before, after)
+            }
+            Change::I(before, after) => write!(f, "I: {:#05x} -> {:#05x}", before, after),
+            Change::ProgramCounter(before, after) => {
+                write!(f, "PC: {:#05x} -> {:#05x}", before, after)
+            }
+            Change::Pushed(address) => write!(f, "stack: pushed {:#05x}", address),
+            Change::Popped(address) => write!(f, "stack: popped {:#05x}", address),
+            Change::DelayTimer(before, after) => write!(f, "DT: {} -> {}", before, after),
+            Change::SoundTimer(before, after) => write!(f, "ST: {} -> {}", before, after),
+            Change::Memory(address, before, after) => {
+                write!(f, "[{:#05x}]: {:#04x} -> {:#04x}", address, before, after)
+            }
+            Change::Screen => write!(f, "screen updated"),
+        }
+    }
+}
+
+// Everything that differs between two states of the same machine, usually
+// right before and right after one instruction.
+pub fn changes(before: &Emulator, after: &Emulator) -> Vec<Change> {
+    let mut changes: Vec<Change> = before
+        .v_registers
+        .iter()
+        .zip(after.v_registers.iter())
+        .enumerate()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(index, (&before, &after))| Change::Register(index, before, after))
+        .collect();
+    if before.i_register != after.i_register {
+        changes.push(Change::I(before.i_register, after.i_register));
+    }
+    if before.program_counter != after.program_counter {
+        changes.push(Change::ProgramCounter(
+            before.program_counter,
+            after.program_counter,
+        ));
+    }
+    if after.stack_pointer > before.stack_pointer {
+        let top = after.stack[after.stack_pointer as usize - 1];
+        changes.push(Change::Pushed(top));
+    } else if after.stack_pointer < before.stack_pointer {
+        let top = before.stack[before.stack_pointer as usize - 1];
+        changes.push(Change::Popped(top));
+    }
+    if before.delay_timer_registry != after.delay_timer_registry {
+        changes.push(Change::DelayTimer(
+            before.delay_timer_registry,
+            after.delay_timer_registry,
+        ));
+    }
+    if before.sound_timer_registry != after.sound_timer_registry {
+        changes.push(Change::SoundTimer(
+            before.sound_timer_registry,
+            after.sound_timer_registry,
+        ));
+    }
+    changes.extend(
+        before
+            .ram
+            .iter()
+            .zip(after.ram.iter())
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(address, (&before, &after))| Change::Memory(address as u16, before, after)),
+    );
+    let screen = |emulator: &Emulator| (emulator.display.resolution(), *emulator.display.plane());
+    if screen(before) != screen(after) {
+        changes.push(Change::Screen);
+    }
+    changes
+}
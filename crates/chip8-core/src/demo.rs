@@ -0,0 +1,7 @@
+// A splash screen for frontends to run when they are given no ROM: the
+// title, a cursor blinking on the delay timer, and the key held drawn in
+// hex with a beep, so it also shows the display, timers, input and sound
+// all work. Assembled from roms/demo.asm, in the public domain.
+pub const ROM: &[u8] = include_bytes!("../../../roms/demo.ch8");
+
+pub const NAME: &str = "demo";
@@ -0,0 +1,161 @@
+use crate::{EmulatorError, INITIAL_ADDRESS, RAM_SIZE, XOCHIP_RAM_SIZE};
+
+// Why a ROM or savestate did not load, or a game stopped, worded for the
+// player with what to try next. Frontends show it on screen, a console the
+// player started them from may be nowhere in sight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub title: String,
+    pub error: String,
+    pub fixes: Vec<String>,
+}
+
+impl Diagnosis {
+    pub fn new(title: &str, error: impl ToString) -> Self {
+        Diagnosis {
+            title: title.to_string(),
+            error: error.to_string(),
+            fixes: Vec::new(),
+        }
+    }
+
+    pub fn fix(mut self, fix: &str) -> Self {
+        self.fixes.push(fix.to_string());
+        self
+    }
+
+    // `name` is the file the player picked, `data` what it held.
+    pub fn rom(name: &str, data: &[u8], error: &EmulatorError) -> Self {
+        let diagnosis = Diagnosis::new(&format!("Cannot load {}", name), error);
+        match error {
+            EmulatorError::RomTooLarge(size)
+                if *size <= XOCHIP_RAM_SIZE - INITIAL_ADDRESS as usize =>
+            {
+                diagnosis.fix("ROMs this large are usually XO-CHIP, run it as xochip.")
+            }
+            EmulatorError::RomTooLarge(_) => {
+                diagnosis.fix("It is too large for any variant, it may not be a CHIP-8 ROM.")
+            }
+            EmulatorError::InvalidConfig(_) => {
+                diagnosis.fix("Check the options it was started with.")
+            }
+            _ => Diagnosis::crash(name, data, error),
+        }
+    }
+
+    // `name` is the savestate file.
+    pub fn savestate(name: &str, error: &EmulatorError) -> Self {
+        let diagnosis = Diagnosis::new(&format!("Cannot load {}", name), error);
+        match error {
+            EmulatorError::InvalidSaveState(message)
+                if message.starts_with("unsupported version") =>
+            {
+                diagnosis
+                    .fix("It was saved by another version of this emulator.")
+                    .fix("Start the ROM from power on instead.")
+            }
+            EmulatorError::InvalidSaveState(message) if message == "not a savestate" => diagnosis
+                .fix("Pick a savestate, not the ROM or another file.")
+                .fix("Start the ROM from power on instead."),
+            _ => diagnosis
+                .fix("The file is corrupt or was cut short, try an older savestate.")
+                .fix("Start the ROM from power on instead."),
+        }
+    }
+
+    // The game running from `data` stopped with `error`.
+    pub fn crash(name: &str, data: &[u8], error: &EmulatorError) -> Self {
+        let diagnosis = Diagnosis::new(&format!("{} stopped", name), error);
+        let large = data.len() > RAM_SIZE - INITIAL_ADDRESS as usize;
+        match error {
+            EmulatorError::Unimplemented(_, "XO-CHIP") => {
+                diagnosis.fix("It is an XO-CHIP ROM, run it as xochip.")
+            }
+            EmulatorError::Unimplemented(_, "CHIP-8X") => {
+                diagnosis.fix("It is a CHIP-8X ROM, run it as chip8x.")
+            }
+            EmulatorError::Unimplemented(_, feature) => diagnosis.fix(&format!(
+                "It uses {}, an extension of this emulator, enable it.",
+                feature
+            )),
+            EmulatorError::UndefinedBehavior(_) => diagnosis.fix(
+                "Interpreters disagree on what it does there, write it another way to run the same on all of them.",
+            ),
+            EmulatorError::Breakpoint(_) => {
+                diagnosis.fix("It stopped at the breakpoint asked for, run without it to play on.")
+            }
+            EmulatorError::DiskBlockOutOfRange(_) => diagnosis
+                .fix("The ROM asked for a block the disk does not have, it is a bug in the ROM."),
+            _ if large => diagnosis
+                .fix("ROMs this large are usually XO-CHIP, run it as xochip.")
+                .fix("The file may be corrupt, download it again."),
+            EmulatorError::UnknownOpcode(0) => diagnosis
+                .fix("It ran into empty memory, the file may be cut short, download it again.")
+                .fix("It may be for another variant, try schip or xochip."),
+            _ => diagnosis
+                .fix("It may be for another variant, try schip or xochip, or other quirks.")
+                .fix("The file may be corrupt, download it again."),
+        }
+    }
+
+    // The diagnosis in a box at most `width` columns wide, one string per
+    // line, for frontends that draw text.
+    pub fn dialog(&self, width: usize) -> Vec<String> {
+        let inner = width.saturating_sub(4).max(20);
+        let mut body = wrap(&self.error, inner);
+        if !self.fixes.is_empty() {
+            body.push(String::new());
+            body.push("Try:".to_string());
+            for fix in &self.fixes {
+                for (index, line) in wrap(fix, inner - 2).into_iter().enumerate() {
+                    let bullet = if index == 0 { "- " } else { "  " };
+                    body.push(format!("{}{}", bullet, line));
+                }
+            }
+        }
+        let inner = body
+            .iter()
+            .chain(wrap(&self.title, inner).iter())
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        lines.push(format!("+{}+", "-".repeat(inner + 2)));
+        for title in wrap(&self.title, inner) {
+            lines.push(format!("| {:<inner$} |", title));
+        }
+        lines.push(format!("+{}+", "-".repeat(inner + 2)));
+        for line in body {
+            lines.push(format!("| {:<inner$} |", line));
+        }
+        lines.push(format!("+{}+", "-".repeat(inner + 2)));
+        lines
+    }
+}
+
+// Words into lines of at most `width` characters, longer words cut.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,146 @@
+// Discord Rich Presence over its local IPC socket, when built with the
+// `discord` feature, for every frontend to share. Discord not running is
+// not an error worth more than a warning, the emulator works the same
+// without it.
+use crate::config::Settings;
+use crate::events::Event;
+use crate::presence::Presence;
+
+// Shows `title` as being played, if the settings ask for it. The presence
+// stays until the returned connection is dropped, `Discord::handle` keeps
+// it up to date.
+pub fn publish(settings: &Settings, title: &str, started: u64) -> Option<Discord> {
+    if !settings.discord {
+        return None;
+    }
+    let Some(client_id) = settings.discord_client_id else {
+        eprintln!("warning: discord is on but discord_client_id is not set");
+        return None;
+    };
+    let mut presence = Presence::new(started);
+    presence.set_title(title, started);
+    let connected = Discord::connect(client_id)
+        .and_then(|mut discord| discord.update(&presence).map(|()| discord));
+    match connected {
+        Ok(discord) => Some(discord),
+        Err(error) => {
+            eprintln!("warning: cannot show the game on Discord: {}", error);
+            None
+        }
+    }
+}
+
+#[cfg(all(feature = "discord", unix))]
+pub struct Discord {
+    socket: std::os::unix::net::UnixStream,
+    nonce: u64,
+    // As last sent.
+    presence: Presence,
+}
+
+#[cfg(all(feature = "discord", unix))]
+impl Discord {
+    pub fn connect(client_id: u64) -> Result<Self, String> {
+        use std::env;
+        use std::os::unix::net::UnixStream;
+        use std::path::PathBuf;
+
+        let directory = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+            .iter()
+            .find_map(env::var_os)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("/tmp"));
+        // Discord takes the first free of discord-ipc-0 to 9.
+        let socket = (0..10)
+            .find_map(|n| UnixStream::connect(directory.join(format!("discord-ipc-{}", n))).ok())
+            .ok_or("Discord is not running")?;
+        let mut discord = Discord {
+            socket,
+            nonce: 0,
+            presence: Presence::new(0),
+        };
+        discord.send(HANDSHAKE, &crate::presence::handshake(client_id))?;
+        discord.receive()?;
+        Ok(discord)
+    }
+
+    pub fn update(&mut self, presence: &Presence) -> Result<(), String> {
+        self.presence = presence.clone();
+        self.nonce += 1;
+        let command = presence.set_activity(std::process::id(), self.nonce);
+        self.send(FRAME, &command)?;
+        self.receive()
+    }
+
+    // Sends the presence again when `event`, at unix second `now`, changed
+    // it, like the game being paused or resumed.
+    pub fn handle(&mut self, event: &Event, now: u64) {
+        let mut presence = self.presence.clone();
+        if presence.handle(event, now) {
+            self.show(&presence);
+        }
+    }
+
+    // The ROM was reset at `now`, the time played starts again.
+    pub fn restart(&mut self, now: u64) {
+        let mut presence = self.presence.clone();
+        presence.restart(now);
+        self.show(&presence);
+    }
+
+    fn show(&mut self, presence: &Presence) {
+        if let Err(error) = self.update(presence) {
+            eprintln!("warning: cannot show the game on Discord: {}", error);
+        }
+    }
+
+    // Every message is an opcode and a length, both little endian u32,
+    // followed by that much JSON.
+    fn send(&mut self, opcode: u32, json: &str) -> Result<(), String> {
+        use std::io::Write;
+        let mut message = Vec::with_capacity(8 + json.len());
+        message.extend(opcode.to_le_bytes());
+        message.extend((json.len() as u32).to_le_bytes());
+        message.extend(json.as_bytes());
+        self.socket
+            .write_all(&message)
+            .map_err(|error| error.to_string())
+    }
+
+    // Replies are read and dropped, only so the socket does not fill up.
+    fn receive(&mut self) -> Result<(), String> {
+        use std::io::Read;
+        let mut header = [0; 8];
+        self.socket
+            .read_exact(&mut header)
+            .map_err(|error| error.to_string())?;
+        let len = u32::from_le_bytes(header[4..].try_into().unwrap());
+        let mut reply = vec![0; len as usize];
+        self.socket
+            .read_exact(&mut reply)
+            .map_err(|error| error.to_string())
+    }
+}
+
+#[cfg(all(feature = "discord", unix))]
+const HANDSHAKE: u32 = 0;
+#[cfg(all(feature = "discord", unix))]
+const FRAME: u32 = 1;
+
+#[cfg(not(all(feature = "discord", unix)))]
+pub struct Discord;
+
+#[cfg(not(all(feature = "discord", unix)))]
+impl Discord {
+    pub fn connect(_client_id: u64) -> Result<Self, String> {
+        Err("built without the discord feature".to_string())
+    }
+
+    pub fn update(&mut self, _presence: &Presence) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn handle(&mut self, _event: &Event, _now: u64) {}
+
+    pub fn restart(&mut self, _now: u64) {}
+}
@@ -0,0 +1,112 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::config::config_dir;
+use crate::host::{Files, StdFiles};
+use crate::rom::rom_hash;
+use crate::EmulatorError;
+
+pub const BLOCK_SIZE: usize = 256;
+pub const BLOCK_COUNT: usize = 16;
+pub const DISK_SIZE: usize = BLOCK_SIZE * BLOCK_COUNT;
+
+// Another extension of this emulator, for homebrew keeping more than the 8
+// flag registers between runs: FxF2 reads block Vx of the disk into the
+// 256 bytes at I and FxF3 writes them back, I staying put. Off unless
+// given to `EmulatorBuilder::disk`, the opcodes fail without it and any
+// block past the 16th is an error. The core only keeps the image, the
+// frontend `load`s it and `save`s it again when `is_dirty`. The file is
+// named after the ROM hash in the config directory, nothing a ROM does
+// can point it anywhere else or grow it past `DISK_SIZE`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Disk {
+    data: Vec<u8>,
+    // Written to since loaded or saved.
+    pub(crate) dirty: bool,
+}
+
+impl Default for Disk {
+    fn default() -> Self {
+        Disk {
+            data: vec![0; DISK_SIZE],
+            dirty: false,
+        }
+    }
+}
+
+impl Disk {
+    pub fn new() -> Self {
+        Disk::default()
+    }
+
+    // Shorter images are padded with zeros.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() > DISK_SIZE {
+            return Err(format!(
+                "disk image of {} bytes is over the {} byte limit",
+                data.len(),
+                DISK_SIZE
+            ));
+        }
+        let mut disk = Disk::new();
+        disk.data[..data.len()].copy_from_slice(data);
+        Ok(disk)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn block(&self, block: u8) -> Result<&[u8], EmulatorError> {
+        let start = Self::start(block)?;
+        Ok(&self.data[start..start + BLOCK_SIZE])
+    }
+
+    pub(crate) fn write_block(&mut self, block: u8, data: &[u8]) -> Result<(), EmulatorError> {
+        let start = Self::start(block)?;
+        self.data[start..start + BLOCK_SIZE].copy_from_slice(data);
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn start(block: u8) -> Result<usize, EmulatorError> {
+        match block as usize {
+            block if block < BLOCK_COUNT => Ok(block * BLOCK_SIZE),
+            _ => Err(EmulatorError::DiskBlockOutOfRange(block)),
+        }
+    }
+
+    pub fn path(rom: &[u8]) -> PathBuf {
+        config_dir()
+            .join("disks")
+            .join(format!("{:016x}.disk", rom_hash(rom)))
+    }
+
+    // A missing file is a blank disk.
+    pub fn load(rom: &[u8]) -> Result<Self, String> {
+        Self::load_from(&StdFiles, rom)
+    }
+
+    pub fn load_from(files: &dyn Files, rom: &[u8]) -> Result<Self, String> {
+        match files.read(&Self::path(rom)) {
+            Ok(data) => Self::from_bytes(&data),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Disk::new()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    pub fn save(&mut self, rom: &[u8]) -> io::Result<()> {
+        self.save_to(&StdFiles, rom)
+    }
+
+    pub fn save_to(&mut self, files: &dyn Files, rom: &[u8]) -> io::Result<()> {
+        files.write(&Self::path(rom), &self.data)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
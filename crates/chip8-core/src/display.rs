@@ -0,0 +1,439 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::render::fill_scaled;
+
+pub const MAX_WIDTH: usize = 128;
+pub const MAX_HEIGHT: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Resolution {
+    // 64x32, the original CHIP-8 screen.
+    Lores,
+    // 64x64, used by the HIRES CHIP-8 interpreters.
+    Tall,
+    // 128x64, SCHIP high resolution mode.
+    Hires,
+    // 64x48, the ETI-660's screen. It uses the top 48 rows of the plane.
+    Eti660,
+}
+
+impl Resolution {
+    pub fn width(self) -> usize {
+        match self {
+            Resolution::Lores | Resolution::Tall | Resolution::Eti660 => 64,
+            Resolution::Hires => 128,
+        }
+    }
+
+    pub fn height(self) -> usize {
+        match self {
+            Resolution::Lores => 32,
+            Resolution::Eti660 => 48,
+            Resolution::Tall | Resolution::Hires => 64,
+        }
+    }
+
+    // How many physical pixels of the 128x64 plane a logical pixel covers.
+    fn scale(self) -> (usize, usize) {
+        (MAX_WIDTH / self.width(), MAX_HEIGHT / self.height())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    pub background: [u8; 4],
+    pub foreground: [u8; 4],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            background: [0x00, 0x00, 0x00, 0xFF],
+            foreground: [0xFF, 0xFF, 0xFF, 0xFF],
+        }
+    }
+}
+
+// The foreground and background as hex RGB, `33ff66 002200`, the form
+// configs store it in.
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let color = |hex: &str| -> Option<[u8; 4]> {
+            let hex = hex.strip_prefix('#').unwrap_or(hex);
+            let rgb = u32::from_str_radix(hex, 16)
+                .ok()
+                .filter(|_| hex.len() == 6)?;
+            let [_, r, g, b] = rgb.to_be_bytes();
+            Some([r, g, b, 0xFF])
+        };
+        let colors: Vec<&str> = text.split_whitespace().collect();
+        let palette = match colors[..] {
+            [foreground, background] => color(background).zip(color(foreground)),
+            _ => None,
+        };
+        palette
+            .map(|(background, foreground)| Palette {
+                background,
+                foreground,
+            })
+            .ok_or_else(|| {
+                format!(
+                    "invalid palette {:?}, expected the foreground and background as RRGGBB",
+                    text
+                )
+            })
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [r, g, b, _] = self.foreground;
+        let [br, bg, bb, _] = self.background;
+        write!(
+            f,
+            "{:02x}{:02x}{:02x} {:02x}{:02x}{:02x}",
+            r, g, b, br, bg, bb
+        )
+    }
+}
+
+// Each row is packed into one word with the leftmost pixel in the most
+// significant bit, so sprites can be blitted a whole row at a time.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Framebuffer<const W: usize, const H: usize> {
+    rows: [u128; H],
+}
+
+impl<const W: usize, const H: usize> Framebuffer<W, H> {
+    const ROW_MASK: u128 = !0 << (u128::BITS as usize - W);
+
+    pub fn new() -> Self {
+        const { assert!(W > 0 && W <= u128::BITS as usize) };
+        Framebuffer { rows: [0; H] }
+    }
+
+    pub fn width(&self) -> usize {
+        W
+    }
+
+    pub fn height(&self) -> usize {
+        H
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & pixel_bit(x) != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, on: bool) {
+        if on {
+            self.rows[y] |= pixel_bit(x);
+        } else {
+            self.rows[y] &= !pixel_bit(x);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.rows = [0; H];
+    }
+
+    pub fn row(&self, y: usize) -> u128 {
+        self.rows[y]
+    }
+
+    // XORs a packed row of pixels, ignoring bits past the width. Returns
+    // true if any pixel that was on got turned off.
+    pub fn xor_row(&mut self, y: usize, bits: u128) -> bool {
+        let bits = bits & Self::ROW_MASK;
+        let collision = self.rows[y] & bits != 0;
+        self.rows[y] ^= bits;
+        collision
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const W: usize, const H: usize> serde::Serialize for Framebuffer<W, H> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let pixels = (0..H).flat_map(|y| (0..W).map(move |x| self.get(x, y)));
+        serializer.collect_seq(pixels)
+    }
+}
+
+// Serialized as one flat row-major sequence of pixels, independent of the
+// packing.
+#[cfg(feature = "serde")]
+impl<'de, const W: usize, const H: usize> serde::Deserialize<'de> for Framebuffer<W, H> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let pixels = Vec::<bool>::deserialize(deserializer)?;
+        if pixels.len() != W * H {
+            return Err(D::Error::custom(format!(
+                "expected {} pixels, found {}",
+                W * H,
+                pixels.len()
+            )));
+        }
+        let mut framebuffer = Framebuffer::new();
+        for (index, &on) in pixels.iter().enumerate() {
+            framebuffer.set(index % W, index / W, on);
+        }
+        Ok(framebuffer)
+    }
+}
+
+impl<const W: usize, const H: usize> Default for Framebuffer<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> fmt::Debug for Framebuffer<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..H {
+            let line: String = (0..W)
+                .map(|x| if self.get(x, y) { '#' } else { '.' })
+                .collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// The screen always keeps a full 128x64 plane, the same way SCHIP does.
+// Lower resolutions draw every logical pixel as a block of physical pixels,
+// so switching modes mid-frame never reallocates and lores content shows up
+// already scaled up in hires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Display {
+    plane: Framebuffer<MAX_WIDTH, MAX_HEIGHT>,
+    resolution: Resolution,
+    dirty: bool,
+}
+
+// The dirty flag only tracks what the frontend has seen, two displays
+// showing the same thing hash the same.
+impl Hash for Display {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.plane.hash(state);
+        self.resolution.hash(state);
+    }
+}
+
+impl Display {
+    pub fn new() -> Self {
+        Display {
+            plane: Framebuffer::new(),
+            resolution: Resolution::Lores,
+            dirty: false,
+        }
+    }
+
+    pub fn with_resolution(resolution: Resolution) -> Self {
+        Display {
+            resolution,
+            ..Display::new()
+        }
+    }
+
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    pub fn set_resolution(&mut self, resolution: Resolution) {
+        if self.resolution != resolution {
+            self.resolution = resolution;
+            self.dirty = true;
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.resolution.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.resolution.height()
+    }
+
+    // Logical pixel in the current resolution.
+    pub fn pixel(&self, x: usize, y: usize) -> bool {
+        let (scale_x, scale_y) = self.resolution.scale();
+        self.plane.get(x * scale_x, y * scale_y)
+    }
+
+    // Rows of logical pixels, top to bottom.
+    pub fn iter_rows(&self) -> impl Iterator<Item = Row> + '_ {
+        let (scale_x, scale_y) = self.resolution.scale();
+        let width = self.width();
+        (0..self.height()).map(move |y| Row {
+            bits: self.plane.row(y * scale_y),
+            width,
+            scale_x,
+        })
+    }
+
+    // Row-major RGBA bytes at the logical resolution, ready to upload as a
+    // texture.
+    pub fn to_rgba(&self, palette: &Palette) -> Vec<u8> {
+        let mut rgba = vec![0; self.width() * self.height() * 4];
+        self.render_rgba(&mut rgba, palette, 1)
+            .expect("the buffer is sized for scale 1");
+        rgba
+    }
+
+    // Same as `to_rgba` but scaled up by an integer factor, at least 1, into
+    // a buffer the frontend owns. It must hold exactly
+    // `width() * scale * height() * scale * 4` bytes, anything else is an
+    // error and leaves the buffer untouched.
+    pub fn render_rgba(
+        &self,
+        buffer: &mut [u8],
+        palette: &Palette,
+        scale: usize,
+    ) -> Result<(), String> {
+        if scale == 0 {
+            return Err("the scale must be at least 1".to_string());
+        }
+        let needed = self.width() * scale * self.height() * scale * 4;
+        if buffer.len() != needed {
+            return Err(format!(
+                "a {}x{} screen at scale {} needs {} bytes, not {}",
+                self.width(),
+                self.height(),
+                scale,
+                needed,
+                buffer.len()
+            ));
+        }
+        fill_scaled(buffer, self.width(), self.height(), scale, |x, y| {
+            if self.pixel(x, y) {
+                palette.foreground
+            } else {
+                palette.background
+            }
+        });
+        Ok(())
+    }
+
+    // The physical 128x64 plane, for frontends that always render at the
+    // highest resolution.
+    pub fn plane(&self) -> &Framebuffer<MAX_WIDTH, MAX_HEIGHT> {
+        &self.plane
+    }
+
+    pub fn clear(&mut self) {
+        self.plane.clear();
+        self.dirty = true;
+    }
+
+    // XORs the sprite at (x, y); the start position always wraps, pixels
+    // that fall past the edges are either clipped or wrapped around to the
+    // other side. Returns true if any pixel was erased.
+    pub fn draw_sprite(&mut self, x: usize, y: usize, sprite: &[u8], clip: bool) -> bool {
+        let (scale_x, scale_y) = self.resolution.scale();
+        let plane_x = (x % self.width()) * scale_x;
+        let y = y % self.height();
+        let rows = if clip {
+            sprite.len().min(self.height() - y)
+        } else {
+            sprite.len()
+        };
+        let mut collision = false;
+        for (row, &byte) in sprite.iter().enumerate().take(rows) {
+            let (bits, bits_width) = match scale_x {
+                1 => (byte as u128, 8),
+                _ => (double_bits(byte) as u128, 16),
+            };
+            // Every resolution spans the full width of the plane, so
+            // wrapping is a rotation of the row.
+            let bits = if clip {
+                align_left(bits, bits_width, plane_x)
+            } else {
+                (bits << (u128::BITS as usize - bits_width)).rotate_right(plane_x as u32)
+            };
+            let plane_y = ((y + row) % self.height()) * scale_y;
+            for plane_row in plane_y..plane_y + scale_y {
+                collision |= self.plane.xor_row(plane_row, bits);
+            }
+        }
+        self.dirty = true;
+        collision
+    }
+
+    // Overwrites a row of the physical plane, for savestates.
+    pub(crate) fn restore_row(&mut self, y: usize, bits: u128) {
+        let old = self.plane.row(y);
+        self.plane.xor_row(y, old ^ bits);
+        self.dirty = true;
+    }
+
+    pub fn is_blank(&self) -> bool {
+        (0..MAX_HEIGHT).all(|y| self.plane.row(y) == 0)
+    }
+
+    // Reports whether the screen changed since the last call.
+    pub fn take_dirty(&mut self) -> bool {
+        let dirty = self.dirty;
+        self.dirty = false;
+        dirty
+    }
+}
+
+// One row of logical pixels, detached from the packing of the plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+    bits: u128,
+    width: usize,
+    scale_x: usize,
+}
+
+impl Row {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn pixel(&self, x: usize) -> bool {
+        self.bits & pixel_bit(x * self.scale_x) != 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = bool> {
+        (0..self.width).map(move |x| self.pixel(x))
+    }
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn pixel_bit(x: usize) -> u128 {
+    1 << (u128::BITS as usize - 1 - x)
+}
+
+// Moves a `width` bit pattern so its first bit lands on column `x`, dropping
+// whatever would go past the right edge.
+fn align_left(bits: u128, width: usize, x: usize) -> u128 {
+    let offset = u128::BITS as usize - width;
+    if x <= offset {
+        bits << (offset - x)
+    } else {
+        bits >> (x - offset)
+    }
+}
+
+// Turns every bit of a sprite row into two, for resolutions whose logical
+// pixels are two physical pixels wide.
+fn double_bits(byte: u8) -> u16 {
+    let mut bits = byte as u16;
+    bits = (bits | (bits << 4)) & 0x0F0F;
+    bits = (bits | (bits << 2)) & 0x3333;
+    bits = (bits | (bits << 1)) & 0x5555;
+    bits | (bits << 1)
+}
@@ -0,0 +1,175 @@
+use crate::labels::Labels;
+use crate::rom::Rom;
+use crate::{Emulator, Instruction, INITIAL_ADDRESS};
+
+// Plain text views of the machine, sized for bug reports and the clipboard.
+
+pub fn registers(emulator: &Emulator) -> String {
+    let row = |range: std::ops::Range<usize>| {
+        range
+            .map(|index| format!("{:02X}", emulator.v_registers[index]))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let stack = &emulator.stack[..emulator.stack_pointer as usize];
+    format!(
+        "V0-V7  {}\nV8-VF  {}\nI={:#05x} PC={:#05x} DT={} ST={} stack={:03X?}\n",
+        row(0..8),
+        row(8..16),
+        emulator.i_register,
+        emulator.program_counter,
+        emulator.delay_timer_registry,
+        emulator.sound_timer_registry,
+        stack
+    )
+}
+
+// Sixteen bytes per line, each line starting with its address. Addresses
+// wrap around the end of memory.
+pub fn hexdump(ram: &[u8], start: u16, len: usize) -> String {
+    let mut text = String::new();
+    for line_start in (0..len).step_by(16) {
+        let address = (start as usize + line_start) % ram.len();
+        let bytes: Vec<String> = (line_start..len.min(line_start + 16))
+            .map(|offset| format!("{:02X}", ram[(start as usize + offset) % ram.len()]))
+            .collect();
+        text.push_str(&format!("{:#05x}: {}\n", address, bytes.join(" ")));
+    }
+    text
+}
+
+// `count` instructions starting at `start`, one per line. The address
+// after an F000 gets a `DB` line of its own.
+pub fn disassemble(ram: &[u8], start: u16, count: usize) -> String {
+    disassemble_labeled(ram, start, count, &Labels::new())
+}
+
+// With the labels of the addresses shown or used, see `Labels::annotate`.
+pub fn disassemble_labeled(ram: &[u8], start: u16, count: usize, labels: &Labels) -> String {
+    let mut text = String::new();
+    let mut long = false;
+    for index in 0..count {
+        let address = (start as usize + 2 * index) % ram.len();
+        let bytes = [ram[address], ram[(address + 1) % ram.len()]];
+        let op_code = u16::from_be_bytes(bytes);
+        let instruction = Instruction::decode(op_code);
+        if long {
+            text.push_str(&format!(
+                "{:#05x}  {:04X}  DB {:02X} {:02X}\n",
+                address, op_code, bytes[0], bytes[1]
+            ));
+        } else {
+            text.push_str(&format!(
+                "{:#05x}  {:04X}  {}{}\n",
+                address,
+                op_code,
+                instruction,
+                labels.annotate(address as u16, instruction)
+            ));
+        }
+        long = !long && instruction == Instruction::LongI;
+    }
+    text
+}
+
+// A whole ROM, instructions where the analyzer found code and `DB` lines of
+// up to eight bytes everywhere else.
+pub fn disassemble_rom(rom: &Rom) -> String {
+    let mut text = String::new();
+    let data_lines = |text: &mut String, from: usize, to: usize| {
+        for line_start in (from..to).step_by(8) {
+            let bytes: Vec<String> = rom.data()[line_start..to.min(line_start + 8)]
+                .iter()
+                .map(|byte| format!("{:#04x}", byte))
+                .collect();
+            text.push_str(&format!(
+                "{:#05x}        DB {}\n",
+                INITIAL_ADDRESS as usize + line_start,
+                bytes.join(", ")
+            ));
+        }
+    };
+    let mut offset = 0;
+    for (address, op_code, instruction) in rom.instructions() {
+        let start = (address - INITIAL_ADDRESS) as usize;
+        if start > offset {
+            data_lines(&mut text, offset, start);
+        }
+        text.push_str(&format!(
+            "{:#05x}  {:04X}  {}\n",
+            address, op_code, instruction
+        ));
+        offset = offset.max(start + 2);
+    }
+    data_lines(&mut text, offset, rom.data().len());
+    text
+}
+
+// Reads bytes written as hex pairs, separated by spaces, commas or nothing
+// at all and optionally 0x prefixed. Tokens ending in ':' are skipped, so
+// the output of `hexdump` pastes back as is.
+pub fn parse_hex_bytes(text: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for token in text.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.is_empty() || token.ends_with(':') {
+            continue;
+        }
+        let digits = token.trim_start_matches("0x").trim_start_matches("0X");
+        if digits.len() % 2 != 0 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("{:?} is not a sequence of hex bytes", token));
+        }
+        for pair in digits.as_bytes().chunks(2) {
+            let pair = std::str::from_utf8(pair).unwrap();
+            bytes.push(u8::from_str_radix(pair, 16).unwrap());
+        }
+    }
+    Ok(bytes)
+}
+
+// Copies `bytes` into memory at `address`, wrapping around the end.
+pub fn poke(ram: &mut [u8], address: u16, bytes: &[u8]) {
+    for (offset, &byte) in bytes.iter().enumerate() {
+        ram[(address as usize + offset) % ram.len()] = byte;
+    }
+}
+
+// The debugger views `debug_pane` draws, each sized for a terminal window
+// of its own so debugging does not take room from the game.
+pub const PANES: [&str; 3] = ["registers", "disassembly", "memory"];
+const PANE_INSTRUCTIONS: usize = 24;
+const PANE_MEMORY_LINES: usize = 16;
+
+// One of `PANES` as the machine is now: the registers, the instructions
+// around PC with an arrow at it, or the memory around I. None for a name
+// that is not in `PANES`.
+pub fn debug_pane(emulator: &Emulator, name: &str) -> Option<String> {
+    debug_pane_labeled(emulator, name, &Labels::new())
+}
+
+// The disassembly with `labels`, see `disassemble_labeled`.
+pub fn debug_pane_labeled(emulator: &Emulator, name: &str, labels: &Labels) -> Option<String> {
+    let pc = emulator.program_counter;
+    match name {
+        "registers" => Some(registers(emulator)),
+        "disassembly" => {
+            let start = pc.saturating_sub(2 * (PANE_INSTRUCTIONS as u16 / 3));
+            let lines = disassemble_labeled(&emulator.ram, start, PANE_INSTRUCTIONS, labels);
+            let marked: Vec<String> = lines
+                .lines()
+                .enumerate()
+                .map(
+                    |(index, line)| match start as usize + 2 * index == pc as usize {
+                        true => format!("> {}\n", line),
+                        false => format!("  {}\n", line),
+                    },
+                )
+                .collect();
+            Some(marked.concat())
+        }
+        "memory" => {
+            let start = (emulator.i_register & !0xF).saturating_sub(0x40);
+            Some(hexdump(&emulator.ram, start, PANE_MEMORY_LINES * 16))
+        }
+        _ => None,
+    }
+}
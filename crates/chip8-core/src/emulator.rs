@@ -0,0 +1,590 @@
+use crate::chip8x::{add_bcd, Chip8x};
+use crate::clock::Clock;
+use crate::disk::{Disk, BLOCK_SIZE};
+use crate::display::{Display, Resolution};
+use crate::error::EmulatorError;
+use crate::font::FONT_ADDRESS;
+use crate::frame::FrameOutput;
+use crate::hooks::{with_hooks, Hooks};
+use crate::instruction::Instruction;
+use crate::keypad::Keypad;
+use crate::printer::Printer;
+use crate::quirks::Quirks;
+use crate::rng::Rng;
+use crate::wallclock::WallClock;
+use crate::xochip::{XoChip, PATTERN_SIZE};
+
+pub const V_REGISTERS_NUMBER: usize = 16;
+pub const STACK_SIZE: usize = 16;
+// Memory of the original machines, and the default.
+pub const RAM_SIZE: usize = 4096;
+// XO-CHIP's, all that a 16 bit I can address.
+pub const XOCHIP_RAM_SIZE: usize = 0x10000;
+pub const INITIAL_ADDRESS: u16 = 0x200;
+pub const CYCLES_PER_FRAME: usize = 10;
+
+// Non-exhaustive, new machine state is not a breaking change. Frontends
+// get one from `EmulatorBuilder` and destructure it with `..`.
+#[derive(Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Emulator {
+    pub v_registers: [u8; V_REGISTERS_NUMBER],
+    // 16 bits on every machine, set past 0xFFF by Fx1E or XO-CHIP's F000.
+    // Accesses through it wrap at the end of `ram`, on a 4K machine only
+    // its low 12 bits count.
+    pub i_register: u16,
+    pub program_counter: u16,
+    pub stack_pointer: u8,
+    pub stack: [u16; STACK_SIZE],
+    // Calls deeper than this overflow, at most `STACK_SIZE`.
+    pub stack_limit: usize,
+    pub delay_timer_registry: usize,
+    pub sound_timer_registry: usize,
+    // `RAM_SIZE` bytes unless the builder was given another size, see
+    // `Variant::ram_size`. Every address wraps around its end.
+    pub ram: Vec<u8>,
+    pub display: Display,
+    pub keypad: Keypad,
+    pub quirks: Quirks,
+    pub rng: Rng,
+    // Instructions run by `run_frame`, the clock speed divided by 60.
+    pub cycles_per_frame: usize,
+    // Runs `clock_hz` rates that are not whole instructions per frame.
+    pub clock: Clock,
+    // Raised by `run_frame_with` for the first instruction of every frame,
+    // when the VIP's vertical blank interrupt fires. Dxyn waits for it with
+    // the `display_wait` quirk.
+    pub vblank: bool,
+    // Instructions executed since the timers last ticked, how far into the
+    // current 60 Hz period the emulator is.
+    pub timer_cycles: usize,
+    // The CHIP-8X colour and sound boards and second keypad, only present
+    // on that variant.
+    pub chip8x: Option<Chip8x>,
+    // The XO-CHIP audio pattern and pitch, only present on that variant.
+    pub xochip: Option<XoChip>,
+    // The FxF0 output, only present when the builder was asked for it.
+    pub printer: Option<Printer>,
+    // The FxF1 time of day, only present when the builder was asked for it.
+    pub wall_clock: Option<WallClock>,
+    // The FxF2 and FxF3 blocks, only present when given to the builder.
+    pub disk: Option<Disk>,
+    // See `ExecutionHook`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hooks: Hooks,
+}
+
+pub fn pop_from_stack(emulator: &mut Emulator) -> Result<u16, EmulatorError> {
+    if emulator.stack_pointer == 0 {
+        return Err(EmulatorError::StackUnderflow);
+    }
+    emulator.stack_pointer -= 1;
+    let element = emulator.stack[emulator.stack_pointer as usize];
+    emulator.stack[emulator.stack_pointer as usize] = 0;
+    Ok(element)
+}
+
+pub fn push_to_stack(emulator: &mut Emulator, element: u16) -> Result<(), EmulatorError> {
+    if emulator.stack_pointer as usize >= emulator.stack_limit.min(STACK_SIZE) {
+        return Err(EmulatorError::StackOverflow);
+    }
+    emulator.stack[emulator.stack_pointer as usize] = element;
+    emulator.stack_pointer += 1;
+    Ok(())
+}
+
+pub fn load_rom_to_memory(emulator: &mut Emulator, data: &[u8]) -> Result<(), EmulatorError> {
+    load_rom_at(emulator, INITIAL_ADDRESS, data)
+}
+
+// For machines whose programs do not start at 0x200, see
+// `Variant::start_address`.
+pub fn load_rom_at(
+    emulator: &mut Emulator,
+    address: u16,
+    data: &[u8],
+) -> Result<(), EmulatorError> {
+    let start = address as usize;
+    let end = start + data.len();
+    if end > emulator.ram.len() {
+        return Err(EmulatorError::RomTooLarge(data.len()));
+    }
+    emulator.ram[start..end].copy_from_slice(data);
+    Ok(())
+}
+
+pub fn get_op_code(emulator: &Emulator) -> u16 {
+    // Addresses wrap around the end of memory, like I does for DRW.
+    let address = emulator.program_counter as usize;
+    let size = emulator.ram.len();
+    let higher_byte = emulator.ram[address % size] as u16;
+    let lowe_byte = emulator.ram[(address + 1) % size] as u16;
+    (higher_byte << 8) | lowe_byte
+}
+
+// The nibble tuple helpers predate `Instruction`, they stay for old callers
+// but are not part of the stable API.
+#[doc(hidden)]
+pub fn parse_op_code(op_code: u16) -> (u16, u16, u16, u16) {
+    let first = (op_code & 0xF000) >> 12;
+    let second = (op_code & 0x0F00) >> 8;
+    let third = (op_code & 0x00F0) >> 4;
+    let fourth = op_code & 0x000F;
+    (first, second, third, fourth)
+}
+
+#[doc(hidden)]
+pub fn get_nnn(op_code: (u16, u16, u16, u16)) -> u16 {
+    let (_, first, second, third) = op_code;
+    (first << 8) | (second << 4) | third
+}
+
+#[doc(hidden)]
+pub fn get_x(op_code: (u16, u16, u16, u16)) -> u16 {
+    let (_, x, _, _) = op_code;
+    x
+}
+
+#[doc(hidden)]
+pub fn get_kk(op_code: (u16, u16, u16, u16)) -> u16 {
+    let (_, _, first, second) = op_code;
+    (first << 4) | second
+}
+
+#[doc(hidden)]
+pub fn get_y(op_code: (u16, u16, u16, u16)) -> u16 {
+    let (_, _, y, _) = op_code;
+    y
+}
+
+#[doc(hidden)]
+pub fn execute_op_code(
+    emulator: &mut Emulator,
+    op_code: (u16, u16, u16, u16),
+) -> Result<(), EmulatorError> {
+    execute(emulator, Instruction::decode(join_op_code(op_code)))
+}
+
+pub fn execute(emulator: &mut Emulator, instruction: Instruction) -> Result<(), EmulatorError> {
+    let result = execute_unmutated(emulator, instruction);
+    #[cfg(feature = "mutants")]
+    if let Some(mutant) = crate::mutants::active() {
+        mutant.apply(emulator, instruction);
+    }
+    result
+}
+
+fn execute_unmutated(
+    emulator: &mut Emulator,
+    instruction: Instruction,
+) -> Result<(), EmulatorError> {
+    let v = &mut emulator.v_registers;
+    match instruction {
+        Instruction::Nop => (),
+        Instruction::Cls => emulator.display.clear(),
+        Instruction::Ret => {
+            let address = pop_from_stack(emulator)?;
+            emulator.program_counter = address;
+        }
+        Instruction::Jp { nnn } => emulator.program_counter = nnn,
+        Instruction::Call { nnn } => {
+            push_to_stack(emulator, emulator.program_counter)?;
+            emulator.program_counter = nnn;
+        }
+        Instruction::SeByte { x, kk } => {
+            if v[x as usize] == kk {
+                skip(emulator);
+            }
+        }
+        Instruction::SneByte { x, kk } => {
+            if v[x as usize] != kk {
+                skip(emulator);
+            }
+        }
+        Instruction::SeReg { x, y } => {
+            if v[x as usize] == v[y as usize] {
+                skip(emulator);
+            }
+        }
+        Instruction::LdByte { x, kk } => v[x as usize] = kk,
+        Instruction::AddByte { x, kk } => v[x as usize] = v[x as usize].wrapping_add(kk),
+        Instruction::LdReg { x, y } => v[x as usize] = v[y as usize],
+        Instruction::Or { x, y } => {
+            v[x as usize] |= v[y as usize];
+            if emulator.quirks.vf_reset {
+                v[0xF] = 0;
+            }
+        }
+        Instruction::And { x, y } => {
+            v[x as usize] &= v[y as usize];
+            if emulator.quirks.vf_reset {
+                v[0xF] = 0;
+            }
+        }
+        Instruction::Xor { x, y } => {
+            v[x as usize] ^= v[y as usize];
+            if emulator.quirks.vf_reset {
+                v[0xF] = 0;
+            }
+        }
+        Instruction::Add { x, y } => {
+            let (sum, overflow) = v[x as usize].overflowing_add(v[y as usize]);
+            v[x as usize] = sum;
+            v[0xF] = overflow as u8;
+        }
+        Instruction::Sub { x, y } => {
+            let (sub, borrow) = v[x as usize].overflowing_sub(v[y as usize]);
+            v[x as usize] = sub;
+            v[0xF] = !borrow as u8;
+        }
+        Instruction::Shr { x, y } => {
+            let source = shift_source(emulator, x, y);
+            emulator.v_registers[x as usize] = source >> 1;
+            emulator.v_registers[0xF] = source & 1;
+        }
+        Instruction::Subn { x, y } => {
+            let (sub, borrow) = v[y as usize].overflowing_sub(v[x as usize]);
+            v[x as usize] = sub;
+            v[0xF] = !borrow as u8;
+        }
+        Instruction::Shl { x, y } => {
+            let source = shift_source(emulator, x, y);
+            emulator.v_registers[x as usize] = source << 1;
+            emulator.v_registers[0xF] = source >> 7;
+        }
+        Instruction::SneReg { x, y } => {
+            if v[x as usize] != v[y as usize] {
+                skip(emulator);
+            }
+        }
+        Instruction::LdI { nnn } => emulator.i_register = nnn,
+        Instruction::JpV0 { nnn } => {
+            let offset = if emulator.quirks.jump_uses_vx {
+                v[(nnn >> 8) as usize]
+            } else {
+                v[0]
+            };
+            emulator.program_counter = offset as u16 + nnn;
+        }
+        Instruction::Rnd { x, kk } => {
+            v[x as usize] = emulator.rng.next_u8() & kk;
+        }
+        Instruction::Drw { .. } if emulator.quirks.display_wait && !emulator.vblank => {
+            emulator.program_counter = emulator.program_counter.wrapping_sub(2);
+        }
+        Instruction::Drw { x, y, n } => {
+            let mut sprite = [0; 15];
+            for (row, byte) in sprite.iter_mut().take(n as usize).enumerate() {
+                *byte = read(&emulator.ram, emulator.i_register as usize + row);
+            }
+            let vx = v[x as usize] as usize;
+            let vy = v[y as usize] as usize;
+            let clip = emulator.quirks.clipping;
+            let collision = emulator
+                .display
+                .draw_sprite(vx, vy, &sprite[..n as usize], clip);
+            emulator.v_registers[0xF] = collision as u8;
+            emulator
+                .hooks
+                .draw(vx, vy, &sprite[..n as usize], collision);
+        }
+        Instruction::Skp { x } => {
+            if emulator.keypad.is_pressed(v[x as usize]) {
+                skip(emulator);
+            }
+        }
+        Instruction::Sknp { x } => {
+            if !emulator.keypad.is_pressed(v[x as usize]) {
+                skip(emulator);
+            }
+        }
+        // Runs again until a key has been pressed and released.
+        Instruction::LdKey { x } => match emulator.keypad.wait_for_key() {
+            Some(key) => v[x as usize] = key,
+            None => emulator.program_counter = emulator.program_counter.wrapping_sub(2),
+        },
+        Instruction::LdVxDt { x } => v[x as usize] = emulator.delay_timer_registry as u8,
+        Instruction::LdDtVx { x } => emulator.delay_timer_registry = v[x as usize] as usize,
+        Instruction::LdStVx { x } => emulator.sound_timer_registry = v[x as usize] as usize,
+        Instruction::AddI { x } => {
+            emulator.i_register = emulator.i_register.wrapping_add(v[x as usize] as u16);
+        }
+        Instruction::LdF { x } => {
+            // 5 bytes a digit.
+            emulator.i_register = FONT_ADDRESS + (v[x as usize] & 0xF) as u16 * 5;
+        }
+        Instruction::LdBcd { x } => {
+            let vx = v[x as usize];
+            let i = emulator.i_register as usize;
+            for (offset, digit) in [vx / 100, (vx / 10) % 10, vx % 10].into_iter().enumerate() {
+                write(emulator, i + offset, digit);
+            }
+        }
+        Instruction::Store { x } => {
+            let i = emulator.i_register as usize;
+            for offset in 0..=x as usize {
+                write(emulator, i + offset, emulator.v_registers[offset]);
+            }
+            if emulator.quirks.memory_increments_i {
+                emulator.i_register = emulator.i_register.wrapping_add(x as u16 + 1);
+            }
+        }
+        Instruction::Load { x } => {
+            let i = emulator.i_register as usize;
+            for (offset, value) in v.iter_mut().enumerate().take(x as usize + 1) {
+                *value = read(&emulator.ram, i + offset);
+            }
+            if emulator.quirks.memory_increments_i {
+                emulator.i_register = emulator.i_register.wrapping_add(x as u16 + 1);
+            }
+        }
+        Instruction::NextBackground => chip8x(&mut emulator.chip8x, instruction)?.next_background(),
+        Instruction::AddBcd { x, y } => {
+            chip8x(&mut emulator.chip8x, instruction)?;
+            v[x as usize] = add_bcd(v[x as usize], v[y as usize]);
+        }
+        Instruction::Color { x, y, n } => {
+            let chip8x = chip8x(&mut emulator.chip8x, instruction)?;
+            let (vx, vx1, color) = (v[x as usize], v[(x as usize + 1) & 0xF], v[y as usize]);
+            if n == 0 {
+                chip8x.color_zones(vx, vx1, color);
+            } else {
+                chip8x.color_rows(vx, vx1, n, color);
+            }
+        }
+        Instruction::Skp2 { x } => {
+            if chip8x(&mut emulator.chip8x, instruction)?
+                .keypad
+                .is_pressed(v[x as usize])
+            {
+                skip(emulator);
+            }
+        }
+        Instruction::Sknp2 { x } => {
+            if !chip8x(&mut emulator.chip8x, instruction)?
+                .keypad
+                .is_pressed(v[x as usize])
+            {
+                skip(emulator);
+            }
+        }
+        Instruction::Out { x } => chip8x(&mut emulator.chip8x, instruction)?.tone = v[x as usize],
+        Instruction::In { x } => v[x as usize] = chip8x(&mut emulator.chip8x, instruction)?.input,
+        Instruction::Low => emulator.display.set_resolution(Resolution::Lores),
+        Instruction::High => emulator.display.set_resolution(Resolution::Hires),
+        Instruction::Audio => {
+            let xochip = xochip(&mut emulator.xochip, instruction)?;
+            let mut pattern = [0; PATTERN_SIZE];
+            for (offset, byte) in pattern.iter_mut().enumerate() {
+                *byte = read(&emulator.ram, emulator.i_register as usize + offset);
+            }
+            xochip.pattern = Some(pattern);
+        }
+        Instruction::Pitch { x } => {
+            xochip(&mut emulator.xochip, instruction)?.pitch = v[x as usize]
+        }
+        Instruction::SaveFlags { x } => {
+            let xochip = xochip(&mut emulator.xochip, instruction)?;
+            xochip.flags[..=x as usize].copy_from_slice(&v[..=x as usize]);
+        }
+        Instruction::LoadFlags { x } => {
+            let flags = xochip(&mut emulator.xochip, instruction)?.flags;
+            v[..=x as usize].copy_from_slice(&flags[..=x as usize]);
+        }
+        Instruction::Print { x } => {
+            let byte = v[x as usize];
+            emulator
+                .printer
+                .as_mut()
+                .ok_or(EmulatorError::Unimplemented(
+                    instruction.encode(),
+                    "the printer",
+                ))?
+                .print(byte);
+        }
+        Instruction::Time { x } => {
+            let time = emulator
+                .wall_clock
+                .ok_or(EmulatorError::Unimplemented(
+                    instruction.encode(),
+                    "the wall clock",
+                ))?
+                .time_of_day();
+            for (offset, value) in time.into_iter().enumerate() {
+                v[(x as usize + offset) % V_REGISTERS_NUMBER] = value;
+            }
+        }
+        Instruction::DiskRead { x } => {
+            let mut block = [0; BLOCK_SIZE];
+            block.copy_from_slice(disk(&mut emulator.disk, instruction)?.block(v[x as usize])?);
+            let i = emulator.i_register as usize;
+            for (offset, value) in block.into_iter().enumerate() {
+                write(emulator, i + offset, value);
+            }
+        }
+        Instruction::DiskWrite { x } => {
+            let i = emulator.i_register as usize;
+            let mut block = [0; BLOCK_SIZE];
+            for (offset, value) in block.iter_mut().enumerate() {
+                *value = read(&emulator.ram, i + offset);
+            }
+            disk(&mut emulator.disk, instruction)?.write_block(v[x as usize], &block)?;
+        }
+        Instruction::LongI => {
+            xochip(&mut emulator.xochip, instruction)?;
+            emulator.i_register = get_op_code(emulator);
+            emulator.program_counter = emulator.program_counter.wrapping_add(2);
+        }
+        Instruction::Unknown(op_code) => return Err(EmulatorError::UnknownOpcode(op_code)),
+    }
+    Ok(())
+}
+
+fn chip8x(
+    hardware: &mut Option<Chip8x>,
+    instruction: Instruction,
+) -> Result<&mut Chip8x, EmulatorError> {
+    hardware.as_mut().ok_or(EmulatorError::Unimplemented(
+        instruction.encode(),
+        "CHIP-8X",
+    ))
+}
+
+fn xochip(
+    hardware: &mut Option<XoChip>,
+    instruction: Instruction,
+) -> Result<&mut XoChip, EmulatorError> {
+    hardware.as_mut().ok_or(EmulatorError::Unimplemented(
+        instruction.encode(),
+        "XO-CHIP",
+    ))
+}
+
+fn disk(disk: &mut Option<Disk>, instruction: Instruction) -> Result<&mut Disk, EmulatorError> {
+    disk.as_mut()
+        .ok_or(EmulatorError::Unimplemented(instruction.encode(), "a disk"))
+}
+
+// Every skip goes through here: over F000 NNNN on XO-CHIP it takes four
+// bytes, so it never lands on the address.
+fn skip(emulator: &mut Emulator) {
+    let next = Instruction::decode(get_op_code(emulator));
+    let size = match emulator.xochip {
+        Some(_) => next.size(),
+        None => 2,
+    };
+    emulator.program_counter = emulator.program_counter.wrapping_add(size);
+}
+
+fn read(ram: &[u8], address: usize) -> u8 {
+    ram[address % ram.len()]
+}
+
+fn write(emulator: &mut Emulator, address: usize, value: u8) {
+    let address = address % emulator.ram.len();
+    emulator.ram[address] = value;
+    emulator.hooks.memory_write(address as u16, value);
+}
+
+fn shift_source(emulator: &Emulator, x: u8, y: u8) -> u8 {
+    if emulator.quirks.shift_uses_vy {
+        emulator.v_registers[y as usize]
+    } else {
+        emulator.v_registers[x as usize]
+    }
+}
+
+fn join_op_code(op_code: (u16, u16, u16, u16)) -> u16 {
+    let (first, second, third, fourth) = op_code;
+    (first << 12) | (second << 8) | (third << 4) | fourth
+}
+
+pub fn step(emulator: &mut Emulator) -> Result<(), EmulatorError> {
+    emulator.keypad.tick(emulator.cycles_per_frame as u64);
+    emulator.timer_cycles = emulator.timer_cycles.saturating_add(1);
+    let op_code = get_op_code(emulator);
+    emulator.program_counter = emulator.program_counter.wrapping_add(2);
+    let instruction = match &mut emulator.chip8x {
+        Some(chip8x) => {
+            chip8x.keypad.tick(emulator.cycles_per_frame as u64);
+            Instruction::decode_chip8x(op_code)
+        }
+        None => Instruction::decode(op_code),
+    };
+    if emulator.hooks.is_empty() {
+        return execute(emulator, instruction);
+    }
+    with_hooks(emulator, |hook, emulator| {
+        hook.before_step(emulator, instruction)
+    });
+    let result = execute(emulator, instruction);
+    with_hooks(emulator, |hook, emulator| {
+        hook.after_step(emulator, instruction, &result)
+    });
+    result
+}
+
+pub fn tick_timers(emulator: &mut Emulator) {
+    emulator.delay_timer_registry = emulator.delay_timer_registry.saturating_sub(1);
+    emulator.sound_timer_registry = emulator.sound_timer_registry.saturating_sub(1);
+    emulator.timer_cycles = 0;
+}
+
+// Fraction of the way to the next timer tick, from 0 right after a tick to
+// almost 1 just before the next one.
+pub fn timer_phase(emulator: &Emulator) -> f32 {
+    let cycles = emulator.timer_cycles.min(emulator.cycles_per_frame);
+    cycles as f32 / emulator.cycles_per_frame.max(1) as f32
+}
+
+// Seconds until the sound timer runs out, counting the part of the current
+// tick that has already passed. For audio backends that end the beep
+// inside a buffer rather than on a frame boundary.
+pub fn sound_time_left(emulator: &Emulator) -> f32 {
+    let ticks = emulator.sound_timer_registry as f32 - timer_phase(emulator);
+    ticks.max(0.0) / 60.0
+}
+
+// Frames report the sound timer after it was ticked, so setting it to 1
+// never beeps: like on the COSMAC VIP, 2 is the shortest audible value.
+pub fn is_beeping(emulator: &Emulator) -> bool {
+    emulator.sound_timer_registry > 0
+}
+
+// Runs one 60 Hz frame worth of instructions and reports what a frontend
+// has to react to. Execution stops at the first error. Neither this nor
+// `step` allocate, for the embedded target and steady frame times, see
+// tests/alloc.rs.
+pub fn run_frame(emulator: &mut Emulator) -> FrameOutput {
+    run_frame_with(emulator, step)
+}
+
+// Same as `run_frame`, with every instruction going through `step` instead,
+// for callers that want to watch or record each one.
+pub fn run_frame_with(
+    emulator: &mut Emulator,
+    mut step: impl FnMut(&mut Emulator) -> Result<(), EmulatorError>,
+) -> FrameOutput {
+    let was_beeping = is_beeping(emulator);
+    let mut output = FrameOutput::default();
+    let cycles = emulator.clock.next_frame(emulator.cycles_per_frame);
+    for cycle in 0..cycles {
+        emulator.vblank = cycle == 0;
+        let result = step(emulator);
+        emulator.vblank = false;
+        if let Err(error) = result {
+            output.display_changed = emulator.display.take_dirty();
+            output.error = Some(error);
+            return output;
+        }
+    }
+    tick_timers(emulator);
+
+    output.display_changed = emulator.display.take_dirty();
+    output.waiting_for_key = emulator.keypad.is_waiting();
+    let beeping = is_beeping(emulator);
+    output.beep_started = !was_beeping && beeping;
+    output.beep_stopped = was_beeping && !beeping;
+    output
+}
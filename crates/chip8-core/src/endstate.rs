@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::checksum::StableHasher;
+use crate::{get_op_code, Emulator, EmulatorError, FrameOutput};
+
+// Distinct screens a run has to show before it counts as playing, title
+// screens usually alternate between a handful.
+const GAMEPLAY_SCREENS: usize = 8;
+// Two seconds without a screen change is a frozen display.
+const FROZEN_FRAMES: u32 = 120;
+// A beep this close to the last screen change is the game-over jingle.
+const BEEP_WINDOW: u32 = 60;
+
+// Where a batch run ended up, for the compatibility reporter and bots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndState {
+    Crashed(EmulatorError),
+    NeverDrew,
+    // Drew a few screens and then waited for input or sat still.
+    StuckAtTitle,
+    // Drew something and jumped to itself without ever animating, what
+    // demos and test ROMs do once they are done.
+    Halted,
+    ReachedGameplay,
+    // Played for a while, then froze after a beep or jumped to itself.
+    GameOver,
+}
+
+impl EndState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EndState::Crashed(_) => "crashed",
+            EndState::NeverDrew => "never drew",
+            EndState::StuckAtTitle => "stuck at title",
+            EndState::Halted => "halted",
+            EndState::ReachedGameplay => "reached gameplay",
+            EndState::GameOver => "game over",
+        }
+    }
+}
+
+impl fmt::Display for EndState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EndState::Crashed(error) => write!(f, "crashed: {}", error),
+            state => write!(f, "{}", state.label()),
+        }
+    }
+}
+
+type Hook = Box<dyn FnMut(&Emulator) -> Option<EndState>>;
+
+// Watches a run frame by frame, fed by the headless runner like the
+// watchdog, and guesses how it ended. ROM specific knowledge, e.g. "the
+// score lives at 0x3A0", goes in hooks: the first hook to return a state
+// decides the outcome.
+#[derive(Default)]
+pub struct Classifier {
+    frames: u32,
+    drew: bool,
+    screens: HashSet<u64>,
+    last_change: u32,
+    last_beep: Option<u32>,
+    halted: bool,
+    error: Option<EmulatorError>,
+    decided: Option<EndState>,
+    hooks: Vec<Hook>,
+}
+
+impl Classifier {
+    pub fn new() -> Self {
+        Classifier::default()
+    }
+
+    pub fn hook(&mut self, hook: impl FnMut(&Emulator) -> Option<EndState> + 'static) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    pub fn observe(&mut self, emulator: &Emulator, output: &FrameOutput) {
+        self.frames += 1;
+        if self.decided.is_none() {
+            self.decided = self.hooks.iter_mut().find_map(|hook| hook(emulator));
+        }
+        if output.display_changed {
+            self.last_change = self.frames;
+            self.drew |= !emulator.display.is_blank();
+            let mut hasher = StableHasher::new();
+            emulator.display.hash(&mut hasher);
+            self.screens.insert(hasher.finish());
+        }
+        if output.beep_started {
+            self.last_beep = Some(self.frames);
+        }
+        self.halted = get_op_code(emulator) == 0x1000 | emulator.program_counter;
+        if let Some(error) = &output.error {
+            self.error = Some(error.clone());
+        }
+    }
+
+    pub fn classify(&self) -> EndState {
+        if let Some(state) = &self.decided {
+            return state.clone();
+        }
+        if let Some(error) = &self.error {
+            return EndState::Crashed(error.clone());
+        }
+        if !self.drew {
+            return EndState::NeverDrew;
+        }
+        if self.screens.len() < GAMEPLAY_SCREENS {
+            // Including ROMs waiting for a key, there is no input in batch
+            // runs.
+            return if self.halted {
+                EndState::Halted
+            } else {
+                EndState::StuckAtTitle
+            };
+        }
+        let frozen = self.frames - self.last_change >= FROZEN_FRAMES;
+        let beeped_last = self
+            .last_beep
+            .is_some_and(|beep| beep + BEEP_WINDOW >= self.last_change);
+        if self.halted || (frozen && beeped_last) {
+            EndState::GameOver
+        } else {
+            EndState::ReachedGameplay
+        }
+    }
+}
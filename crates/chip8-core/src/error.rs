@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::disk::BLOCK_COUNT;
+
+// New variants are not a breaking change, match with a catch-all arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum EmulatorError {
+    UnknownOpcode(u16),
+    // Valid opcode whose subsystem does not exist yet.
+    Unimplemented(u16, &'static str),
+    StackOverflow,
+    StackUnderflow,
+    RomTooLarge(usize),
+    // Rejected by `EmulatorBuilder::build`.
+    InvalidConfig(String),
+    // Rejected by `savestate::load`.
+    InvalidSaveState(String),
+    // FxF2 or FxF3 past the end of the disk, see `disk`.
+    DiskBlockOutOfRange(u8),
+    // Raised by frontends stopping at what `strict` finds.
+    UndefinedBehavior(String),
+    // Raised by frontends stopping at what a `breakpoint` hits.
+    Breakpoint(String),
+}
+
+impl fmt::Display for EmulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmulatorError::UnknownOpcode(op_code) => write!(f, "unknown opcode {:#06x}", op_code),
+            EmulatorError::Unimplemented(op_code, feature) => {
+                write!(f, "opcode {:#06x} requires {}", op_code, feature)
+            }
+            EmulatorError::StackOverflow => write!(f, "stack overflow"),
+            EmulatorError::StackUnderflow => write!(f, "return with an empty stack"),
+            EmulatorError::RomTooLarge(size) => {
+                write!(f, "ROM of {} bytes does not fit in memory", size)
+            }
+            EmulatorError::InvalidConfig(message) => {
+                write!(f, "invalid configuration: {}", message)
+            }
+            EmulatorError::InvalidSaveState(message) => {
+                write!(f, "invalid savestate: {}", message)
+            }
+            EmulatorError::DiskBlockOutOfRange(block) => {
+                write!(
+                    f,
+                    "disk block {} is past the last one, {}",
+                    block,
+                    BLOCK_COUNT - 1
+                )
+            }
+            EmulatorError::UndefinedBehavior(finding) => {
+                write!(f, "undefined behavior at {}", finding)
+            }
+            EmulatorError::Breakpoint(hit) => write!(f, "breakpoint at {}", hit),
+        }
+    }
+}
+
+impl Error for EmulatorError {}
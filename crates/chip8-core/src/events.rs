@@ -0,0 +1,34 @@
+// Session-level happenings frontends may want to react to, e.g. to retitle
+// the window or log what the user did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    RomLoaded { name: String },
+    SpeedChanged { percent: u32 },
+    Paused,
+    Resumed,
+    // A per-ROM memory trigger was satisfied, e.g. an achievement.
+    TriggerFired { name: String, message: String },
+}
+
+type Subscriber = Box<dyn FnMut(&Event) + Send>;
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<Subscriber>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus::default()
+    }
+
+    pub fn subscribe(&mut self, subscriber: impl FnMut(&Event) + Send + 'static) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    pub fn emit(&mut self, event: Event) {
+        for subscriber in self.subscribers.iter_mut() {
+            subscriber(&event);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::host::Files;
+
+// Where the interpreter area keeps the fonts. Nothing requires these,
+// ROMs find the digits through Fx29 and Fx30, but most emulators agree on
+// 0x50 and some badly written ROMs depend on it.
+pub const FONT_ADDRESS: u16 = 0x050;
+pub const LARGE_FONT_ADDRESS: u16 = 0x0A0;
+
+// 16 digits of 5 rows, 4 pixels wide.
+pub const SMALL_FONT_SIZE: usize = 80;
+// SUPER-CHIP's 10 digits of 10 rows, 8 pixels wide.
+pub const LARGE_FONT_SIZE: usize = 100;
+
+// The CHIP-48 font nearly every interpreter since has copied.
+#[rustfmt::skip]
+const CHIP48: [u8; SMALL_FONT_SIZE] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x20, 0x60, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+// The COSMAC VIP's, with the square 7 and boxy B and D.
+#[rustfmt::skip]
+const VIP: [u8; SMALL_FONT_SIZE] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0,
+    0x60, 0x20, 0x20, 0x20, 0x70,
+    0xF0, 0x10, 0xF0, 0x80, 0xF0,
+    0xF0, 0x10, 0xF0, 0x10, 0xF0,
+    0xA0, 0xA0, 0xF0, 0x20, 0x20,
+    0xF0, 0x80, 0xF0, 0x10, 0xF0,
+    0xF0, 0x80, 0xF0, 0x90, 0xF0,
+    0xF0, 0x10, 0x10, 0x10, 0x10,
+    0xF0, 0x90, 0xF0, 0x90, 0xF0,
+    0xF0, 0x90, 0xF0, 0x10, 0xF0,
+    0xF0, 0x90, 0xF0, 0x90, 0x90,
+    0xF0, 0x50, 0x70, 0x50, 0xF0,
+    0xF0, 0x80, 0x80, 0x80, 0xF0,
+    0xF0, 0x50, 0x50, 0x50, 0xF0,
+    0xF0, 0x80, 0xF0, 0x80, 0xF0,
+    0xF0, 0x80, 0xF0, 0x80, 0x80,
+];
+
+// The DREAM 6800's, only 3 pixels wide.
+#[rustfmt::skip]
+const DREAM_6800: [u8; SMALL_FONT_SIZE] = [
+    0xE0, 0xA0, 0xA0, 0xA0, 0xE0,
+    0x40, 0x40, 0x40, 0x40, 0x40,
+    0xE0, 0x20, 0xE0, 0x80, 0xE0,
+    0xE0, 0x20, 0xE0, 0x20, 0xE0,
+    0x80, 0xA0, 0xA0, 0xE0, 0x20,
+    0xE0, 0x80, 0xE0, 0x20, 0xE0,
+    0xE0, 0x80, 0xE0, 0xA0, 0xE0,
+    0xE0, 0x20, 0x20, 0x20, 0x20,
+    0xE0, 0xA0, 0xE0, 0xA0, 0xE0,
+    0xE0, 0xA0, 0xE0, 0x20, 0xE0,
+    0xE0, 0xA0, 0xE0, 0xA0, 0xA0,
+    0xC0, 0xA0, 0xE0, 0xA0, 0xC0,
+    0xE0, 0x80, 0x80, 0x80, 0xE0,
+    0xC0, 0xA0, 0xA0, 0xA0, 0xC0,
+    0xE0, 0x80, 0xE0, 0x80, 0xE0,
+    0xE0, 0x80, 0xC0, 0x80, 0x80,
+];
+
+// Fish 'N' Chips, a rounder 3 pixel font from the Octo community.
+#[rustfmt::skip]
+const FISH_N_CHIPS: [u8; SMALL_FONT_SIZE] = [
+    0x60, 0xA0, 0xA0, 0xA0, 0xC0,
+    0x40, 0xC0, 0x40, 0x40, 0xE0,
+    0xC0, 0x20, 0x40, 0x80, 0xE0,
+    0xC0, 0x20, 0x40, 0x20, 0xC0,
+    0x20, 0xA0, 0xE0, 0x20, 0x20,
+    0xE0, 0x80, 0xC0, 0x20, 0xC0,
+    0x40, 0x80, 0xC0, 0xA0, 0x40,
+    0xE0, 0x20, 0x60, 0x40, 0x40,
+    0x40, 0xA0, 0x40, 0xA0, 0x40,
+    0x40, 0xA0, 0x60, 0x20, 0x40,
+    0x40, 0xA0, 0xE0, 0xA0, 0xA0,
+    0xC0, 0xA0, 0xC0, 0xA0, 0xC0,
+    0x60, 0x80, 0x80, 0x80, 0x60,
+    0xC0, 0xA0, 0xA0, 0xA0, 0xC0,
+    0xE0, 0x80, 0xC0, 0x80, 0xE0,
+    0xE0, 0x80, 0xC0, 0x80, 0x80,
+];
+
+#[rustfmt::skip]
+const SCHIP_LARGE: [u8; LARGE_FONT_SIZE] = [
+    0x3C, 0x7E, 0xE7, 0xC3, 0xC3, 0xC3, 0xC3, 0xE7, 0x7E, 0x3C, // 0
+    0x18, 0x38, 0x58, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x3C, // 1
+    0x3E, 0x7F, 0xC3, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFF, 0xFF, // 2
+    0x3C, 0x7E, 0xC3, 0x03, 0x0E, 0x0E, 0x03, 0xC3, 0x7E, 0x3C, // 3
+    0x06, 0x0E, 0x1E, 0x36, 0x66, 0xC6, 0xFF, 0xFF, 0x06, 0x06, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFE, 0x03, 0xC3, 0x7E, 0x3C, // 5
+    0x3E, 0x7C, 0xC0, 0xC0, 0xFC, 0xFE, 0xC3, 0xC3, 0x7E, 0x3C, // 6
+    0xFF, 0xFF, 0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x60, 0x60, // 7
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C, // 8
+    0x3C, 0x7E, 0xC3, 0xC3, 0x7F, 0x3F, 0x03, 0x03, 0x3E, 0x7C, // 9
+];
+
+// Built-in fonts by the name `--font` and the ROM config take.
+pub const NAMES: [&str; 4] = ["chip48", "vip", "dream6800", "fishnchips"];
+
+// The digit sprites written into the interpreter area. Games draw their
+// scores with them, so a different font is one of the first things
+// players notice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    pub small: [u8; SMALL_FONT_SIZE],
+    pub large: [u8; LARGE_FONT_SIZE],
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Font {
+            small: CHIP48,
+            large: SCHIP_LARGE,
+        }
+    }
+}
+
+impl Font {
+    // A font file is the 80 small font bytes, optionally followed by the
+    // 100 of the large font. Without them the SUPER-CHIP large font stays.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let mut font = Font::default();
+        match data.len() {
+            SMALL_FONT_SIZE => font.small.copy_from_slice(data),
+            len if len == SMALL_FONT_SIZE + LARGE_FONT_SIZE => {
+                let (small, large) = data.split_at(SMALL_FONT_SIZE);
+                font.small.copy_from_slice(small);
+                font.large.copy_from_slice(large);
+            }
+            len => {
+                return Err(format!(
+                    "a font is {} or {} bytes, not {}",
+                    SMALL_FONT_SIZE,
+                    SMALL_FONT_SIZE + LARGE_FONT_SIZE,
+                    len
+                ))
+            }
+        }
+        Ok(font)
+    }
+
+    // A built-in font name, or else the path of a font file.
+    pub fn open(files: &dyn Files, name_or_path: &str) -> Result<Self, String> {
+        if let Ok(font) = name_or_path.parse() {
+            return Ok(font);
+        }
+        let data = files
+            .read(Path::new(name_or_path))
+            .map_err(|error| format!("cannot read the font {}: {}", name_or_path, error))?;
+        Font::from_bytes(&data)
+    }
+
+    // The 5 bytes of hexadecimal `digit`.
+    pub fn digit(&self, digit: u8) -> &[u8] {
+        let start = (digit & 0xF) as usize * 5;
+        &self.small[start..start + 5]
+    }
+
+    pub(crate) fn write_to(&self, ram: &mut [u8]) {
+        let small = FONT_ADDRESS as usize;
+        let large = LARGE_FONT_ADDRESS as usize;
+        ram[small..small + SMALL_FONT_SIZE].copy_from_slice(&self.small);
+        ram[large..large + LARGE_FONT_SIZE].copy_from_slice(&self.large);
+    }
+}
+
+impl FromStr for Font {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let small = match text {
+            "chip48" => CHIP48,
+            "vip" => VIP,
+            "dream6800" => DREAM_6800,
+            "fishnchips" => FISH_N_CHIPS,
+            _ => {
+                return Err(format!(
+                    "unknown font {:?}, expected {}",
+                    text,
+                    NAMES.join(", ")
+                ))
+            }
+        };
+        Ok(Font {
+            small,
+            large: SCHIP_LARGE,
+        })
+    }
+}
@@ -0,0 +1,15 @@
+use crate::error::EmulatorError;
+
+// Everything a frontend needs to know after a call to `run_frame`, so it
+// never has to poll the emulator internals.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FrameOutput {
+    pub display_changed: bool,
+    pub beep_started: bool,
+    pub beep_stopped: bool,
+    // The frame ended on Fx0A, which runs again until a key is pressed and
+    // released. Frontends can prompt for a key or stop fast-forwarding.
+    pub waiting_for_key: bool,
+    pub error: Option<EmulatorError>,
+}
@@ -0,0 +1,129 @@
+use crate::{get_op_code, step, Emulator, EmulatorError, Instruction, RAM_SIZE};
+
+// One cell per byte of RAM.
+pub const HEATMAP_SIDE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+// A range of RAM an instruction reads or writes through I.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Access {
+    pub kind: AccessKind,
+    pub start: u16,
+    pub len: u16,
+}
+
+impl Access {
+    // Addresses touched, wrapping at the end of memory like the core does.
+    pub fn addresses(&self) -> impl Iterator<Item = usize> {
+        let start = self.start as usize;
+        (0..self.len as usize).map(move |offset| (start + offset) % RAM_SIZE)
+    }
+}
+
+// The memory `instruction` accesses when executed on `emulator`, besides
+// its own fetch.
+pub fn memory_access(emulator: &Emulator, instruction: Instruction) -> Option<Access> {
+    let (kind, len) = match instruction {
+        Instruction::Drw { .. } if emulator.quirks.display_wait && !emulator.vblank => return None,
+        Instruction::Drw { n, .. } => (AccessKind::Read, n as u16),
+        Instruction::Load { x } => (AccessKind::Read, x as u16 + 1),
+        Instruction::Store { x } => (AccessKind::Write, x as u16 + 1),
+        Instruction::LdBcd { .. } => (AccessKind::Write, 3),
+        _ => return None,
+    };
+    Some(Access {
+        kind,
+        start: emulator.i_register,
+        len,
+    })
+}
+
+// How often every byte of RAM was read, written and executed. Only the
+// first `RAM_SIZE` bytes are counted, XO-CHIP's memory above them folds
+// onto the same cells.
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    pub reads: Vec<u32>,
+    pub writes: Vec<u32>,
+    pub executes: Vec<u32>,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Heatmap {
+            reads: vec![0; RAM_SIZE],
+            writes: vec![0; RAM_SIZE],
+            executes: vec![0; RAM_SIZE],
+        }
+    }
+}
+
+impl Heatmap {
+    pub fn new() -> Self {
+        Heatmap::default()
+    }
+
+    // Executes one instruction like `emulator::step` and counts the memory
+    // it touches, can be passed to `run_frame_with`.
+    pub fn step(&mut self, emulator: &mut Emulator) -> Result<(), EmulatorError> {
+        let pc = emulator.program_counter as usize;
+        self.executes[pc % RAM_SIZE] += 1;
+        self.executes[(pc + 1) % RAM_SIZE] += 1;
+        let instruction = Instruction::decode(get_op_code(emulator));
+        if let Some(access) = memory_access(emulator, instruction) {
+            let counts = match access.kind {
+                AccessKind::Read => &mut self.reads,
+                AccessKind::Write => &mut self.writes,
+            };
+            for address in access.addresses() {
+                counts[address] += 1;
+            }
+        }
+        step(emulator)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Heatmap::new();
+    }
+
+    // 64x64 RGBA pixels, row-major from address 0: writes in red, reads in
+    // green and executes in blue. Counts are log scaled, so a byte touched
+    // once is still visible next to the main loop.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let scales = [
+            log_scale(&self.writes),
+            log_scale(&self.reads),
+            log_scale(&self.executes),
+        ];
+        let mut pixels = Vec::with_capacity(RAM_SIZE * 4);
+        for address in 0..RAM_SIZE {
+            for (channel, scale) in [&self.writes, &self.reads, &self.executes]
+                .iter()
+                .zip(scales)
+            {
+                pixels.push(intensity(channel[address], scale));
+            }
+            pixels.push(0xFF);
+        }
+        pixels
+    }
+}
+
+fn log_scale(counts: &[u32]) -> f32 {
+    let max = counts.iter().copied().max().unwrap_or_default();
+    (max as f32 + 1.0).log2()
+}
+
+fn intensity(count: u32, scale: f32) -> u8 {
+    if count == 0 {
+        return 0;
+    }
+    // The faintest used byte still gets a quarter of the brightness.
+    let level = (count as f32 + 1.0).log2() / scale;
+    (64.0 + level * 191.0) as u8
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+use crate::{Emulator, EmulatorError, Instruction};
+
+// Called by `step` around every instruction, for profilers, tracers,
+// coverage and cheats built outside the core. Every method does nothing by
+// default, implement the ones needed. Hooks have to be `Send + Sync`,
+// like the emulator they are added to:
+//
+//     struct Lives;
+//     impl ExecutionHook for Lives {
+//         fn after_step(&mut self, emulator: &mut Emulator, _: Instruction, _: &Result<(), EmulatorError>) {
+//             emulator.v_registers[0xB] = 3;
+//         }
+//     }
+//     emulator.hooks.add(Lives);
+pub trait ExecutionHook: Send + Sync {
+    // The instruction has been fetched and PC already points past it.
+    fn before_step(&mut self, _emulator: &mut Emulator, _instruction: Instruction) {}
+
+    fn after_step(
+        &mut self,
+        _emulator: &mut Emulator,
+        _instruction: Instruction,
+        _result: &Result<(), EmulatorError>,
+    ) {
+    }
+
+    // Fx33 and Fx55, one call per byte.
+    fn on_memory_write(&mut self, _address: u16, _value: u8) {}
+
+    // Dxyn at (`x`, `y`) before clipping or wrapping.
+    fn on_draw(&mut self, _x: usize, _y: usize, _sprite: &[u8], _collision: bool) {}
+}
+
+// The hooks registered on an emulator. They watch that one machine: a
+// clone or a loaded savestate starts without any, and they are not part of
+// its hash or serialised state.
+#[derive(Default)]
+pub struct Hooks(Vec<Box<dyn ExecutionHook>>);
+
+impl Hooks {
+    pub fn add(&mut self, hook: impl ExecutionHook + 'static) {
+        self.0.push(Box::new(hook));
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn memory_write(&mut self, address: u16, value: u8) {
+        for hook in self.0.iter_mut() {
+            hook.on_memory_write(address, value);
+        }
+    }
+
+    pub(crate) fn draw(&mut self, x: usize, y: usize, sprite: &[u8], collision: bool) {
+        for hook in self.0.iter_mut() {
+            hook.on_draw(x, y, sprite, collision);
+        }
+    }
+}
+
+// The hooks are taken out of the emulator to hand them the emulator
+// itself. Hooks added meanwhile are kept.
+pub(crate) fn with_hooks(
+    emulator: &mut Emulator,
+    call: impl Fn(&mut dyn ExecutionHook, &mut Emulator),
+) {
+    let mut hooks = mem::take(&mut emulator.hooks);
+    for hook in hooks.0.iter_mut() {
+        call(hook.as_mut(), emulator);
+    }
+    hooks.0.append(&mut emulator.hooks.0);
+    emulator.hooks = hooks;
+}
+
+impl Clone for Hooks {
+    fn clone(&self) -> Self {
+        Hooks::default()
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hooks({})", self.0.len())
+    }
+}
+
+impl Hash for Hooks {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
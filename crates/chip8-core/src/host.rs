@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// What the tools need from the machine they run on, so the headless runner
+// also works where the real file system or clock is missing or off limits:
+// a wasm32-wasi sandbox with only some directories preopened, or a test.
+
+pub trait Files: Send + Sync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    // Creates missing parent directories.
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()>;
+}
+
+pub trait Clock: Send + Sync {
+    fn unix_seconds(&self) -> u64;
+}
+
+// `std::fs`, which on WASI is whatever the runtime preopened.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdFiles;
+
+impl Files for StdFiles {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, data)
+    }
+}
+
+// Files kept in memory, for sandboxes without any file system at all.
+#[derive(Debug, Default)]
+pub struct MemoryFiles(Mutex<BTreeMap<PathBuf, Vec<u8>>>);
+
+impl MemoryFiles {
+    pub fn new() -> Self {
+        MemoryFiles::default()
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.0.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+impl Files for MemoryFiles {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such file"))
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), data.to_vec());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn unix_seconds(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs())
+    }
+}
+
+// Always the same time, for reproducible file names.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn unix_seconds(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+// Emulator-level commands, as opposed to CHIP-8 keypad input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    SaveState(u8),
+    LoadState(u8),
+    ToggleTurbo,
+    TogglePause,
+    Step,
+    Reset,
+    Screenshot,
+    Quit,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::SaveState(slot) => write!(f, "save_state {}", slot),
+            Action::LoadState(slot) => write!(f, "load_state {}", slot),
+            Action::ToggleTurbo => write!(f, "toggle_turbo"),
+            Action::TogglePause => write!(f, "toggle_pause"),
+            Action::Step => write!(f, "step"),
+            Action::Reset => write!(f, "reset"),
+            Action::Screenshot => write!(f, "screenshot"),
+            Action::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut words = text.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let slot = words.next().map(|slot| slot.parse::<u8>());
+        let action = match (name, slot) {
+            ("save_state", Some(Ok(slot))) => Action::SaveState(slot),
+            ("load_state", Some(Ok(slot))) => Action::LoadState(slot),
+            ("toggle_turbo", None) => Action::ToggleTurbo,
+            ("toggle_pause", None) => Action::TogglePause,
+            ("step", None) => Action::Step,
+            ("reset", None) => Action::Reset,
+            ("screenshot", None) => Action::Screenshot,
+            ("quit", None) => Action::Quit,
+            _ => return Err(format!("unknown action {:?}", text)),
+        };
+        if words.next().is_some() {
+            return Err(format!("unknown action {:?}", text));
+        }
+        Ok(action)
+    }
+}
+
+// Maps host key chords such as "F5" or "Shift+F5" to actions. Frontends
+// translate their native key events into the chord name, so every backend
+// shares the same bindings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkeys {
+    bindings: HashMap<String, Action>,
+}
+
+impl Hotkeys {
+    pub fn empty() -> Self {
+        Hotkeys {
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn bind(&mut self, chord: &str, action: Action) {
+        self.bindings.insert(normalize_chord(chord), action);
+    }
+
+    pub fn unbind(&mut self, chord: &str) {
+        self.bindings.remove(&normalize_chord(chord));
+    }
+
+    pub fn action_for(&self, chord: &str) -> Option<Action> {
+        self.bindings.get(&normalize_chord(chord)).copied()
+    }
+
+    // Reads `chord = action` lines, e.g. `shift+f5 = save_state 1`. Blank
+    // lines and `#` comments are skipped.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut hotkeys = Hotkeys::empty();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (chord, action) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `chord = action`", number + 1))?;
+            let action = action
+                .trim()
+                .parse()
+                .map_err(|error| format!("line {}: {}", number + 1, error))?;
+            hotkeys.bind(chord.trim(), action);
+        }
+        Ok(hotkeys)
+    }
+
+    pub fn to_config(&self) -> String {
+        let mut lines: Vec<String> = self
+            .bindings
+            .iter()
+            .map(|(chord, action)| format!("{} = {}", chord, action))
+            .collect();
+        lines.sort();
+        lines.join("\n")
+    }
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        let mut hotkeys = Hotkeys::empty();
+        for slot in 0..4 {
+            hotkeys.bind(&format!("F{}", slot + 5), Action::SaveState(slot));
+            hotkeys.bind(&format!("Shift+F{}", slot + 5), Action::LoadState(slot));
+        }
+        hotkeys.bind("Tab", Action::ToggleTurbo);
+        hotkeys.bind("P", Action::TogglePause);
+        hotkeys.bind("N", Action::Step);
+        hotkeys.bind("F2", Action::Reset);
+        hotkeys.bind("F12", Action::Screenshot);
+        hotkeys.bind("Escape", Action::Quit);
+        hotkeys
+    }
+}
+
+// Lower-cases the chord and puts modifiers in a fixed order, so "F5+Shift"
+// and "shift+f5" name the same binding.
+pub(crate) fn normalize_chord(chord: &str) -> String {
+    let mut modifiers = Vec::new();
+    let mut key = None;
+    for part in chord.split('+').map(|part| part.trim().to_lowercase()) {
+        match part.as_str() {
+            "ctrl" | "control" => modifiers.push(0),
+            "alt" => modifiers.push(1),
+            "shift" => modifiers.push(2),
+            _ => key = Some(part),
+        }
+    }
+    modifiers.sort();
+    modifiers.dedup();
+
+    let mut parts: Vec<String> = modifiers
+        .iter()
+        .map(|&modifier| ["ctrl", "alt", "shift"][modifier].to_string())
+        .collect();
+    parts.extend(key);
+    parts.join("+")
+}
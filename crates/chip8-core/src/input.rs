@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use crate::keypad::{Keypad, KEY_COUNT};
+
+// Something other than the local keyboard pressing keys: a recording, a
+// network bridge, a bot. Frontends poll every source once per frame,
+// before running it.
+pub trait InputSource {
+    fn poll(&mut self, keypad: &mut Keypad);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoteConfig {
+    // Frames votes are collected for before the winner is pressed.
+    pub window_frames: u32,
+    // Frames the winning key stays down.
+    pub hold_frames: u32,
+    // A viewer gets one vote per this many frames, so nobody can flood it.
+    pub cooldown_frames: u32,
+}
+
+impl Default for VoteConfig {
+    fn default() -> Self {
+        VoteConfig {
+            window_frames: 60,
+            hold_frames: 10,
+            cooldown_frames: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteRejected {
+    // Not a key, chat is mostly something else.
+    NotACommand,
+    TooSoon,
+}
+
+// "Twitch plays": chat votes for a key, one of 0-F optionally written as
+// `!5`, and every window the most voted key is pressed. Ties go to the
+// lower key.
+#[derive(Debug, Clone)]
+pub struct ChatVotes {
+    config: VoteConfig,
+    frame: u64,
+    votes: [u32; KEY_COUNT],
+    last_vote: HashMap<String, u64>,
+    // Key held down and the frame it is released at.
+    held: Option<(u8, u64)>,
+}
+
+impl ChatVotes {
+    pub fn new(config: VoteConfig) -> Self {
+        ChatVotes {
+            config,
+            frame: 0,
+            votes: [0; KEY_COUNT],
+            last_vote: HashMap::new(),
+            held: None,
+        }
+    }
+
+    pub fn vote(&mut self, user: &str, message: &str) -> Result<u8, VoteRejected> {
+        let key = parse_command(message).ok_or(VoteRejected::NotACommand)?;
+        if let Some(&last) = self.last_vote.get(user) {
+            if self.frame < last + self.config.cooldown_frames as u64 {
+                return Err(VoteRejected::TooSoon);
+            }
+        }
+        self.last_vote.insert(user.to_string(), self.frame);
+        self.votes[key as usize] += 1;
+        Ok(key)
+    }
+
+    // Votes so far in the current window.
+    pub fn tally(&self) -> [u32; KEY_COUNT] {
+        self.votes
+    }
+
+    fn winner(&self) -> Option<u8> {
+        let (key, &votes) = self
+            .votes
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))?;
+        (votes > 0).then_some(key as u8)
+    }
+}
+
+impl InputSource for ChatVotes {
+    fn poll(&mut self, keypad: &mut Keypad) {
+        self.frame += 1;
+        if let Some((key, until)) = self.held {
+            if self.frame >= until {
+                keypad.release(key);
+                self.held = None;
+            }
+        }
+        if !self
+            .frame
+            .is_multiple_of(self.config.window_frames.max(1) as u64)
+        {
+            return;
+        }
+        if let Some(key) = self.winner() {
+            if let Some((held, _)) = self.held.take() {
+                keypad.release(held);
+            }
+            keypad.press(key);
+            self.held = Some((key, self.frame + self.config.hold_frames as u64));
+        }
+        self.votes = [0; KEY_COUNT];
+    }
+}
+
+fn parse_command(message: &str) -> Option<u8> {
+    let command = message.trim();
+    let command = command.strip_prefix('!').unwrap_or(command);
+    match command.len() {
+        1 => u8::from_str_radix(command, 16).ok(),
+        _ => None,
+    }
+}
+
+// Keys pressed and released at given frames, from a text file with one
+// event per line: `frame key down|up`, the key in hexadecimal and `#`
+// starting a comment. Frames count from 1, the first `poll`.
+//
+//     # start the game, then hold right for a second
+//     30 5 down
+//     32 5 up
+//     60 6 down
+//     120 6 up
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputScript {
+    // (frame, key, pressed), sorted by frame.
+    events: Vec<(u64, u8, bool)>,
+    frame: u64,
+    next: usize,
+}
+
+impl InputScript {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut events = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: &str| format!("line {}: {}", number + 1, message);
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [frame, key, action] = fields[..] else {
+                return Err(error("expected `frame key down|up`"));
+            };
+            let frame = frame.parse().map_err(|_| error("invalid frame"))?;
+            let key = u8::from_str_radix(key, 16)
+                .ok()
+                .filter(|&key| (key as usize) < KEY_COUNT)
+                .ok_or_else(|| error("the key is a hexadecimal digit"))?;
+            let pressed = match action {
+                "down" => true,
+                "up" => false,
+                _ => return Err(error("expected down or up")),
+            };
+            events.push((frame, key, pressed));
+        }
+        Ok(InputScript::from_events(events))
+    }
+
+    // (frame, key, pressed) triples, in any order.
+    pub fn from_events(mut events: Vec<(u64, u8, bool)>) -> Self {
+        events.sort_by_key(|&(frame, _, _)| frame);
+        InputScript {
+            events,
+            frame: 0,
+            next: 0,
+        }
+    }
+
+    pub fn events(&self) -> &[(u64, u8, bool)] {
+        &self.events
+    }
+
+    pub fn to_text(&self) -> String {
+        self.events
+            .iter()
+            .map(|&(frame, key, pressed)| {
+                let action = if pressed { "down" } else { "up" };
+                format!("{} {:X} {}\n", frame, key, action)
+            })
+            .collect()
+    }
+
+    // The frame of the last event, after which the script does nothing.
+    pub fn length(&self) -> u64 {
+        self.events.last().map_or(0, |&(frame, _, _)| frame)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.events.len()
+    }
+
+    // Starts over from the first frame.
+    pub fn rewind(&mut self) {
+        self.frame = 0;
+        self.next = 0;
+    }
+}
+
+impl InputSource for InputScript {
+    fn poll(&mut self, keypad: &mut Keypad) {
+        self.frame += 1;
+        while let Some(&(frame, key, pressed)) = self.events.get(self.next) {
+            if frame > self.frame {
+                break;
+            }
+            if pressed {
+                keypad.press(key);
+            } else {
+                keypad.release(key);
+            }
+            self.next += 1;
+        }
+    }
+}
@@ -0,0 +1,990 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::display::Resolution;
+use crate::emulator::parse_op_code;
+use crate::{INITIAL_ADDRESS, RAM_SIZE, XOCHIP_RAM_SIZE};
+
+// A decoded opcode. Register operands are nibbles (0-F), `nnn` an address,
+// `kk` a byte and `n` the sprite height. Non-exhaustive, extensions add
+// their own instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Instruction {
+    Nop,
+    Cls,
+    Ret,
+    Jp { nnn: u16 },
+    Call { nnn: u16 },
+    SeByte { x: u8, kk: u8 },
+    SneByte { x: u8, kk: u8 },
+    SeReg { x: u8, y: u8 },
+    LdByte { x: u8, kk: u8 },
+    AddByte { x: u8, kk: u8 },
+    LdReg { x: u8, y: u8 },
+    Or { x: u8, y: u8 },
+    And { x: u8, y: u8 },
+    Xor { x: u8, y: u8 },
+    Add { x: u8, y: u8 },
+    Sub { x: u8, y: u8 },
+    Shr { x: u8, y: u8 },
+    Subn { x: u8, y: u8 },
+    Shl { x: u8, y: u8 },
+    SneReg { x: u8, y: u8 },
+    LdI { nnn: u16 },
+    JpV0 { nnn: u16 },
+    Rnd { x: u8, kk: u8 },
+    Drw { x: u8, y: u8, n: u8 },
+    Skp { x: u8 },
+    Sknp { x: u8 },
+    LdVxDt { x: u8 },
+    LdKey { x: u8 },
+    LdDtVx { x: u8 },
+    LdStVx { x: u8 },
+    AddI { x: u8 },
+    LdF { x: u8 },
+    LdBcd { x: u8 },
+    Store { x: u8 },
+    Load { x: u8 },
+    // CHIP-8X, see `chip8x`.
+    NextBackground,
+    AddBcd { x: u8, y: u8 },
+    Color { x: u8, y: u8, n: u8 },
+    Skp2 { x: u8 },
+    Sknp2 { x: u8 },
+    Out { x: u8 },
+    In { x: u8 },
+    // SUPER-CHIP's 64x32 and 128x64 screens.
+    Low,
+    High,
+    // XO-CHIP, see `xochip`.
+    Audio,
+    Pitch { x: u8 },
+    SaveFlags { x: u8 },
+    LoadFlags { x: u8 },
+    // F000 NNNN, the address is the word after it.
+    LongI,
+    // This emulator's own, see `printer`.
+    Print { x: u8 },
+    // This emulator's own, see `wallclock`.
+    Time { x: u8 },
+    // This emulator's own, see `disk`.
+    DiskRead { x: u8 },
+    DiskWrite { x: u8 },
+    Unknown(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Chip8,
+    Schip,
+    XoChip,
+    // CHIP-8 on the ETI-660 learning computer: a 64x48 screen and programs
+    // starting at 0x600.
+    Eti660,
+    // The COSMAC VIP with the colour and sound boards, programs start at
+    // 0x300.
+    Chip8x,
+}
+
+impl Variant {
+    // Where ROMs are loaded and execution starts.
+    pub fn start_address(self) -> u16 {
+        match self {
+            Variant::Eti660 => 0x600,
+            Variant::Chip8x => 0x300,
+            _ => INITIAL_ADDRESS,
+        }
+    }
+
+    // Bytes of memory. XO-CHIP programs routinely go past 4K, every other
+    // machine has what the COSMAC VIP had.
+    pub fn ram_size(self) -> usize {
+        match self {
+            Variant::XoChip => XOCHIP_RAM_SIZE,
+            _ => RAM_SIZE,
+        }
+    }
+
+    pub fn resolution(self) -> Resolution {
+        match self {
+            Variant::Eti660 => Resolution::Eti660,
+            _ => Resolution::Lores,
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Variant::Chip8 => write!(f, "CHIP-8"),
+            Variant::Schip => write!(f, "SUPER-CHIP"),
+            Variant::XoChip => write!(f, "XO-CHIP"),
+            Variant::Eti660 => write!(f, "ETI-660"),
+            Variant::Chip8x => write!(f, "CHIP-8X"),
+        }
+    }
+}
+
+// The same names as the quirk profiles.
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "chip8" => Ok(Variant::Chip8),
+            "schip" => Ok(Variant::Schip),
+            "xochip" => Ok(Variant::XoChip),
+            "eti660" => Ok(Variant::Eti660),
+            "chip8x" => Ok(Variant::Chip8x),
+            _ => Err(format!(
+                "unknown variant {:?}, expected chip8, schip, xochip, eti660 or chip8x",
+                text
+            )),
+        }
+    }
+}
+
+// Reference documentation for one kind of instruction, shared by the
+// disassembler, the debugger and `chip8-rs explain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionInfo {
+    // Opcode with its operands as placeholders, e.g. "8XY6".
+    pub pattern: &'static str,
+    // Assembly syntax, e.g. "SHR Vx {, Vy}".
+    pub mnemonic: &'static str,
+    pub description: &'static str,
+    // The first interpreter that had it.
+    pub variant: Variant,
+    // Names of the `Quirks` flags that change what it does.
+    pub quirks: &'static [&'static str],
+}
+
+const fn info(
+    pattern: &'static str,
+    mnemonic: &'static str,
+    description: &'static str,
+    quirks: &'static [&'static str],
+) -> InstructionInfo {
+    InstructionInfo {
+        pattern,
+        mnemonic,
+        description,
+        variant: Variant::Chip8,
+        quirks,
+    }
+}
+
+const fn chip8x(
+    pattern: &'static str,
+    mnemonic: &'static str,
+    description: &'static str,
+) -> InstructionInfo {
+    InstructionInfo {
+        variant: Variant::Chip8x,
+        ..info(pattern, mnemonic, description, &[])
+    }
+}
+
+const fn schip(
+    pattern: &'static str,
+    mnemonic: &'static str,
+    description: &'static str,
+) -> InstructionInfo {
+    InstructionInfo {
+        variant: Variant::Schip,
+        ..info(pattern, mnemonic, description, &[])
+    }
+}
+
+const fn xochip(
+    pattern: &'static str,
+    mnemonic: &'static str,
+    description: &'static str,
+) -> InstructionInfo {
+    InstructionInfo {
+        variant: Variant::XoChip,
+        ..info(pattern, mnemonic, description, &[])
+    }
+}
+
+pub const INSTRUCTIONS: &[InstructionInfo] = &[
+    info("0000", "NOP", "Does nothing.", &[]),
+    info("00E0", "CLS", "Clears the screen.", &[]),
+    info(
+        "00EE",
+        "RET",
+        "Returns from a subroutine, popping the return address off the stack.",
+        &[],
+    ),
+    info("1NNN", "JP addr", "Jumps to address NNN.", &[]),
+    info(
+        "2NNN",
+        "CALL addr",
+        "Calls the subroutine at NNN, pushing the address of the next instruction.",
+        &[],
+    ),
+    info(
+        "3XKK",
+        "SE Vx, byte",
+        "Skips the next instruction if Vx equals KK.",
+        &[],
+    ),
+    info(
+        "4XKK",
+        "SNE Vx, byte",
+        "Skips the next instruction if Vx is not KK.",
+        &[],
+    ),
+    info(
+        "5XY0",
+        "SE Vx, Vy",
+        "Skips the next instruction if Vx equals Vy.",
+        &[],
+    ),
+    info("6XKK", "LD Vx, byte", "Sets Vx to KK.", &[]),
+    info(
+        "7XKK",
+        "ADD Vx, byte",
+        "Adds KK to Vx, wrapping around. VF is left alone.",
+        &[],
+    ),
+    info("8XY0", "LD Vx, Vy", "Copies Vy into Vx.", &[]),
+    info(
+        "8XY1",
+        "OR Vx, Vy",
+        "Sets Vx to Vx OR Vy.",
+        &["vf_reset"],
+    ),
+    info(
+        "8XY2",
+        "AND Vx, Vy",
+        "Sets Vx to Vx AND Vy.",
+        &["vf_reset"],
+    ),
+    info(
+        "8XY3",
+        "XOR Vx, Vy",
+        "Sets Vx to Vx XOR Vy.",
+        &["vf_reset"],
+    ),
+    info(
+        "8XY4",
+        "ADD Vx, Vy",
+        "Adds Vy to Vx. VF is set to 1 on carry, 0 otherwise.",
+        &[],
+    ),
+    info(
+        "8XY5",
+        "SUB Vx, Vy",
+        "Subtracts Vy from Vx. VF is set to 0 on borrow, 1 otherwise.",
+        &[],
+    ),
+    info(
+        "8XY6",
+        "SHR Vx {, Vy}",
+        "Shifts right by one into Vx, VF gets the bit shifted out.",
+        &["shift_uses_vy"],
+    ),
+    info(
+        "8XY7",
+        "SUBN Vx, Vy",
+        "Sets Vx to Vy minus Vx. VF is set to 0 on borrow, 1 otherwise.",
+        &[],
+    ),
+    info(
+        "8XYE",
+        "SHL Vx {, Vy}",
+        "Shifts left by one into Vx, VF gets the bit shifted out.",
+        &["shift_uses_vy"],
+    ),
+    info(
+        "9XY0",
+        "SNE Vx, Vy",
+        "Skips the next instruction if Vx is not Vy.",
+        &[],
+    ),
+    info("ANNN", "LD I, addr", "Sets I to NNN.", &[]),
+    info(
+        "BNNN",
+        "JP V0, addr",
+        "Jumps to NNN plus V0.",
+        &["jump_uses_vx"],
+    ),
+    info(
+        "CXKK",
+        "RND Vx, byte",
+        "Sets Vx to a random byte AND KK.",
+        &[],
+    ),
+    info(
+        "DXYN",
+        "DRW Vx, Vy, nibble",
+        "Draws the N byte sprite at I to (Vx, Vy) by XORing it onto the screen. VF is set to 1 if any lit pixel got erased.",
+        &["clipping", "display_wait"],
+    ),
+    info(
+        "EX9E",
+        "SKP Vx",
+        "Skips the next instruction if the key in Vx is pressed.",
+        &[],
+    ),
+    info(
+        "EXA1",
+        "SKNP Vx",
+        "Skips the next instruction if the key in Vx is not pressed.",
+        &[],
+    ),
+    info("FX07", "LD Vx, DT", "Sets Vx to the delay timer.", &[]),
+    info(
+        "FX0A",
+        "LD Vx, K",
+        "Waits for a key press and stores the key in Vx.",
+        &[],
+    ),
+    info("FX15", "LD DT, Vx", "Sets the delay timer to Vx.", &[]),
+    info("FX18", "LD ST, Vx", "Sets the sound timer to Vx.", &[]),
+    info("FX1E", "ADD I, Vx", "Adds Vx to I.", &[]),
+    info(
+        "FX29",
+        "LD F, Vx",
+        "Points I at the font sprite of the digit in the low nibble of Vx.",
+        &[],
+    ),
+    info(
+        "FX33",
+        "LD B, Vx",
+        "Stores the decimal digits of Vx at I, I+1 and I+2.",
+        &[],
+    ),
+    info(
+        "FX55",
+        "LD [I], Vx",
+        "Stores V0 to Vx in memory starting at I.",
+        &["memory_increments_i"],
+    ),
+    info(
+        "FX65",
+        "LD Vx, [I]",
+        "Loads V0 to Vx from memory starting at I.",
+        &["memory_increments_i"],
+    ),
+    chip8x("02A0", "BGC", "Cycles the background colour."),
+    chip8x(
+        "5XY1",
+        "ADDBCD Vx, Vy",
+        "Adds Vy to Vx one nibble at a time, each wrapping at 8.",
+    ),
+    chip8x(
+        "BXYN",
+        "COL Vx, Vy, nibble",
+        "Colours N rows of the 8 pixel column at (Vx, V(x+1)) with Vy. With N=0, colours 8x4 zones instead, nibbles of Vx and V(x+1) holding the first zone and how many follow.",
+    ),
+    chip8x(
+        "EXF2",
+        "SKP2 Vx",
+        "Skips the next instruction if the key in Vx is pressed on the second keypad.",
+    ),
+    chip8x(
+        "EXF5",
+        "SKNP2 Vx",
+        "Skips the next instruction if the key in Vx is not pressed on the second keypad.",
+    ),
+    chip8x("FXF8", "OUT Vx", "Sends Vx to the I/O port, the tone of the sound board."),
+    chip8x("FXFB", "IN Vx", "Reads the I/O port into Vx."),
+    schip(
+        "00FE",
+        "LOW",
+        "Switches the screen to 64x32 pixels, each drawn 2x2 on the 128x64 one.",
+    ),
+    schip("00FF", "HIGH", "Switches the screen to 128x64 pixels."),
+    xochip(
+        "F002",
+        "AUDIO",
+        "Loads the 16 byte audio pattern at I, played while the sound timer runs.",
+    ),
+    xochip(
+        "FX3A",
+        "PITCH Vx",
+        "Sets the audio pattern's playback rate to 4000 * 2 ^ ((Vx - 64) / 48) bits per second.",
+    ),
+    xochip(
+        "FX75",
+        "LD R, Vx",
+        "Stores V0 to Vx in the flag registers, which outlive the program.",
+    ),
+    xochip(
+        "FX85",
+        "LD Vx, R",
+        "Reads V0 to Vx from the flag registers.",
+    ),
+    info(
+        "FXF0",
+        "PRINT Vx",
+        "Sends Vx to the host as a byte of text. An extension of this emulator, only with the printer enabled.",
+        &[],
+    ),
+    info(
+        "FXF1",
+        "LD Vx, TIME",
+        "Reads the host's time of day: seconds into Vx, minutes into Vx+1 and hours into Vx+2. An extension of this emulator, only with the wall clock enabled.",
+        &[],
+    ),
+    info(
+        "FXF2",
+        "DREAD Vx",
+        "Reads disk block Vx into the 256 bytes at I. An extension of this emulator, only with a disk attached.",
+        &[],
+    ),
+    info(
+        "FXF3",
+        "DWRITE Vx",
+        "Writes the 256 bytes at I to disk block Vx. An extension of this emulator, only with a disk attached.",
+        &[],
+    ),
+    xochip(
+        "F000",
+        "LD I, LONG",
+        "Sets I to the 16 bit address in the next two bytes, which it skips. The only way to point I past 0xFFF.",
+    ),
+];
+
+const UNKNOWN: InstructionInfo = info(
+    "????",
+    "DW word",
+    "Not an instruction, executing it stops the emulator.",
+    &[],
+);
+
+// Looks up the documentation by pattern, case insensitive, "0x" optional.
+pub fn find(pattern: &str) -> Option<&'static InstructionInfo> {
+    let pattern = pattern.trim_start_matches("0x").trim_start_matches("0X");
+    INSTRUCTIONS
+        .iter()
+        .find(|info| info.pattern.eq_ignore_ascii_case(pattern))
+}
+
+impl Instruction {
+    pub fn decode(op_code: u16) -> Instruction {
+        let x = ((op_code >> 8) & 0xF) as u8;
+        let y = ((op_code >> 4) & 0xF) as u8;
+        let n = (op_code & 0xF) as u8;
+        let kk = (op_code & 0xFF) as u8;
+        let nnn = op_code & 0xFFF;
+        match parse_op_code(op_code) {
+            (0, 0, 0, 0) => Instruction::Nop,
+            (0, 0, 0xE, 0) => Instruction::Cls,
+            (0, 0, 0xE, 0xE) => Instruction::Ret,
+            (0, 0, 0xF, 0xE) => Instruction::Low,
+            (0, 0, 0xF, 0xF) => Instruction::High,
+            (0, 2, 0xA, 0) => Instruction::NextBackground,
+            (1, _, _, _) => Instruction::Jp { nnn },
+            (2, _, _, _) => Instruction::Call { nnn },
+            (3, _, _, _) => Instruction::SeByte { x, kk },
+            (4, _, _, _) => Instruction::SneByte { x, kk },
+            (5, _, _, 0) => Instruction::SeReg { x, y },
+            (5, _, _, 1) => Instruction::AddBcd { x, y },
+            (6, _, _, _) => Instruction::LdByte { x, kk },
+            (7, _, _, _) => Instruction::AddByte { x, kk },
+            (8, _, _, 0) => Instruction::LdReg { x, y },
+            (8, _, _, 1) => Instruction::Or { x, y },
+            (8, _, _, 2) => Instruction::And { x, y },
+            (8, _, _, 3) => Instruction::Xor { x, y },
+            (8, _, _, 4) => Instruction::Add { x, y },
+            (8, _, _, 5) => Instruction::Sub { x, y },
+            (8, _, _, 6) => Instruction::Shr { x, y },
+            (8, _, _, 7) => Instruction::Subn { x, y },
+            (8, _, _, 0xE) => Instruction::Shl { x, y },
+            (9, _, _, 0) => Instruction::SneReg { x, y },
+            (0xA, _, _, _) => Instruction::LdI { nnn },
+            (0xB, _, _, _) => Instruction::JpV0 { nnn },
+            (0xC, _, _, _) => Instruction::Rnd { x, kk },
+            (0xD, _, _, _) => Instruction::Drw { x, y, n },
+            (0xE, _, 9, 0xE) => Instruction::Skp { x },
+            (0xE, _, 0xA, 1) => Instruction::Sknp { x },
+            (0xE, _, 0xF, 2) => Instruction::Skp2 { x },
+            (0xE, _, 0xF, 5) => Instruction::Sknp2 { x },
+            (0xF, 0, 0, 0) => Instruction::LongI,
+            (0xF, 0, 0, 2) => Instruction::Audio,
+            (0xF, _, 0, 7) => Instruction::LdVxDt { x },
+            (0xF, _, 0, 0xA) => Instruction::LdKey { x },
+            (0xF, _, 1, 5) => Instruction::LdDtVx { x },
+            (0xF, _, 1, 8) => Instruction::LdStVx { x },
+            (0xF, _, 1, 0xE) => Instruction::AddI { x },
+            (0xF, _, 2, 9) => Instruction::LdF { x },
+            (0xF, _, 3, 3) => Instruction::LdBcd { x },
+            (0xF, _, 3, 0xA) => Instruction::Pitch { x },
+            (0xF, _, 5, 5) => Instruction::Store { x },
+            (0xF, _, 6, 5) => Instruction::Load { x },
+            (0xF, _, 7, 5) => Instruction::SaveFlags { x },
+            (0xF, _, 8, 5) => Instruction::LoadFlags { x },
+            (0xF, _, 0xF, 0) => Instruction::Print { x },
+            (0xF, _, 0xF, 1) => Instruction::Time { x },
+            (0xF, _, 0xF, 2) => Instruction::DiskRead { x },
+            (0xF, _, 0xF, 3) => Instruction::DiskWrite { x },
+            (0xF, _, 0xF, 8) => Instruction::Out { x },
+            (0xF, _, 0xF, 0xB) => Instruction::In { x },
+            _ => Instruction::Unknown(op_code),
+        }
+    }
+
+    // CHIP-8X reuses Bnnn for colours, everything else decodes the same.
+    pub fn decode_chip8x(op_code: u16) -> Instruction {
+        match Instruction::decode(op_code) {
+            Instruction::JpV0 { .. } => Instruction::Color {
+                x: ((op_code >> 8) & 0xF) as u8,
+                y: ((op_code >> 4) & 0xF) as u8,
+                n: (op_code & 0xF) as u8,
+            },
+            instruction => instruction,
+        }
+    }
+
+    pub fn encode(self) -> u16 {
+        let xy = |high: u16, x: u8, y: u8, low: u16| {
+            (high << 12) | ((x as u16) << 8) | ((y as u16) << 4) | low
+        };
+        let xkk = |high: u16, x: u8, kk: u8| (high << 12) | ((x as u16) << 8) | kk as u16;
+        match self {
+            Instruction::Nop => 0x0000,
+            Instruction::Cls => 0x00E0,
+            Instruction::Ret => 0x00EE,
+            Instruction::Jp { nnn } => 0x1000 | nnn,
+            Instruction::Call { nnn } => 0x2000 | nnn,
+            Instruction::SeByte { x, kk } => xkk(3, x, kk),
+            Instruction::SneByte { x, kk } => xkk(4, x, kk),
+            Instruction::SeReg { x, y } => xy(5, x, y, 0),
+            Instruction::LdByte { x, kk } => xkk(6, x, kk),
+            Instruction::AddByte { x, kk } => xkk(7, x, kk),
+            Instruction::LdReg { x, y } => xy(8, x, y, 0),
+            Instruction::Or { x, y } => xy(8, x, y, 1),
+            Instruction::And { x, y } => xy(8, x, y, 2),
+            Instruction::Xor { x, y } => xy(8, x, y, 3),
+            Instruction::Add { x, y } => xy(8, x, y, 4),
+            Instruction::Sub { x, y } => xy(8, x, y, 5),
+            Instruction::Shr { x, y } => xy(8, x, y, 6),
+            Instruction::Subn { x, y } => xy(8, x, y, 7),
+            Instruction::Shl { x, y } => xy(8, x, y, 0xE),
+            Instruction::SneReg { x, y } => xy(9, x, y, 0),
+            Instruction::LdI { nnn } => 0xA000 | nnn,
+            Instruction::JpV0 { nnn } => 0xB000 | nnn,
+            Instruction::Rnd { x, kk } => xkk(0xC, x, kk),
+            Instruction::Drw { x, y, n } => xy(0xD, x, y, n as u16),
+            Instruction::Skp { x } => xkk(0xE, x, 0x9E),
+            Instruction::Sknp { x } => xkk(0xE, x, 0xA1),
+            Instruction::LdVxDt { x } => xkk(0xF, x, 0x07),
+            Instruction::LdKey { x } => xkk(0xF, x, 0x0A),
+            Instruction::LdDtVx { x } => xkk(0xF, x, 0x15),
+            Instruction::LdStVx { x } => xkk(0xF, x, 0x18),
+            Instruction::AddI { x } => xkk(0xF, x, 0x1E),
+            Instruction::LdF { x } => xkk(0xF, x, 0x29),
+            Instruction::LdBcd { x } => xkk(0xF, x, 0x33),
+            Instruction::Store { x } => xkk(0xF, x, 0x55),
+            Instruction::Load { x } => xkk(0xF, x, 0x65),
+            Instruction::NextBackground => 0x02A0,
+            Instruction::AddBcd { x, y } => xy(5, x, y, 1),
+            Instruction::Color { x, y, n } => xy(0xB, x, y, n as u16),
+            Instruction::Skp2 { x } => xkk(0xE, x, 0xF2),
+            Instruction::Sknp2 { x } => xkk(0xE, x, 0xF5),
+            Instruction::Out { x } => xkk(0xF, x, 0xF8),
+            Instruction::In { x } => xkk(0xF, x, 0xFB),
+            Instruction::Low => 0x00FE,
+            Instruction::High => 0x00FF,
+            Instruction::Audio => 0xF002,
+            Instruction::Pitch { x } => xkk(0xF, x, 0x3A),
+            Instruction::SaveFlags { x } => xkk(0xF, x, 0x75),
+            Instruction::LoadFlags { x } => xkk(0xF, x, 0x85),
+            Instruction::LongI => 0xF000,
+            Instruction::Print { x } => xkk(0xF, x, 0xF0),
+            Instruction::Time { x } => xkk(0xF, x, 0xF1),
+            Instruction::DiskRead { x } => xkk(0xF, x, 0xF2),
+            Instruction::DiskWrite { x } => xkk(0xF, x, 0xF3),
+            Instruction::Unknown(op_code) => op_code,
+        }
+    }
+
+    // Bytes the instruction takes, with the address after F000.
+    pub fn size(self) -> u16 {
+        match self {
+            Instruction::LongI => 4,
+            _ => 2,
+        }
+    }
+
+    pub fn pattern(&self) -> &'static str {
+        self.describe().pattern
+    }
+
+    pub fn describe(&self) -> &'static InstructionInfo {
+        let pattern = match self {
+            Instruction::Nop => "0000",
+            Instruction::Cls => "00E0",
+            Instruction::Ret => "00EE",
+            Instruction::Jp { .. } => "1NNN",
+            Instruction::Call { .. } => "2NNN",
+            Instruction::SeByte { .. } => "3XKK",
+            Instruction::SneByte { .. } => "4XKK",
+            Instruction::SeReg { .. } => "5XY0",
+            Instruction::LdByte { .. } => "6XKK",
+            Instruction::AddByte { .. } => "7XKK",
+            Instruction::LdReg { .. } => "8XY0",
+            Instruction::Or { .. } => "8XY1",
+            Instruction::And { .. } => "8XY2",
+            Instruction::Xor { .. } => "8XY3",
+            Instruction::Add { .. } => "8XY4",
+            Instruction::Sub { .. } => "8XY5",
+            Instruction::Shr { .. } => "8XY6",
+            Instruction::Subn { .. } => "8XY7",
+            Instruction::Shl { .. } => "8XYE",
+            Instruction::SneReg { .. } => "9XY0",
+            Instruction::LdI { .. } => "ANNN",
+            Instruction::JpV0 { .. } => "BNNN",
+            Instruction::Rnd { .. } => "CXKK",
+            Instruction::Drw { .. } => "DXYN",
+            Instruction::Skp { .. } => "EX9E",
+            Instruction::Sknp { .. } => "EXA1",
+            Instruction::LdVxDt { .. } => "FX07",
+            Instruction::LdKey { .. } => "FX0A",
+            Instruction::LdDtVx { .. } => "FX15",
+            Instruction::LdStVx { .. } => "FX18",
+            Instruction::AddI { .. } => "FX1E",
+            Instruction::LdF { .. } => "FX29",
+            Instruction::LdBcd { .. } => "FX33",
+            Instruction::Store { .. } => "FX55",
+            Instruction::Load { .. } => "FX65",
+            Instruction::NextBackground => "02A0",
+            Instruction::AddBcd { .. } => "5XY1",
+            Instruction::Color { .. } => "BXYN",
+            Instruction::Skp2 { .. } => "EXF2",
+            Instruction::Sknp2 { .. } => "EXF5",
+            Instruction::Out { .. } => "FXF8",
+            Instruction::In { .. } => "FXFB",
+            Instruction::Low => "00FE",
+            Instruction::High => "00FF",
+            Instruction::Audio => "F002",
+            Instruction::Pitch { .. } => "FX3A",
+            Instruction::SaveFlags { .. } => "FX75",
+            Instruction::LoadFlags { .. } => "FX85",
+            Instruction::LongI => "F000",
+            Instruction::Print { .. } => "FXF0",
+            Instruction::Time { .. } => "FXF1",
+            Instruction::DiskRead { .. } => "FXF2",
+            Instruction::DiskWrite { .. } => "FXF3",
+            Instruction::Unknown(_) => return &UNKNOWN,
+        };
+        find(pattern).unwrap_or(&UNKNOWN)
+    }
+}
+
+// Disassembly, in the syntax of Cowgod's reference.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Instruction::Nop => write!(f, "NOP"),
+            Instruction::Cls => write!(f, "CLS"),
+            Instruction::Ret => write!(f, "RET"),
+            Instruction::Jp { nnn } => write!(f, "JP {:#05x}", nnn),
+            Instruction::Call { nnn } => write!(f, "CALL {:#05x}", nnn),
+            Instruction::SeByte { x, kk } => write!(f, "SE V{:X}, {:#04x}", x, kk),
+            Instruction::SneByte { x, kk } => write!(f, "SNE V{:X}, {:#04x}", x, kk),
+            Instruction::SeReg { x, y } => write!(f, "SE V{:X}, V{:X}", x, y),
+            Instruction::LdByte { x, kk } => write!(f, "LD V{:X}, {:#04x}", x, kk),
+            Instruction::AddByte { x, kk } => write!(f, "ADD V{:X}, {:#04x}", x, kk),
+            Instruction::LdReg { x, y } => write!(f, "LD V{:X}, V{:X}", x, y),
+            Instruction::Or { x, y } => write!(f, "OR V{:X}, V{:X}", x, y),
+            Instruction::And { x, y } => write!(f, "AND V{:X}, V{:X}", x, y),
+            Instruction::Xor { x, y } => write!(f, "XOR V{:X}, V{:X}", x, y),
+            Instruction::Add { x, y } => write!(f, "ADD V{:X}, V{:X}", x, y),
+            Instruction::Sub { x, y } => write!(f, "SUB V{:X}, V{:X}", x, y),
+            Instruction::Shr { x, y } => write!(f, "SHR V{:X}, V{:X}", x, y),
+            Instruction::Subn { x, y } => write!(f, "SUBN V{:X}, V{:X}", x, y),
+            Instruction::Shl { x, y } => write!(f, "SHL V{:X}, V{:X}", x, y),
+            Instruction::SneReg { x, y } => write!(f, "SNE V{:X}, V{:X}", x, y),
+            Instruction::LdI { nnn } => write!(f, "LD I, {:#05x}", nnn),
+            Instruction::JpV0 { nnn } => write!(f, "JP V0, {:#05x}", nnn),
+            Instruction::Rnd { x, kk } => write!(f, "RND V{:X}, {:#04x}", x, kk),
+            Instruction::Drw { x, y, n } => write!(f, "DRW V{:X}, V{:X}, {}", x, y, n),
+            Instruction::Skp { x } => write!(f, "SKP V{:X}", x),
+            Instruction::Sknp { x } => write!(f, "SKNP V{:X}", x),
+            Instruction::LdVxDt { x } => write!(f, "LD V{:X}, DT", x),
+            Instruction::LdKey { x } => write!(f, "LD V{:X}, K", x),
+            Instruction::LdDtVx { x } => write!(f, "LD DT, V{:X}", x),
+            Instruction::LdStVx { x } => write!(f, "LD ST, V{:X}", x),
+            Instruction::AddI { x } => write!(f, "ADD I, V{:X}", x),
+            Instruction::LdF { x } => write!(f, "LD F, V{:X}", x),
+            Instruction::LdBcd { x } => write!(f, "LD B, V{:X}", x),
+            Instruction::Store { x } => write!(f, "LD [I], V{:X}", x),
+            Instruction::Load { x } => write!(f, "LD V{:X}, [I]", x),
+            Instruction::NextBackground => write!(f, "BGC"),
+            Instruction::AddBcd { x, y } => write!(f, "ADDBCD V{:X}, V{:X}", x, y),
+            Instruction::Color { x, y, n } => write!(f, "COL V{:X}, V{:X}, {}", x, y, n),
+            Instruction::Skp2 { x } => write!(f, "SKP2 V{:X}", x),
+            Instruction::Sknp2 { x } => write!(f, "SKNP2 V{:X}", x),
+            Instruction::Out { x } => write!(f, "OUT V{:X}", x),
+            Instruction::In { x } => write!(f, "IN V{:X}", x),
+            Instruction::Low => write!(f, "LOW"),
+            Instruction::High => write!(f, "HIGH"),
+            Instruction::Audio => write!(f, "AUDIO"),
+            Instruction::Pitch { x } => write!(f, "PITCH V{:X}", x),
+            Instruction::SaveFlags { x } => write!(f, "LD R, V{:X}", x),
+            Instruction::LoadFlags { x } => write!(f, "LD V{:X}, R", x),
+            Instruction::LongI => write!(f, "LD I, LONG"),
+            Instruction::Print { x } => write!(f, "PRINT V{:X}", x),
+            Instruction::Time { x } => write!(f, "LD V{:X}, TIME", x),
+            Instruction::DiskRead { x } => write!(f, "DREAD V{:X}", x),
+            Instruction::DiskWrite { x } => write!(f, "DWRITE V{:X}", x),
+            Instruction::Unknown(op_code) => write!(f, "DW {:#06x}", op_code),
+        }
+    }
+}
+
+// Assembles one line in the same syntax `Display` produces. Numbers can be
+// decimal, 0x prefixed or # prefixed hexadecimal, and the Vy of SHR and
+// SHL may be left out to shift Vx in place.
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        parse(line).map_err(|error| error.message)
+    }
+}
+
+// Why a line does not parse, and the part of it to blame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ParseError {
+    pub blame: Blame,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Blame {
+    Mnemonic,
+    // None of the mnemonic's forms takes operands like these.
+    Operands,
+    // By its index, operands being split at commas.
+    Operand(usize),
+}
+
+pub(crate) fn parse(line: &str) -> Result<Instruction, ParseError> {
+    let line = line.trim();
+    let (mnemonic, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let operands: Vec<Operand> = if rest.trim().is_empty() {
+        Vec::new()
+    } else {
+        rest.split(',')
+            .enumerate()
+            .map(|(index, operand)| {
+                operand.trim().parse().map_err(|message| ParseError {
+                    blame: Blame::Operand(index),
+                    message,
+                })
+            })
+            .collect::<Result<_, _>>()?
+    };
+    let mnemonic = mnemonic.to_ascii_uppercase();
+    match build(&mnemonic, &operands) {
+        Ok(Some(instruction)) => Ok(instruction),
+        Ok(None) if forms(&mnemonic).next().is_some() => Err(ParseError {
+            blame: Blame::Operands,
+            message: format!("invalid operands for {}", mnemonic),
+        }),
+        Ok(None) => Err(ParseError {
+            blame: Blame::Mnemonic,
+            message: format!("unknown mnemonic {:?}", mnemonic),
+        }),
+        // Every form has at most one number, the one out of range.
+        Err(message) => Err(ParseError {
+            blame: Blame::Operand(
+                operands
+                    .iter()
+                    .position(|operand| matches!(operand, Operand::Number(_)))
+                    .unwrap_or_default(),
+            ),
+            message,
+        }),
+    }
+}
+
+// The documented forms of `mnemonic`, such as "LD Vx, byte" for LD.
+pub(crate) fn forms(mnemonic: &str) -> impl Iterator<Item = &'static str> + '_ {
+    INSTRUCTIONS
+        .iter()
+        .chain([&UNKNOWN])
+        .map(|info| info.mnemonic)
+        .filter(move |form| form.split(' ').next() == Some(mnemonic))
+}
+
+// None when no form of the mnemonic takes these operands.
+fn build(mnemonic: &str, operands: &[Operand]) -> Result<Option<Instruction>, String> {
+    use Operand::*;
+    let instruction = match (mnemonic, operands) {
+        ("NOP", []) => Instruction::Nop,
+        ("CLS", []) => Instruction::Cls,
+        ("RET", []) => Instruction::Ret,
+        ("JP", [Number(nnn)]) => Instruction::Jp {
+            nnn: address(*nnn)?,
+        },
+        ("JP", [Register(0), Number(nnn)]) => Instruction::JpV0 {
+            nnn: address(*nnn)?,
+        },
+        ("CALL", [Number(nnn)]) => Instruction::Call {
+            nnn: address(*nnn)?,
+        },
+        ("SE", [Register(x), Number(kk)]) => Instruction::SeByte {
+            x: *x,
+            kk: byte(*kk)?,
+        },
+        ("SE", [Register(x), Register(y)]) => Instruction::SeReg { x: *x, y: *y },
+        ("SNE", [Register(x), Number(kk)]) => Instruction::SneByte {
+            x: *x,
+            kk: byte(*kk)?,
+        },
+        ("SNE", [Register(x), Register(y)]) => Instruction::SneReg { x: *x, y: *y },
+        ("LD", [Register(x), Number(kk)]) => Instruction::LdByte {
+            x: *x,
+            kk: byte(*kk)?,
+        },
+        ("LD", [Register(x), Register(y)]) => Instruction::LdReg { x: *x, y: *y },
+        ("LD", [I, Number(nnn)]) => Instruction::LdI {
+            nnn: address(*nnn)?,
+        },
+        ("LD", [Register(x), Dt]) => Instruction::LdVxDt { x: *x },
+        ("LD", [Register(x), Key]) => Instruction::LdKey { x: *x },
+        ("LD", [Dt, Register(x)]) => Instruction::LdDtVx { x: *x },
+        ("LD", [St, Register(x)]) => Instruction::LdStVx { x: *x },
+        ("LD", [Font, Register(x)]) => Instruction::LdF { x: *x },
+        ("LD", [Bcd, Register(x)]) => Instruction::LdBcd { x: *x },
+        ("LD", [Memory, Register(x)]) => Instruction::Store { x: *x },
+        ("LD", [Register(x), Memory]) => Instruction::Load { x: *x },
+        ("ADD", [Register(x), Number(kk)]) => Instruction::AddByte {
+            x: *x,
+            kk: byte(*kk)?,
+        },
+        ("ADD", [Register(x), Register(y)]) => Instruction::Add { x: *x, y: *y },
+        ("ADD", [I, Register(x)]) => Instruction::AddI { x: *x },
+        ("OR", [Register(x), Register(y)]) => Instruction::Or { x: *x, y: *y },
+        ("AND", [Register(x), Register(y)]) => Instruction::And { x: *x, y: *y },
+        ("XOR", [Register(x), Register(y)]) => Instruction::Xor { x: *x, y: *y },
+        ("SUB", [Register(x), Register(y)]) => Instruction::Sub { x: *x, y: *y },
+        ("SUBN", [Register(x), Register(y)]) => Instruction::Subn { x: *x, y: *y },
+        ("SHR", [Register(x)]) => Instruction::Shr { x: *x, y: *x },
+        ("SHR", [Register(x), Register(y)]) => Instruction::Shr { x: *x, y: *y },
+        ("SHL", [Register(x)]) => Instruction::Shl { x: *x, y: *x },
+        ("SHL", [Register(x), Register(y)]) => Instruction::Shl { x: *x, y: *y },
+        ("RND", [Register(x), Number(kk)]) => Instruction::Rnd {
+            x: *x,
+            kk: byte(*kk)?,
+        },
+        ("DRW", [Register(x), Register(y), Number(n)]) => Instruction::Drw {
+            x: *x,
+            y: *y,
+            n: nibble(*n)?,
+        },
+        ("SKP", [Register(x)]) => Instruction::Skp { x: *x },
+        ("SKNP", [Register(x)]) => Instruction::Sknp { x: *x },
+        ("BGC", []) => Instruction::NextBackground,
+        ("ADDBCD", [Register(x), Register(y)]) => Instruction::AddBcd { x: *x, y: *y },
+        ("COL", [Register(x), Register(y), Number(n)]) => Instruction::Color {
+            x: *x,
+            y: *y,
+            n: nibble(*n)?,
+        },
+        ("SKP2", [Register(x)]) => Instruction::Skp2 { x: *x },
+        ("SKNP2", [Register(x)]) => Instruction::Sknp2 { x: *x },
+        ("OUT", [Register(x)]) => Instruction::Out { x: *x },
+        ("IN", [Register(x)]) => Instruction::In { x: *x },
+        ("LOW", []) => Instruction::Low,
+        ("HIGH", []) => Instruction::High,
+        ("AUDIO", []) => Instruction::Audio,
+        ("PITCH", [Register(x)]) => Instruction::Pitch { x: *x },
+        ("LD", [Flags, Register(x)]) => Instruction::SaveFlags { x: *x },
+        ("LD", [Register(x), Flags]) => Instruction::LoadFlags { x: *x },
+        ("LD", [I, Long]) => Instruction::LongI,
+        ("PRINT", [Register(x)]) => Instruction::Print { x: *x },
+        ("LD", [Register(x), Time]) => Instruction::Time { x: *x },
+        ("DREAD", [Register(x)]) => Instruction::DiskRead { x: *x },
+        ("DWRITE", [Register(x)]) => Instruction::DiskWrite { x: *x },
+        ("DW", [Number(word)]) => {
+            let word = u16::try_from(*word).map_err(|_| format!("{} is not a word", word))?;
+            Instruction::decode(word)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(instruction))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Register(u8),
+    Number(u32),
+    I,
+    // [I]
+    Memory,
+    Dt,
+    St,
+    Key,
+    // The font sprite of a digit.
+    Font,
+    Bcd,
+    // The XO-CHIP flag registers.
+    Flags,
+    // The address following F000.
+    Long,
+    // The FxF1 time of day.
+    Time,
+}
+
+impl FromStr for Operand {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let upper = text.to_ascii_uppercase();
+        let operand = match upper.as_str() {
+            "I" => Operand::I,
+            "[I]" => Operand::Memory,
+            "DT" => Operand::Dt,
+            "ST" => Operand::St,
+            "K" => Operand::Key,
+            "F" => Operand::Font,
+            "B" => Operand::Bcd,
+            "R" => Operand::Flags,
+            "LONG" => Operand::Long,
+            "TIME" => Operand::Time,
+            _ => {
+                if let Some(register) = upper.strip_prefix('V') {
+                    if register.len() == 1 {
+                        if let Ok(x) = u8::from_str_radix(register, 16) {
+                            return Ok(Operand::Register(x));
+                        }
+                    }
+                }
+                let number = if let Some(hex) = upper.strip_prefix("0X").or(upper.strip_prefix('#'))
+                {
+                    u32::from_str_radix(hex, 16)
+                } else {
+                    upper.parse()
+                };
+                Operand::Number(number.map_err(|_| format!("invalid operand {:?}", text))?)
+            }
+        };
+        Ok(operand)
+    }
+}
+
+fn address(value: u32) -> Result<u16, String> {
+    match value {
+        0..=0xFFF => Ok(value as u16),
+        _ => Err(format!("address {:#x} is out of range", value)),
+    }
+}
+
+fn byte(value: u32) -> Result<u8, String> {
+    u8::try_from(value).map_err(|_| format!("{} does not fit in a byte", value))
+}
+
+fn nibble(value: u32) -> Result<u8, String> {
+    match value {
+        0..=0xF => Ok(value as u8),
+        _ => Err(format!("{} does not fit in a nibble", value)),
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::audio::{Beeper, PatternVoice};
+use crate::checksum::StableHasher;
+use crate::{is_beeping, run_frame, Emulator, EmulatorError};
+
+pub const SAMPLE_RATE: u32 = 44_100;
+const FRAMES_PER_SECOND: u32 = 60;
+// Music ROMs pause between phrases, rarely for this long.
+pub const DEFAULT_SILENCE_SECONDS: f32 = 3.0;
+
+// Why a track is over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackEnd {
+    // Nothing played for the silence limit after something had, or the
+    // machine settled into a state it never leaves.
+    Silence,
+    // The machine is back in the state it had at frame `start`, from here
+    // on the music repeats.
+    Loop { start: u64 },
+    Crashed(EmulatorError),
+}
+
+impl fmt::Display for TrackEnd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrackEnd::Silence => write!(f, "went silent"),
+            TrackEnd::Loop { start } => write!(
+                f,
+                "loops back to {:.2}s",
+                *start as f32 / FRAMES_PER_SECOND as f32
+            ),
+            TrackEnd::Crashed(error) => write!(f, "crashed: {}", error),
+        }
+    }
+}
+
+// Plays an XO-CHIP music ROM without a screen: runs it frame by frame,
+// renders what the sound timer, audio pattern and pitch make of it, and
+// notices when the track is over.
+pub struct Jukebox {
+    emulator: Emulator,
+    sample_rate: u32,
+    beeper: Beeper,
+    voice: PatternVoice,
+    frame: u64,
+    silence_frames: u32,
+    quiet_frames: u32,
+    heard: bool,
+    // Frame each machine state was first seen at.
+    seen: HashMap<u64, u64>,
+}
+
+impl Jukebox {
+    pub fn new(emulator: Emulator, sample_rate: u32, silence_seconds: f32) -> Self {
+        Jukebox {
+            emulator,
+            sample_rate,
+            beeper: Beeper::new(sample_rate),
+            voice: PatternVoice::new(sample_rate),
+            frame: 0,
+            silence_frames: (silence_seconds.max(0.0) * FRAMES_PER_SECOND as f32) as u32,
+            quiet_frames: 0,
+            heard: false,
+            seen: HashMap::new(),
+        }
+    }
+
+    pub fn emulator(&self) -> &Emulator {
+        &self.emulator
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.beeper.set_volume(volume);
+        self.voice.set_volume(volume);
+    }
+
+    // Frames played so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn seconds(&self) -> f32 {
+        self.frame as f32 / FRAMES_PER_SECOND as f32
+    }
+
+    // Seconds up to the end of the last sound, what is left once the
+    // trailing silence is cut.
+    pub fn track_seconds(&self) -> f32 {
+        (self.frame - self.quiet_frames as u64) as f32 / FRAMES_PER_SECOND as f32
+    }
+
+    pub fn is_sounding(&self) -> bool {
+        is_beeping(&self.emulator)
+    }
+
+    // Runs one frame and appends its samples. Once this returns the end of
+    // the track the jukebox should not be played any further; a loop adds
+    // no samples, it would be the first frame of the repeat.
+    pub fn play_frame(&mut self, samples: &mut Vec<f32>) -> Option<TrackEnd> {
+        let state = fingerprint(&self.emulator);
+        if let Some(&start) = self.seen.get(&state) {
+            // Idling in silence is not music repeating.
+            let sounded = (self.quiet_frames as u64) < self.frame - start;
+            return Some(match sounded {
+                true => TrackEnd::Loop { start },
+                false => TrackEnd::Silence,
+            });
+        }
+        self.seen.insert(state, self.frame);
+
+        let output = run_frame(&mut self.emulator);
+        let rate = self.sample_rate as u64;
+        let end = ((self.frame + 1) * rate / FRAMES_PER_SECOND as u64) as usize;
+        let start = (self.frame * rate / FRAMES_PER_SECOND as u64) as usize;
+        let offset = samples.len();
+        samples.resize(offset + end - start, 0.0);
+        let buffer = &mut samples[offset..];
+        self.frame += 1;
+
+        let sounding = is_beeping(&self.emulator);
+        let pattern = self.emulator.xochip.as_ref().and_then(|xochip| {
+            let pattern = xochip.pattern?;
+            Some((pattern, xochip.pitch))
+        });
+        match pattern {
+            Some((pattern, pitch)) if sounding => self.voice.fill(buffer, &pattern, pitch),
+            Some(_) => {}
+            None => {
+                self.beeper.set_beeping(sounding);
+                self.beeper.fill(buffer);
+            }
+        }
+
+        if let Some(error) = output.error {
+            return Some(TrackEnd::Crashed(error));
+        }
+        if sounding {
+            self.heard = true;
+            self.quiet_frames = 0;
+        } else {
+            self.quiet_frames += 1;
+        }
+        (self.heard && self.quiet_frames >= self.silence_frames.max(1)).then_some(TrackEnd::Silence)
+    }
+}
+
+// The machine without the keypad, whose clock never repeats. Nobody
+// presses keys on a jukebox.
+fn fingerprint(emulator: &Emulator) -> u64 {
+    let mut hasher = StableHasher::new();
+    emulator.v_registers.hash(&mut hasher);
+    emulator.i_register.hash(&mut hasher);
+    emulator.program_counter.hash(&mut hasher);
+    emulator.stack_pointer.hash(&mut hasher);
+    emulator.stack.hash(&mut hasher);
+    emulator.delay_timer_registry.hash(&mut hasher);
+    emulator.sound_timer_registry.hash(&mut hasher);
+    emulator.ram.hash(&mut hasher);
+    emulator.display.hash(&mut hasher);
+    emulator.rng.hash(&mut hasher);
+    emulator.timer_cycles.hash(&mut hasher);
+    emulator.xochip.hash(&mut hasher);
+    hasher.finish()
+}
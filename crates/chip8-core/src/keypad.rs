@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+
+use crate::savestate::{Reader, Writer};
+use crate::EmulatorError;
+
+pub const KEY_COUNT: usize = 16;
+// Events the queue holds without allocating, so pressing keys between
+// frames stays off the heap like the frames themselves. Every key going
+// down and up twice in a frame fits.
+const QUEUE_CAPACITY: usize = 4 * KEY_COUNT;
+
+// A key going down or up, stamped with the keypad clock (instructions
+// executed so far) it happened at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyEvent {
+    pub key: u8,
+    pub pressed: bool,
+    pub at: u64,
+}
+
+// The hex keypad, fed by frontends as a queue of events rather than a
+// bitmask. Events are applied one instruction at a time in order, and a key
+// stays down for at least a frame worth of instructions, so a tap that
+// starts and ends between two frames is still seen by a ROM polling with
+// Ex9E. Fx0A waits for a key to be pressed and then released, like the
+// COSMAC VIP.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keypad {
+    state: u16,
+    queue: VecDeque<KeyEvent>,
+    clock: u64,
+    pressed_at: [u64; KEY_COUNT],
+    // Keys pressed since Fx0A started waiting, while it waits.
+    waiting: Option<u16>,
+    released: Option<u8>,
+}
+
+impl Default for Keypad {
+    fn default() -> Self {
+        Keypad {
+            state: 0,
+            queue: VecDeque::with_capacity(QUEUE_CAPACITY),
+            clock: 0,
+            pressed_at: [0; KEY_COUNT],
+            waiting: None,
+            released: None,
+        }
+    }
+}
+
+impl Keypad {
+    pub fn new() -> Self {
+        Keypad::default()
+    }
+
+    // Queues a press at the current clock. Keys above F are ignored.
+    pub fn press(&mut self, key: u8) {
+        self.push(KeyEvent {
+            key,
+            pressed: true,
+            at: self.clock,
+        });
+    }
+
+    pub fn release(&mut self, key: u8) {
+        self.push(KeyEvent {
+            key,
+            pressed: false,
+            at: self.clock,
+        });
+    }
+
+    // Queues an event with its own timestamp, e.g. from a recording.
+    pub fn push(&mut self, event: KeyEvent) {
+        if (event.key as usize) < KEY_COUNT {
+            self.queue.push_back(event);
+        }
+    }
+
+    pub fn is_pressed(&self, key: u8) -> bool {
+        (key as usize) < KEY_COUNT && self.state & (1 << key) != 0
+    }
+
+    // One bit per key, bit 0 for key 0.
+    pub fn state(&self) -> u16 {
+        self.state
+    }
+
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    // Fx0A has not got its key yet, it rewound the PC to run again.
+    pub fn is_waiting(&self) -> bool {
+        self.waiting.is_some()
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    // The events not applied yet, oldest first.
+    pub fn queued(&self) -> impl Iterator<Item = KeyEvent> + '_ {
+        self.queue.iter().copied()
+    }
+
+    // Advances the clock by one instruction and applies the events that are
+    // due. A release waits until its key has been down for `min_hold`
+    // instructions, and holds back every event queued after it.
+    pub fn tick(&mut self, min_hold: u64) {
+        self.clock += 1;
+        while let Some(&event) = self.queue.front() {
+            let key = event.key as usize;
+            if event.at > self.clock
+                || (!event.pressed
+                    && self.is_pressed(event.key)
+                    && self.clock - self.pressed_at[key] < min_hold)
+            {
+                break;
+            }
+            self.queue.pop_front();
+            if event.pressed {
+                self.state |= 1 << key;
+                self.pressed_at[key] = self.clock;
+                if let Some(armed) = &mut self.waiting {
+                    *armed |= 1 << key;
+                }
+            } else {
+                self.state &= !(1 << key);
+                let armed = self.waiting.is_some_and(|armed| armed & (1 << key) != 0);
+                if armed && self.released.is_none() {
+                    self.released = Some(event.key);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn save(&self, writer: &mut Writer) {
+        writer.u16(self.state);
+        writer.u64(self.clock);
+        for &at in &self.pressed_at {
+            writer.u64(at);
+        }
+        writer.u8(self.waiting.is_some() as u8);
+        writer.u16(self.waiting.unwrap_or_default());
+        writer.u8(self.released.map_or(0xFF, |key| key));
+        writer.u64(self.queue.len() as u64);
+        for event in &self.queue {
+            writer.u8(event.key);
+            writer.u8(event.pressed as u8);
+            writer.u64(event.at);
+        }
+    }
+
+    pub(crate) fn load(reader: &mut Reader) -> Result<Self, EmulatorError> {
+        let mut keypad = Keypad {
+            state: reader.u16()?,
+            clock: reader.u64()?,
+            ..Keypad::default()
+        };
+        for at in keypad.pressed_at.iter_mut() {
+            *at = reader.u64()?;
+        }
+        let waiting = reader.u8()? != 0;
+        let armed = reader.u16()?;
+        keypad.waiting = waiting.then_some(armed);
+        keypad.released = Some(reader.u8()?).filter(|&key| (key as usize) < KEY_COUNT);
+        for _ in 0..reader.u64()? {
+            let key = reader.u8()?;
+            let pressed = reader.u8()? != 0;
+            let at = reader.u64()?;
+            keypad.push(KeyEvent { key, pressed, at });
+        }
+        Ok(keypad)
+    }
+
+    // Fx0A: the key that was pressed and released since the wait started,
+    // or None to keep waiting.
+    pub fn wait_for_key(&mut self) -> Option<u8> {
+        if self.waiting.is_none() {
+            self.waiting = Some(0);
+            self.released = None;
+        }
+        let key = self.released.take()?;
+        self.waiting = None;
+        Some(key)
+    }
+}
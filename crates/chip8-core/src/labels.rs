@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+use crate::instruction::Instruction;
+
+// Keywords of conditions and watch expressions, not usable as names.
+const RESERVED: [&str; 6] = ["I", "PC", "DT", "ST", "RAM", "WORD"];
+
+// Names given to addresses while debugging, e.g. `ball_x` for 0x3A0. They
+// stand for the address in watches and trigger conditions, "RAM[ball_x]",
+// and are shown next to it in disassembly and traces. Kept per ROM as
+// `label.<name> = <address>` config entries, see `RomConfig::labels`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Labels {
+    addresses: BTreeMap<String, u16>,
+    names: BTreeMap<u16, String>,
+}
+
+impl Labels {
+    pub fn new() -> Self {
+        Labels::default()
+    }
+
+    // Names are a letter or `_` and then letters, digits and `_`, not a
+    // register. Naming a labelled address again renames it.
+    pub fn add(&mut self, address: u16, name: &str) -> Result<(), String> {
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!(
+                "{:?} is not a name, use letters, digits and _",
+                name
+            ));
+        }
+        let upper = name.to_ascii_uppercase();
+        let register = upper.len() == 2 && upper.starts_with('V');
+        if register || RESERVED.contains(&upper.as_str()) {
+            return Err(format!("{} is reserved", name));
+        }
+        self.remove(name);
+        if let Some(old) = self.names.insert(address, name.to_string()) {
+            self.addresses.remove(&old);
+        }
+        self.addresses.insert(name.to_string(), address);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<u16> {
+        let address = self.addresses.remove(name)?;
+        self.names.remove(&address);
+        Some(address)
+    }
+
+    pub fn address(&self, name: &str) -> Option<u16> {
+        self.addresses.get(name).copied()
+    }
+
+    pub fn name(&self, address: u16) -> Option<&str> {
+        self.names.get(&address).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    // By address.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &str)> {
+        self.names
+            .iter()
+            .map(|(address, name)| (*address, name.as_str()))
+    }
+
+    // "  ; ball_x" for a labelled address and "  ; -> ball_x" for an
+    // instruction using one, or nothing, to append to a disassembly line.
+    pub fn annotate(&self, address: u16, instruction: Instruction) -> String {
+        let target = match instruction {
+            Instruction::Jp { nnn }
+            | Instruction::Call { nnn }
+            | Instruction::LdI { nnn }
+            | Instruction::JpV0 { nnn } => self.name(nnn),
+            _ => None,
+        };
+        match (self.name(address), target) {
+            (Some(name), Some(target)) => format!("  ; {}, -> {}", name, target),
+            (Some(name), None) => format!("  ; {}", name),
+            (None, Some(target)) => format!("  ; -> {}", target),
+            (None, None) => String::new(),
+        }
+    }
+
+    // Reads `label.<name> = <address>` config entries.
+    pub fn from_config<'a>(
+        entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, String> {
+        let mut labels = Labels::new();
+        for (key, value) in entries {
+            let Some(name) = key.strip_prefix("label.") else {
+                continue;
+            };
+            let address = match value.strip_prefix("0x").or(value.strip_prefix("0X")) {
+                Some(hex) => u16::from_str_radix(hex, 16),
+                None => value.parse(),
+            }
+            .map_err(|_| format!("label {}: {:?} is not an address", name, value))?;
+            labels
+                .add(address, name)
+                .map_err(|error| format!("label {}: {}", name, error))?;
+        }
+        Ok(labels)
+    }
+
+    // The entries `from_config` reads back.
+    pub fn to_config(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.iter()
+            .map(|(address, name)| (format!("label.{}", name), format!("{:#05x}", address)))
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::{Emulator, EmulatorBuilder, EmulatorError, Quirks};
+
+// Enough instructions per frame for the probe to flash the whole screen
+// within the frame the key is seen in.
+const PROBE_CYCLES_PER_FRAME: usize = 1000;
+
+// Inverts the whole screen while any key is held down: scans the keypad
+// with Exa1, XORs 8x15 blocks over every 8 pixel column and 15 pixel row,
+// waits for the key to go up and then inverts it back.
+#[rustfmt::skip]
+const PROBE: [u16; 27] = [
+    0x00E0,         // 200: CLS
+    0x6100,         // 202: wait: LD V1, 0
+    0xE1A1,         // 204: scan: SKNP V1
+    0x1210,         // 206:   JP flash
+    0x7101,         // 208: ADD V1, 1
+    0x3110,         // 20A: SE V1, 16
+    0x1204,         // 20C:   JP scan
+    0x1202,         // 20E: JP wait
+    0x2220,         // 210: flash: CALL invert
+    0xE1A1,         // 212: held: SKNP V1
+    0x1212,         // 214:   JP held
+    0x2220,         // 216: CALL invert
+    0x1202,         // 218: JP wait
+    0x0000,         // 21A
+    0x0000,         // 21C
+    0x0000,         // 21E
+    0xA236,         // 220: invert: LD I, block
+    0x6300,         // 222: LD V3, 0
+    0x6200,         // 224: row: LD V2, 0
+    0xD23F,         // 226: column: DRW V2, V3, 15
+    0x7208,         // 228: ADD V2, 8
+    0x3240,         // 22A: SE V2, 64
+    0x1226,         // 22C:   JP column
+    0x730F,         // 22E: ADD V3, 15
+    0x332D,         // 230: SE V3, 45
+    0x1224,         // 232:   JP row
+    0x00EE,         // 234: RET
+];
+const BLOCK: [u8; 15] = [0xFF; 15];
+
+// The probe ROM: the screen flashes the moment a key goes down.
+pub fn probe_rom() -> Vec<u8> {
+    let mut rom: Vec<u8> = PROBE.iter().flat_map(|op| op.to_be_bytes()).collect();
+    rom.extend_from_slice(&BLOCK);
+    rom
+}
+
+// An emulator running the probe, without the display wait that would hold
+// every sprite back to the next frame.
+pub fn probe_emulator() -> Result<Emulator, EmulatorError> {
+    let quirks = Quirks {
+        display_wait: false,
+        ..Quirks::chip8()
+    };
+    EmulatorBuilder::new()
+        .quirks(quirks)
+        .cycles_per_frame(PROBE_CYCLES_PER_FRAME)
+        .rom(&probe_rom())
+        .build()
+}
+
+// One key press, from the host event to the flash being presented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    // Event until the frame that hands the key to the emulator starts.
+    pub input: Duration,
+    // That frame's start until a frame has drawn the flash.
+    pub emulation: Duration,
+    // Drawn until the frontend has presented it.
+    pub render: Duration,
+}
+
+impl Sample {
+    pub fn total(&self) -> Duration {
+        self.input + self.emulation + self.render
+    }
+}
+
+type Stage = fn(&Sample) -> Duration;
+
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    event: Instant,
+    polled: Option<Instant>,
+    emulated: Option<Instant>,
+}
+
+// Measures event-to-present latency with the probe ROM. A frontend reports
+// the pipeline's stages as they happen, for the key press it is tracking:
+//
+//     probe.key_event(event_time);    // when the host saw the key
+//     probe.polled(Instant::now());   // pressed on the keypad, frame starts
+//     probe.emulated(Instant::now(), output.display_changed);
+//     probe.presented(Instant::now());    // after the swap or flush
+//
+// Presses while one is being measured are ignored, they would overlap.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyProbe {
+    pending: Option<Pending>,
+    samples: Vec<Sample>,
+}
+
+impl LatencyProbe {
+    pub fn new() -> Self {
+        LatencyProbe::default()
+    }
+
+    // False if the press is ignored.
+    pub fn key_event(&mut self, at: Instant) -> bool {
+        if self.pending.is_some() {
+            return false;
+        }
+        self.pending = Some(Pending {
+            event: at,
+            polled: None,
+            emulated: None,
+        });
+        true
+    }
+
+    pub fn polled(&mut self, at: Instant) {
+        if let Some(pending) = &mut self.pending {
+            pending.polled.get_or_insert(at);
+        }
+    }
+
+    pub fn emulated(&mut self, at: Instant, display_changed: bool) {
+        if let Some(pending) = &mut self.pending {
+            if pending.polled.is_some() && display_changed {
+                pending.emulated.get_or_insert(at);
+            }
+        }
+    }
+
+    pub fn presented(&mut self, at: Instant) {
+        let Some(pending) = self.pending else { return };
+        let (Some(polled), Some(emulated)) = (pending.polled, pending.emulated) else {
+            return;
+        };
+        self.samples.push(Sample {
+            input: polled.saturating_duration_since(pending.event),
+            emulation: emulated.saturating_duration_since(polled),
+            render: at.saturating_duration_since(emulated),
+        });
+        self.pending = None;
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    // Statistics per stage, one line each, in milliseconds.
+    pub fn report(&self) -> String {
+        let stages: [(&str, Stage); 4] = [
+            ("input", |sample| sample.input),
+            ("emulation", |sample| sample.emulation),
+            ("render", |sample| sample.render),
+            ("total", Sample::total),
+        ];
+        let mut report = format!(
+            "{} presses\n{:<10} {:>8} {:>8} {:>8} {:>8} {:>8}\n",
+            self.samples.len(),
+            "STAGE",
+            "MIN",
+            "MEDIAN",
+            "P95",
+            "MAX",
+            "MEAN"
+        );
+        for (name, stage) in stages {
+            let durations: Vec<Duration> = self.samples.iter().map(stage).collect();
+            let Some(summary) = Summary::of(&durations) else {
+                continue;
+            };
+            let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+            let _ = writeln!(
+                report,
+                "{:<10} {:>8.2} {:>8.2} {:>8.2} {:>8.2} {:>8.2}",
+                name,
+                ms(summary.min),
+                ms(summary.median),
+                ms(summary.p95),
+                ms(summary.max),
+                ms(summary.mean)
+            );
+        }
+        report
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+}
+
+impl Summary {
+    // None without any durations. Percentiles are the nearest rank.
+    pub fn of(durations: &[Duration]) -> Option<Summary> {
+        if durations.is_empty() {
+            return None;
+        }
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let rank = |percent: usize| sorted[(sorted.len() * percent).div_ceil(100).max(1) - 1];
+        Some(Summary {
+            min: sorted[0],
+            median: rank(50),
+            p95: rank(95),
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+        })
+    }
+}
@@ -0,0 +1,95 @@
+// The stable API is what frontends need to run a ROM: `Emulator` and
+// `EmulatorBuilder`, `step`/`run_frame` and `FrameOutput`, `Display`,
+// `Keypad`, `Quirks`, `Instruction` and `EmulatorError`, or `Chip8` wrapping
+// them, see tests/api.rs.
+// `testing` is for ROM developers' own tests and kept as stable. The tool
+// modules below are public for the workspace's own crates and may still
+// change, hidden items are internals.
+pub mod analyzer;
+pub mod assembler;
+pub mod audio;
+pub mod branches;
+pub mod breakpoint;
+mod builder;
+pub mod calibration;
+pub mod cast;
+pub mod checksum;
+pub mod chip8;
+pub mod chip8x;
+pub mod clock;
+pub mod compress;
+pub mod config;
+pub mod delta;
+pub mod demo;
+pub mod diagnosis;
+pub mod discord;
+pub mod disk;
+pub mod display;
+pub mod dump;
+mod emulator;
+pub mod endstate;
+mod error;
+pub mod events;
+pub mod font;
+mod frame;
+pub mod heatmap;
+pub mod hooks;
+pub mod host;
+pub mod hotkeys;
+pub mod input;
+pub mod instruction;
+pub mod jukebox;
+pub mod keypad;
+pub mod labels;
+pub mod latency;
+pub mod lint;
+pub mod macros;
+#[doc(hidden)]
+pub mod mutants;
+pub mod narration;
+pub mod ocr;
+pub mod octo;
+pub mod patch;
+pub mod perftrace;
+pub mod playlist;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod presence;
+pub mod printer;
+pub mod quirks;
+pub mod quirkstest;
+pub mod reload;
+pub mod render;
+mod rng;
+pub mod rom;
+pub mod savestate;
+#[cfg(feature = "serde")]
+mod serde_support;
+pub mod session;
+pub mod stats;
+pub mod strict;
+pub mod sync;
+pub mod terminal;
+pub mod testing;
+#[doc(hidden)]
+pub mod testrom;
+pub mod timeline;
+pub mod title;
+pub mod trace;
+pub mod triggers;
+pub mod wallclock;
+pub mod watch;
+pub mod watchdog;
+pub mod xochip;
+
+pub use builder::EmulatorBuilder;
+pub use chip8::Chip8;
+pub use display::{Display, Palette};
+pub use emulator::*;
+pub use error::EmulatorError;
+pub use frame::FrameOutput;
+pub use hooks::ExecutionHook;
+pub use instruction::Instruction;
+pub use keypad::Keypad;
+pub use quirks::Quirks;
+pub use rng::Rng;
@@ -0,0 +1,417 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::analyzer::{analyze, decode, fetch, successors};
+use crate::narration::json_string;
+use crate::{Instruction, INITIAL_ADDRESS};
+
+// How bad a lint is, named after SARIF's levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    // The ROM breaks when it gets there.
+    Error,
+    // It works on some interpreters, or by luck.
+    Warning,
+    // Worth a look, it may be on purpose.
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        })
+    }
+}
+
+// Bugs homebrew ROMs often have, found without running them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rule {
+    // Dxyn before any 00E0: the screen holds whatever the machine left on
+    // it, blank on most interpreters only.
+    DrawBeforeClear,
+    // Fx33 writing its three digits over instructions.
+    BcdOverCode,
+    // Fx55 saving registers no Fx65 or Dxyn reads back.
+    StoreNeverLoaded,
+    // A jump or call into a sprite.
+    JumpIntoSprite,
+    // 00EE reached without a call, with an empty stack.
+    RetWithoutCall,
+    // A subroutine that jumps back instead of returning, so every call
+    // leaves an address on the stack until it overflows.
+    CallNeverReturns,
+}
+
+pub const RULES: [Rule; 6] = [
+    Rule::DrawBeforeClear,
+    Rule::BcdOverCode,
+    Rule::StoreNeverLoaded,
+    Rule::JumpIntoSprite,
+    Rule::RetWithoutCall,
+    Rule::CallNeverReturns,
+];
+
+impl Rule {
+    pub fn id(self) -> &'static str {
+        match self {
+            Rule::DrawBeforeClear => "draw-before-clear",
+            Rule::BcdOverCode => "bcd-over-code",
+            Rule::StoreNeverLoaded => "store-never-loaded",
+            Rule::JumpIntoSprite => "jump-into-sprite",
+            Rule::RetWithoutCall => "ret-without-call",
+            Rule::CallNeverReturns => "call-never-returns",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            Rule::DrawBeforeClear | Rule::CallNeverReturns => Severity::Warning,
+            Rule::BcdOverCode | Rule::JumpIntoSprite | Rule::RetWithoutCall => Severity::Error,
+            Rule::StoreNeverLoaded => Severity::Note,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Rule::DrawBeforeClear => "Draws before clearing the screen with 00E0",
+            Rule::BcdOverCode => "Fx33 writes its digits over code",
+            Rule::StoreNeverLoaded => "Fx55 saves registers nothing reads back",
+            Rule::JumpIntoSprite => "Jumps or calls into a sprite",
+            Rule::RetWithoutCall => "00EE returns with an empty stack",
+            Rule::CallNeverReturns => "Calls a subroutine that never returns",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub rule: Rule,
+    // Of the instruction at fault.
+    pub address: u16,
+    pub message: String,
+}
+
+impl Lint {
+    pub fn severity(&self) -> Severity {
+        self.rule.severity()
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:#05x}  {}[{}]: {}",
+            self.address,
+            self.severity(),
+            self.rule.id(),
+            self.message
+        )
+    }
+}
+
+// Every lint in `rom`, loaded at `INITIAL_ADDRESS`, by address. Only code
+// `analyze` reaches is looked at, and I only where every path sets it to
+// the same address, so a lint is seldom wrong but some bugs go unseen.
+pub fn lint(rom: &[u8]) -> Vec<Lint> {
+    let code: BTreeSet<u16> = analyze(rom).code().collect();
+    let instructions: BTreeMap<u16, Instruction> = code
+        .iter()
+        .filter_map(|&address| Some((address, decode(rom, address)?)))
+        .collect();
+    let known_i = known_i(rom);
+    let i_at = |address: u16| known_i.get(&address).copied().flatten();
+    let mut lints = Vec::new();
+    let mut push = |rule, address, message: String| {
+        lints.push(Lint {
+            rule,
+            address,
+            message,
+        })
+    };
+
+    let mut clears = Clears::new(rom);
+    clears.returns_uncleared(INITIAL_ADDRESS);
+    for &address in &clears.draws {
+        push(
+            Rule::DrawBeforeClear,
+            address,
+            "draws before the screen is cleared with 00E0, which is blank at power on only on some interpreters".to_string(),
+        );
+    }
+
+    // What Dxyn and Fx65 read, start and length, wide enough not to wrap.
+    let mut sprites = Vec::new();
+    let mut loads = Vec::new();
+    let mut loads_known = true;
+    for (&address, &instruction) in &instructions {
+        match (instruction, i_at(address)) {
+            (Instruction::Drw { n, .. }, Some(i)) => {
+                let len = if n == 0 { 32 } else { n as u32 };
+                sprites.push((i as u32, len, address));
+            }
+            (Instruction::Load { x }, Some(i)) => loads.push((i as u32, x as u32 + 1)),
+            (Instruction::Load { .. }, None) => loads_known = false,
+            _ => {}
+        }
+    }
+    let overlaps = |a: (u32, u32), b: (u32, u32)| a.0 < b.0 + b.1 && b.0 < a.0 + a.1;
+
+    for (&address, &instruction) in &instructions {
+        match (instruction, i_at(address)) {
+            (Instruction::LdBcd { .. }, Some(i)) => {
+                if let Some(over) = (i..i.saturating_add(3)).find(|byte| code.contains(byte)) {
+                    push(
+                        Rule::BcdOverCode,
+                        address,
+                        format!(
+                            "writes its digits to {:#05x}, over the instruction at {:#05x}",
+                            i, over
+                        ),
+                    );
+                }
+            }
+            (Instruction::Store { x }, Some(i)) if loads_known => {
+                let saved = (i as u32, x as u32 + 1);
+                let read = loads.iter().any(|&load| overlaps(saved, load))
+                    || sprites
+                        .iter()
+                        .any(|&(at, len, _)| overlaps(saved, (at, len)));
+                if !read {
+                    push(
+                        Rule::StoreNeverLoaded,
+                        address,
+                        format!(
+                            "saves V0 to V{:X} at {:#05x}, no Fx65 loads them back",
+                            x, i
+                        ),
+                    );
+                }
+            }
+            (Instruction::Jp { nnn } | Instruction::Call { nnn }, _) => {
+                if let Some(&(sprite, _, drawn)) = sprites
+                    .iter()
+                    .find(|&&(at, len, _)| (at..at + len).contains(&(nnn as u32)))
+                {
+                    push(
+                        Rule::JumpIntoSprite,
+                        address,
+                        format!(
+                            "goes to {:#05x}, inside the sprite at {:#05x} drawn at {:#05x}",
+                            nnn, sprite, drawn
+                        ),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let main = body(rom, INITIAL_ADDRESS);
+    for &address in &main {
+        if let Some(Instruction::Ret) = instructions.get(&address) {
+            push(
+                Rule::RetWithoutCall,
+                address,
+                "returns outside any subroutine, with nothing on the stack".to_string(),
+            );
+        }
+    }
+    let mut routines: BTreeMap<u16, Vec<u16>> = BTreeMap::new();
+    for (&address, &instruction) in &instructions {
+        if let Instruction::Call { nnn } = instruction {
+            routines.entry(nnn).or_default().push(address);
+        }
+    }
+    for (entry, calls) in routines {
+        let routine = body(rom, entry);
+        let leaves = routine.iter().any(|address| {
+            matches!(
+                instructions.get(address),
+                Some(Instruction::Ret | Instruction::JpV0 { .. })
+            )
+        });
+        let Some(&back) = routine.intersection(&main).next().filter(|_| !leaves) else {
+            continue;
+        };
+        for call in calls {
+            push(
+                Rule::CallNeverReturns,
+                call,
+                format!(
+                    "the subroutine at {:#05x} never returns, it goes back to {:#05x} and leaves an address on the stack",
+                    entry, back
+                ),
+            );
+        }
+    }
+
+    lints.sort_by_key(|lint| (lint.address, lint.rule));
+    lints
+}
+
+// The lints as a JSON array of objects with the rule, severity, address
+// and message.
+pub fn to_json(lints: &[Lint]) -> String {
+    let objects: Vec<String> = lints
+        .iter()
+        .map(|lint| {
+            format!(
+                "{{\"rule\":{},\"severity\":\"{}\",\"address\":{},\"message\":{}}}",
+                json_string(lint.rule.id()),
+                lint.severity(),
+                lint.address,
+                json_string(&lint.message)
+            )
+        })
+        .collect();
+    format!("[{}]\n", objects.join(","))
+}
+
+// The lints as a SARIF 2.1.0 log, for editors and code scanning. `uri` is
+// the ROM file, the regions are byte offsets into it.
+pub fn to_sarif(lints: &[Lint], uri: &str) -> String {
+    let rules: Vec<String> = RULES
+        .iter()
+        .map(|rule| {
+            format!(
+                "{{\"id\":{},\"shortDescription\":{{\"text\":{}}},\"defaultConfiguration\":{{\"level\":\"{}\"}}}}",
+                json_string(rule.id()),
+                json_string(rule.description()),
+                rule.severity()
+            )
+        })
+        .collect();
+    let results: Vec<String> = lints
+        .iter()
+        .map(|lint| {
+            format!(
+                "{{\"ruleId\":{},\"level\":\"{}\",\"message\":{{\"text\":{}}},\
+                 \"locations\":[{{\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":{}}},\
+                 \"region\":{{\"byteOffset\":{},\"byteLength\":2}}}}}}]}}",
+                json_string(lint.rule.id()),
+                lint.severity(),
+                json_string(&format!("{:#05x}: {}", lint.address, lint.message)),
+                json_string(uri),
+                lint.address.saturating_sub(INITIAL_ADDRESS)
+            )
+        })
+        .collect();
+    format!(
+        "{{\"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\"version\":\"2.1.0\",\
+         \"runs\":[{{\"tool\":{{\"driver\":{{\"name\":\"chip8-tools\",\"rules\":[{}]}}}},\
+         \"results\":[{}]}}]}}\n",
+        rules.join(","),
+        results.join(",")
+    )
+}
+
+// I at every reachable instruction, when all paths there leave the same
+// address in it. Fx55 and Fx65 may move it depending on the quirks, and a
+// call may change it before returning.
+fn known_i(rom: &[u8]) -> BTreeMap<u16, Option<u16>> {
+    let mut known: BTreeMap<u16, Option<u16>> = BTreeMap::new();
+    let mut pending = vec![(INITIAL_ADDRESS, None)];
+    while let Some((address, i)) = pending.pop() {
+        let i = match known.get(&address) {
+            Some(&seen) if seen == i || seen.is_none() => continue,
+            Some(_) => None,
+            None => i,
+        };
+        known.insert(address, i);
+        let Some(instruction) = decode(rom, address) else {
+            continue;
+        };
+        let after = match instruction {
+            Instruction::LdI { nnn } => Some(nnn),
+            Instruction::LongI => fetch(rom, address.wrapping_add(2)),
+            Instruction::AddI { .. }
+            | Instruction::LdF { .. }
+            | Instruction::Store { .. }
+            | Instruction::Load { .. } => None,
+            _ => i,
+        };
+        for next in successors(rom, address, instruction) {
+            let returned = matches!(instruction, Instruction::Call { nnn } if next != nnn);
+            pending.push((next, if returned { None } else { after }));
+        }
+    }
+    known
+}
+
+// The instructions of the routine at `entry`, calls taken to return.
+fn body(rom: &[u8], entry: u16) -> BTreeSet<u16> {
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![entry];
+    while let Some(address) = pending.pop() {
+        let Some(instruction) = decode(rom, address) else {
+            continue;
+        };
+        if !seen.insert(address) {
+            continue;
+        }
+        match instruction {
+            Instruction::Call { .. } => pending.push(address.wrapping_add(2)),
+            _ => pending.extend(successors(rom, address, instruction)),
+        }
+    }
+    seen
+}
+
+// Follows the code from power on until 00E0, into the subroutines it
+// calls, noting the Dxyn on the way.
+struct Clears<'a> {
+    rom: &'a [u8],
+    // Whether each routine walked can return before clearing the screen.
+    returns: BTreeMap<u16, bool>,
+    draws: BTreeSet<u16>,
+}
+
+impl<'a> Clears<'a> {
+    fn new(rom: &'a [u8]) -> Self {
+        Clears {
+            rom,
+            returns: BTreeMap::new(),
+            draws: BTreeSet::new(),
+        }
+    }
+
+    fn returns_uncleared(&mut self, entry: u16) -> bool {
+        if let Some(&returns) = self.returns.get(&entry) {
+            return returns;
+        }
+        // Calls back into itself are taken not to return.
+        self.returns.insert(entry, false);
+        let mut returns = false;
+        let mut seen = BTreeSet::new();
+        let mut pending = vec![entry];
+        while let Some(address) = pending.pop() {
+            if !seen.insert(address) {
+                continue;
+            }
+            let Some(instruction) = decode(self.rom, address) else {
+                continue;
+            };
+            match instruction {
+                Instruction::Cls => {}
+                Instruction::Ret => returns = true,
+                Instruction::Call { nnn } => {
+                    if self.returns_uncleared(nnn) {
+                        pending.push(address.wrapping_add(2));
+                    }
+                }
+                _ => {
+                    if let Instruction::Drw { .. } = instruction {
+                        self.draws.insert(address);
+                    }
+                    pending.extend(successors(self.rom, address, instruction));
+                }
+            }
+        }
+        self.returns.insert(entry, returns);
+        returns
+    }
+}
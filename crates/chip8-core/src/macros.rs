@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use crate::hotkeys::normalize_chord;
+use crate::input::{InputScript, InputSource};
+use crate::keypad::{Keypad, KEY_COUNT};
+
+// Short recorded key sequences, such as the presses that get past a title
+// screen, played back on a hotkey or as soon as the ROM loads. Kept per ROM
+// as config entries, see `RomConfig::macros`:
+//
+//     macro.skip_title = 30 5 down, 34 5 up
+//     macro.skip_title.hotkey = ctrl+t
+//     macro.skip_title.autoplay = on
+//
+// The events are `InputScript` lines, frames counted from when the macro
+// starts playing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Macros {
+    macros: BTreeMap<String, Macro>,
+    // The ones started and not finished yet.
+    playing: Vec<InputScript>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Macro {
+    pub script: InputScript,
+    pub hotkey: Option<String>,
+    pub autoplay: bool,
+}
+
+impl Macros {
+    pub fn new() -> Self {
+        Macros::default()
+    }
+
+    // Names are a letter or `_` and then letters, digits and `_`. Adding
+    // one again replaces its events and keeps its hotkey and autoplay.
+    pub fn add(&mut self, name: &str, script: InputScript) -> Result<(), String> {
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!(
+                "{:?} is not a name, use letters, digits and _",
+                name
+            ));
+        }
+        self.macros
+            .entry(name.to_string())
+            .and_modify(|old| old.script = script.clone())
+            .or_insert(Macro {
+                script,
+                hotkey: None,
+                autoplay: false,
+            });
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Macro> {
+        self.macros.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Macro> {
+        self.macros.get_mut(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Macro> {
+        self.macros.remove(name)
+    }
+
+    // By name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Macro)> {
+        self.macros.iter().map(|(name, m)| (name.as_str(), m))
+    }
+
+    pub fn len(&self) -> usize {
+        self.macros.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.macros.is_empty()
+    }
+
+    // The macro bound to `chord`, "Ctrl+T" and "ctrl+t" alike.
+    pub fn for_hotkey(&self, chord: &str) -> Option<&str> {
+        let chord = normalize_chord(chord);
+        self.iter()
+            .find(|(_, m)| {
+                m.hotkey
+                    .as_deref()
+                    .is_some_and(|hotkey| normalize_chord(hotkey) == chord)
+            })
+            .map(|(name, _)| name)
+    }
+
+    // Starts playing `name` from its first frame, alongside any other
+    // playing. False if there is no such macro.
+    pub fn play(&mut self, name: &str) -> bool {
+        let Some(found) = self.macros.get(name) else {
+            return false;
+        };
+        let mut script = found.script.clone();
+        script.rewind();
+        self.playing.push(script);
+        true
+    }
+
+    // Plays every autoplay macro, for frontends to call once the ROM has
+    // loaded.
+    pub fn autoplay(&mut self) {
+        let names: Vec<String> = self
+            .iter()
+            .filter(|(_, m)| m.autoplay)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        for name in names {
+            self.play(&name);
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        !self.playing.is_empty()
+    }
+
+    // Reads `macro.<name>`, `macro.<name>.hotkey` and
+    // `macro.<name>.autoplay` config entries.
+    pub fn from_config<'a>(
+        entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, String> {
+        let mut macros = Macros::new();
+        let mut options = Vec::new();
+        for (key, value) in entries {
+            let Some(name) = key.strip_prefix("macro.") else {
+                continue;
+            };
+            if let Some((name, option)) = name.split_once('.') {
+                options.push((name, option, value));
+                continue;
+            }
+            let script = InputScript::parse(&value.replace(',', "\n"))
+                .map_err(|error| format!("macro {}: {}", name, error))?;
+            macros
+                .add(name, script)
+                .map_err(|error| format!("macro {}: {}", name, error))?;
+        }
+        for (name, option, value) in options {
+            let error = |message: &str| format!("macro {}: {}", name, message);
+            let found = macros
+                .get_mut(name)
+                .ok_or_else(|| error("has options but no events"))?;
+            match (option, value) {
+                ("hotkey", chord) => found.hotkey = Some(chord.to_string()),
+                ("autoplay", "on") => found.autoplay = true,
+                ("autoplay", "off") => found.autoplay = false,
+                ("autoplay", _) => return Err(error("autoplay is either on or off")),
+                _ => return Err(error(&format!("unknown option {:?}", option))),
+            }
+        }
+        Ok(macros)
+    }
+
+    // The entries `from_config` reads back.
+    pub fn to_config(&self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        for (name, m) in self.iter() {
+            let events = m.script.to_text().trim_end().replace('\n', ", ");
+            entries.push((format!("macro.{}", name), events));
+            if let Some(chord) = &m.hotkey {
+                entries.push((format!("macro.{}.hotkey", name), chord.clone()));
+            }
+            if m.autoplay {
+                entries.push((format!("macro.{}.autoplay", name), "on".to_string()));
+            }
+        }
+        entries
+    }
+}
+
+// Presses the keys of every macro playing, once per frame like any other
+// source.
+impl InputSource for Macros {
+    fn poll(&mut self, keypad: &mut Keypad) {
+        for script in &mut self.playing {
+            script.poll(keypad);
+        }
+        self.playing.retain(|script| !script.is_finished());
+    }
+}
+
+// Records the keys pressed from when it starts, into the script of a new
+// macro. It sees every event queued on the keypad, whoever queued it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MacroRecorder {
+    events: Vec<(u64, u8, bool)>,
+    frames: u64,
+    pending: usize,
+}
+
+impl MacroRecorder {
+    pub fn start(keypad: &Keypad) -> Self {
+        MacroRecorder {
+            pending: keypad.pending(),
+            ..MacroRecorder::default()
+        }
+    }
+
+    // Call right before every `run_frame`, with the keys already pressed.
+    pub fn before_frame(&mut self, keypad: &Keypad) {
+        self.frames += 1;
+        for event in keypad.queued().skip(self.pending) {
+            self.events.push((self.frames, event.key, event.pressed));
+        }
+    }
+
+    // Call right after every `run_frame`.
+    pub fn after_frame(&mut self, keypad: &Keypad) {
+        self.pending = keypad.pending();
+    }
+
+    // The keys still down are let go on the frame after the last, so
+    // playing the macro never leaves one held.
+    pub fn finish(self) -> InputScript {
+        let mut events = self.events;
+        let mut held = 0u16;
+        for &(_, key, pressed) in &events {
+            if pressed {
+                held |= 1 << key;
+            } else {
+                held &= !(1 << key);
+            }
+        }
+        let end = self.frames + 1;
+        events.extend(
+            (0..KEY_COUNT as u8)
+                .filter(|key| held & 1 << key != 0)
+                .map(|key| (end, key, false)),
+        );
+        InputScript::from_events(events)
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+
+use crate::{Emulator, Instruction};
+
+// Deliberate executor bugs for `chip8-tools mutants`, which runs the test
+// suite once per mutant and reports the ones no test noticed: each of
+// those is an opcode behaviour nothing checks. The executor only applies
+// them when built with the `mutants` feature, and only the one named by
+// `ENV_VAR`, so a normal build pays nothing.
+pub const ENV_VAR: &str = "CHIP8_MUTANT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    // VF ends up the other way round.
+    FlipFlag,
+    // The register written ends up one more.
+    ResultPlusOne,
+    // PC ends up an instruction further.
+    PcPlusTwo,
+    // I ends up one more.
+    IPlusOne,
+    // The timer set ends up one more.
+    TimerPlusOne,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::FlipFlag => "flip-vf",
+            Kind::ResultPlusOne => "vx-plus-1",
+            Kind::PcPlusTwo => "pc-plus-2",
+            Kind::IPlusOne => "i-plus-1",
+            Kind::TimerPlusOne => "timer-plus-1",
+        }
+    }
+}
+
+// Which instructions each kind applies to, by `Instruction::pattern`.
+const TARGETS: &[(Kind, &[&str])] = &[
+    (
+        Kind::FlipFlag,
+        &[
+            "8XY1", "8XY2", "8XY3", "8XY4", "8XY5", "8XY6", "8XY7", "8XYE", "DXYN",
+        ],
+    ),
+    (
+        Kind::ResultPlusOne,
+        &[
+            "6XKK", "7XKK", "8XY0", "8XY1", "8XY2", "8XY3", "8XY4", "8XY5", "8XY6", "8XY7", "8XYE",
+            "FX07", "FX0A",
+        ],
+    ),
+    (
+        Kind::PcPlusTwo,
+        &[
+            "00EE", "1NNN", "2NNN", "3XKK", "4XKK", "5XY0", "9XY0", "BNNN", "EX9E", "EXA1",
+        ],
+    ),
+    (Kind::IPlusOne, &["ANNN", "FX1E", "FX55", "FX65"]),
+    (Kind::TimerPlusOne, &["FX15", "FX18"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mutant {
+    pub kind: Kind,
+    pub pattern: &'static str,
+}
+
+impl Mutant {
+    // "flip-vf-8XY4", what `ENV_VAR` takes.
+    pub fn name(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn find(name: &str) -> Option<Mutant> {
+        all().find(|mutant| mutant.name().eq_ignore_ascii_case(name))
+    }
+
+    // Breaks the machine right after `instruction` ran, if it is the one
+    // this mutant targets.
+    pub fn apply(&self, emulator: &mut Emulator, instruction: Instruction) {
+        if instruction.pattern() != self.pattern {
+            return;
+        }
+        let x = (instruction.encode() >> 8 & 0xF) as usize;
+        match self.kind {
+            Kind::FlipFlag => emulator.v_registers[0xF] ^= 1,
+            Kind::ResultPlusOne => {
+                emulator.v_registers[x] = emulator.v_registers[x].wrapping_add(1)
+            }
+            Kind::PcPlusTwo => emulator.program_counter = emulator.program_counter.wrapping_add(2),
+            Kind::IPlusOne => emulator.i_register = emulator.i_register.wrapping_add(1),
+            Kind::TimerPlusOne => match self.pattern {
+                "FX15" => emulator.delay_timer_registry += 1,
+                _ => emulator.sound_timer_registry += 1,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Mutant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.kind.name(), self.pattern)
+    }
+}
+
+pub fn all() -> impl Iterator<Item = Mutant> {
+    TARGETS.iter().flat_map(|&(kind, patterns)| {
+        patterns
+            .iter()
+            .map(move |&pattern| Mutant { kind, pattern })
+    })
+}
+
+// The one `ENV_VAR` names, looked up once.
+#[cfg(feature = "mutants")]
+pub(crate) fn active() -> Option<Mutant> {
+    use std::sync::OnceLock;
+
+    static ACTIVE: OnceLock<Option<Mutant>> = OnceLock::new();
+    *ACTIVE.get_or_init(|| {
+        let name = std::env::var(ENV_VAR).ok()?;
+        Some(Mutant::find(&name).unwrap_or_else(|| panic!("{} names no mutant", name)))
+    })
+}
@@ -0,0 +1,128 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::delta::{changes, Change};
+use crate::{get_op_code, step, Emulator, EmulatorError, Instruction};
+
+// Writes one JSON object per executed instruction: the decoded instruction,
+// the registers before and after, the memory bytes it changed and whether
+// it touched the screen. Meant for visualization tools and for debugging
+// the core without sprinkling prints around.
+//
+// Write errors don't interrupt emulation, the first one is kept and
+// returned by `finish`.
+pub struct Narrator<W: Write> {
+    out: W,
+    steps: u64,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Narrator<W> {
+    pub fn new(out: W) -> Self {
+        Narrator {
+            out,
+            steps: 0,
+            error: None,
+        }
+    }
+
+    // Executes one instruction like `emulator::step` and narrates it, can
+    // be passed to `run_frame_with`.
+    pub fn step(&mut self, emulator: &mut Emulator) -> Result<(), EmulatorError> {
+        let before = emulator.clone();
+        let result = step(emulator);
+        self.steps += 1;
+        if self.error.is_none() {
+            let line = narrate(self.steps, &before, emulator, result.as_ref().err());
+            if let Err(error) = writeln!(self.out, "{}", line) {
+                self.error = Some(error);
+            }
+        }
+        result
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+// One narration line, without the trailing newline.
+pub fn narrate(
+    step: u64,
+    before: &Emulator,
+    after: &Emulator,
+    error: Option<&EmulatorError>,
+) -> String {
+    let op_code = get_op_code(before);
+    let instruction = Instruction::decode(op_code);
+    let changes = changes(before, after);
+    let memory: Vec<String> = changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::Memory(address, before, after) => Some(format!(
+                "{{\"address\":{},\"before\":{},\"after\":{}}}",
+                address, before, after
+            )),
+            _ => None,
+        })
+        .collect();
+
+    let mut line = String::new();
+    write!(
+        line,
+        "{{\"step\":{},\"address\":{},\"opcode\":\"{:04X}\",\"pattern\":\"{}\",\"mnemonic\":{},",
+        step,
+        before.program_counter,
+        op_code,
+        instruction.pattern(),
+        json_string(&instruction.to_string())
+    )
+    .unwrap();
+    write!(
+        line,
+        "\"before\":{},\"after\":{},\"memory\":[{}],\"screen_changed\":{},\"error\":{}}}",
+        registers(before),
+        registers(after),
+        memory.join(","),
+        changes.contains(&Change::Screen),
+        error.map_or("null".to_string(), |error| json_string(&error.to_string()))
+    )
+    .unwrap();
+    line
+}
+
+fn registers(emulator: &Emulator) -> String {
+    let v: Vec<String> = emulator.v_registers.iter().map(u8::to_string).collect();
+    let stack: Vec<String> = emulator.stack[..emulator.stack_pointer as usize]
+        .iter()
+        .map(u16::to_string)
+        .collect();
+    format!(
+        "{{\"v\":[{}],\"i\":{},\"pc\":{},\"stack\":[{}],\"dt\":{},\"st\":{}}}",
+        v.join(","),
+        emulator.i_register,
+        emulator.program_counter,
+        stack.join(","),
+        emulator.delay_timer_registry,
+        emulator.sound_timer_registry
+    )
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,127 @@
+use crate::font::{Font, FONT_ADDRESS, LARGE_FONT_ADDRESS, LARGE_FONT_SIZE, SMALL_FONT_SIZE};
+use crate::{Display, Emulator};
+
+const SMALL_SIZE: (usize, usize) = (4, 5);
+const LARGE_SIZE: (usize, usize) = (8, 10);
+// Columns between two glyphs from which they are separate words. Games
+// usually leave one between the digits of a number.
+const WORD_GAP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Glyph {
+    x: usize,
+    y: usize,
+    width: usize,
+    character: char,
+}
+
+// The hex digits drawn with `font` on the screen, a line of text per row
+// they start on, left to right, glyphs further apart than a digit's gap
+// separated by a space. Glyphs only count with blank pixels all around, so
+// a sprite that happens to contain one is not read as text.
+//
+// The fonts only have the sixteen small digits and ten large SUPER-CHIP
+// ones, letters past F that a ROM draws with its own sprites are not read.
+pub fn read_text(display: &Display, font: &Font) -> String {
+    let mut glyphs = Vec::new();
+    let small: Vec<&[u8]> = font.small.chunks(SMALL_SIZE.1).collect();
+    let large: Vec<&[u8]> = font.large.chunks(LARGE_SIZE.1).collect();
+    for y in 0..display.height() {
+        let mut x = 0;
+        while x < display.width() {
+            let found = find(display, x, y, &large, LARGE_SIZE)
+                .or_else(|| find(display, x, y, &small, SMALL_SIZE));
+            match found {
+                Some(glyph) => {
+                    x += glyph.width;
+                    glyphs.push(glyph);
+                }
+                None => x += 1,
+            }
+        }
+    }
+
+    let mut text = String::new();
+    let mut line: Option<(usize, usize)> = None;
+    for glyph in glyphs {
+        match line {
+            Some((y, end)) if y == glyph.y && glyph.x - end >= WORD_GAP => text.push(' '),
+            Some((y, _)) if y == glyph.y => {}
+            Some(_) => text.push('\n'),
+            None => {}
+        }
+        text.push(glyph.character);
+        line = Some((glyph.y, glyph.x + glyph.width));
+    }
+    if line.is_some() {
+        text.push('\n');
+    }
+    text
+}
+
+// `read_text` with the fonts in the emulator's interpreter area, the ones
+// its ROM draws with.
+pub fn screen_text(emulator: &Emulator) -> String {
+    let mut font = Font::default();
+    let small = FONT_ADDRESS as usize;
+    let large = LARGE_FONT_ADDRESS as usize;
+    font.small
+        .copy_from_slice(&emulator.ram[small..small + SMALL_FONT_SIZE]);
+    font.large
+        .copy_from_slice(&emulator.ram[large..large + LARGE_FONT_SIZE]);
+    read_text(&emulator.display, &font)
+}
+
+// True if a line of `screen_text` contains `text`, e.g. "1 0" for a score.
+pub fn screen_shows(emulator: &Emulator, text: &str) -> bool {
+    screen_text(emulator)
+        .lines()
+        .any(|line| line.contains(text))
+}
+
+fn find(
+    display: &Display,
+    x: usize,
+    y: usize,
+    glyphs: &[&[u8]],
+    (width, height): (usize, usize),
+) -> Option<Glyph> {
+    if x + width > display.width() || y + height > display.height() {
+        return None;
+    }
+    let lit = |dx: usize, dy: usize| display.pixel(x + dx, y + dy);
+    // Blank around, outside the screen counts as blank.
+    let blank = |px: Option<usize>, py: Option<usize>| match (px, py) {
+        (Some(px), Some(py)) if px < display.width() && py < display.height() => {
+            !display.pixel(px, py)
+        }
+        _ => true,
+    };
+    let left = x.checked_sub(1);
+    let top = y.checked_sub(1);
+    let border = (0..height)
+        .all(|dy| blank(left, Some(y + dy)) && blank(Some(x + width), Some(y + dy)))
+        && (0..width + 2).all(|dx| {
+            let px = (x + dx).checked_sub(1);
+            blank(px, top) && blank(px, Some(y + height))
+        });
+    if !border {
+        return None;
+    }
+    let index = glyphs.iter().position(|glyph| {
+        glyph
+            .iter()
+            .enumerate()
+            .all(|(dy, row)| (0..width).all(|dx| lit(dx, dy) == (row & (0x80 >> dx) != 0)))
+    })?;
+    // A blank cell is not a glyph, and neither is a blank glyph.
+    if glyphs[index].iter().all(|&row| row == 0) {
+        return None;
+    }
+    Some(Glyph {
+        x,
+        y,
+        width,
+        character: char::from_digit(index as u32, 16)?.to_ascii_uppercase(),
+    })
+}
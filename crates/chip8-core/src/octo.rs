@@ -0,0 +1,258 @@
+use crate::instruction::Variant;
+use crate::xochip::FLAG_COUNT;
+use crate::{Emulator, EmulatorBuilder, EmulatorError, Quirks};
+
+// Octo's default speed, in instructions per frame.
+pub const OCTO_TICKRATE: usize = 20;
+
+// What Octo, the web IDE, keeps of a program in the browser: its options,
+// the same JSON object Octo cartridges and the CHIP-8 archive use, and the
+// flag registers saved with FX75, as an array of numbers. A dump from the
+// browser's storage may have them side by side or the options nested:
+//
+//     {"tickrate": 30, "shiftQuirks": true, "loadStoreQuirks": false,
+//      "fillColor": "#FFCC00", "flags": [3, 0, 120]}
+//     {"options": {"tickrate": 30}, "flags": [3, 0, 120]}
+//
+// Colors, fonts and the like are for the frontend and left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OctoState {
+    pub quirks: Quirks,
+    pub cycles_per_frame: usize,
+    pub flags: Option<[u8; FLAG_COUNT]>,
+    // Options set to something this emulator does not do, for a warning.
+    pub unsupported: Vec<String>,
+}
+
+impl OctoState {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let Json::Object(fields) = Parser::new(json).document()? else {
+            return Err("expected a JSON object".to_string());
+        };
+        // Octo's quirks all default to off, which is XO-CHIP's behavior.
+        let mut state = OctoState {
+            quirks: Quirks {
+                shift_uses_vy: true,
+                memory_increments_i: true,
+                ..Quirks::none()
+            },
+            cycles_per_frame: OCTO_TICKRATE,
+            flags: None,
+            unsupported: Vec::new(),
+        };
+        let options = fields
+            .iter()
+            .find_map(|(key, value)| match (key.as_str(), value) {
+                ("options", Json::Object(options)) => Some(options),
+                _ => None,
+            });
+        for (key, value) in fields.iter().chain(options.into_iter().flatten()) {
+            state.apply(key, value)?;
+        }
+        Ok(state)
+    }
+
+    fn apply(&mut self, key: &str, value: &Json) -> Result<(), String> {
+        let on = matches!(value, Json::Bool(true));
+        let quirks = &mut self.quirks;
+        match key {
+            "tickrate" => match value {
+                Json::Number(rate) if *rate >= 1.0 => self.cycles_per_frame = *rate as usize,
+                _ => return Err("tickrate is not a positive number".to_string()),
+            },
+            "shiftQuirks" => quirks.shift_uses_vy = !on,
+            "loadStoreQuirks" => quirks.memory_increments_i = !on,
+            "jumpQuirks" => quirks.jump_uses_vx = on,
+            "clipQuirks" => quirks.clipping = on,
+            "logicQuirks" => quirks.vf_reset = on,
+            "vBlankQuirks" => quirks.display_wait = on,
+            "vfOrderQuirks" | "screenRotation"
+                if *value != Json::Bool(false) && *value != Json::Number(0.0) =>
+            {
+                self.unsupported.push(key.to_string())
+            }
+            "flags" => {
+                let Json::Array(values) = value else {
+                    return Err("flags is not an array".to_string());
+                };
+                if values.len() > FLAG_COUNT {
+                    return Err(format!("more than {} flags", FLAG_COUNT));
+                }
+                let mut flags = [0; FLAG_COUNT];
+                for (flag, value) in flags.iter_mut().zip(values) {
+                    *flag = match value {
+                        Json::Number(n) if (0.0..=255.0).contains(n) && n.fract() == 0.0 => {
+                            *n as u8
+                        }
+                        _ => return Err("flags are numbers from 0 to 255".to_string()),
+                    };
+                }
+                self.flags = Some(flags);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // `rom` as Octo would start it, an XO-CHIP machine with the flags
+    // already in place, ready for `savestate::save`.
+    pub fn emulator(&self, rom: &[u8]) -> Result<Emulator, EmulatorError> {
+        let mut emulator = EmulatorBuilder::new()
+            .variant(Variant::XoChip)
+            .quirks(self.quirks)
+            .cycles_per_frame(self.cycles_per_frame)
+            .rom(rom)
+            .build()?;
+        if let (Some(xochip), Some(flags)) = (&mut emulator.xochip, self.flags) {
+            xochip.flags = flags;
+        }
+        Ok(emulator)
+    }
+}
+
+// Just enough JSON for the options: no escapes beyond the simple ones.
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    // Only keys are needed, strings are skipped.
+    String,
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Parser { text, offset: 0 }
+    }
+
+    fn document(&mut self) -> Result<Json, String> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.offset == self.text.len() {
+            true => Ok(value),
+            false => Err(self.error("trailing characters")),
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("invalid JSON at byte {}: {}", self.offset, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.offset..];
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.offset += c.len_utf8();
+                Ok(())
+            }
+            _ => Err(self.error(&format!("expected {:?}", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        let rest = &self.text[self.offset..];
+        for (word, value) in [
+            ("null", Json::Null),
+            ("true", Json::Bool(true)),
+            ("false", Json::Bool(false)),
+        ] {
+            if rest.starts_with(word) {
+                self.offset += word.len();
+                return Ok(value);
+            }
+        }
+        match self.peek() {
+            Some('"') => self.string().map(|_| Json::String),
+            Some('[') => {
+                self.offset += 1;
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.offset += 1;
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.offset += 1,
+                        _ => break,
+                    }
+                }
+                self.expect(']')?;
+                Ok(Json::Array(values))
+            }
+            Some('{') => {
+                self.offset += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.offset += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.offset += 1,
+                        _ => break,
+                    }
+                }
+                self.expect('}')?;
+                Ok(Json::Object(fields))
+            }
+            _ => {
+                let length = rest
+                    .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+                    .unwrap_or(rest.len());
+                let number = rest[..length]
+                    .parse()
+                    .map_err(|_| self.error("expected a value"))?;
+                self.offset += length;
+                Ok(Json::Number(number))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        let mut chars = self.text[self.offset..].char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.offset += index + 1;
+                    return Ok(string);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some(c @ ('"' | '\\' | '/')) => string.push(c),
+                    _ => return Err(self.error("unsupported escape")),
+                },
+                c => string.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
@@ -0,0 +1,301 @@
+use std::fmt;
+
+use crate::{Instruction, INITIAL_ADDRESS};
+
+// Differences between two versions of a ROM, for hackers passing a fix
+// around as a patch rather than as the whole game. `diff` lines the ROMs up
+// instruction by instruction, so code moved down by an insertion is not
+// reported as changed. `Patch` is the same difference byte by byte, in the
+// IPS format most patching tools read.
+
+// One instruction of either ROM, by its address once loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Same { old: u16, new: u16, op_code: u16 },
+    Removed { address: u16, op_code: u16 },
+    Added { address: u16, op_code: u16 },
+}
+
+// The address in the old ROM, in the new and the instruction, either
+// address left blank when the instruction is not in that ROM:
+//
+//      0x20e  0x20e  6E00  LD VE, 0x00
+//     +       0x210  6005  LD V0, 0x05
+//      0x210  0x212  22D4  CALL 0x2d4
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let address = |address: Option<u16>| match address {
+            Some(address) => format!("{:#05x}", address),
+            None => String::new(),
+        };
+        let (sign, old, new, op_code) = match *self {
+            Edit::Same { old, new, op_code } => (' ', Some(old), Some(new), op_code),
+            Edit::Removed { address, op_code } => ('-', Some(address), None, op_code),
+            Edit::Added { address, op_code } => ('+', None, Some(address), op_code),
+        };
+        write!(
+            f,
+            "{}{:<5}  {:<5}  {:04X}  {}",
+            sign,
+            address(old),
+            address(new),
+            op_code,
+            Instruction::decode(op_code)
+        )
+    }
+}
+
+// Past this many instructions compared with as many, the differing middle
+// of the ROMs is reported as removed and added whole.
+const MAX_ALIGNED: usize = 1 << 24;
+
+// Every instruction of `old` and `new` in order, the longest common
+// sequence of them kept as `Same`. The ROMs are read two bytes at a time
+// from their start, a last odd byte as the high one of its op code.
+pub fn diff(old: &[u8], new: &[u8]) -> Vec<Edit> {
+    let (old, new) = (words(old), words(new));
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    let address = |index: usize| INITIAL_ADDRESS.wrapping_add(2 * index as u16);
+    let same = |i: usize, j: usize| Edit::Same {
+        old: address(i),
+        new: address(j),
+        op_code: old[i],
+    };
+
+    let mut edits: Vec<Edit> = (0..prefix).map(|i| same(i, i)).collect();
+    // length(i, j): the longest common sequence of a[i..] and b[j..], or 0
+    // for all when the ROMs differ too much to line up.
+    let aligned = a.len() * b.len() <= MAX_ALIGNED;
+    let width = b.len() + 1;
+    let mut lengths = vec![0u16; if aligned { (a.len() + 1) * width } else { 0 }];
+    if aligned {
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lengths[i * width + j] = if a[i] == b[j] {
+                    lengths[(i + 1) * width + j + 1] + 1
+                } else {
+                    lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+                };
+            }
+        }
+    }
+    let length = |i: usize, j: usize| match aligned {
+        true => lengths[i * width + j],
+        false => 0,
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] && length(i, j) > 0 {
+            edits.push(same(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || length(i + 1, j) >= length(i, j + 1)) {
+            edits.push(Edit::Removed {
+                address: address(prefix + i),
+                op_code: a[i],
+            });
+            i += 1;
+        } else {
+            edits.push(Edit::Added {
+                address: address(prefix + j),
+                op_code: b[j],
+            });
+            j += 1;
+        }
+    }
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    edits.extend((0..suffix).map(|k| same(old_end + k, new_end + k)));
+    edits
+}
+
+// The edits as a unified diff: every change with `context` instructions
+// around it, runs of changes further apart than that under their own
+// `@@ -old +new @@` header.
+pub fn unified(edits: &[Edit], context: usize) -> String {
+    let changed: Vec<usize> = (0..edits.len())
+        .filter(|&index| !matches!(edits[index], Edit::Same { .. }))
+        .collect();
+    let mut text = String::new();
+    let mut index = 0;
+    while index < changed.len() {
+        let start = changed[index].saturating_sub(context);
+        let mut end = changed[index];
+        while index < changed.len() && changed[index] <= end + 2 * context + 1 {
+            end = changed[index];
+            index += 1;
+        }
+        let end = (end + context + 1).min(edits.len());
+        let (old, new) = addresses(edits, start);
+        text.push_str(&format!("@@ -{:#05x} +{:#05x} @@\n", old, new));
+        for edit in &edits[start..end] {
+            text.push_str(&format!("{}\n", edit));
+        }
+    }
+    text
+}
+
+// Where the ROMs are at edit `index`, in each.
+fn addresses(edits: &[Edit], index: usize) -> (u16, u16) {
+    let mut next = (INITIAL_ADDRESS, INITIAL_ADDRESS);
+    for edit in &edits[..index] {
+        match *edit {
+            Edit::Same { old, new, .. } => next = (old.wrapping_add(2), new.wrapping_add(2)),
+            Edit::Removed { address, .. } => next.0 = address.wrapping_add(2),
+            Edit::Added { address, .. } => next.1 = address.wrapping_add(2),
+        }
+    }
+    next
+}
+
+fn words(rom: &[u8]) -> Vec<u16> {
+    rom.chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or_default()]))
+        .collect()
+}
+
+// The bytes to write over a ROM to turn it into another, and how long the
+// result is when that is shorter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Patch {
+    pub records: Vec<Record>,
+    pub truncate: Option<usize>,
+}
+
+// `data` written from `offset`, counted from the first byte of the ROM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub offset: usize,
+    pub data: Vec<u8>,
+}
+
+const IPS_HEADER: &[u8] = b"PATCH";
+const IPS_FOOTER: &[u8] = b"EOF";
+// A record header is five bytes, fewer bytes alike between two changes
+// than that are cheaper written over.
+const GAP: usize = 5;
+const MAX_RECORD: usize = 0xFFFF;
+const MAX_OFFSET: usize = 0xFF_FFFF;
+
+impl Patch {
+    pub fn between(old: &[u8], new: &[u8]) -> Patch {
+        let differs = |offset: usize| old.get(offset) != new.get(offset);
+        let mut records: Vec<Record> = Vec::new();
+        let mut offset = 0;
+        while offset < new.len() {
+            if !differs(offset) {
+                offset += 1;
+                continue;
+            }
+            let start = offset;
+            let mut end = offset + 1;
+            while end < new.len() && end - start < MAX_RECORD {
+                if !(end..(end + GAP).min(new.len())).any(differs) {
+                    break;
+                }
+                end += 1;
+            }
+            // Alike bytes carried along at the end are left out.
+            while !differs(end - 1) {
+                end -= 1;
+            }
+            records.push(Record {
+                offset: start,
+                data: new[start..end].to_vec(),
+            });
+            offset = end;
+        }
+        Patch {
+            records,
+            truncate: (new.len() < old.len()).then_some(new.len()),
+        }
+    }
+
+    // `rom` with the records written over it, grown with zeros where one
+    // starts past its end.
+    pub fn apply(&self, rom: &[u8]) -> Vec<u8> {
+        let mut patched = rom.to_vec();
+        for record in &self.records {
+            let end = record.offset + record.data.len();
+            if patched.len() < end {
+                patched.resize(end, 0);
+            }
+            patched[record.offset..end].copy_from_slice(&record.data);
+        }
+        if let Some(len) = self.truncate {
+            patched.truncate(len);
+        }
+        patched
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty() && self.truncate.is_none()
+    }
+
+    pub fn to_ips(&self) -> Result<Vec<u8>, String> {
+        let mut ips = IPS_HEADER.to_vec();
+        for record in &self.records {
+            for (index, chunk) in record.data.chunks(MAX_RECORD).enumerate() {
+                let offset = record.offset + index * MAX_RECORD;
+                // An offset spelling EOF would end the patch there.
+                if offset > MAX_OFFSET || offset == 0x454F46 {
+                    return Err(format!("offset {:#x} does not fit in IPS", offset));
+                }
+                ips.extend(&(offset as u32).to_be_bytes()[1..]);
+                ips.extend((chunk.len() as u16).to_be_bytes());
+                ips.extend(chunk);
+            }
+        }
+        ips.extend(IPS_FOOTER);
+        if let Some(len) = self.truncate {
+            ips.extend(&(len.min(MAX_OFFSET) as u32).to_be_bytes()[1..]);
+        }
+        Ok(ips)
+    }
+
+    // Reads plain and run-length records, and the length to cut the ROM to
+    // some tools add after the end.
+    pub fn from_ips(ips: &[u8]) -> Result<Patch, String> {
+        let mut rest = ips
+            .strip_prefix(IPS_HEADER)
+            .ok_or("not an IPS patch, it does not start with PATCH")?;
+        let mut take = |len: usize| -> Result<&[u8], String> {
+            if rest.len() < len {
+                return Err("the patch ends in the middle of a record".to_string());
+            }
+            let (taken, after) = rest.split_at(len);
+            rest = after;
+            Ok(taken)
+        };
+        let number = |bytes: &[u8]| bytes.iter().fold(0, |n, &byte| n << 8 | byte as usize);
+        let mut patch = Patch::default();
+        loop {
+            let offset = take(3)?;
+            if offset == IPS_FOOTER {
+                break;
+            }
+            let offset = number(offset);
+            let data = match number(take(2)?) {
+                0 => {
+                    let run = number(take(2)?);
+                    vec![take(1)?[0]; run]
+                }
+                len => take(len)?.to_vec(),
+            };
+            patch.records.push(Record { offset, data });
+        }
+        if let Ok(len) = take(3) {
+            patch.truncate = Some(number(len));
+        }
+        Ok(patch)
+    }
+}
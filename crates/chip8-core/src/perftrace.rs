@@ -0,0 +1,290 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::{get_op_code, run_frame_with, Emulator, EmulatorError, FrameOutput, Instruction};
+
+// The rows of the trace, shown as threads of one process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Track {
+    Emulation = 1,
+    Audio = 2,
+    Frontend = 3,
+}
+
+impl Track {
+    const ALL: [Track; 3] = [Track::Emulation, Track::Audio, Track::Frontend];
+
+    fn name(self) -> &'static str {
+        match self {
+            Track::Emulation => "emulation",
+            Track::Audio => "audio",
+            Track::Frontend => "frontend",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TraceEvent {
+    name: &'static str,
+    // 'X' for a span, 'i' for an instant, 'C' for a counter.
+    phase: char,
+    track: Track,
+    // Microseconds since the trace started.
+    start: f64,
+    duration: f64,
+    args: Vec<(&'static str, u64)>,
+}
+
+// Where an emulator spends its host time, in the Trace Event Format that
+// chrome://tracing and Perfetto open: every frame as a span, the
+// instructions between two DRWs as a batch, each DRW that drew (one
+// waiting for the vertical blank is part of the batch), the timers ticking
+// as a counter, plus the audio callbacks and whatever the frontend times
+// itself. Frames go through `run_frame`, the rest is reported:
+//
+//     let output = trace.run_frame(&mut emulator);
+//     let started = Instant::now();
+//     present(&emulator.display);
+//     trace.span(Track::Frontend, "present", started);
+//
+// Audio callbacks run on a thread of their own, share the trace behind a
+// mutex to record them. Timestamps count from `new`, whose wall clock time
+// is kept in the trace to line it up with a host profile.
+#[derive(Debug, Clone)]
+pub struct PerfTrace {
+    started: Instant,
+    started_unix: u128,
+    frame: u64,
+    events: Vec<TraceEvent>,
+}
+
+impl Default for PerfTrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerfTrace {
+    pub fn new() -> Self {
+        PerfTrace {
+            started: Instant::now(),
+            started_unix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros(),
+            frame: 0,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    // `chip8_core::run_frame`, traced.
+    pub fn run_frame(&mut self, emulator: &mut Emulator) -> FrameOutput {
+        self.run_frame_with(emulator, crate::step)
+    }
+
+    // `chip8_core::run_frame_with`, traced, for callers that step through
+    // a narrator, profiler and the like.
+    pub fn run_frame_with(
+        &mut self,
+        emulator: &mut Emulator,
+        mut step: impl FnMut(&mut Emulator) -> Result<(), EmulatorError>,
+    ) -> FrameOutput {
+        let started = Instant::now();
+        let mut batch = (started, 0);
+        let mut instructions = 0;
+        let output = run_frame_with(emulator, |emulator| {
+            instructions += 1;
+            let Instruction::Drw { x, y, n } = Instruction::decode(get_op_code(emulator)) else {
+                batch.1 += 1;
+                return step(emulator);
+            };
+            let drawing = Instant::now();
+            let address = emulator.program_counter;
+            let (vx, vy) = (
+                emulator.v_registers[x as usize],
+                emulator.v_registers[y as usize],
+            );
+            let result = step(emulator);
+            // Waiting for the vertical blank, it runs again next cycle.
+            if emulator.program_counter == address && result.is_ok() {
+                batch.1 += 1;
+                return result;
+            }
+            self.batch(batch, drawing);
+            let collision = emulator.v_registers[0xF];
+            self.push(Track::Emulation, "DRW", drawing, Instant::now(), |args| {
+                args.extend([
+                    ("x", vx as u64),
+                    ("y", vy as u64),
+                    ("height", n as u64),
+                    ("collision", collision as u64),
+                ])
+            });
+            batch = (Instant::now(), 0);
+            result
+        });
+        let ended = Instant::now();
+        self.batch(batch, ended);
+        // The timers tick once at the end of a frame that ran through.
+        if output.error.is_none() {
+            let start = self.micros(ended);
+            self.events.push(TraceEvent {
+                name: "timers",
+                phase: 'C',
+                track: Track::Emulation,
+                start,
+                duration: 0.0,
+                args: vec![
+                    ("delay", emulator.delay_timer_registry as u64),
+                    ("sound", emulator.sound_timer_registry as u64),
+                ],
+            });
+        }
+        let frame = self.frame;
+        self.push(Track::Emulation, "frame", started, ended, |args| {
+            args.extend([
+                ("frame", frame),
+                ("instructions", instructions),
+                ("display_changed", output.display_changed as u64),
+            ])
+        });
+        if let Some(error) = &output.error {
+            self.instant(Track::Emulation, error_name(error));
+        }
+        self.frame += 1;
+        output
+    }
+
+    // An audio callback that started at `started` and filled `samples`.
+    pub fn audio_callback(&mut self, started: Instant, samples: usize) {
+        self.push(
+            Track::Audio,
+            "audio callback",
+            started,
+            Instant::now(),
+            |args| args.push(("samples", samples as u64)),
+        );
+    }
+
+    // Something that started at `started` and ends now, e.g. the frontend
+    // presenting a frame.
+    pub fn span(&mut self, track: Track, name: &'static str, started: Instant) {
+        self.push(track, name, started, Instant::now(), |_| {});
+    }
+
+    // Something that happened now, e.g. a key press.
+    pub fn instant(&mut self, track: Track, name: &'static str) {
+        let start = self.micros(Instant::now());
+        self.events.push(TraceEvent {
+            name,
+            phase: 'i',
+            track,
+            start,
+            duration: 0.0,
+            args: Vec::new(),
+        });
+    }
+
+    // The JSON object form of the format, with the tracks named.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"traceEvents\":[\n");
+        let _ = write!(
+            json,
+            "{{\"name\":\"process_name\",\"ph\":\"M\",\"pid\":1,\"tid\":0,\"args\":{{\"name\":\"chip8\"}}}}"
+        );
+        for track in Track::ALL {
+            let _ = write!(
+                json,
+                ",\n{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{},\"args\":{{\"name\":\"{}\"}}}}",
+                track as u8,
+                track.name()
+            );
+        }
+        for event in &self.events {
+            let _ = write!(
+                json,
+                ",\n{{\"name\":\"{}\",\"ph\":\"{}\",\"pid\":1,\"tid\":{},\"ts\":{:.3}",
+                event.name, event.phase, event.track as u8, event.start
+            );
+            match event.phase {
+                'X' => {
+                    let _ = write!(json, ",\"dur\":{:.3}", event.duration);
+                }
+                'i' => json.push_str(",\"s\":\"t\""),
+                _ => {}
+            }
+            if !event.args.is_empty() {
+                json.push_str(",\"args\":{");
+                for (index, (key, value)) in event.args.iter().enumerate() {
+                    let comma = if index == 0 { "" } else { "," };
+                    let _ = write!(json, "{}\"{}\":{}", comma, key, value);
+                }
+                json.push('}');
+            }
+            json.push('}');
+        }
+        let _ = write!(
+            json,
+            "\n],\"displayTimeUnit\":\"ms\",\"otherData\":{{\"started_unix_us\":\"{}\"}}}}\n",
+            self.started_unix
+        );
+        json
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(self.to_json().as_bytes())
+    }
+
+    fn micros(&self, at: Instant) -> f64 {
+        at.saturating_duration_since(self.started).as_secs_f64() * 1e6
+    }
+
+    fn batch(&mut self, (started, instructions): (Instant, u64), ended: Instant) {
+        if instructions > 0 {
+            self.push(Track::Emulation, "instructions", started, ended, |args| {
+                args.push(("count", instructions))
+            });
+        }
+    }
+
+    fn push(
+        &mut self,
+        track: Track,
+        name: &'static str,
+        started: Instant,
+        ended: Instant,
+        args: impl FnOnce(&mut Vec<(&'static str, u64)>),
+    ) {
+        let mut event = TraceEvent {
+            name,
+            phase: 'X',
+            track,
+            start: self.micros(started),
+            duration: 0.0,
+            args: Vec::new(),
+        };
+        event.duration = self.micros(ended) - event.start;
+        args(&mut event.args);
+        self.events.push(event);
+    }
+}
+
+// Event names are fixed strings, the error is named by its kind only.
+fn error_name(error: &EmulatorError) -> &'static str {
+    match error {
+        EmulatorError::UnknownOpcode(_) => "error: unknown opcode",
+        EmulatorError::Unimplemented(..) => "error: unimplemented",
+        EmulatorError::StackOverflow => "error: stack overflow",
+        EmulatorError::StackUnderflow => "error: stack underflow",
+        _ => "error",
+    }
+}
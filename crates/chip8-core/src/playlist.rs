@@ -0,0 +1,81 @@
+use std::path::{Path, PathBuf};
+
+use crate::host::Files;
+use crate::Quirks;
+
+// How long a ROM plays when the playlist does not say.
+pub const DEFAULT_SECONDS: u32 = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    pub rom: PathBuf,
+    pub seconds: Option<u32>,
+    // An `InputScript` playing the game, or else nobody touches the keys
+    // and the ROM shows its title or demo screen.
+    pub inputs: Option<PathBuf>,
+    pub quirks: Option<Quirks>,
+}
+
+// ROMs for attract mode, one per line: the ROM's path followed by
+// optional `seconds=N`, `inputs=FILE` and `quirks=PROFILE`. Paths are
+// relative to the playlist, `#` starts a comment.
+//
+//     # the arcade corner
+//     pong.ch8 seconds=20 inputs=pong.inputs
+//     breakout.ch8 quirks=chip8
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Playlist {
+    pub entries: Vec<PlaylistEntry>,
+}
+
+impl Playlist {
+    pub fn load_from(files: &dyn Files, path: &Path) -> Result<Self, String> {
+        let data = files
+            .read(path)
+            .map_err(|error| format!("cannot read {}: {}", path.display(), error))?;
+        let mut playlist = Playlist::parse(&String::from_utf8_lossy(&data))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        for entry in &mut playlist.entries {
+            entry.rom = base.join(&entry.rom);
+            if let Some(inputs) = &mut entry.inputs {
+                *inputs = base.join(&*inputs);
+            }
+        }
+        Ok(playlist)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut fields = line.split_whitespace();
+            let Some(rom) = fields.next() else { continue };
+            let error = |message: &str| format!("line {}: {}", number + 1, message);
+            let mut entry = PlaylistEntry {
+                rom: PathBuf::from(rom),
+                seconds: None,
+                inputs: None,
+                quirks: None,
+            };
+            for field in fields {
+                let (key, value) = field
+                    .split_once('=')
+                    .ok_or_else(|| error("expected `key=value`"))?;
+                match key {
+                    "seconds" => {
+                        let seconds = value.parse().map_err(|_| error("invalid seconds"))?;
+                        entry.seconds = Some(seconds);
+                    }
+                    "inputs" => entry.inputs = Some(PathBuf::from(value)),
+                    "quirks" => entry.quirks = Some(value.parse().map_err(|e: String| error(&e))?),
+                    _ => return Err(error(&format!("unknown key {:?}", key))),
+                }
+            }
+            entries.push(entry);
+        }
+        if entries.is_empty() {
+            return Err("the playlist is empty".to_string());
+        }
+        Ok(Playlist { entries })
+    }
+}
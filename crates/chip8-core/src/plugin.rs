@@ -0,0 +1,147 @@
+use std::ffi::c_void;
+use std::path::Path;
+
+use libloading::{Library, Symbol};
+
+use crate::hooks::ExecutionHook;
+use crate::{Emulator, EmulatorError, Instruction};
+
+// Bumped on any change to the structs below. Plugins built against another
+// version are refused instead of crashing.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+// The symbol every plugin library exports:
+//
+//     Chip8Plugin chip8_plugin(void);
+pub const PLUGIN_SYMBOL: &str = "chip8_plugin";
+
+// What the callbacks get to see of the machine, and may change: a cheat
+// writes to `ram` or `v`, a tracer only reads.
+#[repr(C)]
+pub struct PluginState {
+    pub v: *mut u8,
+    pub i: *mut u16,
+    pub pc: *mut u16,
+    pub ram: *mut u8,
+    pub ram_len: usize,
+}
+
+// A plugin, as returned by `chip8_plugin`. `user` is handed back to every
+// callback and to `drop` when the plugin is unloaded. Callbacks are
+// optional and may be called from any thread, but never concurrently.
+#[repr(C)]
+pub struct Chip8Plugin {
+    pub abi_version: u32,
+    pub user: *mut c_void,
+    pub before_step: Option<unsafe extern "C" fn(*mut c_void, *mut PluginState, u16)>,
+    // `error` is 0 when the instruction succeeded.
+    pub after_step: Option<unsafe extern "C" fn(*mut c_void, *mut PluginState, u16, i32)>,
+    pub on_memory_write: Option<unsafe extern "C" fn(*mut c_void, u16, u8)>,
+    pub on_draw: Option<unsafe extern "C" fn(*mut c_void, usize, usize, *const u8, usize, bool)>,
+    pub drop: Option<unsafe extern "C" fn(*mut c_void)>,
+}
+
+// An `ExecutionHook` backed by a plugin, usually from a shared library.
+pub struct Plugin {
+    plugin: Chip8Plugin,
+    // Closed after the plugin has been dropped.
+    library: Option<Library>,
+}
+
+// The ABI requires plugins to cope with being moved between threads, the
+// emulator never calls one from two threads at once.
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+impl Plugin {
+    // Loads the shared library at `path` and asks it for its plugin.
+    pub fn load(path: &Path) -> Result<Plugin, String> {
+        unsafe {
+            let library =
+                Library::new(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+            let plugin = {
+                let entry: Symbol<unsafe extern "C" fn() -> Chip8Plugin> = library
+                    .get(PLUGIN_SYMBOL.as_bytes())
+                    .map_err(|_| format!("{}: no {} symbol", path.display(), PLUGIN_SYMBOL))?;
+                entry()
+            };
+            let mut plugin =
+                Plugin::new(plugin).map_err(|error| format!("{}: {}", path.display(), error))?;
+            plugin.library = Some(library);
+            Ok(plugin)
+        }
+    }
+
+    // Wraps a plugin that is already in memory, for instance one linked
+    // statically. Fails if it was built for another ABI version.
+    //
+    // Safety: the callbacks must be safe to call with `user`.
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn new(plugin: Chip8Plugin) -> Result<Plugin, String> {
+        // Nothing of a plugin for another version can be trusted, not even
+        // its `drop`.
+        if plugin.abi_version != PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "plugin ABI version {}, expected {}",
+                plugin.abi_version, PLUGIN_ABI_VERSION
+            ));
+        }
+        Ok(Plugin {
+            plugin,
+            library: None,
+        })
+    }
+}
+
+fn state(emulator: &mut Emulator) -> PluginState {
+    PluginState {
+        v: emulator.v_registers.as_mut_ptr(),
+        i: &mut emulator.i_register,
+        pc: &mut emulator.program_counter,
+        ram: emulator.ram.as_mut_ptr(),
+        ram_len: emulator.ram.len(),
+    }
+}
+
+impl ExecutionHook for Plugin {
+    fn before_step(&mut self, emulator: &mut Emulator, instruction: Instruction) {
+        if let Some(callback) = self.plugin.before_step {
+            unsafe { callback(self.plugin.user, &mut state(emulator), instruction.encode()) }
+        }
+    }
+
+    fn after_step(
+        &mut self,
+        emulator: &mut Emulator,
+        instruction: Instruction,
+        result: &Result<(), EmulatorError>,
+    ) {
+        if let Some(callback) = self.plugin.after_step {
+            let op_code = instruction.encode();
+            let error = result.is_err() as i32;
+            unsafe { callback(self.plugin.user, &mut state(emulator), op_code, error) }
+        }
+    }
+
+    fn on_memory_write(&mut self, address: u16, value: u8) {
+        if let Some(callback) = self.plugin.on_memory_write {
+            unsafe { callback(self.plugin.user, address, value) }
+        }
+    }
+
+    fn on_draw(&mut self, x: usize, y: usize, sprite: &[u8], collision: bool) {
+        if let Some(callback) = self.plugin.on_draw {
+            let (rows, len) = (sprite.as_ptr(), sprite.len());
+            unsafe { callback(self.plugin.user, x, y, rows, len, collision) }
+        }
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        if let Some(drop) = self.plugin.drop {
+            unsafe { drop(self.plugin.user) }
+        }
+        // The library closes as its field drops, after the callback above.
+    }
+}
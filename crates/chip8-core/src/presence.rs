@@ -0,0 +1,73 @@
+use crate::events::Event;
+use crate::narration::json_string;
+
+// What Discord's Rich Presence shows under the player's name: the game,
+// and for how long it has been played unless it is paused. Kept up to date
+// from events like `WindowTitle`, frontends send `set_activity` whenever
+// `handle` reports a change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Presence {
+    title: Option<String>,
+    // Unix seconds the ROM started at.
+    started: u64,
+    paused: bool,
+}
+
+impl Presence {
+    pub fn new(started: u64) -> Self {
+        Presence {
+            title: None,
+            started,
+            paused: false,
+        }
+    }
+
+    // `RomLoaded` carries the file name, frontends that know the ROM's
+    // proper name, see `title::rom_title`, set it here instead.
+    pub fn set_title(&mut self, title: &str, started: u64) {
+        self.title = Some(title.to_string());
+        self.started = started;
+    }
+
+    // The ROM was reset, the time played counts from `now`.
+    pub fn restart(&mut self, now: u64) {
+        self.started = now;
+    }
+
+    // Returns true when the presence needs to be sent again.
+    pub fn handle(&mut self, event: &Event, now: u64) -> bool {
+        let before = self.clone();
+        match event {
+            Event::RomLoaded { name } => self.set_title(name, now),
+            Event::Paused => self.paused = true,
+            Event::Resumed => self.paused = false,
+            Event::SpeedChanged { .. } | Event::TriggerFired { .. } => (),
+        }
+        *self != before
+    }
+
+    // The SET_ACTIVITY command for Discord's IPC socket, from process `pid`.
+    pub fn set_activity(&self, pid: u32, nonce: u64) -> String {
+        let activity = match &self.title {
+            None => "null".to_string(),
+            Some(title) if self.paused => format!(
+                "{{\"details\":{},\"state\":\"Paused\"}}",
+                json_string(title)
+            ),
+            Some(title) => format!(
+                "{{\"details\":{},\"state\":\"Playing\",\"timestamps\":{{\"start\":{}}}}}",
+                json_string(title),
+                self.started
+            ),
+        };
+        format!(
+            "{{\"cmd\":\"SET_ACTIVITY\",\"args\":{{\"pid\":{},\"activity\":{}}},\"nonce\":\"{}\"}}",
+            pid, activity, nonce
+        )
+    }
+}
+
+// The first message on the socket, naming the Discord application.
+pub fn handshake(client_id: u64) -> String {
+    format!("{{\"v\":1,\"client_id\":\"{}\"}}", client_id)
+}
@@ -0,0 +1,35 @@
+// An extension of this emulator rather than of a real machine: FxF0 sends
+// Vx to the host as a byte, for "hello world" teaching programs and test
+// ROMs reporting their results to CI as text. `Emulator::printer` is only
+// set when asked for with `EmulatorBuilder::printer`, the opcode fails
+// without it. The core never does I/O, frontends `take` the bytes printed
+// so far after each frame and write them where they like:
+//
+//     run_frame(&mut emulator);
+//     if let Some(printer) = &mut emulator.printer {
+//         stdout.write_all(&printer.take())?;
+//     }
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Printer {
+    output: Vec<u8>,
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Printer::default()
+    }
+
+    pub(crate) fn print(&mut self, byte: u8) {
+        self.output.push(byte);
+    }
+
+    // Printed and not taken yet.
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    pub fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+}
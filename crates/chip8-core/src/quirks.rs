@@ -0,0 +1,166 @@
+use std::fmt;
+use std::str::FromStr;
+
+// Behaviors that differ between interpreters. ROMs written for one of them
+// often break on the others, so they can be toggled one by one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quirks {
+    // 8xy1, 8xy2 and 8xy3 reset VF to 0.
+    pub vf_reset: bool,
+    // Fx55 and Fx65 leave I pointing past the last register copied.
+    pub memory_increments_i: bool,
+    // 8xy6 and 8xyE shift Vy into Vx instead of shifting Vx in place.
+    pub shift_uses_vy: bool,
+    // Sprites are cut at the screen edges instead of wrapping around.
+    pub clipping: bool,
+    // Bnnn jumps to nnn + Vx, x being the highest nibble of nnn, instead of
+    // nnn + V0.
+    pub jump_uses_vx: bool,
+    // Dxyn waits for the vertical blank interrupt before drawing, so at most
+    // one sprite is drawn per frame.
+    pub display_wait: bool,
+}
+
+impl Quirks {
+    pub fn none() -> Self {
+        Quirks {
+            vf_reset: false,
+            memory_increments_i: false,
+            shift_uses_vy: false,
+            clipping: false,
+            jump_uses_vx: false,
+            display_wait: false,
+        }
+    }
+
+    // The original COSMAC VIP interpreter.
+    pub fn chip8() -> Self {
+        Quirks {
+            vf_reset: true,
+            memory_increments_i: true,
+            shift_uses_vy: true,
+            clipping: true,
+            jump_uses_vx: false,
+            display_wait: true,
+        }
+    }
+
+    // SUPER-CHIP 1.1 on the HP48.
+    pub fn schip() -> Self {
+        Quirks {
+            vf_reset: false,
+            memory_increments_i: false,
+            shift_uses_vy: false,
+            clipping: true,
+            jump_uses_vx: true,
+            display_wait: false,
+        }
+    }
+
+    // Octo's XO-CHIP.
+    pub fn xo_chip() -> Self {
+        Quirks {
+            vf_reset: false,
+            memory_increments_i: true,
+            shift_uses_vy: true,
+            clipping: false,
+            jump_uses_vx: false,
+            display_wait: false,
+        }
+    }
+
+    pub fn flags(&self) -> [(&'static str, bool); 6] {
+        [
+            ("vf_reset", self.vf_reset),
+            ("memory_increments_i", self.memory_increments_i),
+            ("shift_uses_vy", self.shift_uses_vy),
+            ("clipping", self.clipping),
+            ("jump_uses_vx", self.jump_uses_vx),
+            ("display_wait", self.display_wait),
+        ]
+    }
+
+    pub fn set_flag(&mut self, name: &str, on: bool) -> Result<(), String> {
+        let flag = match name {
+            "vf_reset" => &mut self.vf_reset,
+            "memory_increments_i" => &mut self.memory_increments_i,
+            "shift_uses_vy" => &mut self.shift_uses_vy,
+            "clipping" => &mut self.clipping,
+            "jump_uses_vx" => &mut self.jump_uses_vx,
+            "display_wait" => &mut self.display_wait,
+            _ => return Err(format!("unknown quirk {:?}", name)),
+        };
+        *flag = on;
+        Ok(())
+    }
+
+    // Every combination of the quirk flags.
+    pub fn matrix() -> Vec<Quirks> {
+        let names = Quirks::none().flags().map(|(name, _)| name);
+        (0..1 << names.len())
+            .map(|bits: u32| {
+                let mut quirks = Quirks::none();
+                for (index, name) in names.iter().enumerate() {
+                    quirks.set_flag(name, bits & (1 << index) != 0).unwrap();
+                }
+                quirks
+            })
+            .collect()
+    }
+
+    pub fn profiles() -> [(&'static str, Quirks); 3] {
+        [
+            ("chip8", Quirks::chip8()),
+            ("schip", Quirks::schip()),
+            ("xochip", Quirks::xo_chip()),
+        ]
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks::chip8()
+    }
+}
+
+// A profile name when the flags match one, otherwise the comma separated
+// list of enabled flags ("none" if there are none).
+impl fmt::Display for Quirks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((name, _)) = Quirks::profiles().iter().find(|(_, quirks)| quirks == self) {
+            return write!(f, "{}", name);
+        }
+        let enabled: Vec<&str> = self
+            .flags()
+            .iter()
+            .filter(|(_, on)| *on)
+            .map(|(name, _)| *name)
+            .collect();
+        if enabled.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", enabled.join(","))
+        }
+    }
+}
+
+impl FromStr for Quirks {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        if let Some((_, quirks)) = Quirks::profiles().iter().find(|(name, _)| *name == text) {
+            return Ok(*quirks);
+        }
+        let mut quirks = Quirks::none();
+        if text == "none" {
+            return Ok(quirks);
+        }
+        for name in text.split(',') {
+            quirks
+                .set_flag(name.trim(), true)
+                .map_err(|_| format!("unknown quirk profile or flag {:?}", name.trim()))?;
+        }
+        Ok(quirks)
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::display::MAX_HEIGHT;
+use crate::instruction::Variant;
+use crate::{run_frame, Display, EmulatorBuilder, Quirks};
+
+// Timendus' quirks test (5-quirks.ch8 of the CHIP-8 test suite) skips its
+// menu when the platform is already in memory at this address.
+pub const PLATFORM_ADDRESS: u16 = 0x1FF;
+// Long enough for the display wait test, which counts frames.
+pub const DEFAULT_FRAMES: u32 = 600;
+// The results screen's last six lines, one per quirk, in this order.
+pub const QUIRK_LINES: [&str; 6] = [
+    "vf_reset",
+    "memory_increments_i",
+    "display_wait",
+    "clipping",
+    "shift_uses_vy",
+    "jump_uses_vx",
+];
+
+// The suite's own numbers for the platforms it tests against.
+pub fn platform_number(variant: Variant) -> Option<u8> {
+    match variant {
+        Variant::Chip8 => Some(1),
+        Variant::Schip => Some(2),
+        Variant::XoChip => Some(3),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    // The line reads as it does when the quirk behaves like the platform's.
+    Pass,
+    // As it does when the quirk is the other way round.
+    Fail,
+    // Neither, e.g. the test printed an error code, or flipping the quirk
+    // did not change the line.
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Verdict::Pass => "ok",
+            Verdict::Fail => "FAIL",
+            Verdict::Unknown => "?",
+        };
+        f.pad(text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuirksRow {
+    pub profile: String,
+    pub verdicts: [Verdict; 6],
+}
+
+// Runs the quirks test once per profile and says for every quirk whether
+// the result matches the platform's. The glyphs are never decoded: the
+// test is first run with the platform's own quirks, to see each result
+// line passing, and with each quirk flipped, to see it failing, and every
+// profile's lines are compared with those.
+pub fn quirks_report(
+    rom: &[u8],
+    variant: Variant,
+    profiles: &[(String, Quirks)],
+    frames: u32,
+) -> Result<Vec<QuirksRow>, String> {
+    let platform = EmulatorBuilder::new()
+        .variant(variant)
+        .build()
+        .map_err(|error| error.to_string())?
+        .quirks;
+    let passing = result_lines(rom, variant, platform, frames)?;
+    let mut failing = Vec::new();
+    for (index, name) in QUIRK_LINES.iter().enumerate() {
+        let mut flipped = platform;
+        let on = platform.flags()[quirk_index(name)].1;
+        flipped.set_flag(name, !on).unwrap();
+        failing.push(result_lines(rom, variant, flipped, frames)?[index].clone());
+    }
+
+    let mut rows = Vec::new();
+    for (profile, quirks) in profiles {
+        let lines = result_lines(rom, variant, *quirks, frames)?;
+        let mut verdicts = [Verdict::Unknown; 6];
+        for (index, verdict) in verdicts.iter_mut().enumerate() {
+            if passing[index] == failing[index] {
+                continue;
+            }
+            if lines[index] == passing[index] {
+                *verdict = Verdict::Pass;
+            } else if lines[index] == failing[index] {
+                *verdict = Verdict::Fail;
+            }
+        }
+        rows.push(QuirksRow {
+            profile: profile.clone(),
+            verdicts,
+        });
+    }
+    Ok(rows)
+}
+
+// One line per profile, one column per quirk.
+pub fn format_report(rows: &[QuirksRow]) -> String {
+    let width = rows
+        .iter()
+        .map(|row| row.profile.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    let mut text = format!("{:<width$}", "PROFILE");
+    for name in QUIRK_LINES {
+        text.push_str(&format!("  {}", name));
+    }
+    text.push('\n');
+    for row in rows {
+        text.push_str(&format!("{:<width$}", row.profile));
+        for (name, verdict) in QUIRK_LINES.iter().zip(&row.verdicts) {
+            text.push_str(&format!("  {:<1$}", verdict, name.len()));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn quirk_index(name: &str) -> usize {
+    Quirks::none()
+        .flags()
+        .iter()
+        .position(|(flag, _)| flag == &name)
+        .unwrap()
+}
+
+// The last six lines of text on the results screen, as rows of pixels.
+fn result_lines(
+    rom: &[u8],
+    variant: Variant,
+    quirks: Quirks,
+    frames: u32,
+) -> Result<Vec<Vec<u128>>, String> {
+    let number = platform_number(variant)
+        .ok_or_else(|| format!("the quirks test does not know {}", variant))?;
+    let mut emulator = EmulatorBuilder::new()
+        .variant(variant)
+        .quirks(quirks)
+        .seed(0)
+        .memory(PLATFORM_ADDRESS, &[number])
+        .rom(rom)
+        .build()
+        .map_err(|error| error.to_string())?;
+    for frame in 1..=frames {
+        if let Some(error) = run_frame(&mut emulator).error {
+            return Err(format!("frame {} with quirks {}: {}", frame, quirks, error));
+        }
+    }
+    let lines = text_lines(&emulator.display);
+    if lines.len() < QUIRK_LINES.len() {
+        return Err(format!(
+            "the screen shows {} lines of text, the quirks test's results have {}",
+            lines.len(),
+            QUIRK_LINES.len()
+        ));
+    }
+    Ok(lines[lines.len() - QUIRK_LINES.len()..].to_vec())
+}
+
+// Runs of lit rows separated by blank ones, of the physical plane.
+pub fn text_lines(display: &Display) -> Vec<Vec<u128>> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    for y in 0..MAX_HEIGHT {
+        let row = display.plane().row(y);
+        if row == 0 {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+        } else {
+            line.push(row);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use crate::clock::Clock;
+use crate::config::RomConfig;
+use crate::host::Files;
+use crate::Emulator;
+
+// Watches a ROM's config for edits made while the game runs, and applies
+// what can be without starting over. The file is read again on every
+// `poll`, about once a second is plenty, so an editor saving through a
+// temporary file and a rename is seen like any other write.
+//
+// The speed and quirks apply on the next frame: quirks only change how
+// instructions behave from then on, nothing the machine remembers. So do
+// the palette and the macros with their hotkeys, which the frontend holds.
+// The font is copied into memory at power on and the game may have changed
+// that memory since, so it waits for a reset. Taking the speed or the
+// quirks out of the file also does, what they were before the config set
+// them is not known here.
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    // The file as last read, None while it does not exist.
+    seen: Option<Vec<u8>>,
+    config: RomConfig,
+}
+
+// The options a reload changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reload {
+    pub applied: Vec<&'static str>,
+    // Take effect once the ROM is reset.
+    pub on_reset: Vec<&'static str>,
+}
+
+impl ConfigWatcher {
+    // Watches the config of `rom`, `config` being what was loaded from it.
+    pub fn new(files: &dyn Files, rom: &[u8], config: RomConfig) -> Self {
+        let path = RomConfig::path(rom);
+        ConfigWatcher {
+            seen: files.read(&path).ok(),
+            path,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &RomConfig {
+        &self.config
+    }
+
+    // None until the file changes. An edit that does not parse is reported
+    // once and leaves everything as it was, the next good one reloads.
+    pub fn poll(
+        &mut self,
+        files: &dyn Files,
+        emulator: &mut Emulator,
+    ) -> Option<Result<Reload, String>> {
+        let data = files.read(&self.path).ok();
+        if data == self.seen {
+            return None;
+        }
+        self.seen = data;
+        let text = self.seen.as_deref().map(String::from_utf8_lossy);
+        let config = match RomConfig::parse(text.as_deref().unwrap_or_default()) {
+            Ok(config) => config,
+            Err(error) => return Some(Err(error)),
+        };
+        if let Err(error) = config.macros() {
+            return Some(Err(error));
+        }
+        let reload = Reload::between(&self.config, &config);
+        if let Some(cycles) = config.cycles_per_frame {
+            emulator.cycles_per_frame = cycles;
+            emulator.clock = Clock::default();
+        }
+        if let Some(quirks) = config.quirks {
+            emulator.quirks = quirks;
+        }
+        self.config = config;
+        Some(Ok(reload))
+    }
+}
+
+impl Reload {
+    // What going from `old` to `new` changes, by the rules above.
+    pub fn between(old: &RomConfig, new: &RomConfig) -> Reload {
+        let mut reload = Reload::default();
+        let mut option = |name, changed, live| match (changed, live) {
+            (false, _) => {}
+            (true, true) => reload.applied.push(name),
+            (true, false) => reload.on_reset.push(name),
+        };
+        option(
+            "speed",
+            old.cycles_per_frame != new.cycles_per_frame,
+            new.cycles_per_frame.is_some(),
+        );
+        option("quirks", old.quirks != new.quirks, new.quirks.is_some());
+        option("palette", old.palette != new.palette, true);
+        option("macros", old.macros() != new.macros(), true);
+        option("font", old.font != new.font, false);
+        reload
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.on_reset.is_empty()
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+use crate::display::{Display, Palette};
+
+pub const MAX_PERSISTENCE: usize = 4;
+
+// Shape of a single CHIP-8 pixel once scaled up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelStyle {
+    #[default]
+    Solid,
+    // Leaves a 1px gap on the right and bottom of every pixel.
+    Grid,
+    // Round dots, like an LED matrix.
+    Led,
+}
+
+impl PixelStyle {
+    // Which sub-pixels of a `scale` x `scale` cell are lit, row-major.
+    fn cell_mask(self, scale: usize) -> Vec<bool> {
+        let center = scale as f32 / 2.0;
+        (0..scale * scale)
+            .map(|index| {
+                let (x, y) = (index % scale, index / scale);
+                match self {
+                    PixelStyle::Solid => true,
+                    PixelStyle::Grid => scale < 2 || (x < scale - 1 && y < scale - 1),
+                    PixelStyle::Led => {
+                        let dx = x as f32 + 0.5 - center;
+                        let dy = y as f32 + 0.5 - center;
+                        dx * dx + dy * dy <= center * center
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl FromStr for PixelStyle {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "solid" => Ok(PixelStyle::Solid),
+            "grid" => Ok(PixelStyle::Grid),
+            "led" => Ok(PixelStyle::Led),
+            _ => Err(format!(
+                "unknown pixel style {:?}, expected solid, grid or led",
+                text
+            )),
+        }
+    }
+}
+
+// Which way the picture is turned, clockwise, for vertical cabinets and
+// monitors stood on their side. Parsed from degrees: 0, 90, 180 or 270.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    // A quarter turn, the top of the game on the right.
+    Right,
+    UpsideDown,
+    // Three quarters, the top of the game on the left.
+    Left,
+}
+
+// The keypad's usual arrows, up, right, down and left.
+const ARROWS: [u8; 4] = [0x2, 0x6, 0x8, 0x4];
+
+impl Rotation {
+    fn quarter_turns(self) -> usize {
+        match self {
+            Rotation::None => 0,
+            Rotation::Right => 1,
+            Rotation::UpsideDown => 2,
+            Rotation::Left => 3,
+        }
+    }
+
+    // The size of a `width` x `height` picture once turned.
+    pub fn size(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Rotation::None | Rotation::UpsideDown => (width, height),
+            Rotation::Right | Rotation::Left => (height, width),
+        }
+    }
+
+    // The pixel of a `width` x `height` picture that shows at `x`, `y`
+    // once it is turned.
+    pub fn source(self, width: usize, height: usize, x: usize, y: usize) -> (usize, usize) {
+        match self {
+            Rotation::None => (x, y),
+            Rotation::Right => (y, height - 1 - x),
+            Rotation::UpsideDown => (width - 1 - x, height - 1 - y),
+            Rotation::Left => (width - 1 - y, x),
+        }
+    }
+
+    // The key the game gets when `key` is pressed: the arrows 2, 4, 6 and 8
+    // turn with the picture, so up on the keypad is up on the screen. Other
+    // keys stay where they are.
+    pub fn key(self, key: u8) -> u8 {
+        match ARROWS.iter().position(|&arrow| arrow == key) {
+            Some(direction) => ARROWS[(direction + 4 - self.quarter_turns()) % 4],
+            None => key,
+        }
+    }
+}
+
+impl FromStr for Rotation {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "0" => Ok(Rotation::None),
+            "90" => Ok(Rotation::Right),
+            "180" => Ok(Rotation::UpsideDown),
+            "270" => Ok(Rotation::Left),
+            _ => Err(format!(
+                "unknown rotation {:?}, expected 0, 90, 180 or 270",
+                text
+            )),
+        }
+    }
+}
+
+// The display as the player sees it once turned, and blown up `scale`
+// times for renderers drawing one character per pixel.
+pub(crate) struct Turned<'a> {
+    display: &'a Display,
+    rotation: Rotation,
+    scale: usize,
+}
+
+impl<'a> Turned<'a> {
+    pub(crate) fn new(display: &'a Display, rotation: Rotation) -> Self {
+        Turned::scaled(display, rotation, 1)
+    }
+
+    pub(crate) fn scaled(display: &'a Display, rotation: Rotation, scale: usize) -> Self {
+        Turned {
+            display,
+            rotation,
+            scale: scale.max(1),
+        }
+    }
+
+    pub(crate) fn width(&self) -> usize {
+        self.size().0
+    }
+
+    pub(crate) fn height(&self) -> usize {
+        self.size().1
+    }
+
+    fn size(&self) -> (usize, usize) {
+        let (width, height) = self
+            .rotation
+            .size(self.display.width(), self.display.height());
+        (width * self.scale, height * self.scale)
+    }
+
+    pub(crate) fn pixel(&self, x: usize, y: usize) -> bool {
+        let (width, height) = (self.display.width(), self.display.height());
+        let (x, y) = (x / self.scale, y / self.scale);
+        let (x, y) = self.rotation.source(width, height, x, y);
+        self.display.pixel(x, y)
+    }
+}
+
+// Turns the display into RGBA pixels with the options every frontend shares,
+// so SDL, terminal and web builds all look the same.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub palette: Palette,
+    // Output pixels per pixel across and down, at least 1.
+    scale: usize,
+    pub style: PixelStyle,
+    pub rotation: Rotation,
+    persistence: usize,
+    // Most recent frame first, one entry per logical pixel.
+    history: VecDeque<Vec<bool>>,
+}
+
+impl Renderer {
+    pub fn new(palette: Palette, scale: usize) -> Self {
+        Renderer {
+            palette,
+            scale: scale.max(1),
+            style: PixelStyle::Solid,
+            rotation: Rotation::None,
+            persistence: 1,
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    // 0 draws at 1 like `Turned::scaled`.
+    pub fn set_scale(&mut self, scale: usize) {
+        self.scale = scale.max(1);
+    }
+
+    pub fn persistence(&self) -> usize {
+        self.persistence
+    }
+
+    // Number of frames averaged together, 1 disables blending. Averaging
+    // 2-4 frames imitates a slow LCD and hides the flicker of games that
+    // erase and redraw their sprites every frame.
+    pub fn set_persistence(&mut self, frames: usize) {
+        self.persistence = frames.clamp(1, MAX_PERSISTENCE);
+        self.history.clear();
+    }
+
+    // Size in pixels of the buffer `render` fills for this display.
+    pub fn output_size(&self, display: &Display) -> (usize, usize) {
+        let (width, height) = self.rotation.size(display.width(), display.height());
+        (width * self.scale, height * self.scale)
+    }
+
+    // Renders one frame, call it once per emulated frame so blending sees
+    // every one of them.
+    pub fn render(&mut self, display: &Display, buffer: &mut [u8]) {
+        let (width, height) = (display.width(), display.height());
+        let frame: Vec<bool> = display.iter_rows().flat_map(|row| row.iter()).collect();
+        if self.history.front().map(Vec::len) != Some(frame.len()) {
+            // The resolution changed, old frames no longer line up.
+            self.history.clear();
+        }
+        self.history.push_front(frame);
+        self.history.truncate(self.persistence);
+
+        let history = &self.history;
+        let palette = &self.palette;
+        let cell = self.style.cell_mask(self.scale);
+        let background = palette.background;
+        let rotation = self.rotation;
+        let (turned_width, turned_height) = rotation.size(width, height);
+        fill_cells(
+            buffer,
+            turned_width,
+            turned_height,
+            self.scale,
+            &cell,
+            background,
+            |x, y| {
+                let (x, y) = rotation.source(width, height, x, y);
+                let lit = history.iter().filter(|frame| frame[y * width + x]).count();
+                blend(palette, lit, history.len())
+            },
+        );
+    }
+}
+
+// Writes a `width` x `height` image into `buffer`, repeating every pixel
+// `scale` times in both directions.
+pub(crate) fn fill_scaled(
+    buffer: &mut [u8],
+    width: usize,
+    height: usize,
+    scale: usize,
+    color_at: impl FnMut(usize, usize) -> [u8; 4],
+) {
+    let cell = vec![true; scale * scale];
+    fill_cells(buffer, width, height, scale, &cell, [0; 4], color_at);
+}
+
+// Like `fill_scaled`, but sub-pixels left out of the cell mask get the
+// background color instead.
+fn fill_cells(
+    buffer: &mut [u8],
+    width: usize,
+    height: usize,
+    scale: usize,
+    cell: &[bool],
+    background: [u8; 4],
+    mut color_at: impl FnMut(usize, usize) -> [u8; 4],
+) {
+    let line_len = width * scale * 4;
+    assert_eq!(
+        buffer.len(),
+        line_len * height * scale,
+        "RGBA buffer does not match a {}x{} image at scale {}",
+        width,
+        height,
+        scale
+    );
+
+    for (y, lines) in buffer.chunks_exact_mut(line_len * scale).enumerate() {
+        let colors: Vec<[u8; 4]> = (0..width).map(|x| color_at(x, y)).collect();
+        for (cell_row, line) in cell
+            .chunks_exact(scale)
+            .zip(lines.chunks_exact_mut(line_len))
+        {
+            for (color, pixels) in colors.iter().zip(line.chunks_exact_mut(scale * 4)) {
+                for (&lit, pixel) in cell_row.iter().zip(pixels.chunks_exact_mut(4)) {
+                    pixel.copy_from_slice(if lit { color } else { &background });
+                }
+            }
+        }
+    }
+}
+
+fn blend(palette: &Palette, lit: usize, total: usize) -> [u8; 4] {
+    let mut color = [0; 4];
+    for (channel, value) in color.iter_mut().enumerate() {
+        let background = palette.background[channel] as usize;
+        let foreground = palette.foreground[channel] as usize;
+        *value = ((foreground * lit + background * (total - lit)) / total) as u8;
+    }
+    color
+}
+
+// Where a windowed frontend draws the display. Window systems size windows
+// in logical points, the drawable has `scale_factor` physical pixels per
+// point (2 on most 4K and Retina screens, 1.25 or 1.5 on many laptops).
+// Scaling by a fractional amount smears pixels, so the display is scaled by
+// a whole number of physical pixels and centered, the rest is border.
+//
+// Recompute it on every resize and on every scale factor change, which is
+// what a window gets when it is dragged onto a monitor with another DPI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    // Physical pixels per CHIP-8 pixel, at least 1.
+    pub scale: usize,
+    // Top left corner of the image in the drawable, in physical pixels.
+    pub x: usize,
+    pub y: usize,
+    // Size of the drawable, in physical pixels.
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    pub fn fit(display: &Display, logical: (f64, f64), scale_factor: f64) -> Viewport {
+        Viewport::fit_turned(display, Rotation::None, logical, scale_factor)
+    }
+
+    // Like `fit`, for a picture turned by `rotation` as `Renderer` draws it.
+    pub fn fit_turned(
+        display: &Display,
+        rotation: Rotation,
+        logical: (f64, f64),
+        scale_factor: f64,
+    ) -> Viewport {
+        let physical = |points: f64| (points * scale_factor.max(0.0)).round() as usize;
+        let size = rotation.size(display.width(), display.height());
+        Viewport::fit_size(size, (physical(logical.0), physical(logical.1)))
+    }
+
+    // For frontends that are handed the drawable's size in physical pixels.
+    pub fn fit_physical(display: &Display, physical: (usize, usize)) -> Viewport {
+        Viewport::fit_size((display.width(), display.height()), physical)
+    }
+
+    fn fit_size(size: (usize, usize), physical: (usize, usize)) -> Viewport {
+        let (width, height) = physical;
+        let scale = (width / size.0).min(height / size.1).max(1);
+        let (image_width, image_height) = (size.0 * scale, size.1 * scale);
+        Viewport {
+            scale,
+            x: width.saturating_sub(image_width) / 2,
+            y: height.saturating_sub(image_height) / 2,
+            width,
+            height,
+        }
+    }
+
+    // The smallest window, in logical points, that shows the display at
+    // `scale` physical pixels per pixel.
+    pub fn logical_size(display: &Display, scale: usize, scale_factor: f64) -> (f64, f64) {
+        let points = |pixels: usize| (pixels * scale) as f64 / scale_factor.max(f64::MIN_POSITIVE);
+        (points(display.width()), points(display.height()))
+    }
+
+    // The CHIP-8 pixel under a point given in physical pixels, None on the
+    // border.
+    pub fn display_pixel(&self, display: &Display, x: usize, y: usize) -> Option<(usize, usize)> {
+        let column = x.checked_sub(self.x)? / self.scale;
+        let row = y.checked_sub(self.y)? / self.scale;
+        (column < display.width() && row < display.height()).then_some((column, row))
+    }
+}
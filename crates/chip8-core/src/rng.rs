@@ -0,0 +1,38 @@
+// The interpreter's own random number generator, SplitMix64, so the core
+// needs no dependency for Cxkk and every run can be replayed from its seed.
+// The state is part of the machine like any register: it is cloned, hashed
+// and serialized along with the rest of `Emulator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    // A different sequence every run, seeded from the OS.
+    #[cfg(feature = "rand")]
+    pub fn from_entropy() -> Self {
+        Rng::new(rand::random())
+    }
+
+    // `Rng::new(rng.state())` continues the same sequence.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u8(&mut self) -> u8 {
+        (self.next_u64() >> 56) as u8
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::btree_set;
+use std::hash::Hasher;
+
+use crate::analyzer::{analyze, Analysis};
+use crate::checksum::StableHasher;
+use crate::instruction::Variant;
+use crate::{EmulatorError, Instruction, INITIAL_ADDRESS};
+
+// Stable 64-bit FNV-1a hash of a ROM image. Unlike `DefaultHasher` it never
+// changes between builds, so it can key files on disk.
+pub fn rom_hash(data: &[u8]) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write(data);
+    hasher.finish()
+}
+
+// A ROM image that fits in memory, analyzed once when loaded, for tools
+// that look at programs without running them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rom {
+    data: Vec<u8>,
+    analysis: Analysis,
+}
+
+impl Rom {
+    // A CHIP-8 ROM, at most 4K with the interpreter area.
+    pub fn new(data: Vec<u8>) -> Result<Self, EmulatorError> {
+        Rom::for_variant(data, Variant::Chip8)
+    }
+
+    // A ROM for `variant`'s memory, e.g. up to 64K for XO-CHIP.
+    pub fn for_variant(data: Vec<u8>, variant: Variant) -> Result<Self, EmulatorError> {
+        if variant.start_address() as usize + data.len() > variant.ram_size() {
+            return Err(EmulatorError::RomTooLarge(data.len()));
+        }
+        let analysis = analyze(&data);
+        Ok(Rom { data, analysis })
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn hash(&self) -> u64 {
+        rom_hash(&self.data)
+    }
+
+    pub fn analysis(&self) -> &Analysis {
+        &self.analysis
+    }
+
+    // The opcode at `address`, if both of its bytes are in the ROM.
+    pub fn op_code(&self, address: u16) -> Option<u16> {
+        let offset = (address as usize).checked_sub(INITIAL_ADDRESS as usize)?;
+        let bytes = self.data.get(offset..offset + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    // `(address, opcode, instruction)` for every instruction the analyzer
+    // could reach, in address order, leaving out sprites and other data.
+    pub fn instructions(&self) -> Instructions<'_> {
+        Instructions {
+            rom: self,
+            addresses: self.analysis.code_set().iter(),
+        }
+    }
+}
+
+pub struct Instructions<'a> {
+    rom: &'a Rom,
+    addresses: btree_set::Iter<'a, u16>,
+}
+
+impl Iterator for Instructions<'_> {
+    type Item = (u16, u16, Instruction);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let address = *self.addresses.next()?;
+        let op_code = self.rom.op_code(address)?;
+        Some((address, op_code, Instruction::decode(op_code)))
+    }
+}
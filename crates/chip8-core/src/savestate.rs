@@ -0,0 +1,336 @@
+use crate::chip8x::Chip8x;
+use crate::clock::Clock;
+use crate::compress;
+use crate::disk::{Disk, DISK_SIZE};
+use crate::display::{Display, Resolution, MAX_HEIGHT};
+use crate::hooks::Hooks;
+use crate::keypad::Keypad;
+use crate::printer::Printer;
+use crate::wallclock::WallClock;
+use crate::xochip::{XoChip, FLAG_COUNT, PATTERN_SIZE};
+use crate::XOCHIP_RAM_SIZE;
+use crate::{Emulator, EmulatorError, Quirks, Rng, RAM_SIZE, STACK_SIZE, V_REGISTERS_NUMBER};
+
+const MAGIC: &[u8; 4] = b"C8SS";
+// A state as `compress::pack` packed it.
+const PACKED_MAGIC: &[u8; 4] = b"C8SZ";
+// And as `compress::zstd_pack` did.
+const ZSTD_MAGIC: &[u8; 4] = b"C8ZS";
+const VERSION: u8 = 9;
+
+// The whole machine as bytes, without needing the `serde` feature. Besides
+// what a ROM can see this includes the random number generator, the
+// keypad queue and where the emulator is inside the current frame, so
+// loading a state and running on is indistinguishable from never having
+// stopped: rewind, netplay and TAS replays depend on that. The bytes are
+// packed, a few hundred instead of several thousand: with zstd under the
+// `compression` feature, run-length encoded without it. `load` reads states
+// packed either way, or not at all.
+pub fn save(emulator: &Emulator) -> Vec<u8> {
+    let state = save_plain(emulator);
+    #[cfg(feature = "compression")]
+    let (magic, packed) = (ZSTD_MAGIC, compress::zstd_pack(&state));
+    #[cfg(not(feature = "compression"))]
+    let (magic, packed) = (PACKED_MAGIC, compress::pack(&state));
+    [magic.as_slice(), &packed].concat()
+}
+
+pub fn load(data: &[u8]) -> Result<Emulator, EmulatorError> {
+    if let Some(packed) = data.strip_prefix(ZSTD_MAGIC) {
+        return load_plain(&unpack_zstd(packed)?);
+    }
+    match data.strip_prefix(PACKED_MAGIC) {
+        Some(packed) => load_plain(&compress::unpack(packed).map_err(|error| invalid(&error))?),
+        None => load_plain(data),
+    }
+}
+
+#[cfg(feature = "compression")]
+fn unpack_zstd(packed: &[u8]) -> Result<Vec<u8>, EmulatorError> {
+    compress::zstd_unpack(packed).map_err(|error| invalid(&error))
+}
+
+#[cfg(not(feature = "compression"))]
+fn unpack_zstd(_packed: &[u8]) -> Result<Vec<u8>, EmulatorError> {
+    Err(invalid(
+        "packed with zstd, built without the compression feature",
+    ))
+}
+
+// The state unpacked, for the timeline to pack against the one before.
+pub(crate) fn save_plain(emulator: &Emulator) -> Vec<u8> {
+    let mut writer = Writer(Vec::with_capacity(emulator.ram.len() + 1024));
+    writer.bytes(MAGIC);
+    writer.u8(VERSION);
+    writer.bytes(&emulator.v_registers);
+    writer.u16(emulator.i_register);
+    writer.u16(emulator.program_counter);
+    writer.u8(emulator.stack_pointer);
+    for &address in &emulator.stack {
+        writer.u16(address);
+    }
+    writer.u64(emulator.stack_limit as u64);
+    writer.u64(emulator.delay_timer_registry as u64);
+    writer.u64(emulator.sound_timer_registry as u64);
+    writer.u64(emulator.ram.len() as u64);
+    writer.bytes(&emulator.ram);
+    save_display(&mut writer, &emulator.display);
+    emulator.keypad.save(&mut writer);
+    let mut quirks = 0u16;
+    for (bit, (_, on)) in emulator.quirks.flags().iter().enumerate() {
+        quirks |= (*on as u16) << bit;
+    }
+    writer.u16(quirks);
+    writer.u64(emulator.rng.state());
+    writer.u64(emulator.cycles_per_frame as u64);
+    writer.u64(emulator.clock.hz.unwrap_or(0) as u64);
+    writer.u64(emulator.clock.phase as u64);
+    writer.u8(emulator.vblank as u8);
+    writer.u64(emulator.timer_cycles as u64);
+    writer.u8(emulator.chip8x.is_some() as u8);
+    if let Some(chip8x) = &emulator.chip8x {
+        writer.u8(chip8x.background);
+        writer.bytes(&chip8x.zones);
+        chip8x.keypad.save(&mut writer);
+        writer.u8(chip8x.tone);
+        writer.u8(chip8x.input);
+    }
+    writer.u8(emulator.xochip.is_some() as u8);
+    if let Some(xochip) = &emulator.xochip {
+        writer.u8(xochip.pitch);
+        writer.u8(xochip.pattern.is_some() as u8);
+        writer.bytes(&xochip.pattern.unwrap_or_default());
+        writer.bytes(&xochip.flags);
+    }
+    writer.u8(emulator.printer.is_some() as u8);
+    if let Some(printer) = &emulator.printer {
+        writer.u64(printer.output().len() as u64);
+        writer.bytes(printer.output());
+    }
+    writer.u8(emulator.wall_clock.is_some() as u8);
+    if let Some(clock) = &emulator.wall_clock {
+        writer.u64(clock.unix_seconds());
+        writer.u64(clock.utc_offset() as i64 as u64);
+    }
+    writer.u8(emulator.disk.is_some() as u8);
+    if let Some(disk) = &emulator.disk {
+        writer.u8(disk.is_dirty() as u8);
+        writer.bytes(disk.as_bytes());
+    }
+    writer.0
+}
+
+pub(crate) fn load_plain(data: &[u8]) -> Result<Emulator, EmulatorError> {
+    let mut reader = Reader { data, offset: 0 };
+    if reader.bytes(MAGIC.len())? != MAGIC {
+        return Err(invalid("not a savestate"));
+    }
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(invalid(&format!("unsupported version {}", version)));
+    }
+    let mut v_registers = [0; V_REGISTERS_NUMBER];
+    v_registers.copy_from_slice(reader.bytes(V_REGISTERS_NUMBER)?);
+    let i_register = reader.u16()?;
+    let program_counter = reader.u16()?;
+    let stack_pointer = reader.u8()?;
+    let mut stack = [0; STACK_SIZE];
+    for address in stack.iter_mut() {
+        *address = reader.u16()?;
+    }
+    let stack_limit = reader.u64()? as usize;
+    let delay_timer_registry = reader.u64()? as usize;
+    let sound_timer_registry = reader.u64()? as usize;
+    let ram_size = reader.u64()? as usize;
+    if !ram_size.is_power_of_two() || !(RAM_SIZE..=XOCHIP_RAM_SIZE).contains(&ram_size) {
+        return Err(invalid(&format!("{} bytes of memory", ram_size)));
+    }
+    let ram = reader.bytes(ram_size)?.to_vec();
+    let display = load_display(&mut reader)?;
+    let keypad = Keypad::load(&mut reader)?;
+    let quirk_bits = reader.u16()?;
+    let mut quirks = Quirks::none();
+    for (bit, (name, _)) in Quirks::none().flags().iter().enumerate() {
+        quirks.set_flag(name, quirk_bits & (1 << bit) != 0).unwrap();
+    }
+    let rng = Rng::new(reader.u64()?);
+    let cycles_per_frame = reader.u64()? as usize;
+    let clock = Clock {
+        hz: Some(reader.u64()? as u32).filter(|&hz| hz != 0),
+        phase: reader.u64()? as u32,
+    };
+    let vblank = reader.u8()? != 0;
+    let timer_cycles = reader.u64()? as usize;
+    let chip8x = match reader.u8()? {
+        0 => None,
+        _ => {
+            let mut chip8x = Chip8x::new();
+            chip8x.background = reader.u8()?;
+            let zones = reader.bytes(chip8x.zones.len())?;
+            chip8x.zones.copy_from_slice(zones);
+            chip8x.keypad = Keypad::load(&mut reader)?;
+            chip8x.tone = reader.u8()?;
+            chip8x.input = reader.u8()?;
+            Some(chip8x)
+        }
+    };
+    let xochip = match reader.u8()? {
+        0 => None,
+        _ => {
+            let mut xochip = XoChip::new();
+            xochip.pitch = reader.u8()?;
+            let loaded = reader.u8()? != 0;
+            let mut pattern = [0; PATTERN_SIZE];
+            pattern.copy_from_slice(reader.bytes(PATTERN_SIZE)?);
+            xochip.pattern = loaded.then_some(pattern);
+            xochip.flags.copy_from_slice(reader.bytes(FLAG_COUNT)?);
+            Some(xochip)
+        }
+    };
+    let printer = match reader.u8()? {
+        0 => None,
+        _ => {
+            let mut printer = Printer::new();
+            let length = reader.u64()? as usize;
+            for &byte in reader.bytes(length)? {
+                printer.print(byte);
+            }
+            Some(printer)
+        }
+    };
+    let wall_clock = match reader.u8()? {
+        0 => None,
+        _ => {
+            let unix_seconds = reader.u64()?;
+            let utc_offset = i32::try_from(reader.u64()? as i64)
+                .map_err(|_| invalid("wall clock offset out of range"))?;
+            let mut clock = WallClock::new(utc_offset);
+            clock.set(unix_seconds);
+            Some(clock)
+        }
+    };
+    let disk = match reader.u8()? {
+        0 => None,
+        _ => {
+            let dirty = reader.u8()? != 0;
+            let mut disk =
+                Disk::from_bytes(reader.bytes(DISK_SIZE)?).map_err(|error| invalid(&error))?;
+            disk.dirty = dirty;
+            Some(disk)
+        }
+    };
+    if reader.offset != data.len() {
+        return Err(invalid("trailing bytes"));
+    }
+    if stack_pointer as usize > STACK_SIZE || program_counter as usize >= ram.len() {
+        return Err(invalid("registers out of range"));
+    }
+
+    Ok(Emulator {
+        v_registers,
+        i_register,
+        program_counter,
+        stack_pointer,
+        stack,
+        stack_limit,
+        delay_timer_registry,
+        sound_timer_registry,
+        ram,
+        display,
+        keypad,
+        quirks,
+        rng,
+        cycles_per_frame,
+        clock,
+        vblank,
+        timer_cycles,
+        chip8x,
+        xochip,
+        printer,
+        wall_clock,
+        disk,
+        hooks: Hooks::default(),
+    })
+}
+
+fn save_display(writer: &mut Writer, display: &Display) {
+    writer.u8(match display.resolution() {
+        Resolution::Lores => 0,
+        Resolution::Tall => 1,
+        Resolution::Hires => 2,
+        Resolution::Eti660 => 3,
+    });
+    for y in 0..MAX_HEIGHT {
+        writer.bytes(&display.plane().row(y).to_le_bytes());
+    }
+}
+
+fn load_display(reader: &mut Reader) -> Result<Display, EmulatorError> {
+    let mut display = Display::new();
+    display.set_resolution(match reader.u8()? {
+        0 => Resolution::Lores,
+        1 => Resolution::Tall,
+        2 => Resolution::Hires,
+        3 => Resolution::Eti660,
+        _ => return Err(invalid("unknown resolution")),
+    });
+    for y in 0..MAX_HEIGHT {
+        let row = u128::from_le_bytes(reader.bytes(16)?.try_into().unwrap());
+        display.restore_row(y, row);
+    }
+    // The frontend has not seen the loaded screen yet.
+    Ok(display)
+}
+
+fn invalid(message: &str) -> EmulatorError {
+    EmulatorError::InvalidSaveState(message.to_string())
+}
+
+// Little endian fields, appended in order.
+pub(crate) struct Writer(Vec<u8>);
+
+impl Writer {
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    pub(crate) fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], EmulatorError> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| invalid("truncated"))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, EmulatorError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, EmulatorError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, EmulatorError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt::Write;
+
+use crate::checksum::ChecksumLog;
+use crate::input::{InputScript, InputSource};
+use crate::rng::Rng;
+use crate::rom::rom_hash;
+use crate::{run_frame, Emulator, EmulatorBuilder, FrameOutput, Keypad, Quirks, CYCLES_PER_FRAME};
+
+// Frames between the checksums a session log keeps, a replay that goes
+// wrong is caught within a second of it.
+pub const CHECKSUM_INTERVAL: u64 = 60;
+
+// Everything needed to play a session again exactly: which ROM, the quirks,
+// speed and random seed it ran with, every key event and what went wrong,
+// if anything. It is plain text a user can attach to an issue:
+//
+//     chip8 0.1.0
+//     rom 5f0c2d3f1e2a4b6c pong.ch8
+//     quirks chip8
+//     speed 10
+//     seed 10908411165832781453
+//     key 30 5 down
+//     key 34 5 up
+//     checksum 60 0123456789abcdef
+//     error 75 stack overflow at 0x2a4
+//     end 75
+//
+// The ROM itself is left out, it is often not the reporter's to share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionLog {
+    pub version: String,
+    pub rom_name: String,
+    pub rom_hash: u64,
+    pub quirks: Quirks,
+    pub cycles_per_frame: usize,
+    pub seed: u64,
+    // (frame, key, pressed), frames count from 1 like `InputScript`'s.
+    pub events: Vec<(u64, u8, bool)>,
+    pub checksums: ChecksumLog,
+    pub error: Option<(u64, String)>,
+    pub frames: u64,
+    // Events queued at the end of the last frame, the ones after them were
+    // added for this one.
+    pending: usize,
+}
+
+impl SessionLog {
+    // Starts logging `emulator`, freshly built from `rom` and not run yet.
+    pub fn start(rom_name: &str, rom: &[u8], emulator: &Emulator) -> Self {
+        SessionLog {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            rom_name: rom_name.to_string(),
+            rom_hash: rom_hash(rom),
+            quirks: emulator.quirks,
+            cycles_per_frame: emulator.cycles_per_frame,
+            seed: emulator.rng.state(),
+            events: Vec::new(),
+            checksums: ChecksumLog::new(CHECKSUM_INTERVAL),
+            error: None,
+            frames: 0,
+            pending: emulator.keypad.pending(),
+        }
+    }
+
+    // Call right before every `run_frame`, with the keys already pressed.
+    pub fn before_frame(&mut self, keypad: &Keypad) {
+        self.frames += 1;
+        for event in keypad.queued().skip(self.pending) {
+            self.events.push((self.frames, event.key, event.pressed));
+        }
+    }
+
+    // Call right after every `run_frame`.
+    pub fn after_frame(&mut self, emulator: &Emulator, output: &FrameOutput) {
+        self.pending = emulator.keypad.pending();
+        self.checksums.record(self.frames, emulator);
+        if let Some(error) = &output.error {
+            self.error.get_or_insert((self.frames, error.to_string()));
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "chip8 {}\nrom {:016x} {}\nquirks {}\nspeed {}\nseed {}\n",
+            self.version,
+            self.rom_hash,
+            self.rom_name,
+            self.quirks,
+            self.cycles_per_frame,
+            self.seed
+        );
+        for &(frame, key, pressed) in &self.events {
+            let action = if pressed { "down" } else { "up" };
+            let _ = writeln!(text, "key {} {:X} {}", frame, key, action);
+        }
+        for line in self.checksums.to_text().lines().skip(1) {
+            let _ = writeln!(text, "checksum {}", line);
+        }
+        if let Some((frame, error)) = &self.error {
+            let _ = writeln!(text, "error {} {}", frame, error);
+        }
+        let _ = writeln!(text, "end {}", self.frames);
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut log = SessionLog {
+            version: String::new(),
+            rom_name: String::new(),
+            rom_hash: 0,
+            quirks: Quirks::default(),
+            cycles_per_frame: CYCLES_PER_FRAME,
+            seed: 0,
+            events: Vec::new(),
+            checksums: ChecksumLog::new(CHECKSUM_INTERVAL),
+            error: None,
+            frames: 0,
+            pending: 0,
+        };
+        let mut seen_rom = false;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| format!("line {}: {}", number + 1, message);
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match keyword {
+                "chip8" => log.version = rest.to_string(),
+                "rom" => {
+                    let (hash, name) = rest.split_once(' ').unwrap_or((rest, ""));
+                    log.rom_hash =
+                        u64::from_str_radix(hash, 16).map_err(|_| error("invalid ROM hash"))?;
+                    log.rom_name = name.to_string();
+                    seen_rom = true;
+                }
+                "quirks" => log.quirks = rest.parse().map_err(|e: String| error(&e))?,
+                "speed" => {
+                    log.cycles_per_frame = rest.parse().map_err(|_| error("invalid speed"))?
+                }
+                "seed" => log.seed = rest.parse().map_err(|_| error("invalid seed"))?,
+                "key" => {
+                    let script =
+                        InputScript::parse(rest).map_err(|_| error("invalid key event"))?;
+                    log.events.extend_from_slice(script.events());
+                }
+                "checksum" => {
+                    let entry = rest.split_once(' ').and_then(|(frame, checksum)| {
+                        Some((frame.parse().ok()?, u64::from_str_radix(checksum, 16).ok()?))
+                    });
+                    let (frame, checksum) = entry.ok_or_else(|| error("invalid checksum"))?;
+                    log.checksums.insert(frame, checksum);
+                }
+                "error" => {
+                    let (frame, message) = rest.split_once(' ').unwrap_or((rest, ""));
+                    let frame = frame.parse().map_err(|_| error("invalid frame"))?;
+                    log.error = Some((frame, message.to_string()));
+                }
+                "end" => log.frames = rest.parse().map_err(|_| error("invalid frame count"))?,
+                _ => return Err(error(&format!("unknown entry {:?}", keyword))),
+            }
+        }
+        if !seen_rom {
+            return Err("not a session log, it names no ROM".to_string());
+        }
+        Ok(log)
+    }
+
+    // Plays the session again on `rom`, which has to be the one it was
+    // recorded with. Fails at the first frame whose checksum differs, or if
+    // the recorded error does not happen again.
+    pub fn replay(&self, rom: &[u8]) -> Result<Emulator, String> {
+        self.replay_with(rom, &mut |_, _| {})
+    }
+
+    // `replay` calling `after_frame` with every frame's number and the
+    // machine at its end.
+    pub fn replay_with(
+        &self,
+        rom: &[u8],
+        after_frame: &mut dyn FnMut(u64, &Emulator),
+    ) -> Result<Emulator, String> {
+        if rom_hash(rom) != self.rom_hash {
+            return Err(format!(
+                "the ROM does not match the session's, its hash is {:016x} instead of {:016x}",
+                rom_hash(rom),
+                self.rom_hash
+            ));
+        }
+        let mut emulator = EmulatorBuilder::new()
+            .quirks(self.quirks)
+            .cycles_per_frame(self.cycles_per_frame)
+            .rom(rom)
+            .build()
+            .map_err(|error| error.to_string())?;
+        emulator.rng = Rng::new(self.seed);
+        let mut inputs = InputScript::from_events(self.events.clone());
+        for frame in 1..=self.frames {
+            inputs.poll(&mut emulator.keypad);
+            let output = run_frame(&mut emulator);
+            after_frame(frame, &emulator);
+            self.checksums
+                .check(frame, &emulator)
+                .map_err(|desync| desync.to_string())?;
+            if let Some(error) = output.error {
+                return match &self.error {
+                    Some((at, message)) if *at == frame && *message == error.to_string() => {
+                        Ok(emulator)
+                    }
+                    _ => Err(format!("frame {}: unexpected error: {}", frame, error)),
+                };
+            }
+        }
+        match &self.error {
+            Some((frame, message)) => Err(format!(
+                "the error at frame {} did not happen again: {}",
+                frame, message
+            )),
+            None => Ok(emulator),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::config_dir;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayStats {
+    // The name the ROM was last launched under, for listings.
+    pub name: String,
+    pub launches: u32,
+    pub seconds_played: u64,
+    // Unix time of the last launch.
+    pub last_played: Option<u64>,
+}
+
+impl PlayStats {
+    // "1h 05m", "12m 30s" or "45s".
+    pub fn playtime(&self) -> String {
+        let seconds = self.seconds_played;
+        match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+            (0, 0, s) => format!("{}s", s),
+            (0, m, s) => format!("{}m {:02}s", m, s),
+            (h, m, _) => format!("{}h {:02}m", h, m),
+        }
+    }
+
+    // Day of the last launch as YYYY-MM-DD, in UTC.
+    pub fn last_played_date(&self) -> Option<String> {
+        self.last_played.map(|time| {
+            let (year, month, day) = civil_date(time / 86_400);
+            format!("{:04}-{:02}-{:02}", year, month, day)
+        })
+    }
+}
+
+// Play statistics of every ROM ever launched, keyed by `rom_hash` and kept
+// in a single file next to the per-ROM configs, one
+// `hash = launches seconds last_played name` line per ROM.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayStatsStore {
+    roms: BTreeMap<u64, PlayStats>,
+}
+
+impl PlayStatsStore {
+    pub fn path() -> PathBuf {
+        config_dir().join("playtime.cfg")
+    }
+
+    // A missing file is just an empty store.
+    pub fn load() -> Result<Self, String> {
+        match fs::read_to_string(Self::path()) {
+            Ok(text) => Self::parse(&text),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_text())
+    }
+
+    pub fn get(&self, hash: u64) -> Option<&PlayStats> {
+        self.roms.get(&hash)
+    }
+
+    // Most recently played first.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &PlayStats)> {
+        let mut roms: Vec<(u64, &PlayStats)> = self
+            .roms
+            .iter()
+            .map(|(&hash, stats)| (hash, stats))
+            .collect();
+        roms.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.last_played));
+        roms.into_iter()
+    }
+
+    pub fn record_launch(&mut self, hash: u64, name: &str, now: SystemTime) {
+        let stats = self.roms.entry(hash).or_default();
+        stats.name = name.to_string();
+        stats.launches += 1;
+        stats.last_played = now
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|time| time.as_secs());
+    }
+
+    pub fn add_playtime(&mut self, hash: u64, played: Duration) {
+        self.roms.entry(hash).or_default().seconds_played += played.as_secs();
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut store = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = || {
+                format!(
+                    "line {}: expected `hash = launches seconds last name`",
+                    number + 1
+                )
+            };
+            let (hash, value) = line.split_once('=').ok_or_else(error)?;
+            let hash = u64::from_str_radix(hash.trim(), 16).map_err(|_| error())?;
+            let mut fields = value.trim().splitn(4, ' ');
+            let mut next = || fields.next().ok_or_else(error);
+            let launches = next()?.parse().map_err(|_| error())?;
+            let seconds_played = next()?.parse().map_err(|_| error())?;
+            let last_played = match next()? {
+                "-" => None,
+                time => Some(time.parse().map_err(|_| error())?),
+            };
+            let name = fields.next().unwrap_or("").to_string();
+            store.roms.insert(
+                hash,
+                PlayStats {
+                    name,
+                    launches,
+                    seconds_played,
+                    last_played,
+                },
+            );
+        }
+        Ok(store)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (hash, stats) in self.roms.iter() {
+            let last_played = stats
+                .last_played
+                .map_or("-".to_string(), |time| time.to_string());
+            text.push_str(&format!(
+                "{:016x} = {} {} {} {}\n",
+                hash, stats.launches, stats.seconds_played, last_played, stats.name
+            ));
+        }
+        text
+    }
+}
+
+// Measures one play session, from launch until `finish`.
+pub struct PlaySession {
+    hash: u64,
+    started: SystemTime,
+}
+
+impl PlaySession {
+    pub fn start(store: &mut PlayStatsStore, hash: u64, name: &str) -> Self {
+        let started = SystemTime::now();
+        store.record_launch(hash, name, started);
+        PlaySession { hash, started }
+    }
+
+    pub fn finish(self, store: &mut PlayStatsStore) {
+        let played = self.started.elapsed().unwrap_or_default();
+        store.add_playtime(self.hash, played);
+    }
+}
+
+// Year, month and day of a count of days since 1970-01-01, from Howard
+// Hinnant's `civil_from_days`.
+fn civil_date(days: u64) -> (i64, u32, u32) {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::font::{FONT_ADDRESS, LARGE_FONT_ADDRESS, LARGE_FONT_SIZE, SMALL_FONT_SIZE};
+use crate::hooks::ExecutionHook;
+use crate::{Emulator, EmulatorError, Instruction};
+
+// What a ROM does that interpreters disagree on, so it only works on some
+// of them. Homebrew authors run with `Strict` to find those places before
+// players do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Behavior {
+    // 8xyN with VF as Vx or Vy: whether the result or the flag ends up in
+    // VF, and which VF is read, varies.
+    FlagOperand,
+    // An instruction fetched from an odd address, which the VIP cannot do.
+    OddAddress,
+    // Memory neither the ROM, the font nor the program itself put anything
+    // in, which holds whatever the interpreter left there.
+    Uninitialized,
+    // An address past the end of memory, wrapped around here.
+    PastMemory,
+    // Dxyn starting off the screen, wrapped around here, and cut or not
+    // elsewhere.
+    OffScreen,
+    // A sprite running over the screen edge, cut or wrapped depending on
+    // the `clipping` quirk.
+    AcrossEdge,
+}
+
+impl fmt::Display for Behavior {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Behavior::FlagOperand => "VF is an operand of 8xyN, which also sets it as the flag",
+            Behavior::OddAddress => "instruction at an odd address",
+            Behavior::Uninitialized => "reads memory nothing was written to",
+            Behavior::PastMemory => "reads or writes past the end of memory",
+            Behavior::OffScreen => "draws a sprite starting off the screen",
+            Behavior::AcrossEdge => "draws a sprite across the screen edge",
+        })
+    }
+}
+
+// One place a behavior was seen, by the instruction's address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Finding {
+    pub behavior: Behavior,
+    pub address: u16,
+    pub op_code: u16,
+    // Times it happened there.
+    pub count: u32,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:#05x} ({:04X}): {}",
+            self.address, self.op_code, self.behavior
+        )?;
+        if self.count > 1 {
+            write!(f, ", {} times", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+// What a `Strict` hook found, readable while the hook is on the emulator.
+#[derive(Debug, Clone, Default)]
+pub struct Findings(Arc<Mutex<Vec<Finding>>>);
+
+impl Findings {
+    // In the order first seen.
+    pub fn list(&self) -> Vec<Finding> {
+        self.0.lock().unwrap().clone()
+    }
+
+    // Places found so far, each counted once however often it happens.
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // The latest place found, as the error that stops a run trapping on
+    // them.
+    pub fn trap(&self) -> Option<EmulatorError> {
+        let last = *self.0.lock().unwrap().last()?;
+        Some(EmulatorError::UndefinedBehavior(last.to_string()))
+    }
+
+    fn record(&self, behavior: Behavior, address: u16, op_code: u16) {
+        let mut findings = self.0.lock().unwrap();
+        let seen = findings
+            .iter_mut()
+            .find(|finding| finding.behavior == behavior && finding.address == address);
+        match seen {
+            Some(finding) => finding.count += 1,
+            None => findings.push(Finding {
+                behavior,
+                address,
+                op_code,
+                count: 1,
+            }),
+        }
+    }
+}
+
+// An execution hook noting every `Behavior` with where it happened. Make
+// it at power on, it takes the memory there is then to be the ROM and the
+// font:
+//
+//     let strict = Strict::new(&emulator, rom.len());
+//     let findings = strict.findings();
+//     emulator.hooks.add(strict);
+pub struct Strict {
+    findings: Findings,
+    // Bytes something was put in.
+    written: Vec<bool>,
+}
+
+impl Strict {
+    pub fn new(emulator: &Emulator, rom_len: usize) -> Self {
+        let mut written = vec![false; emulator.ram.len()];
+        let start = emulator.program_counter as usize;
+        for (from, len) in [
+            (FONT_ADDRESS as usize, SMALL_FONT_SIZE),
+            (LARGE_FONT_ADDRESS as usize, LARGE_FONT_SIZE),
+            (start, rom_len),
+        ] {
+            let end = (from + len).min(written.len());
+            written[from.min(end)..end].fill(true);
+        }
+        Strict {
+            findings: Findings::default(),
+            written,
+        }
+    }
+
+    pub fn findings(&self) -> Findings {
+        self.findings.clone()
+    }
+
+    // Notes reading `len` bytes from `from` as the instruction at `address`.
+    fn read(&self, from: usize, len: usize, address: u16, op_code: u16) {
+        if from + len > self.written.len() {
+            self.findings.record(Behavior::PastMemory, address, op_code);
+        } else if !self.written[from..from + len].iter().all(|&byte| byte) {
+            self.findings
+                .record(Behavior::Uninitialized, address, op_code);
+        }
+    }
+}
+
+impl ExecutionHook for Strict {
+    fn before_step(&mut self, emulator: &mut Emulator, instruction: Instruction) {
+        let address = emulator.program_counter.wrapping_sub(2);
+        let op_code = instruction.encode();
+        if address % 2 == 1 {
+            self.findings.record(Behavior::OddAddress, address, op_code);
+        }
+        self.read(address as usize, 2, address, op_code);
+        let v = &emulator.v_registers;
+        let i = emulator.i_register as usize;
+        match instruction {
+            Instruction::Or { x, y }
+            | Instruction::And { x, y }
+            | Instruction::Xor { x, y }
+            | Instruction::Add { x, y }
+            | Instruction::Sub { x, y }
+            | Instruction::Shr { x, y }
+            | Instruction::Subn { x, y }
+            | Instruction::Shl { x, y }
+                if x == 0xF || y == 0xF =>
+            {
+                self.findings
+                    .record(Behavior::FlagOperand, address, op_code);
+            }
+            Instruction::Load { x } => self.read(i, x as usize + 1, address, op_code),
+            // Waiting for the vertical blank, it runs again.
+            Instruction::Drw { .. } if emulator.quirks.display_wait && !emulator.vblank => {}
+            Instruction::Drw { x, y, n } => {
+                // Dxy0 is SUPER-CHIP's 16x16 sprite.
+                let (columns, rows) = match n {
+                    0 => (16, 16),
+                    n => (8, n as usize),
+                };
+                let bytes = if n == 0 { 32 } else { rows };
+                self.read(i, bytes, address, op_code);
+                let (vx, vy) = (v[x as usize] as usize, v[y as usize] as usize);
+                let (width, height) = (emulator.display.width(), emulator.display.height());
+                if vx >= width || vy >= height {
+                    self.findings.record(Behavior::OffScreen, address, op_code);
+                } else if vx + columns > width || vy + rows > height {
+                    self.findings.record(Behavior::AcrossEdge, address, op_code);
+                }
+            }
+            Instruction::LdBcd { .. } if i + 3 > self.written.len() => {
+                self.findings.record(Behavior::PastMemory, address, op_code);
+            }
+            Instruction::Store { x } if i + x as usize + 1 > self.written.len() => {
+                self.findings.record(Behavior::PastMemory, address, op_code);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_memory_write(&mut self, address: u16, _value: u8) {
+        if let Some(byte) = self.written.get_mut(address as usize) {
+            *byte = true;
+        }
+    }
+}
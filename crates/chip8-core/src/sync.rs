@@ -0,0 +1,182 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+const FRAMES_PER_SECOND: f64 = 60.0;
+// Drift is left alone up to a frame, below that nobody sees or hears it.
+const TOLERANCE: f64 = 1.0 / FRAMES_PER_SECOND;
+// Resampling never speeds up or slows down the audio by more than this,
+// half a percent is inaudible even on a held tone.
+const MAX_RATE_CHANGE: f64 = 0.005;
+// Seconds it should take to absorb a drift by resampling.
+const RESAMPLE_SECONDS: f64 = 2.0;
+
+// How drift is corrected. The 60 Hz emulation follows the clock it is
+// synced to, the audio device's or the display's, and the other one is
+// brought back in line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Resync {
+    // Only measure.
+    Off,
+    // Emulation follows the display, the audio is played slightly faster
+    // or slower until it catches up. Nothing is skipped or repeated.
+    #[default]
+    Resample,
+    // Emulation follows the audio: an extra frame is emulated or one is
+    // shown twice, a visible hitch but the beeps stay exact.
+    DuplicateFrames,
+}
+
+impl FromStr for Resync {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "off" => Ok(Resync::Off),
+            "resample" => Ok(Resync::Resample),
+            "duplicate" => Ok(Resync::DuplicateFrames),
+            _ => Err(format!(
+                "unknown resync {:?}, expected off, resample or duplicate",
+                text
+            )),
+        }
+    }
+}
+
+// What the frontend has to do about the drift before its next frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Correction {
+    None,
+    // Generate this many samples for every sample's worth of emulated
+    // time, e.g. 1.002.
+    Resample(f64),
+    // Show the last frame again without emulating one.
+    DuplicateFrame,
+    // Emulate an extra frame before showing one.
+    SkipFrame,
+}
+
+// Drift of the audio and render clocks against the 60 Hz one, in seconds.
+// Positive means the clock is ahead of the emulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SyncStats {
+    pub audio_drift: f64,
+    pub render_drift: f64,
+    // The current resampling ratio, 1 when not resampling.
+    pub rate: f64,
+    pub duplicated_frames: u64,
+    pub skipped_frames: u64,
+}
+
+impl fmt::Display for SyncStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "audio {:+.1} ms, video {:+.1} ms, rate {:.4}, {} duplicated, {} skipped",
+            self.audio_drift * 1000.0,
+            self.render_drift * 1000.0,
+            self.rate,
+            self.duplicated_frames,
+            self.skipped_frames
+        )
+    }
+}
+
+// Keeps count of the three clocks of a session: frames emulated (the 60 Hz
+// timers), audio samples the device played and time spent presenting.
+// Each runs off its own crystal, over an evening the differences add up to
+// beeps that come a noticeable moment after what they belong to.
+#[derive(Debug, Clone)]
+pub struct SyncMonitor {
+    resync: Resync,
+    sample_rate: f64,
+    frames: u64,
+    // Emulated time the played samples covered, they were generated at the
+    // rate at the time.
+    audio: Option<f64>,
+    rendered: Option<Duration>,
+    stats: SyncStats,
+}
+
+impl SyncMonitor {
+    pub fn new(sample_rate: u32, resync: Resync) -> Self {
+        SyncMonitor {
+            resync,
+            sample_rate: sample_rate.max(1) as f64,
+            frames: 0,
+            audio: None,
+            rendered: None,
+            stats: SyncStats {
+                rate: 1.0,
+                ..SyncStats::default()
+            },
+        }
+    }
+
+    pub fn frame_emulated(&mut self) {
+        self.frames += 1;
+    }
+
+    // From the audio callback, samples it was asked for.
+    pub fn audio_played(&mut self, samples: usize) {
+        let seconds = samples as f64 / (self.sample_rate * self.stats.rate);
+        *self.audio.get_or_insert(0.0) += seconds;
+    }
+
+    // When the latest frame was presented, counted from the first one.
+    pub fn presented(&mut self, since_first: Duration) {
+        self.rendered = Some(since_first);
+    }
+
+    pub fn stats(&self) -> SyncStats {
+        let emulated = self.frames as f64 / FRAMES_PER_SECOND;
+        SyncStats {
+            audio_drift: self.audio.map_or(0.0, |audio| audio - emulated),
+            render_drift: self.rendered.map_or(0.0, |rendered| {
+                let shown = self.frames.saturating_sub(1) as f64 / FRAMES_PER_SECOND;
+                rendered.as_secs_f64() - shown
+            }),
+            ..self.stats
+        }
+    }
+
+    // Call once per presented frame, and apply the result.
+    pub fn correction(&mut self) -> Correction {
+        let stats = self.stats();
+        match self.resync {
+            Resync::Off => Correction::None,
+            Resync::Resample => {
+                // A device ahead of the emulation gets its audio stretched,
+                // one that lags behind gets it squeezed.
+                let change =
+                    (stats.audio_drift / RESAMPLE_SECONDS).clamp(-MAX_RATE_CHANGE, MAX_RATE_CHANGE);
+                let rate = match stats.audio_drift.abs() > TOLERANCE / 2.0 {
+                    true => 1.0 + change,
+                    false => 1.0,
+                };
+                self.stats.rate = rate;
+                match rate == 1.0 {
+                    true => Correction::None,
+                    false => Correction::Resample(rate),
+                }
+            }
+            Resync::DuplicateFrames => {
+                // Whichever clock paces the frontend, the audio is what the
+                // emulation follows.
+                let drift = match self.audio {
+                    Some(_) => stats.audio_drift,
+                    None => stats.render_drift,
+                };
+                if drift > TOLERANCE {
+                    self.stats.skipped_frames += 1;
+                    Correction::SkipFrame
+                } else if drift < -TOLERANCE {
+                    self.stats.duplicated_frames += 1;
+                    Correction::DuplicateFrame
+                } else {
+                    Correction::None
+                }
+            }
+        }
+    }
+}
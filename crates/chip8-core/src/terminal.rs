@@ -0,0 +1,293 @@
+use std::str::FromStr;
+
+use crate::display::{Display, Palette};
+use crate::render::{fill_scaled, Rotation, Turned};
+
+// How the screen is drawn with characters, for terminals and text files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextStyle {
+    // `#` and space, one character per pixel. Works everywhere.
+    #[default]
+    Ascii,
+    // Half block characters, two pixel rows per line, so the screen keeps
+    // its shape with the usual 1:2 character cells.
+    Blocks,
+    // Braille patterns, 2x4 pixels per character: the 128x64 SUPER-CHIP
+    // screen fits in 64x16, inside an 80x24 terminal.
+    Braille,
+}
+
+impl FromStr for TextStyle {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "ascii" => Ok(TextStyle::Ascii),
+            "blocks" => Ok(TextStyle::Blocks),
+            "braille" => Ok(TextStyle::Braille),
+            _ => Err(format!(
+                "unknown text style {:?}, expected ascii, blocks or braille",
+                text
+            )),
+        }
+    }
+}
+
+impl TextStyle {
+    // Lines and columns the display takes up.
+    pub fn size(self, display: &Display) -> (usize, usize) {
+        self.size_of(display.width(), display.height())
+    }
+
+    fn size_of(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            TextStyle::Ascii => (width, height),
+            TextStyle::Blocks => (width, height.div_ceil(2)),
+            TextStyle::Braille => (width.div_ceil(2), height.div_ceil(4)),
+        }
+    }
+
+    // The screen as lines of text, each ending in a newline.
+    pub fn render(self, display: &Display) -> String {
+        self.render_rotated(display, Rotation::None)
+    }
+
+    // Like `render`, with the picture turned.
+    pub fn render_rotated(self, display: &Display, rotation: Rotation) -> String {
+        self.render_scaled(display, rotation, 1)
+    }
+
+    // Like `render_rotated`, every pixel `scale` characters or half blocks
+    // wide and tall.
+    pub fn render_scaled(self, display: &Display, rotation: Rotation, scale: usize) -> String {
+        let display = &Turned::scaled(display, rotation, scale);
+        let (columns, lines) = self.size_of(display.width(), display.height());
+        let mut text = String::with_capacity((columns + 1) * lines * 3);
+        for line in 0..lines {
+            for x in 0..columns {
+                text.push(match self {
+                    TextStyle::Ascii => ascii(display.pixel(x, line)),
+                    TextStyle::Blocks => block(
+                        display.pixel(x, line * 2),
+                        line * 2 + 1 < display.height() && display.pixel(x, line * 2 + 1),
+                    ),
+                    TextStyle::Braille => braille(display, x * 2, line * 4),
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn ascii(on: bool) -> char {
+    if on {
+        '#'
+    } else {
+        ' '
+    }
+}
+
+fn block(top: bool, bottom: bool) -> char {
+    match (top, bottom) {
+        (false, false) => ' ',
+        (true, false) => '▀',
+        (false, true) => '▄',
+        (true, true) => '█',
+    }
+}
+
+// Dot numbers of a Braille cell by position, as bits of U+2800's offset.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+fn braille(display: &Turned, left: usize, top: usize) -> char {
+    let mut dots = 0;
+    for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+        for (dx, bit) in row.iter().enumerate() {
+            let (x, y) = (left + dx, top + dy);
+            if x < display.width() && y < display.height() && display.pixel(x, y) {
+                dots |= bit;
+            }
+        }
+    }
+    char::from_u32(0x2800 + dots).unwrap()
+}
+
+// Terminal protocols that draw real pixels instead of characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Graphics {
+    Sixel,
+    // The Kitty graphics protocol, also spoken by WezTerm and Ghostty.
+    Kitty,
+}
+
+impl Graphics {
+    // Guesses from the environment, `var` being `std::env::var` or a stand
+    // in. Terminals can be queried instead, but that needs a reply read back
+    // from the input and not all of them answer.
+    pub fn detect(var: impl Fn(&str) -> Option<String>) -> Option<Graphics> {
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        if var("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || ["WezTerm", "ghostty"].contains(&program.as_str())
+        {
+            Some(Graphics::Kitty)
+        } else if term.contains("sixel")
+            || ["mlterm", "foot", "yaft-256color"].contains(&term.as_str())
+            || program == "iTerm.app"
+        {
+            Some(Graphics::Sixel)
+        } else {
+            None
+        }
+    }
+
+    // The display scaled up by `scale`, as an escape sequence drawn at the
+    // cursor. Each call replaces the previous image.
+    pub fn render(self, display: &Display, palette: &Palette, scale: usize) -> String {
+        self.render_rotated(display, palette, scale, Rotation::None)
+    }
+
+    // Like `render`, with the picture turned.
+    pub fn render_rotated(
+        self,
+        display: &Display,
+        palette: &Palette,
+        scale: usize,
+        rotation: Rotation,
+    ) -> String {
+        let display = &Turned::new(display, rotation);
+        match self {
+            Graphics::Sixel => sixel(display, palette, scale),
+            Graphics::Kitty => kitty(display, palette, scale),
+        }
+    }
+}
+
+impl FromStr for Graphics {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "sixel" => Ok(Graphics::Sixel),
+            "kitty" => Ok(Graphics::Kitty),
+            _ => Err(format!(
+                "unknown graphics protocol {:?}, expected sixel or kitty",
+                text
+            )),
+        }
+    }
+}
+
+// Two colour registers, then bands of six pixel rows. In a band every
+// column is one character holding six bits, runs of the same character
+// compress as `!count`.
+fn sixel(display: &Turned, palette: &Palette, scale: usize) -> String {
+    let scale = scale.max(1);
+    let (width, height) = (display.width() * scale, display.height() * scale);
+    let lit = |x: usize, y: usize| y < height && display.pixel(x / scale, y / scale);
+    let mut text = String::from("\x1bPq");
+    text.push_str(&format!("\"1;1;{};{}", width, height));
+    for (register, color) in [palette.background, palette.foreground].iter().enumerate() {
+        let [r, g, b, _] = color.map(|channel| channel as u32 * 100 / 255);
+        text.push_str(&format!("#{};2;{};{};{}", register, r, g, b));
+    }
+    for band in (0..height).step_by(6) {
+        for register in 0..2 {
+            text.push_str(&format!("#{}", register));
+            let columns = (0..width).map(|x| {
+                let bits = (0..6).fold(0, |bits, dy| {
+                    let on = band + dy < height && lit(x, band + dy) == (register == 1);
+                    bits | (on as u8) << dy
+                });
+                (63 + bits) as char
+            });
+            push_runs(&mut text, columns);
+            // Back to the start of the band for the other colour.
+            text.push(if register == 0 { '$' } else { '-' });
+        }
+    }
+    text.push_str("\x1b\\");
+    text
+}
+
+fn push_runs(text: &mut String, columns: impl Iterator<Item = char>) {
+    let mut run: Option<(char, usize)> = None;
+    let flush = |text: &mut String, (c, count): (char, usize)| match count {
+        1..=3 => text.extend(std::iter::repeat_n(c, count)),
+        _ => text.push_str(&format!("!{}{}", count, c)),
+    };
+    for c in columns {
+        run = match run {
+            Some((last, count)) if last == c => Some((c, count + 1)),
+            Some(done) => {
+                flush(text, done);
+                Some((c, 1))
+            }
+            None => Some((c, 1)),
+        };
+    }
+    if let Some(done) = run {
+        flush(text, done);
+    }
+}
+
+// RGBA pixels in base64, sent in chunks of at most 4096 bytes. Image id 1
+// is reused, so every frame replaces the last one.
+fn kitty(display: &Turned, palette: &Palette, scale: usize) -> String {
+    let scale = scale.max(1);
+    let (width, height) = (display.width() * scale, display.height() * scale);
+    let mut rgba = vec![0; width * height * 4];
+    fill_scaled(
+        &mut rgba,
+        display.width(),
+        display.height(),
+        scale,
+        |x, y| {
+            if display.pixel(x, y) {
+                palette.foreground
+            } else {
+                palette.background
+            }
+        },
+    );
+    let encoded = base64(&rgba);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+    let mut text = String::with_capacity(encoded.len() + chunks.len() * 16);
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = (index + 1 < chunks.len()) as u8;
+        if index == 0 {
+            text.push_str(&format!(
+                "\x1b_Ga=T,f=32,i=1,q=2,C=1,s={},v={},m={};",
+                width, height, more
+            ));
+        } else {
+            text.push_str(&format!("\x1b_Gm={};", more));
+        }
+        text.push_str(std::str::from_utf8(chunk).unwrap());
+        text.push_str("\x1b\\");
+    }
+    text
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(ALPHABET[(bits >> (18 - index * 6) & 0x3F) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
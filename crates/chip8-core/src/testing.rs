@@ -0,0 +1,179 @@
+use crate::ocr;
+use crate::{run_frame, Emulator, EmulatorBuilder};
+
+// Frames a key stays down for `Harness::tap`, long enough for ROMs that
+// only read the keypad every few frames.
+pub const TAP_FRAMES: u32 = 4;
+
+// For ROM developers testing their own programs from Rust, with `cargo
+// test` in the ROM's repository:
+//
+//     let mut game = Harness::new(include_bytes!("../game.ch8"));
+//     game.run_until(600, |emulator| ocr::screen_shows(emulator, "PRESS 5"));
+//     game.tap(0x5).run_frames(30);
+//     game.assert_register(0xA, 3);
+//     game.assert_pixel(0, 0, true);
+//
+// The assertions panic with what differs, at the caller's line, like
+// `assert_eq!`. Runs are seeded, the same test always sees the same random
+// numbers.
+pub struct Harness {
+    pub emulator: Emulator,
+    // Frames run so far, for failure messages.
+    frames: u64,
+}
+
+impl Harness {
+    // `rom` on the original CHIP-8, see `with` for other variants or
+    // quirks.
+    #[track_caller]
+    pub fn new(rom: &[u8]) -> Self {
+        Harness::with(EmulatorBuilder::new().rom(rom))
+    }
+
+    #[track_caller]
+    pub fn with(builder: EmulatorBuilder) -> Self {
+        match builder.seed(0).build() {
+            Ok(emulator) => Harness {
+                emulator,
+                frames: 0,
+            },
+            Err(error) => panic!("the ROM did not load: {}", error),
+        }
+    }
+
+    // Panics if the ROM stops with an error.
+    #[track_caller]
+    pub fn run_frames(&mut self, frames: u32) -> &mut Self {
+        for _ in 0..frames {
+            self.frame();
+        }
+        self
+    }
+
+    // Runs until `done` holds after a frame, and returns the frames that
+    // took. Panics if it still does not after `max_frames`.
+    #[track_caller]
+    pub fn run_until(&mut self, max_frames: u32, done: impl Fn(&Emulator) -> bool) -> u32 {
+        for frame in 1..=max_frames {
+            self.frame();
+            if done(&self.emulator) {
+                return frame;
+            }
+        }
+        panic!("still waiting after {} frames", max_frames);
+    }
+
+    pub fn press(&mut self, key: u8) -> &mut Self {
+        self.emulator.keypad.press(key);
+        self
+    }
+
+    pub fn release(&mut self, key: u8) -> &mut Self {
+        self.emulator.keypad.release(key);
+        self
+    }
+
+    // Presses `key` for `TAP_FRAMES` frames and lets it go.
+    #[track_caller]
+    pub fn tap(&mut self, key: u8) -> &mut Self {
+        self.press(key).run_frames(TAP_FRAMES).release(key)
+    }
+
+    #[track_caller]
+    pub fn assert_register(&self, x: usize, expected: u8) {
+        let actual = self.emulator.v_registers[x];
+        if actual != expected {
+            let hex = |value| format!("{:#04x}", value);
+            self.fail(&format!("V{:X}", x), hex(expected), hex(actual));
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_i(&self, expected: u16) {
+        let actual = self.emulator.i_register;
+        if actual != expected {
+            self.fail(
+                "I",
+                format!("{:#05x}", expected),
+                format!("{:#05x}", actual),
+            );
+        }
+    }
+
+    // The bytes from `address` on.
+    #[track_caller]
+    pub fn assert_memory(&self, address: u16, expected: &[u8]) {
+        let start = address as usize;
+        let actual = self.emulator.ram.get(start..start + expected.len());
+        if actual != Some(expected) {
+            let actual = actual.map_or("past the end of memory".to_string(), hex);
+            self.fail(
+                &format!("memory at {:#05x}", address),
+                hex(expected),
+                actual,
+            );
+        }
+    }
+
+    #[track_caller]
+    pub fn assert_pixel(&self, x: usize, y: usize, on: bool) {
+        let actual = self.emulator.display.pixel(x, y);
+        if actual != on {
+            let name = |on| if on { "on" } else { "off" };
+            let (on, actual) = (name(on).to_string(), name(actual).to_string());
+            self.fail(&format!("pixel ({}, {})", x, y), on, actual);
+        }
+    }
+
+    // `expected` is `screen` as it should be, surrounding whitespace and
+    // the indentation of its lines ignored so it can be written inline.
+    #[track_caller]
+    pub fn assert_screen(&self, expected: &str) {
+        let expected: Vec<&str> = expected.trim().lines().map(str::trim).collect();
+        let actual = self.screen();
+        if actual.lines().ne(expected.iter().copied()) {
+            panic!(
+                "the screen differs after {} frames, expected:\n{}\nfound:\n{}",
+                self.frames,
+                expected.join("\n"),
+                actual
+            );
+        }
+    }
+
+    // The screen as text, `#` for a pixel on and `.` off.
+    pub fn screen(&self) -> String {
+        let mut text = String::new();
+        for row in self.emulator.display.iter_rows() {
+            text.extend(row.iter().map(|on| if on { '#' } else { '.' }));
+            text.push('\n');
+        }
+        text
+    }
+
+    // Whether the ROM wrote `text` with its font, see `ocr`.
+    pub fn screen_shows(&self, text: &str) -> bool {
+        ocr::screen_shows(&self.emulator, text)
+    }
+
+    #[track_caller]
+    fn frame(&mut self) {
+        self.frames += 1;
+        if let Some(error) = run_frame(&mut self.emulator).error {
+            panic!("the ROM stopped at frame {}: {}", self.frames, error);
+        }
+    }
+
+    #[track_caller]
+    fn fail(&self, what: &str, expected: String, actual: String) {
+        panic!(
+            "{} after {} frames: expected {}, found {}",
+            what, self.frames, expected, actual
+        );
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
@@ -0,0 +1,145 @@
+use crate::emulator::{load_rom_to_memory, step, Emulator, INITIAL_ADDRESS};
+
+// Data blobs are copied here, after any code a test could reasonably need.
+pub const DATA_ADDRESS: u16 = 0xD00;
+// Register dumps written by `expect_registers` start here, 16 bytes each.
+pub const RESULTS_ADDRESS: u16 = 0xE00;
+const MAX_STEPS: usize = 100_000;
+
+#[derive(Debug, Clone)]
+struct Check {
+    address: u16,
+    expected: u8,
+    what: String,
+}
+
+// Builds tiny ROMs out of raw opcodes. Assertions are part of the program:
+// `expect_registers` emits code that dumps V0-VF to memory at that point,
+// and `run` compares the dumps once the ROM reaches its final halt loop, so
+// every instruction goes through the real fetch, decode and execute path.
+#[derive(Debug, Clone, Default)]
+pub struct TestRom {
+    code: Vec<u16>,
+    data: Vec<u8>,
+    checks: Vec<Check>,
+    dumps: u16,
+}
+
+impl TestRom {
+    pub fn new() -> Self {
+        TestRom::default()
+    }
+
+    pub fn op(mut self, op_code: u16) -> Self {
+        self.code.push(op_code);
+        self
+    }
+
+    pub fn ops(mut self, op_codes: &[u16]) -> Self {
+        self.code.extend_from_slice(op_codes);
+        self
+    }
+
+    // Appends bytes to the data area, the first call's bytes start at
+    // `DATA_ADDRESS`.
+    pub fn data(mut self, bytes: &[u8]) -> Self {
+        self.data.extend_from_slice(bytes);
+        self
+    }
+
+    // Address the next instruction will be at, handy for jumps.
+    pub fn next_address(&self) -> u16 {
+        INITIAL_ADDRESS + 2 * self.code.len() as u16
+    }
+
+    // Dumps all registers (overwriting I) and checks the given ones hold
+    // the expected values at this point of the program.
+    pub fn expect_registers(mut self, expected: &[(usize, u8)]) -> Self {
+        let dump = RESULTS_ADDRESS + 16 * self.dumps;
+        self.dumps += 1;
+        self.code.push(0xA000 | dump);
+        self.code.push(0xFF55);
+        for &(register, value) in expected {
+            self.checks.push(Check {
+                address: dump + register as u16,
+                expected: value,
+                what: format!("V{:X} at {:#05x}", register, self.next_address() - 4),
+            });
+        }
+        self
+    }
+
+    // Checks a memory byte once the program halts.
+    pub fn expect_memory(mut self, address: u16, value: u8) -> Self {
+        self.checks.push(Check {
+            address,
+            expected: value,
+            what: format!("memory at {:#05x}", address),
+        });
+        self
+    }
+
+    // The `JP` to itself the program ends with.
+    pub fn halt_address(&self) -> u16 {
+        self.next_address()
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let halt = self.halt_address();
+        let mut rom: Vec<u8> = self
+            .code
+            .iter()
+            .chain(Some(&(0x1000 | halt)))
+            .flat_map(|op_code| op_code.to_be_bytes())
+            .collect();
+        assert!(
+            INITIAL_ADDRESS as usize + rom.len() <= DATA_ADDRESS as usize,
+            "test ROM code runs into the data area"
+        );
+        if !self.data.is_empty() {
+            rom.resize((DATA_ADDRESS - INITIAL_ADDRESS) as usize, 0);
+            rom.extend_from_slice(&self.data);
+        }
+        assert!(
+            INITIAL_ADDRESS as usize + rom.len() <= RESULTS_ADDRESS as usize,
+            "test ROM data runs into the results area"
+        );
+        rom
+    }
+
+    // Loads the ROM, runs it until it halts and checks every expectation.
+    pub fn run(&self, emulator: &mut Emulator) -> Result<(), String> {
+        load_rom_to_memory(emulator, &self.build()).map_err(|error| error.to_string())?;
+        let halt = self.halt_address();
+        let mut steps = 0;
+        while emulator.program_counter != halt {
+            if steps == MAX_STEPS {
+                return Err(format!("did not halt after {} steps", MAX_STEPS));
+            }
+            // Frames as `run_frame` would run them, for the display wait.
+            emulator.vblank = steps % emulator.cycles_per_frame == 0;
+            step(emulator)
+                .map_err(|error| format!("{} at {:#05x}", error, emulator.program_counter - 2))?;
+            steps += 1;
+        }
+
+        let failures: Vec<String> = self
+            .checks
+            .iter()
+            .filter_map(|check| {
+                let actual = emulator.ram[check.address as usize];
+                (actual != check.expected).then(|| {
+                    format!(
+                        "{}: expected {:#04x}, found {:#04x}",
+                        check.what, check.expected, actual
+                    )
+                })
+            })
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("\n"))
+        }
+    }
+}
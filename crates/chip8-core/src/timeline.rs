@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+
+// zstd with the `compression` feature, the run-length codec without it.
+#[cfg(not(feature = "compression"))]
+use crate::compress::{
+    pack, pack_delta, unpack as unpack_packed, unpack_delta as unpack_packed_delta,
+};
+#[cfg(feature = "compression")]
+use crate::compress::{
+    zstd_pack as pack, zstd_pack_delta as pack_delta, zstd_unpack as unpack_packed,
+    zstd_unpack_delta as unpack_packed_delta,
+};
+use crate::display::Display;
+use crate::savestate;
+use crate::Emulator;
+
+pub const THUMBNAIL_WIDTH: usize = 32;
+pub const THUMBNAIL_HEIGHT: usize = 16;
+
+// A small, lossy picture of the screen: a thumbnail pixel is lit when any
+// display pixel it covers is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    pixels: Vec<bool>,
+}
+
+impl Thumbnail {
+    pub fn of(display: &Display) -> Self {
+        let scale = display.width() / THUMBNAIL_WIDTH;
+        let mut pixels = vec![false; THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT];
+        for y in 0..display.height() {
+            for x in 0..display.width() {
+                if display.pixel(x, y) {
+                    pixels[y / scale * THUMBNAIL_WIDTH + x / scale] = true;
+                }
+            }
+        }
+        Thumbnail { pixels }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * THUMBNAIL_WIDTH + x]
+    }
+}
+
+// One point of the timeline, with the whole machine so the session can be
+// rewound to it, see `Timeline::state`.
+#[derive(Debug, Clone)]
+pub struct Moment {
+    pub frame: u64,
+    pub thumbnail: Thumbnail,
+    // The savestate, packed on its own when `key` and against the moment
+    // before otherwise, see `compress`.
+    state: Vec<u8>,
+    key: bool,
+}
+
+// Packed moments in a row between two packed on their own, so going back
+// to one unpacks at most this many.
+const KEY_INTERVAL: usize = 32;
+
+// Snapshots of the session every `interval` frames, the oldest dropped once
+// `capacity` is reached. The thumbnails are what a timeline UI scrubs
+// through, the states what it jumps back to. A state is mostly what the one
+// before was, so packed as the difference ten seconds of them, one every
+// frame, take about as much memory as four unpacked.
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    interval: u64,
+    capacity: usize,
+    moments: VecDeque<Moment>,
+    // The newest state unpacked, for the next to be packed against.
+    latest: Vec<u8>,
+}
+
+impl Timeline {
+    pub fn new(interval: u64, capacity: usize) -> Self {
+        Timeline {
+            interval: interval.max(1),
+            capacity: capacity.max(1),
+            moments: VecDeque::new(),
+            latest: Vec::new(),
+        }
+    }
+
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    pub fn len(&self) -> usize {
+        self.moments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moments.is_empty()
+    }
+
+    pub fn moments(&self) -> impl Iterator<Item = &Moment> {
+        self.moments.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Moment> {
+        self.moments.get(index)
+    }
+
+    // Call once per frame, only every `interval`th frame is kept.
+    pub fn record(&mut self, frame: u64, emulator: &Emulator) {
+        if !frame.is_multiple_of(self.interval) {
+            return;
+        }
+        if self.moments.len() == self.capacity {
+            self.drop_oldest();
+        }
+        let state = savestate::save_plain(emulator);
+        let since_key = self.moments.iter().rev().take_while(|moment| !moment.key);
+        let key = self.moments.is_empty() || since_key.count() + 1 == KEY_INTERVAL;
+        self.moments.push_back(Moment {
+            frame,
+            thumbnail: Thumbnail::of(&emulator.display),
+            state: match key {
+                true => pack(&state),
+                false => pack_delta(&self.latest, &state),
+            },
+            key,
+        });
+        self.latest = state;
+    }
+
+    // The machine at moment `index`.
+    pub fn state(&self, index: usize) -> Option<Emulator> {
+        let state = self.unpack(index)?;
+        Some(savestate::load_plain(&state).expect("the timeline's own savestate"))
+    }
+
+    // Bytes the states take, as kept.
+    pub fn state_bytes(&self) -> usize {
+        self.moments.iter().map(|moment| moment.state.len()).sum()
+    }
+
+    // Index of the latest moment at or before `frame`.
+    pub fn seek(&self, frame: u64) -> Option<usize> {
+        self.moments
+            .iter()
+            .rposition(|moment| moment.frame <= frame)
+    }
+
+    // Puts the emulator back to moment `index` and forgets everything after
+    // it, since the session branches from there. Returns the frame it
+    // resumes at.
+    pub fn rewind(&mut self, index: usize, emulator: &mut Emulator) -> Option<u64> {
+        let frame = self.moments.get(index)?.frame;
+        let state = self.unpack(index)?;
+        *emulator = savestate::load_plain(&state).expect("the timeline's own savestate");
+        self.moments.truncate(index + 1);
+        self.latest = state;
+        Some(frame)
+    }
+
+    fn unpack(&self, index: usize) -> Option<Vec<u8>> {
+        self.moments.get(index)?;
+        let key = (0..=index).rev().find(|&i| self.moments[i].key)?;
+        let mut state = unpack(&self.moments[key].state);
+        for moment in self.moments.range(key + 1..=index) {
+            state = unpack_delta(&state, &moment.state);
+        }
+        Some(state)
+    }
+
+    // The oldest moment goes, the one after it packed on its own in its
+    // place when it was packed against it.
+    fn drop_oldest(&mut self) {
+        let Some(oldest) = self.moments.pop_front() else {
+            return;
+        };
+        if let Some(next) = self.moments.front_mut().filter(|next| !next.key) {
+            let state = unpack(&oldest.state);
+            let state = unpack_delta(&state, &next.state);
+            next.state = pack(&state);
+            next.key = true;
+        }
+    }
+}
+
+fn unpack(state: &[u8]) -> Vec<u8> {
+    unpack_packed(state).expect("the timeline's own savestate")
+}
+
+fn unpack_delta(base: &[u8], state: &[u8]) -> Vec<u8> {
+    unpack_packed_delta(base, state).expect("the timeline's own delta")
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::events::Event;
+use crate::rom::rom_hash;
+
+// Window title showing the game, its speed and whether it is paused, e.g.
+// "pong - 200% - paused - chip8-rs".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowTitle {
+    rom_name: Option<String>,
+    speed_percent: u32,
+    paused: bool,
+}
+
+impl WindowTitle {
+    pub fn new() -> Self {
+        WindowTitle {
+            rom_name: None,
+            speed_percent: 100,
+            paused: false,
+        }
+    }
+
+    // Updates the title from an event, returns true when the window needs
+    // to be retitled.
+    pub fn handle(&mut self, event: &Event) -> bool {
+        let before = self.clone();
+        match event {
+            Event::RomLoaded { name } => self.rom_name = Some(name.clone()),
+            Event::SpeedChanged { percent } => self.speed_percent = *percent,
+            Event::Paused => self.paused = true,
+            Event::Resumed => self.paused = false,
+            Event::TriggerFired { .. } => (),
+        }
+        *self != before
+    }
+}
+
+impl Default for WindowTitle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for WindowTitle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = &self.rom_name {
+            write!(f, "{} - ", name)?;
+        }
+        if self.speed_percent != 100 {
+            write!(f, "{}% - ", self.speed_percent)?;
+        }
+        if self.paused {
+            write!(f, "paused - ")?;
+        }
+        write!(f, "chip8-rs")
+    }
+}
+
+// Name to show for a ROM file, its file name without the extension.
+pub fn rom_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+// Proper names of ROMs recognised by `rom_hash`, for places where the file
+// name is not good enough, like Discord.
+const KNOWN_TITLES: &[(u64, &str)] = &[(0x9495_733f_6062_4ee6, "Pong (1 player)")];
+
+// The ROM's proper name if it is a known one, its file name otherwise.
+pub fn rom_title(data: &[u8], path: &Path) -> String {
+    let hash = rom_hash(data);
+    match KNOWN_TITLES.iter().find(|(known, _)| *known == hash) {
+        Some((_, title)) => title.to_string(),
+        None => rom_name(path),
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::VecDeque;
+
+use crate::labels::Labels;
+use crate::{get_op_code, Emulator, Instruction};
+
+// The last `capacity` instructions executed, as address and opcode, for
+// post-mortems and backtraces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    capacity: usize,
+    entries: VecDeque<(u16, u16)>,
+}
+
+impl Trace {
+    pub fn new(capacity: usize) -> Self {
+        Trace {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    // Call before executing the instruction at PC.
+    pub fn record(&mut self, emulator: &Emulator) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries
+            .push_back((emulator.program_counter, get_op_code(emulator)));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    // "0x200  6A02  LD VA, 0x02" lines, oldest first.
+    pub fn to_text(&self) -> String {
+        self.to_text_labeled(&Labels::new())
+    }
+
+    // With the labels of the addresses shown or used, see `Labels::annotate`.
+    pub fn to_text_labeled(&self, labels: &Labels) -> String {
+        let mut text = String::new();
+        for (address, op_code) in self.iter() {
+            let instruction = Instruction::decode(op_code);
+            text.push_str(&format!(
+                "{:#05x}  {:04X}  {}{}\n",
+                address,
+                op_code,
+                instruction,
+                labels.annotate(address, instruction)
+            ));
+        }
+        text
+    }
+}
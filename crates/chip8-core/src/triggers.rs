@@ -0,0 +1,291 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::labels::Labels;
+use crate::Emulator;
+
+// Something a condition can read from the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Ram(u16),
+    Register(u8),
+    I,
+    ProgramCounter,
+    DelayTimer,
+    SoundTimer,
+}
+
+impl Target {
+    pub fn read(self, emulator: &Emulator) -> u16 {
+        match self {
+            Target::Ram(address) => emulator.ram[address as usize % emulator.ram.len()] as u16,
+            Target::Register(x) => emulator.v_registers[x as usize] as u16,
+            Target::I => emulator.i_register,
+            Target::ProgramCounter => emulator.program_counter,
+            Target::DelayTimer => emulator.delay_timer_registry as u16,
+            Target::SoundTimer => emulator.sound_timer_registry as u16,
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Ram(address) => write!(f, "RAM[{:#05x}]", address),
+            Target::Register(x) => write!(f, "V{:X}", x),
+            Target::I => write!(f, "I"),
+            Target::ProgramCounter => write!(f, "PC"),
+            Target::DelayTimer => write!(f, "DT"),
+            Target::SoundTimer => write!(f, "ST"),
+        }
+    }
+}
+
+impl Target {
+    // `RAM[name]` also takes one of `labels`.
+    pub fn parse_with(text: &str, labels: &Labels) -> Result<Self, String> {
+        let text = text.trim();
+        let address = text
+            .get(..4)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("RAM["))
+            .and_then(|_| text[4..].strip_suffix(']'))
+            .and_then(|name| labels.address(name.trim()));
+        match address {
+            Some(address) => Ok(Target::Ram(address)),
+            None => text.parse(),
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let upper = text.trim().to_ascii_uppercase();
+        let target = match upper.as_str() {
+            "I" => Target::I,
+            "PC" => Target::ProgramCounter,
+            "DT" => Target::DelayTimer,
+            "ST" => Target::SoundTimer,
+            _ => {
+                if let Some(address) = upper
+                    .strip_prefix("RAM[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                {
+                    // Past the end of a 4K machine's memory it wraps.
+                    Target::Ram(parse_number(address)?)
+                } else if let Some(x) = upper.strip_prefix('V').filter(|x| x.len() == 1) {
+                    Target::Register(
+                        u8::from_str_radix(x, 16)
+                            .map_err(|_| format!("unknown register {}", text))?,
+                    )
+                } else {
+                    return Err(format!(
+                        "unknown target {:?}, expected RAM[addr], V0-VF, I, PC, DT or ST",
+                        text.trim()
+                    ));
+                }
+            }
+        };
+        Ok(target)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    // Longest operators first, so ">=" is not read as ">".
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+
+    fn compare(self, left: u16, right: u16) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        Comparison::OPERATORS
+            .iter()
+            .find(|(_, comparison)| *comparison == self)
+            .map(|(symbol, _)| *symbol)
+            .unwrap()
+    }
+}
+
+// One or more comparisons joined by `&&`, e.g. "RAM[0x3A0] >= 100 && V0 == 1".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    terms: Vec<(Target, Comparison, u16)>,
+}
+
+impl Condition {
+    pub fn holds(&self, emulator: &Emulator) -> bool {
+        self.terms
+            .iter()
+            .all(|&(target, comparison, value)| comparison.compare(target.read(emulator), value))
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms: Vec<String> = self
+            .terms
+            .iter()
+            .map(|(target, comparison, value)| {
+                format!("{} {} {}", target, comparison.symbol(), value)
+            })
+            .collect();
+        write!(f, "{}", terms.join(" && "))
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Condition::parse_with(text, &Labels::new())
+    }
+}
+
+impl Condition {
+    // With `labels` for addresses, e.g. "RAM[ball_x] >= 60".
+    pub fn parse_with(text: &str, labels: &Labels) -> Result<Self, String> {
+        let terms = text
+            .split("&&")
+            .map(|term| {
+                let (index, symbol, comparison) = Comparison::OPERATORS
+                    .iter()
+                    .filter_map(|&(symbol, comparison)| {
+                        term.find(symbol).map(|index| (index, symbol, comparison))
+                    })
+                    .min_by_key(|(index, symbol, _)| (*index, std::cmp::Reverse(symbol.len())))
+                    .ok_or_else(|| format!("{:?} has no comparison", term.trim()))?;
+                let target = Target::parse_with(&term[..index], labels)?;
+                let value = parse_number(&term[index + symbol.len()..])?;
+                Ok((target, comparison, value))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Condition { terms })
+    }
+}
+
+fn parse_number(text: &str) -> Result<u16, String> {
+    let text = text.trim();
+    let parsed = match text
+        .strip_prefix("0x")
+        .or(text.strip_prefix("0X"))
+        .or(text.strip_prefix('#'))
+    {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    parsed.map_err(|_| format!("{:?} is not a number", text))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trigger {
+    pub name: String,
+    pub condition: Condition,
+    pub message: String,
+    fired: bool,
+}
+
+impl Trigger {
+    pub fn new(name: &str, condition: Condition, message: &str) -> Self {
+        Trigger {
+            name: name.to_string(),
+            condition,
+            message: message.to_string(),
+            fired: false,
+        }
+    }
+
+    pub fn fired(&self) -> bool {
+        self.fired
+    }
+}
+
+// A ROM's memory conditions. Each trigger fires once per session, the
+// first time its condition holds, which is what achievements and "the game
+// got to its ending" checks want.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Triggers {
+    triggers: Vec<Trigger>,
+}
+
+impl Triggers {
+    pub fn new() -> Self {
+        Triggers::default()
+    }
+
+    pub fn add(&mut self, trigger: Trigger) {
+        self.triggers.push(trigger);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Trigger> {
+        self.triggers.iter()
+    }
+
+    // Evaluates every trigger that has not fired yet, returns the ones that
+    // fire now.
+    pub fn check(&mut self, emulator: &Emulator) -> Vec<&Trigger> {
+        let mut fired = Vec::new();
+        for trigger in self.triggers.iter_mut() {
+            if !trigger.fired && trigger.condition.holds(emulator) {
+                trigger.fired = true;
+                fired.push(&*trigger);
+            }
+        }
+        fired
+    }
+
+    // Makes every trigger able to fire again, e.g. after a reset.
+    pub fn rearm(&mut self) {
+        for trigger in self.triggers.iter_mut() {
+            trigger.fired = false;
+        }
+    }
+
+    // Reads `trigger.<name> = <condition> | <message>` config entries, the
+    // message defaults to the name. Conditions can use `labels`.
+    pub fn from_config<'a>(
+        entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+        labels: &Labels,
+    ) -> Result<Self, String> {
+        let mut triggers = Triggers::new();
+        for (key, value) in entries {
+            let Some(name) = key.strip_prefix("trigger.") else {
+                continue;
+            };
+            let (condition, message) = value.split_once('|').unwrap_or((value, name));
+            let condition = Condition::parse_with(condition, labels)
+                .map_err(|error| format!("trigger {}: {}", name, error))?;
+            triggers.add(Trigger::new(name, condition, message.trim()));
+        }
+        Ok(triggers)
+    }
+}
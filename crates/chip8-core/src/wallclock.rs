@@ -0,0 +1,59 @@
+use crate::host::Clock;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Another extension of this emulator, for clock ROMs: FxF1 reads the time
+// of day into Vx (seconds), Vx+1 (minutes) and Vx+2 (hours), the register
+// numbers wrapping past VF. Off unless asked for with
+// `EmulatorBuilder::wall_clock`, the opcode fails without it. The core
+// never reads the host clock itself, so runs and replays stay reproducible
+// until a frontend `sync`s it, typically once per frame:
+//
+//     if let Some(clock) = &mut emulator.wall_clock {
+//         clock.sync(&SystemClock);
+//     }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WallClock {
+    // The last time synced, in seconds since the Unix epoch.
+    unix_seconds: u64,
+    // Added to UTC for the time of day register values, e.g. 3600 for CET.
+    utc_offset: i32,
+}
+
+impl WallClock {
+    pub fn new(utc_offset: i32) -> Self {
+        WallClock {
+            unix_seconds: 0,
+            utc_offset,
+        }
+    }
+
+    pub fn sync(&mut self, clock: &dyn Clock) {
+        self.set(clock.unix_seconds());
+    }
+
+    pub fn set(&mut self, unix_seconds: u64) {
+        self.unix_seconds = unix_seconds;
+    }
+
+    pub fn unix_seconds(&self) -> u64 {
+        self.unix_seconds
+    }
+
+    pub fn utc_offset(&self) -> i32 {
+        self.utc_offset
+    }
+
+    // Seconds, minutes and hours of the local time of day, in the order
+    // FxF1 stores them.
+    pub fn time_of_day(&self) -> [u8; 3] {
+        let local = self.unix_seconds as i64 + self.utc_offset as i64;
+        let seconds = local.rem_euclid(SECONDS_PER_DAY as i64);
+        [
+            (seconds % 60) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds / 3600) as u8,
+        ]
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::labels::Labels;
+use crate::triggers::Target;
+use crate::Emulator;
+
+// A value to keep an eye on while a game runs: registers, memory and
+// numbers with `+ - * /` and parentheses, e.g. "RAM[0x3A0]", "V3 - V2" or
+// "WORD[I + 2]". RAM[...] reads a byte and WORD[...] two, big-endian, at
+// any address expression. Everything is 16-bit and wraps, like I.
+// Names from `Labels` stand for their address, "RAM[ball_x]". Evaluating
+// never stops the machine, for that see `triggers`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression {
+    text: String,
+    node: Node,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Number(u16),
+    Target(Target),
+    Byte(Box<Node>),
+    Word(Box<Node>),
+    Binary(Operator, Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Expression {
+    // None when it divides by zero.
+    pub fn eval(&self, emulator: &Emulator) -> Option<u16> {
+        self.node.eval(emulator)
+    }
+}
+
+impl Node {
+    fn eval(&self, emulator: &Emulator) -> Option<u16> {
+        let byte = |address: u16| emulator.ram[address as usize % emulator.ram.len()] as u16;
+        Some(match self {
+            Node::Number(value) => *value,
+            Node::Target(target) => target.read(emulator),
+            Node::Byte(address) => byte(address.eval(emulator)?),
+            Node::Word(address) => {
+                let address = address.eval(emulator)?;
+                byte(address) << 8 | byte(address.wrapping_add(1))
+            }
+            Node::Binary(operator, left, right) => {
+                let (left, right) = (left.eval(emulator)?, right.eval(emulator)?);
+                match operator {
+                    Operator::Add => left.wrapping_add(right),
+                    Operator::Subtract => left.wrapping_sub(right),
+                    Operator::Multiply => left.wrapping_mul(right),
+                    Operator::Divide => left.checked_div(right)?,
+                }
+            }
+        })
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.text)
+    }
+}
+
+impl FromStr for Expression {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Expression::parse_with(text, &Labels::new())
+    }
+}
+
+impl Expression {
+    pub fn parse_with(text: &str, labels: &Labels) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            next: 0,
+            labels,
+        };
+        let node = parser.sum()?;
+        if let Some(token) = parser.tokens.get(parser.next) {
+            return Err(format!("unexpected {:?} in {:?}", token, text.trim()));
+        }
+        Ok(Expression {
+            text: text.trim().to_string(),
+            node,
+        })
+    }
+}
+
+// Words are names and numbers, everything else one character.
+fn tokenize(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '+' | '-' | '*' | '/' | '(' | ')' | '[' | ']' => tokens.push(c.to_string()),
+            c if c.is_ascii_alphanumeric() || c == '#' || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(index, c)) = chars
+                    .peek()
+                    .filter(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    end = index + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(text[start..end].to_string());
+            }
+            _ => return Err(format!("unexpected {:?} in {:?}", c, text.trim())),
+        }
+    }
+    if tokens.is_empty() {
+        return Err("empty expression".to_string());
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<String>,
+    next: usize,
+    labels: &'a Labels,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.next).map(String::as_str)
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        match self.peek() {
+            Some(next) if next == token => {
+                self.next += 1;
+                Ok(())
+            }
+            Some(next) => Err(format!("expected {:?}, found {:?}", token, next)),
+            None => Err(format!("expected {:?} at the end", token)),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Node, String> {
+        let mut node = self.product()?;
+        while let Some(operator) = match self.peek() {
+            Some("+") => Some(Operator::Add),
+            Some("-") => Some(Operator::Subtract),
+            _ => None,
+        } {
+            self.next += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.product()?));
+        }
+        Ok(node)
+    }
+
+    fn product(&mut self) -> Result<Node, String> {
+        let mut node = self.atom()?;
+        while let Some(operator) = match self.peek() {
+            Some("*") => Some(Operator::Multiply),
+            Some("/") => Some(Operator::Divide),
+            _ => None,
+        } {
+            self.next += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.atom()?));
+        }
+        Ok(node)
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        let token = self
+            .peek()
+            .ok_or("expected a value at the end")?
+            .to_string();
+        self.next += 1;
+        if let Some(address) = self.labels.address(&token) {
+            return Ok(Node::Number(address));
+        }
+        let token = token.to_ascii_uppercase();
+        match token.as_str() {
+            "(" => {
+                let node = self.sum()?;
+                self.expect(")")?;
+                Ok(node)
+            }
+            "RAM" | "WORD" => {
+                self.expect("[")?;
+                let address = Box::new(self.sum()?);
+                self.expect("]")?;
+                Ok(match token.as_str() {
+                    "RAM" => Node::Byte(address),
+                    _ => Node::Word(address),
+                })
+            }
+            _ if token.starts_with(|c: char| c.is_ascii_digit() || c == '#') => {
+                let number = match token.strip_prefix("0X").or(token.strip_prefix('#')) {
+                    Some(hex) => u16::from_str_radix(hex, 16),
+                    None => token.parse(),
+                };
+                number
+                    .map(Node::Number)
+                    .map_err(|_| format!("{:?} is not a number", token))
+            }
+            _ => token.parse().map(Node::Target),
+        }
+    }
+}
+
+// The expressions shown in a debugger panel, in the order added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Watches {
+    expressions: Vec<Expression>,
+}
+
+impl Watches {
+    pub fn new() -> Self {
+        Watches::default()
+    }
+
+    pub fn add(&mut self, text: &str) -> Result<(), String> {
+        self.add_with(text, &Labels::new())
+    }
+
+    // Names in `text` are looked up in `labels` now, renaming one later
+    // does not change the expression.
+    pub fn add_with(&mut self, text: &str, labels: &Labels) -> Result<(), String> {
+        self.expressions.push(Expression::parse_with(text, labels)?);
+        Ok(())
+    }
+
+    // By position, from 0.
+    pub fn remove(&mut self, index: usize) -> Option<Expression> {
+        (index < self.expressions.len()).then(|| self.expressions.remove(index))
+    }
+
+    pub fn len(&self) -> usize {
+        self.expressions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expressions.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Expression> {
+        self.expressions.iter()
+    }
+
+    // One line per expression with its value in decimal and hex, lined up.
+    pub fn panel(&self, emulator: &Emulator) -> String {
+        let width = self
+            .expressions
+            .iter()
+            .map(|expression| expression.text.len())
+            .max()
+            .unwrap_or(0);
+        self.expressions
+            .iter()
+            .map(|expression| match expression.eval(emulator) {
+                Some(value) => format!("{:<width$}  {:>5}  {:#06x}\n", expression, value, value),
+                None => format!("{:<width$}  division by zero\n", expression),
+            })
+            .collect()
+    }
+}
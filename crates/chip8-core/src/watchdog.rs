@@ -0,0 +1,89 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::{get_op_code, Emulator};
+
+// Detects ROMs that stopped making progress, e.g. a `JP` to itself with no
+// timers running. Fed once per frame by the headless runner.
+pub struct Watchdog {
+    limit: u32,
+    idle_frames: u32,
+    last_state: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct Stall {
+    pub frames: u32,
+    pub program_counter: u16,
+    pub op_code: u16,
+}
+
+impl fmt::Display for Stall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "no state change for {} frames, PC stuck at {:#05x} (opcode {:#06x})",
+            self.frames, self.program_counter, self.op_code
+        )
+    }
+}
+
+impl Watchdog {
+    pub fn new(limit: u32) -> Self {
+        Watchdog {
+            limit,
+            idle_frames: 0,
+            last_state: None,
+        }
+    }
+
+    pub fn observe(&mut self, emulator: &Emulator) -> Result<(), Stall> {
+        let state = fingerprint(emulator);
+        if self.last_state == Some(state) {
+            self.idle_frames += 1;
+        } else {
+            self.idle_frames = 0;
+            self.last_state = Some(state);
+        }
+
+        if self.idle_frames >= self.limit {
+            return Err(Stall {
+                frames: self.idle_frames,
+                program_counter: emulator.program_counter,
+                op_code: get_op_code(emulator),
+            });
+        }
+        Ok(())
+    }
+}
+
+// The machine state a ROM making progress changes. The keypad clock and
+// when keys went down move on with every instruction, so only the keys and
+// the events still queued count, a ROM waiting on the same keys hashes the
+// same as long as it waits.
+fn fingerprint(emulator: &Emulator) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    emulator.v_registers.hash(&mut hasher);
+    emulator.i_register.hash(&mut hasher);
+    emulator.program_counter.hash(&mut hasher);
+    emulator.stack_pointer.hash(&mut hasher);
+    emulator.stack.hash(&mut hasher);
+    emulator.delay_timer_registry.hash(&mut hasher);
+    emulator.sound_timer_registry.hash(&mut hasher);
+    emulator.ram.hash(&mut hasher);
+    emulator.display.hash(&mut hasher);
+    emulator.rng.hash(&mut hasher);
+    emulator.chip8x.hash(&mut hasher);
+    emulator.xochip.hash(&mut hasher);
+    emulator.printer.hash(&mut hasher);
+    emulator.disk.hash(&mut hasher);
+
+    let keypad = &emulator.keypad;
+    keypad.state().hash(&mut hasher);
+    keypad.is_waiting().hash(&mut hasher);
+    for event in keypad.queued() {
+        (event.key, event.pressed).hash(&mut hasher);
+    }
+    hasher.finish()
+}
@@ -0,0 +1,52 @@
+// XO-CHIP's sound: while the sound timer runs, a 128 bit pattern loaded
+// with F002 plays on repeat, one bit per sample, at a rate set with FX3A.
+pub const PATTERN_SIZE: usize = 16;
+pub const PATTERN_BITS: usize = PATTERN_SIZE * 8;
+// Pitch 64 plays the pattern at 4000 bits per second.
+pub const DEFAULT_PITCH: u8 = 64;
+// FX75 and FX85 reach all sixteen registers, SUPER-CHIP only had eight.
+pub const FLAG_COUNT: usize = 16;
+
+// What XO-CHIP adds to the machine. `Emulator::xochip` is only set for the
+// XO-CHIP variant, its opcodes fail without it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XoChip {
+    // None until the ROM loads one, the plain beep plays until then.
+    pub pattern: Option<[u8; PATTERN_SIZE]>,
+    pub pitch: u8,
+    // Saved with FX75, for high scores and progress. Frontends keep them
+    // between runs of a ROM.
+    pub flags: [u8; FLAG_COUNT],
+}
+
+impl Default for XoChip {
+    fn default() -> Self {
+        XoChip {
+            pattern: None,
+            pitch: DEFAULT_PITCH,
+            flags: [0; FLAG_COUNT],
+        }
+    }
+}
+
+impl XoChip {
+    pub fn new() -> Self {
+        XoChip::default()
+    }
+
+    // Pattern bits per second, 4000 * 2 ^ ((pitch - 64) / 48).
+    pub fn playback_rate(&self) -> f32 {
+        playback_rate(self.pitch)
+    }
+}
+
+pub fn playback_rate(pitch: u8) -> f32 {
+    4000.0 * 2f32.powf((pitch as f32 - 64.0) / 48.0)
+}
+
+// Bit `index` of the pattern, most significant bit of the first byte first.
+pub fn pattern_bit(pattern: &[u8; PATTERN_SIZE], index: usize) -> bool {
+    let index = index % PATTERN_BITS;
+    pattern[index / 8] & (0x80 >> (index % 8)) != 0
+}
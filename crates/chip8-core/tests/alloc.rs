@@ -0,0 +1,128 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use chip8_core::instruction::Variant;
+use chip8_core::{run_frame, EmulatorBuilder, ExecutionHook, Instruction, Quirks};
+
+// Counts the allocations of the current thread, the test harness runs
+// others of its own meanwhile.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations(run: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    run();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn assemble(lines: &[&str]) -> Vec<u8> {
+    lines
+        .iter()
+        .flat_map(|line| line.parse::<Instruction>().unwrap().encode().to_be_bytes())
+        .collect()
+}
+
+// A loop going through every kind of instruction a game uses each frame:
+// arithmetic, calls, draws, BCD, register dumps, timers, keys and random
+// numbers.
+const GAME_LOOP: &[&str] = &[
+    "CLS",
+    "LD V0, 10",
+    "LD V1, 20",
+    "LD I, 0x50",
+    "DRW V0, V1, 5",
+    "ADD V0, 3",
+    "SUB V1, V0",
+    "SHL V1",
+    "XOR V2, V1",
+    "RND V3, 0xFF",
+    "CALL 0x22A",
+    "SKP V3",
+    "LD DT, V0",
+    "LD ST, V0",
+    "LD V4, DT",
+    "LD I, 0x300",
+    "LD B, V3",
+    "LD [I], V4",
+    "LD V4, [I]",
+    "ADD I, V0",
+    "JP 0x208",
+    "SE V0, 0",
+    "RET",
+];
+
+#[test]
+fn frames_do_not_allocate() {
+    let counted = allocations(|| drop(std::hint::black_box(Vec::<u8>::with_capacity(1))));
+    assert_eq!(counted, 1);
+    let rom = assemble(GAME_LOOP);
+    for (variant, quirks) in [
+        (Variant::Chip8, Quirks::default()),
+        (Variant::Chip8, Quirks::none()),
+        (Variant::Schip, Quirks::default()),
+        (Variant::XoChip, Quirks::default()),
+    ] {
+        let mut emulator = EmulatorBuilder::new()
+            .variant(variant)
+            .quirks(quirks)
+            .seed(1)
+            .rom(&rom)
+            .build()
+            .unwrap();
+        let count = allocations(|| {
+            for frame in 0..120 {
+                assert!(run_frame(&mut emulator).error.is_none());
+                emulator.keypad.press(frame % 16);
+                emulator.keypad.release((frame + 8) % 16);
+            }
+        });
+        assert_eq!(count, 0, "{} with quirks {}", variant, quirks);
+    }
+}
+
+struct Draws(usize);
+
+impl ExecutionHook for Draws {
+    fn on_draw(&mut self, _x: usize, _y: usize, _sprite: &[u8], _collision: bool) {
+        self.0 += 1;
+    }
+}
+
+#[test]
+fn hooks_do_not_allocate_either() {
+    let mut emulator = EmulatorBuilder::new()
+        .seed(1)
+        .rom(&assemble(GAME_LOOP))
+        .build()
+        .unwrap();
+    emulator.hooks.add(Draws(0));
+    run_frame(&mut emulator);
+    let count = allocations(|| {
+        for _ in 0..120 {
+            run_frame(&mut emulator);
+        }
+    });
+    assert_eq!(count, 0);
+}
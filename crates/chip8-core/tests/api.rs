@@ -0,0 +1,120 @@
+// The stable API, written the way a frontend uses it. A refactor that
+// renames, moves or changes the signature of any of these stops this file
+// from compiling, which is the point: that is a breaking change.
+use chip8_core::instruction::Variant;
+use chip8_core::testing::Harness;
+use chip8_core::{
+    is_beeping, run_frame, step, tick_timers, Chip8, Display, Emulator, EmulatorBuilder,
+    EmulatorError, FrameOutput, Instruction, Keypad, Quirks,
+};
+
+#[test]
+fn stable_signatures() {
+    let _: fn() -> EmulatorBuilder = EmulatorBuilder::new;
+    let _: fn(EmulatorBuilder, Variant) -> EmulatorBuilder = EmulatorBuilder::variant;
+    let _: fn(EmulatorBuilder, Quirks) -> EmulatorBuilder = EmulatorBuilder::quirks;
+    let _: fn(EmulatorBuilder, u64) -> EmulatorBuilder = EmulatorBuilder::seed;
+    let _: fn(EmulatorBuilder, &[u8]) -> EmulatorBuilder = EmulatorBuilder::rom;
+    let _: fn(EmulatorBuilder) -> Result<Emulator, EmulatorError> = EmulatorBuilder::build;
+
+    let _: fn(&mut Emulator) -> Result<(), EmulatorError> = step;
+    let _: fn(&mut Emulator) -> FrameOutput = run_frame;
+    let _: fn(&mut Emulator) = tick_timers;
+    let _: fn(&Emulator) -> bool = is_beeping;
+
+    let _: fn(&Display) -> usize = Display::width;
+    let _: fn(&Display) -> usize = Display::height;
+    let _: fn(&Display, usize, usize) -> bool = Display::pixel;
+    let _: fn(&Display) -> bool = Display::is_blank;
+
+    let _: fn(EmulatorBuilder) -> Result<Chip8, EmulatorError> = Chip8::new;
+    let _: fn(&mut Chip8, &[u8]) -> Result<(), EmulatorError> = Chip8::load_rom;
+    let _: fn(&mut Chip8) -> Result<(), EmulatorError> = Chip8::step;
+    let _: fn(&mut Chip8) = Chip8::tick_timers;
+    let _: fn(&Chip8) -> &Display = Chip8::framebuffer;
+    let _: fn(&mut Chip8, u8, bool) = Chip8::set_key;
+    let _: fn(&Chip8) -> &Emulator = Chip8::emulator;
+
+    let _: fn(&mut Keypad, u8) = Keypad::press;
+    let _: fn(&mut Keypad, u8) = Keypad::release;
+    let _: fn(&Keypad, u8) -> bool = Keypad::is_pressed;
+
+    let _: fn() -> Quirks = Quirks::chip8;
+    let _: fn() -> Quirks = Quirks::schip;
+    let _: fn() -> Quirks = Quirks::xo_chip;
+
+    let _: fn(u16) -> Instruction = Instruction::decode;
+    let _: fn(Instruction) -> u16 = Instruction::encode;
+
+    let _: fn(&[u8]) -> Harness = Harness::new;
+    let _: fn(EmulatorBuilder) -> Harness = Harness::with;
+    let _: fn(&mut Harness, u32) -> &mut Harness = Harness::run_frames;
+    let _: fn(&mut Harness, u8) -> &mut Harness = Harness::tap;
+    let _: fn(&Harness, usize, u8) = Harness::assert_register;
+    let _: fn(&Harness, usize, usize, bool) = Harness::assert_pixel;
+    let _: fn(&Harness, &str) = Harness::assert_screen;
+}
+
+#[test]
+fn a_frontend_loop() {
+    // CLS; JP 0x202
+    let mut emulator = EmulatorBuilder::new()
+        .variant(Variant::Chip8)
+        .seed(0)
+        .rom(&[0x00, 0xE0, 0x12, 0x02])
+        .build()
+        .unwrap();
+    emulator.keypad.press(0x5);
+    let output: FrameOutput = run_frame(&mut emulator);
+    assert!(output.error.is_none());
+    assert!(emulator.keypad.is_pressed(0x5));
+    assert!(emulator.display.is_blank());
+    // The emulator is non-exhaustive too, built by `EmulatorBuilder` and
+    // destructured with `..`.
+    let Emulator {
+        program_counter,
+        ref keypad,
+        ..
+    } = emulator;
+    assert_eq!(program_counter, 0x202);
+    assert!(keypad.is_pressed(0x5));
+    assert_eq!(Instruction::decode(0x00E0), Instruction::Cls);
+
+    let error = EmulatorBuilder::new().stack_limit(0).build().unwrap_err();
+    // Errors are non-exhaustive, frontends need a catch-all arm.
+    let message = match error {
+        EmulatorError::InvalidConfig(message) => message,
+        other => other.to_string(),
+    };
+    assert!(message.contains("stack limit"));
+}
+
+#[test]
+fn a_chip8_frontend_loop() {
+    let mut chip8 = Chip8::new(EmulatorBuilder::new().quirks(Quirks::schip())).unwrap();
+    // LD V0, 2; LD F, V0; DRW V0, V0, 5; LD DT, V0; JP 0x208
+    chip8
+        .load_rom(&[0x60, 0x02, 0xF0, 0x29, 0xD0, 0x05, 0xF0, 0x15, 0x12, 0x08])
+        .unwrap();
+    for _ in 0..5 {
+        chip8.step().unwrap();
+    }
+    chip8.tick_timers();
+    assert!(chip8.framebuffer().pixel(2, 2));
+    assert_eq!(chip8.emulator().delay_timer_registry, 1);
+    // Keys are queued, the next instructions see them.
+    chip8.set_key(0xA, true);
+    chip8.step().unwrap();
+    assert!(chip8.emulator().keypad.is_pressed(0xA));
+    chip8.set_key(0xA, false);
+    for _ in 0..100 {
+        chip8.step().unwrap();
+    }
+    assert!(!chip8.emulator().keypad.is_pressed(0xA));
+
+    // A new ROM starts on a fresh machine, one too big keeps the old one.
+    chip8.load_rom(&[0x12, 0x00]).unwrap();
+    assert!(chip8.framebuffer().is_blank());
+    assert!(chip8.load_rom(&vec![0; 8192]).is_err());
+    assert_eq!(chip8.emulator().ram[0x200..0x202], [0x12, 0x00]);
+}
@@ -0,0 +1,58 @@
+use chip8_core::assembler::{assemble, Diagnostic};
+
+#[test]
+fn every_bad_line_gets_a_diagnostic() {
+    let source = "CLS\nLDD V0, 1\nLD V0, 300 ; too big\nJP 0x200\nLD VG, 1\n";
+    let diagnostics = assemble(source).unwrap_err();
+    let spans: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.line, diagnostic.column, diagnostic.len))
+        .collect();
+    assert_eq!(spans, [(2, 1, 3), (3, 8, 3), (5, 4, 2)]);
+    assert_eq!(diagnostics[0].help.as_deref(), Some("did you mean LD?"));
+    assert_eq!(diagnostics[1].message, "300 does not fit in a byte");
+    assert_eq!(
+        diagnostics[2].help.as_deref(),
+        Some("registers are V0 to VF")
+    );
+}
+
+#[test]
+fn suggestions_are_for_likely_typos_only() {
+    let help = |line: &str| assemble(line).unwrap_err()[0].help.clone();
+    assert_eq!(help("JMP 0x200").as_deref(), Some("did you mean JP?"));
+    assert_eq!(help("cal 0x200").as_deref(), Some("did you mean CALL?"));
+    assert_eq!(help("MOV V0, V1"), None);
+    assert_eq!(
+        help("JP V1, 0x200").as_deref(),
+        Some("JP takes JP addr; or JP V0, addr")
+    );
+}
+
+#[test]
+fn diagnostics_render_with_the_line_and_a_caret() {
+    let source = "CLS\n\tDRW V0, V1, 16\n";
+    let diagnostics = assemble(source).unwrap_err();
+    assert_eq!(
+        diagnostics,
+        [Diagnostic {
+            line: 2,
+            column: 14,
+            len: 2,
+            message: "16 does not fit in a nibble".to_string(),
+            help: None,
+        }]
+    );
+    assert_eq!(
+        diagnostics[0].render("game.asm", source),
+        "error: 16 does not fit in a nibble\n \
+         --> game.asm:2:14\n  \
+         |\n\
+         2 | \tDRW V0, V1, 16\n  \
+         | \t            ^^\n"
+    );
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "line 2, column 14: 16 does not fit in a nibble"
+    );
+}
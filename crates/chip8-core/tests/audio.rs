@@ -0,0 +1,111 @@
+use chip8_core::audio::{Beeper, BeeperState, SlowMotion};
+use chip8_core::events::Event;
+use chip8_core::{
+    is_beeping, run_frame, sound_time_left, step, tick_timers, timer_phase, EmulatorBuilder, Quirks,
+};
+
+fn beeps(sound_timer: u8) -> bool {
+    // LD V0, nn; LD ST, V0; JP 0x204
+    let rom = [0x60, sound_timer, 0xF0, 0x18, 0x12, 0x04];
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    let output = run_frame(&mut emulator);
+    output.beep_started && is_beeping(&emulator)
+}
+
+#[test]
+fn sound_timer_of_one_is_silent() {
+    assert!(!beeps(1));
+    assert!(beeps(2));
+}
+
+#[test]
+fn short_beeps_can_be_stretched() {
+    let played = |min_duration: f32| {
+        let mut beeper = Beeper::new(1000);
+        beeper.set_min_duration(min_duration);
+        beeper.set_beeping(true);
+        let mut samples = vec![0.0; 16];
+        beeper.fill(&mut samples);
+        beeper.set_beeping(false);
+        let mut tail = vec![0.0; 100];
+        beeper.fill(&mut tail);
+        tail.iter().filter(|sample| **sample != 0.0).count()
+    };
+    // The 5 ms fade out, then silence.
+    assert!(played(0.0) <= 5);
+    assert!(played(0.05) > 30);
+}
+
+#[test]
+fn timers_report_progress_between_ticks() {
+    // LD V0, 3; LD ST, V0; JP 0x204
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .cycles_per_frame(10)
+        .rom(&[0x60, 0x03, 0xF0, 0x18, 0x12, 0x04])
+        .build()
+        .unwrap();
+    for _ in 0..5 {
+        step(&mut emulator).unwrap();
+    }
+    assert_eq!(timer_phase(&emulator), 0.5);
+    assert!((sound_time_left(&emulator) - 2.5 / 60.0).abs() < 1e-6);
+    tick_timers(&mut emulator);
+    assert_eq!(timer_phase(&emulator), 0.0);
+    assert!((sound_time_left(&emulator) - 2.0 / 60.0).abs() < 1e-6);
+
+    // The beep ends mid buffer, 2/60 s = 20 samples at 600 Hz, then fades.
+    let mut beeper = Beeper::new(600);
+    beeper.set_time_left(sound_time_left(&emulator));
+    let mut samples = vec![0.0; 40];
+    beeper.fill(&mut samples);
+    assert_ne!(samples[19], 0.0);
+    assert_eq!(samples[39], 0.0);
+}
+
+// Sign changes of the square wave and samples sounding, over `samples`.
+fn listen(beeper: &mut Beeper, samples: usize) -> (usize, usize) {
+    let mut buffer = vec![0.0; samples];
+    beeper.fill(&mut buffer);
+    let edges = buffer
+        .windows(2)
+        .filter(|pair| pair[0] > 0.0 && pair[1] < 0.0)
+        .count();
+    let sounding = buffer.iter().filter(|sample| **sample != 0.0).count();
+    (edges, sounding)
+}
+
+#[test]
+fn slow_motion_keeps_the_pitch_and_stretches_the_beep() {
+    let mut full = Beeper::new(8000);
+    full.set_time_left(0.1);
+    let mut slow = Beeper::new(8000);
+    slow.handle(&Event::SpeedChanged { percent: 50 });
+    slow.set_time_left(0.1);
+
+    let (full_edges, full_sounding) = listen(&mut full, 4000);
+    let (slow_edges, slow_sounding) = listen(&mut slow, 4000);
+    // The same wave, for twice as long.
+    assert!(full_edges > 40, "{}", full_edges);
+    assert!((slow_edges as i32 - 2 * full_edges as i32).abs() <= 2);
+    assert!((slow_sounding as i32 - 2 * full_sounding as i32).abs() < 60);
+}
+
+#[test]
+fn slow_motion_can_mute_instead() {
+    let mut beeper = Beeper::new(8000);
+    beeper.set_slow_motion(SlowMotion::Mute);
+    beeper.set_speed(50);
+    beeper.set_beeping(true);
+    assert_eq!(listen(&mut beeper, 800).1, 0);
+    assert_eq!(beeper.state(), BeeperState::Silent);
+
+    beeper.handle(&Event::SpeedChanged { percent: 100 });
+    assert!(listen(&mut beeper, 800).1 > 700);
+}
@@ -0,0 +1,51 @@
+use chip8_core::branches::{Branch, BranchHistory, Branches};
+use chip8_core::labels::Labels;
+use chip8_core::{step, EmulatorBuilder};
+
+// CALL 0x20A; SE V0, 1; SE V0, 0; CLS; JP 0x208; at 0x20A RET
+const ROM: [u8; 12] = [
+    0x22, 0x0A, 0x30, 0x01, 0x30, 0x00, 0x00, 0xE0, 0x12, 0x08, 0x00, 0xEE,
+];
+
+fn run(capacity: usize, steps: usize) -> Branches {
+    let mut emulator = EmulatorBuilder::new().rom(&ROM).build().unwrap();
+    let history = BranchHistory::new(capacity);
+    let branches = history.branches();
+    emulator.hooks.add(history);
+    for _ in 0..steps {
+        step(&mut emulator).unwrap();
+    }
+    branches
+}
+
+fn branch(from: u16, to: u16, op_code: u16) -> Branch {
+    Branch { from, to, op_code }
+}
+
+#[test]
+fn keeps_jumps_calls_returns_and_skips_taken() {
+    // The skip not taken falls through, and the jump to itself only runs
+    // again.
+    let branches = run(8, 10);
+    assert_eq!(
+        branches.list(),
+        [
+            branch(0x200, 0x20A, 0x220A),
+            branch(0x20A, 0x202, 0x00EE),
+            branch(0x204, 0x208, 0x3000),
+        ]
+    );
+}
+
+#[test]
+fn forgets_the_oldest_past_its_capacity() {
+    let branches = run(2, 10);
+    assert_eq!(branches.list()[0], branch(0x20A, 0x202, 0x00EE));
+    let mut labels = Labels::new();
+    labels.add(0x20A, "update").unwrap();
+    assert_eq!(
+        branches.panel(&labels),
+        "0x20a  00EE  RET             -> 0x202  ; from update\n\
+         0x204  3000  SE V0, 0x00     -> 0x208\n"
+    );
+}
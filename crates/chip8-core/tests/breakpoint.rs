@@ -0,0 +1,68 @@
+use chip8_core::breakpoint::{AccessBreakpoint, AddressRange, Hits};
+use chip8_core::heatmap::AccessKind;
+use chip8_core::{step, Emulator, EmulatorBuilder, EmulatorError};
+
+// LD I, 0x2FE; LD V3, [I]; LD I, 0x310; LD V0, [I]; LD I, 0x305;
+// LD B, V0; JP 0x200
+const ROM: [u8; 14] = [
+    0xA2, 0xFE, 0xF3, 0x65, 0xA3, 0x10, 0xF0, 0x65, 0xA3, 0x05, 0xF0, 0x33, 0x12, 0x00,
+];
+
+fn watch(range: &str) -> (Emulator, Hits) {
+    let mut emulator = EmulatorBuilder::new().rom(&ROM).build().unwrap();
+    let breakpoint = AccessBreakpoint::new(range.parse().unwrap());
+    let hits = breakpoint.hits();
+    emulator.hooks.add(breakpoint);
+    (emulator, hits)
+}
+
+#[test]
+fn hits_every_instruction_touching_the_range_through_i() {
+    let (mut emulator, hits) = watch("0x300-0x30F");
+    for _ in 0..14 {
+        step(&mut emulator).unwrap();
+    }
+    let found: Vec<_> = hits
+        .list()
+        .iter()
+        .map(|hit| (hit.address, hit.access.kind, hit.count))
+        .collect();
+    // The read from 0x2FE runs into the range, the one from 0x310 is past
+    // it.
+    assert_eq!(
+        found,
+        [(0x202, AccessKind::Read, 2), (0x20A, AccessKind::Write, 2)]
+    );
+    assert_eq!(
+        hits.list()[0].to_string(),
+        "0x202 (F365): reads 0x2fe-0x301, 2 times"
+    );
+}
+
+#[test]
+fn trap_stops_at_the_first_hit() {
+    let (mut emulator, hits) = watch("0x307");
+    let mut steps = 0;
+    let error = loop {
+        step(&mut emulator).unwrap();
+        steps += 1;
+        if let Some(error) = hits.trap() {
+            break error;
+        }
+    };
+    assert_eq!(steps, 6);
+    assert!(matches!(error, EmulatorError::Breakpoint(_)));
+    assert_eq!(
+        error.to_string(),
+        "breakpoint at 0x20a (F033): writes 0x305-0x307"
+    );
+}
+
+#[test]
+fn ranges_parse_as_hex_addresses() {
+    let range: AddressRange = "0x300-0x30F".parse().unwrap();
+    assert_eq!((range.start, range.end), (0x300, 0x30F));
+    assert!(range.contains(0x30F) && !range.contains(0x310));
+    assert!("0x30F-0x300".parse::<AddressRange>().is_err());
+    assert!("table".parse::<AddressRange>().is_err());
+}
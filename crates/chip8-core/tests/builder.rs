@@ -0,0 +1,177 @@
+use chip8_core::instruction::Variant;
+use chip8_core::{
+    push_to_stack, run_frame, run_frame_with, savestate, step, EmulatorBuilder, EmulatorError,
+    Quirks, CYCLES_PER_FRAME, INITIAL_ADDRESS, RAM_SIZE, XOCHIP_RAM_SIZE,
+};
+
+#[test]
+fn builds_a_preloaded_machine() {
+    let emulator = EmulatorBuilder::new()
+        .variant(Variant::Schip)
+        .clock_hz(1000)
+        .rom(&[0x60, 0x05])
+        .memory(0x300, &[1, 2, 3])
+        .register(0xA, 7)
+        .i_register(0x300)
+        .build()
+        .unwrap();
+    assert_eq!(emulator.quirks, Quirks::schip());
+    assert_eq!(emulator.cycles_per_frame, 17);
+    assert_eq!(
+        &emulator.ram[INITIAL_ADDRESS as usize..][..2],
+        &[0x60, 0x05]
+    );
+    assert_eq!(&emulator.ram[0x300..0x303], &[1, 2, 3]);
+    assert_eq!(emulator.v_registers[0xA], 7);
+    assert_eq!(emulator.i_register, 0x300);
+
+    let default = EmulatorBuilder::new().build().unwrap();
+    assert_eq!(default.cycles_per_frame, CYCLES_PER_FRAME);
+    assert_eq!(default.program_counter, INITIAL_ADDRESS);
+}
+
+#[test]
+fn seeds_repeat_and_stack_limits_apply() {
+    let seeded = || EmulatorBuilder::new().seed(3).build().unwrap().rng;
+    assert_eq!(seeded().next_u64(), seeded().next_u64());
+
+    let mut emulator = EmulatorBuilder::new().stack_limit(2).build().unwrap();
+    push_to_stack(&mut emulator, 0x200).unwrap();
+    push_to_stack(&mut emulator, 0x202).unwrap();
+    assert_eq!(
+        push_to_stack(&mut emulator, 0x204),
+        Err(EmulatorError::StackOverflow)
+    );
+}
+
+#[test]
+fn clocks_run_exactly_their_rate() {
+    for (hz, seconds, per_frame) in [(1, 2, 1), (29, 1, 1), (1000, 1, 17), (600, 1, 10)] {
+        let mut emulator = EmulatorBuilder::new()
+            .clock_hz(hz)
+            .rom(&[0x12, 0x00])
+            .build()
+            .unwrap();
+        assert_eq!(emulator.cycles_per_frame, per_frame);
+        let mut steps = 0;
+        for _ in 0..60 * seconds {
+            run_frame_with(&mut emulator, |emulator| {
+                steps += 1;
+                step(emulator)
+            });
+        }
+        assert_eq!(steps, hz * seconds, "{} Hz", hz);
+    }
+}
+
+#[test]
+fn rejects_invalid_settings() {
+    for builder in [
+        EmulatorBuilder::new().stack_limit(0),
+        EmulatorBuilder::new().stack_limit(17),
+        EmulatorBuilder::new().cycles_per_frame(0),
+        EmulatorBuilder::new().clock_hz(0),
+        EmulatorBuilder::new().register(16, 0),
+        EmulatorBuilder::new().memory(0xFFE, &[0; 4]),
+        EmulatorBuilder::new().program_counter(0x1000),
+        EmulatorBuilder::new().ram_size(0x800),
+        EmulatorBuilder::new().ram_size(0x3000),
+        EmulatorBuilder::new().ram_size(0x20000),
+    ] {
+        assert!(matches!(
+            builder.build(),
+            Err(EmulatorError::InvalidConfig(_))
+        ));
+    }
+    assert_eq!(
+        EmulatorBuilder::new().rom(&[0; 4000]).build().err(),
+        Some(EmulatorError::RomTooLarge(4000))
+    );
+}
+
+#[test]
+fn xo_chip_has_64k_that_wraps_at_its_end() {
+    assert_eq!(EmulatorBuilder::new().build().unwrap().ram.len(), RAM_SIZE);
+    // A ROM past 4K reading the last byte and the first: LD V1, [I];
+    // JP 0x202.
+    let mut rom = vec![0; 0x2000];
+    rom[..4].copy_from_slice(&[0xF1, 0x65, 0x12, 0x02]);
+    let mut emulator = EmulatorBuilder::new()
+        .variant(Variant::XoChip)
+        .rom(&rom)
+        .i_register(0xFFFF)
+        .memory(0xFFFF, &[0xAB])
+        .memory(0, &[0xCD])
+        .build()
+        .unwrap();
+    assert_eq!(emulator.ram.len(), XOCHIP_RAM_SIZE);
+    run_frame(&mut emulator);
+    assert_eq!(emulator.v_registers[..2], [0xAB, 0xCD]);
+
+    let restored = savestate::load(&savestate::save(&emulator)).unwrap();
+    assert_eq!(restored.ram, emulator.ram);
+
+    // Other machines keep 4K unless told otherwise.
+    let big = vec![0; 0x2000];
+    assert!(EmulatorBuilder::new().rom(&big).build().is_err());
+    let emulator = EmulatorBuilder::new()
+        .ram_size(XOCHIP_RAM_SIZE)
+        .rom(&big)
+        .build()
+        .unwrap();
+    assert_eq!(emulator.ram.len(), XOCHIP_RAM_SIZE);
+}
+
+#[test]
+fn fetching_at_the_top_of_memory_wraps() {
+    // ADD V0, 1 in the last two bytes and the first two.
+    let mut emulator = EmulatorBuilder::new()
+        .variant(Variant::XoChip)
+        .memory(0xFFFE, &[0x70, 0x01])
+        .memory(0, &[0x70, 0x01])
+        .build()
+        .unwrap();
+    emulator.program_counter = 0xFFFE;
+    step(&mut emulator).unwrap();
+    assert_eq!(emulator.program_counter, 0);
+    step(&mut emulator).unwrap();
+    assert_eq!((emulator.program_counter, emulator.v_registers[0]), (2, 2));
+
+    // LD V0, K waiting in the last two bytes stays there.
+    emulator.ram[0xFFFE..].copy_from_slice(&[0xF0, 0x0A]);
+    emulator.program_counter = 0xFFFE;
+    step(&mut emulator).unwrap();
+    assert_eq!(emulator.program_counter, 0xFFFE);
+}
+
+#[test]
+fn emulators_can_move_between_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<chip8_core::Emulator>();
+    assert_send_sync::<chip8_core::rom::Rom>();
+    assert_send_sync::<chip8_core::FrameOutput>();
+}
+
+#[test]
+fn eti660_loads_at_0x600_with_a_taller_screen() {
+    // LD V1, 47; LD I, 0x608; DRW V0, V1, 2; JP 0x606; sprite
+    let rom = [0x61, 0x2F, 0xA6, 0x08, 0xD0, 0x12, 0x16, 0x06, 0xC0, 0xC0];
+    let mut emulator = EmulatorBuilder::new()
+        .variant("eti660".parse().unwrap())
+        .seed(0)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    assert_eq!(emulator.program_counter, 0x600);
+    assert_eq!(&emulator.ram[0x600..0x602], &[0x61, 0x2F]);
+    assert_eq!(
+        (emulator.display.width(), emulator.display.height()),
+        (64, 48)
+    );
+    for _ in 0..2 {
+        run_frame(&mut emulator);
+    }
+    // The bottom row is 47, the second row of the sprite is clipped.
+    assert!(emulator.display.pixel(0, 47));
+    assert!(!emulator.display.pixel(0, 0));
+}
@@ -0,0 +1,39 @@
+use chip8_core::calibration::{Calibration, MAX_CYCLES_PER_FRAME};
+use chip8_core::config::RomConfig;
+
+#[test]
+fn steps_are_about_a_tenth_and_undo_each_other() {
+    let mut calibration = Calibration::new(10);
+    assert!(!calibration.changed());
+    assert_eq!(calibration.faster(), 11);
+    assert_eq!(calibration.slower(), 10);
+    assert!(!calibration.changed());
+
+    let mut calibration = Calibration::new(99);
+    assert_eq!(calibration.faster(), 108);
+    assert_eq!(calibration.slower(), 99);
+    assert_eq!(calibration.slower(), 90);
+    assert!(calibration.changed());
+    assert_eq!(
+        calibration.to_string(),
+        "90 instructions per frame (5400 Hz)"
+    );
+}
+
+#[test]
+fn the_speed_stays_in_range() {
+    let mut calibration = Calibration::new(1);
+    assert_eq!(calibration.slower(), 1);
+    let mut calibration = Calibration::new(5000);
+    assert_eq!(calibration.cycles_per_frame(), MAX_CYCLES_PER_FRAME);
+    assert_eq!(calibration.faster(), MAX_CYCLES_PER_FRAME);
+}
+
+#[test]
+fn the_speed_is_kept_in_the_rom_config() {
+    let config = RomConfig::parse("cycles_per_frame = 15\n").unwrap();
+    assert_eq!(config.cycles_per_frame, Some(15));
+    assert_eq!(RomConfig::parse(&config.to_text()).unwrap(), config);
+    assert!(RomConfig::parse("cycles_per_frame = 0").is_err());
+    assert!(RomConfig::parse("cycles_per_frame = fast").is_err());
+}
@@ -0,0 +1,88 @@
+use chip8_core::checksum::{state_checksum, ChecksumLog, Desync, FrameChain};
+use chip8_core::rom::rom_hash;
+use chip8_core::{run_frame, Emulator, EmulatorBuilder, Quirks};
+
+fn emulator() -> Emulator {
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .build()
+        .unwrap();
+    // Counts up in V0 and copies it to the delay timer, forever.
+    let program = [0x70, 0x01, 0xF0, 0x15, 0x12, 0x00];
+    emulator.ram[0x200..0x206].copy_from_slice(&program);
+    emulator
+}
+
+#[test]
+fn checksums_are_stable_across_builds() {
+    assert_eq!(rom_hash(b"CHIP-8"), 0x6700_8479_e496_15fa);
+    assert_eq!(state_checksum(&emulator()), state_checksum(&emulator()));
+}
+
+#[test]
+fn recorded_checksums_catch_the_first_desync() {
+    let mut recording = emulator();
+    let mut log = ChecksumLog::new(2);
+    for frame in 0..10 {
+        run_frame(&mut recording);
+        log.record(frame, &recording);
+    }
+    assert_eq!(log.len(), 5);
+
+    let mut playback = emulator();
+    for frame in 0..10 {
+        if frame == 5 {
+            playback.v_registers[3] = 1;
+        }
+        run_frame(&mut playback);
+        let checked = log.check(frame, &playback);
+        match frame {
+            0..=5 | 7 | 9 => assert_eq!(checked, Ok(()), "frame {}", frame),
+            _ => assert!(matches!(checked, Err(Desync { frame: f, .. }) if f == frame)),
+        }
+    }
+}
+
+#[test]
+fn logs_round_trip_through_text() {
+    let mut log = ChecksumLog::new(60);
+    log.insert(0, 0xdead_beef);
+    log.insert(60, u64::MAX);
+    assert_eq!(ChecksumLog::parse(&log.to_text()), Ok(log));
+    assert!(ChecksumLog::parse("60 0000").is_err());
+    assert!(ChecksumLog::parse("interval 60\n60 xyz").is_err());
+}
+
+#[test]
+fn overlay_codes_follow_from_every_frame_before() {
+    let codes = |emulator: &mut Emulator, frames: usize| {
+        let mut chain = FrameChain::new();
+        (0..frames)
+            .map(|_| {
+                run_frame(emulator);
+                chain.advance(emulator);
+                chain.code()
+            })
+            .collect::<Vec<u32>>()
+    };
+    let run = codes(&mut emulator(), 4);
+    assert_eq!(run, codes(&mut emulator(), 4));
+    // The same last frame, reached from another second one, shows another
+    // code.
+    let mut spliced = emulator();
+    let mut chain = FrameChain::new();
+    for frame in 0..4 {
+        run_frame(&mut spliced);
+        if frame == 1 {
+            spliced.v_registers[1] ^= 1;
+            chain.advance(&spliced);
+            spliced.v_registers[1] ^= 1;
+        } else {
+            chain.advance(&spliced);
+        }
+    }
+    assert_eq!(chain.frame(), 4);
+    assert_ne!(chain.code(), run[3]);
+    assert!(chain.to_string().starts_with("frame 4 "));
+}
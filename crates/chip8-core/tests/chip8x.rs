@@ -0,0 +1,75 @@
+use chip8_core::chip8x::{add_bcd, BACKGROUNDS, COLORS};
+use chip8_core::instruction::Variant;
+use chip8_core::{step, EmulatorBuilder, EmulatorError, Instruction, Quirks};
+
+#[test]
+fn decodes_the_extension() {
+    assert_eq!(Instruction::decode(0x02A0), Instruction::NextBackground);
+    assert_eq!(
+        Instruction::decode(0x5231),
+        Instruction::AddBcd { x: 2, y: 3 }
+    );
+    assert_eq!(
+        Instruction::decode(0xB123),
+        Instruction::JpV0 { nnn: 0x123 }
+    );
+    assert_eq!(
+        Instruction::decode_chip8x(0xB123),
+        Instruction::Color { x: 1, y: 2, n: 3 }
+    );
+    for op_code in [0x02A0, 0x5231, 0xB123, 0xE1F2, 0xE1F5, 0xF1F8, 0xF1FB] {
+        let instruction = Instruction::decode_chip8x(op_code);
+        assert_eq!(instruction.encode(), op_code);
+        assert_eq!(instruction.to_string().parse(), Ok(instruction));
+    }
+    assert_eq!(add_bcd(0x37, 0x51), 0x00);
+    assert_eq!(add_bcd(0x12, 0x34), 0x46);
+}
+
+#[test]
+fn colours_zones_and_reads_the_second_keypad() {
+    // BGC; LD V0, 0x10; LD V1, 0x00; LD V2, 4; COL V0, V2, 0; SKP2 V2;
+    // LD V3, 1; LD V4, 2
+    let rom = [
+        0x02, 0xA0, 0x60, 0x10, 0x61, 0x00, 0x62, 0x04, 0xB0, 0x20, 0xE2, 0xF2, 0x63, 0x01, 0x64,
+        0x02,
+    ];
+    let mut emulator = EmulatorBuilder::new()
+        .variant(Variant::Chip8x)
+        .seed(0)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    assert_eq!(emulator.program_counter, 0x300);
+    emulator.chip8x.as_mut().unwrap().keypad.press(4);
+    for _ in 0..8 {
+        step(&mut emulator).unwrap();
+    }
+    let chip8x = emulator.chip8x.as_ref().unwrap();
+    assert_eq!(chip8x.background, 1);
+    // Columns 0 and 1 of the first zone row are green now.
+    assert_eq!(chip8x.color(0, 0), 4);
+    assert_eq!(chip8x.color(15, 3), 4);
+    assert_eq!(chip8x.color(16, 0), 1);
+    assert_eq!(chip8x.color(0, 4), 1);
+    assert_eq!((emulator.v_registers[3], emulator.v_registers[4]), (0, 2));
+
+    emulator.display.draw_sprite(0, 0, &[0x80], false);
+    let rgba = chip8x.to_rgba(&emulator.display);
+    assert_eq!(&rgba[..4], &COLORS[4]);
+    assert_eq!(&rgba[4..8], &BACKGROUNDS[1]);
+}
+
+#[test]
+fn needs_the_hardware() {
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .rom(&[0x02, 0xA0])
+        .build()
+        .unwrap();
+    assert_eq!(
+        step(&mut emulator),
+        Err(EmulatorError::Unimplemented(0x02A0, "CHIP-8X"))
+    );
+}
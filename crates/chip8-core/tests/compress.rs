@@ -0,0 +1,75 @@
+use chip8_core::compress::{pack, pack_delta, unpack, unpack_delta};
+
+#[test]
+fn packs_runs_and_keeps_the_rest() {
+    let mut data = vec![0u8; 4096];
+    data[100..106].copy_from_slice(b"CHIP-8");
+    data.extend([1, 2, 2, 3, 3, 3, 3]);
+    let packed = pack(&data);
+    assert!(packed.len() < 24, "{} bytes", packed.len());
+    assert_eq!(unpack(&packed).unwrap(), data);
+    assert_eq!(unpack(&pack(&[])).unwrap(), []);
+}
+
+#[test]
+fn deltas_pack_what_changed() {
+    let base: Vec<u8> = (0..2048).map(|i| (i * 7 % 251) as u8).collect();
+    let mut data = base.clone();
+    data[1000] ^= 0x40;
+    data.extend([9, 9]);
+    let packed = pack_delta(&base, &data);
+    assert!(packed.len() < 16, "{} bytes", packed.len());
+    assert_eq!(unpack_delta(&base, &packed).unwrap(), data);
+}
+
+#[test]
+fn corrupt_data_does_not_unpack() {
+    let packed = pack(b"some bytes that stay literal");
+    assert!(unpack(&packed[..packed.len() - 1]).is_err());
+    assert!(unpack(&[packed.as_slice(), &[0]].concat()).is_err());
+    // Claims more than anything unpacks to.
+    assert!(unpack(&[0xFF, 0xFF, 0xFF, 0xFF, 0x7F]).is_err());
+}
+
+#[test]
+fn savestates_are_written_packed() {
+    use chip8_core::savestate::{load, save};
+    use chip8_core::{demo, run_frame, EmulatorBuilder};
+
+    let mut emulator = EmulatorBuilder::new().rom(demo::ROM).build().unwrap();
+    for _ in 0..30 {
+        run_frame(&mut emulator);
+    }
+    let state = save(&emulator);
+    #[cfg(feature = "compression")]
+    let plain = chip8_core::compress::zstd_unpack(&state[4..]).unwrap();
+    #[cfg(not(feature = "compression"))]
+    let plain = unpack(&state[4..]).unwrap();
+    assert!(
+        state.len() * 4 < plain.len(),
+        "{} of {}",
+        state.len(),
+        plain.len()
+    );
+    assert_eq!(load(&state).unwrap().ram, emulator.ram);
+
+    // Run-length packed states load in every build, and so do plain ones.
+    let packed = [b"C8SZ".as_slice(), &pack(&plain)].concat();
+    assert_eq!(load(&packed).unwrap().ram, emulator.ram);
+    assert_eq!(load(&plain).unwrap().ram, emulator.ram);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn zstd_packs_states_and_deltas() {
+    use chip8_core::compress::{zstd_pack, zstd_pack_delta, zstd_unpack, zstd_unpack_delta};
+
+    let base: Vec<u8> = (0..4096).map(|i| (i * 7 % 251) as u8).collect();
+    let mut data = base.clone();
+    data[1000] ^= 0x40;
+    assert_eq!(zstd_unpack(&zstd_pack(&data)).unwrap(), data);
+    let delta = zstd_pack_delta(&base, &data);
+    assert!(delta.len() < 32, "{} bytes", delta.len());
+    assert_eq!(zstd_unpack_delta(&base, &delta).unwrap(), data);
+    assert!(zstd_unpack(b"not zstd").is_err());
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::Path;
+
+use chip8_core::assembler::assemble;
+use chip8_core::{demo, run_frame, Emulator, EmulatorBuilder};
+
+fn lit(emulator: &Emulator) -> usize {
+    let display = &emulator.display;
+    (0..display.height())
+        .flat_map(|y| (0..display.width()).map(move |x| display.pixel(x, y)))
+        .filter(|&on| on)
+        .count()
+}
+
+#[test]
+fn the_demo_is_its_source_assembled() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../roms/demo.asm");
+    let source = fs::read_to_string(path).unwrap();
+    assert_eq!(assemble(&source).unwrap(), demo::ROM);
+}
+
+#[test]
+fn the_demo_blinks_and_shows_the_key_held() {
+    let mut emulator = EmulatorBuilder::new().rom(demo::ROM).build().unwrap();
+    for _ in 0..10 {
+        assert!(run_frame(&mut emulator).error.is_none());
+    }
+    // The title and the cursor.
+    let shown = lit(&emulator);
+    assert!(shown > 20);
+    for _ in 0..15 {
+        run_frame(&mut emulator);
+    }
+    assert_eq!(lit(&emulator), shown - 4);
+
+    emulator.keypad.press(0x5);
+    for _ in 0..5 {
+        run_frame(&mut emulator);
+    }
+    assert!(emulator.sound_timer_registry > 0);
+    assert!(lit(&emulator) > shown);
+    assert_eq!(emulator.v_registers[8], 0x5);
+}
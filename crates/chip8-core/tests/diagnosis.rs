@@ -0,0 +1,70 @@
+use chip8_core::diagnosis::Diagnosis;
+use chip8_core::{run_frame, savestate, EmulatorBuilder, EmulatorError};
+
+#[test]
+fn a_rom_too_large_for_chip8_suggests_xo_chip() {
+    let data = vec![0; 8000];
+    let error = EmulatorBuilder::new().rom(&data).build().unwrap_err();
+    let diagnosis = Diagnosis::rom("big", &data, &error);
+    assert_eq!(diagnosis.title, "Cannot load big");
+    assert_eq!(diagnosis.error, "ROM of 8000 bytes does not fit in memory");
+    assert!(diagnosis.fixes[0].contains("xochip"));
+
+    let data = vec![0; 0x10000];
+    let error = EmulatorBuilder::new().rom(&data).build().unwrap_err();
+    let diagnosis = Diagnosis::rom("huge", &data, &error);
+    assert!(diagnosis.fixes[0].contains("not be a CHIP-8 ROM"));
+}
+
+#[test]
+fn savestates_that_do_not_load_say_why() {
+    let error = savestate::load(b"\x00\xE0\x12\x00").unwrap_err();
+    let diagnosis = Diagnosis::savestate("pong.ch8", &error);
+    assert_eq!(diagnosis.title, "Cannot load pong.ch8");
+    assert!(diagnosis.fixes[0].contains("not the ROM"));
+
+    let mut state = savestate::save(&EmulatorBuilder::new().build().unwrap());
+    state.pop();
+    let error = savestate::load(&state).unwrap_err();
+    let diagnosis = Diagnosis::savestate("pong.state", &error);
+    assert!(diagnosis.fixes[0].contains("corrupt"));
+}
+
+#[test]
+fn a_rom_for_another_variant_says_which() {
+    // F000 NNNN is XO-CHIP only.
+    let data = [0xF0, 0x00, 0x12, 0x34];
+    let mut emulator = EmulatorBuilder::new().rom(&data).build().unwrap();
+    let error = run_frame(&mut emulator).error.unwrap();
+    let diagnosis = Diagnosis::crash("game", &data, &error);
+    assert_eq!(diagnosis.title, "game stopped");
+    assert_eq!(diagnosis.fixes, ["It is an XO-CHIP ROM, run it as xochip."]);
+
+    let diagnosis = Diagnosis::crash("game", &[], &EmulatorError::UnknownOpcode(0));
+    assert!(diagnosis.fixes[0].contains("empty memory"));
+}
+
+#[test]
+fn the_dialog_wraps_into_a_box() {
+    let diagnosis = Diagnosis::new(
+        "Cannot load pong",
+        "ROM of 8000 bytes does not fit in memory",
+    )
+    .fix("ROMs this large are usually XO-CHIP, run it as xochip.");
+    assert_eq!(
+        diagnosis.dialog(30),
+        [
+            "+----------------------------+",
+            "| Cannot load pong           |",
+            "+----------------------------+",
+            "| ROM of 8000 bytes does not |",
+            "| fit in memory              |",
+            "|                            |",
+            "| Try:                       |",
+            "| - ROMs this large are      |",
+            "|   usually XO-CHIP, run it  |",
+            "|   as xochip.               |",
+            "+----------------------------+",
+        ]
+    );
+}
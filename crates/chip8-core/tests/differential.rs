@@ -0,0 +1,381 @@
+// Differential fuzzing: random ROM snippets run through the emulator and
+// through the small reference interpreter below, written independently from
+// the spec, and every step the two states are compared. Cxkk and the
+// keyboard opcodes are left out since their results depend on the outside
+// world.
+//
+// CHIP8_FUZZ_CASES sets how many snippets to try (50 by default) and
+// CHIP8_FUZZ_SEED replays a run, the seed of a failing case is printed.
+
+use chip8_core::{
+    step, tick_timers, Emulator, EmulatorBuilder, EmulatorError, Quirks, CYCLES_PER_FRAME,
+    INITIAL_ADDRESS, RAM_SIZE, STACK_SIZE,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const DEFAULT_CASES: u64 = 50;
+const SNIPPET_LEN: usize = 48;
+const MAX_STEPS: usize = 400;
+const WIDTH: usize = 64;
+const HEIGHT: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fault {
+    StackOverflow,
+    StackUnderflow,
+    UnknownOpcode,
+}
+
+struct Reference {
+    v: [u8; 16],
+    i: u16,
+    pc: u16,
+    stack: Vec<u16>,
+    delay: u8,
+    sound: u8,
+    memory: [u8; RAM_SIZE],
+    screen: [[bool; WIDTH]; HEIGHT],
+    quirks: Quirks,
+}
+
+impl Reference {
+    // Starts from the emulator's memory, font included, with the ROM loaded.
+    fn new(memory: [u8; RAM_SIZE], quirks: Quirks) -> Self {
+        Reference {
+            v: [0; 16],
+            i: 0,
+            pc: INITIAL_ADDRESS,
+            stack: Vec::new(),
+            delay: 0,
+            sound: 0,
+            memory,
+            screen: [[false; WIDTH]; HEIGHT],
+            quirks,
+        }
+    }
+
+    fn read(&self, address: usize) -> u8 {
+        self.memory[address % RAM_SIZE]
+    }
+
+    fn write(&mut self, address: usize, value: u8) {
+        self.memory[address % RAM_SIZE] = value;
+    }
+
+    fn tick(&mut self) {
+        self.delay = self.delay.saturating_sub(1);
+        self.sound = self.sound.saturating_sub(1);
+    }
+
+    fn step(&mut self) -> Result<(), Fault> {
+        let pc = self.pc as usize;
+        let op = u16::from_be_bytes([self.read(pc), self.read(pc + 1)]);
+        self.pc += 2;
+
+        let x = ((op >> 8) & 0xF) as usize;
+        let y = ((op >> 4) & 0xF) as usize;
+        let n = (op & 0xF) as usize;
+        let kk = (op & 0xFF) as u8;
+        let nnn = op & 0xFFF;
+        let (vx, vy) = (self.v[x], self.v[y]);
+
+        match op >> 12 {
+            0x0 if op == 0x0000 => {}
+            0x0 if op == 0x00E0 => self.screen = [[false; WIDTH]; HEIGHT],
+            0x0 if op == 0x00EE => self.pc = self.stack.pop().ok_or(Fault::StackUnderflow)?,
+            0x1 => self.pc = nnn,
+            0x2 => {
+                if self.stack.len() == STACK_SIZE {
+                    return Err(Fault::StackOverflow);
+                }
+                self.stack.push(self.pc);
+                self.pc = nnn;
+            }
+            0x3 if vx == kk => self.pc += 2,
+            0x3 => {}
+            0x4 if vx != kk => self.pc += 2,
+            0x4 => {}
+            0x5 if n == 0 && vx == vy => self.pc += 2,
+            0x5 if n == 0 => {}
+            0x6 => self.v[x] = kk,
+            0x7 => self.v[x] = vx.wrapping_add(kk),
+            0x8 => {
+                let shifted = if self.quirks.shift_uses_vy { vy } else { vx };
+                let (result, flag) = match n {
+                    0x0 => (vy, None),
+                    0x1 => (vx | vy, self.quirks.vf_reset.then_some(0)),
+                    0x2 => (vx & vy, self.quirks.vf_reset.then_some(0)),
+                    0x3 => (vx ^ vy, self.quirks.vf_reset.then_some(0)),
+                    0x4 => {
+                        let sum = vx as u16 + vy as u16;
+                        (sum as u8, Some((sum > 0xFF) as u8))
+                    }
+                    0x5 => (vx.wrapping_sub(vy), Some((vx >= vy) as u8)),
+                    0x6 => (shifted >> 1, Some(shifted & 1)),
+                    0x7 => (vy.wrapping_sub(vx), Some((vy >= vx) as u8)),
+                    0xE => (shifted << 1, Some(shifted >> 7)),
+                    _ => return Err(Fault::UnknownOpcode),
+                };
+                self.v[x] = result;
+                if let Some(flag) = flag {
+                    self.v[0xF] = flag;
+                }
+            }
+            0x9 if n == 0 && vx != vy => self.pc += 2,
+            0x9 if n == 0 => {}
+            0xA => self.i = nnn,
+            0xB => {
+                let offset = if self.quirks.jump_uses_vx {
+                    vx
+                } else {
+                    self.v[0]
+                };
+                self.pc = nnn + offset as u16;
+            }
+            0xD => {
+                let (left, top) = (vx as usize % WIDTH, vy as usize % HEIGHT);
+                self.v[0xF] = 0;
+                for row in 0..n {
+                    let byte = self.read(self.i as usize + row);
+                    for column in 0..8 {
+                        let (mut px, mut py) = (left + column, top + row);
+                        if self.quirks.clipping && (px >= WIDTH || py >= HEIGHT) {
+                            continue;
+                        }
+                        px %= WIDTH;
+                        py %= HEIGHT;
+                        if byte & (0x80 >> column) != 0 {
+                            if self.screen[py][px] {
+                                self.v[0xF] = 1;
+                            }
+                            self.screen[py][px] ^= true;
+                        }
+                    }
+                }
+            }
+            0xF => match kk {
+                0x07 => self.v[x] = self.delay,
+                0x15 => self.delay = vx,
+                0x18 => self.sound = vx,
+                0x1E => self.i = self.i.wrapping_add(vx as u16),
+                0x33 => {
+                    let i = self.i as usize;
+                    self.write(i, vx / 100);
+                    self.write(i + 1, vx / 10 % 10);
+                    self.write(i + 2, vx % 10);
+                }
+                0x55 | 0x65 => {
+                    for register in 0..=x {
+                        let address = self.i as usize + register;
+                        if kk == 0x55 {
+                            self.write(address, self.v[register]);
+                        } else {
+                            self.v[register] = self.read(address);
+                        }
+                    }
+                    if self.quirks.memory_increments_i {
+                        self.i = self.i.wrapping_add(x as u16 + 1);
+                    }
+                }
+                _ => return Err(Fault::UnknownOpcode),
+            },
+            _ => return Err(Fault::UnknownOpcode),
+        }
+        Ok(())
+    }
+}
+
+fn emulator(quirks: Quirks) -> Emulator {
+    EmulatorBuilder::new()
+        .quirks(quirks)
+        .seed(0)
+        .build()
+        .unwrap()
+}
+
+fn fault(error: EmulatorError) -> Fault {
+    match error {
+        EmulatorError::StackOverflow => Fault::StackOverflow,
+        EmulatorError::StackUnderflow => Fault::StackUnderflow,
+        // Extension opcodes on a machine without the extension.
+        EmulatorError::UnknownOpcode(_) | EmulatorError::Unimplemented(..) => Fault::UnknownOpcode,
+        error => panic!("unexpected error {}", error),
+    }
+}
+
+// First difference between the two machines, if any.
+fn divergence(emulator: &Emulator, reference: &Reference) -> Option<String> {
+    let stack = &emulator.stack[..emulator.stack_pointer as usize];
+    let checks = [
+        ("V registers", emulator.v_registers[..] != reference.v[..]),
+        ("I", emulator.i_register != reference.i),
+        ("PC", emulator.program_counter != reference.pc),
+        ("stack", stack != &reference.stack[..]),
+        (
+            "delay timer",
+            emulator.delay_timer_registry != reference.delay as usize,
+        ),
+        (
+            "sound timer",
+            emulator.sound_timer_registry != reference.sound as usize,
+        ),
+        ("memory", emulator.ram[..] != reference.memory[..]),
+        (
+            "screen",
+            (0..HEIGHT).any(|y| {
+                (0..WIDTH).any(|x| emulator.display.pixel(x, y) != reference.screen[y][x])
+            }),
+        ),
+    ];
+    let differs: Vec<&str> = checks
+        .iter()
+        .filter(|(_, differs)| *differs)
+        .map(|(what, _)| *what)
+        .collect();
+    if differs.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} differ\n  emulator:  V={:02X?} I={:#05x} PC={:#05x} stack={:03X?}\n  reference: V={:02X?} I={:#05x} PC={:#05x} stack={:03X?}",
+        differs.join(", "),
+        emulator.v_registers,
+        emulator.i_register,
+        emulator.program_counter,
+        stack,
+        reference.v,
+        reference.i,
+        reference.pc,
+        reference.stack,
+    ))
+}
+
+// RND and the keyboard opcodes, which the reference does not model.
+fn is_excluded(op: u16) -> bool {
+    op >> 12 == 0xC || op >> 12 == 0xE || op & 0xF0FF == 0xF00A
+}
+
+// Opcodes biased towards the interesting ones: jumps stay inside the
+// snippet, while I and sprite coordinates roam the whole address space and
+// screen so wrapping gets exercised too.
+fn random_op(rng: &mut StdRng) -> u16 {
+    let x = rng.gen_range(0..16u16) << 8;
+    let y = rng.gen_range(0..16u16) << 4;
+    let kk = match rng.gen_range(0..4) {
+        0 => 0x00,
+        1 => 0xFF,
+        _ => rng.gen::<u8>() as u16,
+    };
+    let target = INITIAL_ADDRESS + 2 * rng.gen_range(0..SNIPPET_LEN as u16);
+    match rng.gen_range(0..24) {
+        0 => 0x00E0,
+        1 => 0x00EE,
+        2 => 0x1000 | target,
+        3 => 0x2000 | target,
+        4 => 0x3000 | x | kk,
+        5 => 0x4000 | x | kk,
+        6 => 0x5000 | x | y,
+        7 | 8 => 0x6000 | x | kk,
+        9 => 0x7000 | x | kk,
+        10..=13 => {
+            let n = [0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0xE][rng.gen_range(0..9)];
+            0x8000 | x | y | n
+        }
+        14 => 0x9000 | x | y,
+        15 => 0xA000 | rng.gen_range(0..0x1000u16),
+        16 => 0xB000 | (target - rng.gen_range(0..0x20u16).min(target)),
+        17 => 0xD000 | x | y | rng.gen_range(0..16u16),
+        18 => 0xF007 | x,
+        19 => 0xF015 | x,
+        20 => 0xF018 | x,
+        21 => [0xF01E, 0xF033][rng.gen_range(0..2)] | x,
+        22 => [0xF055, 0xF065][rng.gen_range(0..2)] | x,
+        _ => loop {
+            // Anything, to check both sides reject the same opcodes.
+            let op: u16 = rng.gen();
+            if !is_excluded(op) {
+                break op;
+            }
+        },
+    }
+}
+
+fn fuzz_case(seed: u64, quirks: Quirks) -> Result<(), String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let rom: Vec<u8> = (0..SNIPPET_LEN)
+        .flat_map(|_| random_op(&mut rng).to_be_bytes())
+        .collect();
+
+    let mut emulator = emulator(quirks);
+    emulator.ram[INITIAL_ADDRESS as usize..][..rom.len()].copy_from_slice(&rom);
+    let mut reference = Reference::new(emulator.ram.clone().try_into().unwrap(), quirks);
+
+    for steps in 1..=MAX_STEPS {
+        let pc = emulator.program_counter;
+        let op = u16::from_be_bytes([
+            emulator.ram[pc as usize % RAM_SIZE],
+            emulator.ram[(pc as usize + 1) % RAM_SIZE],
+        ]);
+        if is_excluded(op) {
+            // Reached through a jump into data or code written at runtime.
+            return Ok(());
+        }
+        let theirs = reference.step();
+        // The reference has no notion of frames, so never make DRW wait.
+        emulator.vblank = true;
+        let ours = step(&mut emulator).map_err(fault);
+        let context = || {
+            format!(
+                "seed {} with {} quirks, step {} ({:04X} at {:#05x})",
+                seed, quirks, steps, op, pc
+            )
+        };
+        if ours != theirs {
+            return Err(format!(
+                "{}: emulator returned {:?}, reference {:?}",
+                context(),
+                ours,
+                theirs
+            ));
+        }
+        if ours.is_err() {
+            return Ok(());
+        }
+        if steps % CYCLES_PER_FRAME == 0 {
+            tick_timers(&mut emulator);
+            reference.tick();
+        }
+        if let Some(difference) = divergence(&emulator, &reference) {
+            return Err(format!("{}: {}", context(), difference));
+        }
+    }
+    Ok(())
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a number", name))
+    })
+}
+
+#[test]
+fn emulator_matches_the_reference_interpreter() {
+    let cases = env_u64("CHIP8_FUZZ_CASES").unwrap_or(DEFAULT_CASES);
+    let first_seed = env_u64("CHIP8_FUZZ_SEED").unwrap_or(0);
+    let mut failures = Vec::new();
+    for seed in first_seed..first_seed + cases {
+        for quirks in [Quirks::chip8(), Quirks::schip(), Quirks::xo_chip()] {
+            if let Err(failure) = fuzz_case(seed, quirks) {
+                failures.push(failure);
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} divergences, first ones:\n{}",
+        failures.len(),
+        failures[..failures.len().min(5)].join("\n")
+    );
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chip8_core::disk::{Disk, BLOCK_SIZE, DISK_SIZE};
+use chip8_core::host::Files;
+use chip8_core::savestate::{load, save};
+use chip8_core::{run_frame, EmulatorBuilder, EmulatorError, Instruction, Quirks};
+
+fn assemble(lines: &[&str]) -> Vec<u8> {
+    lines
+        .iter()
+        .flat_map(|line| line.parse::<Instruction>().unwrap().encode().to_be_bytes())
+        .collect()
+}
+
+#[derive(Default)]
+struct MemoryFiles(Mutex<BTreeMap<PathBuf, Vec<u8>>>);
+
+impl Files for MemoryFiles {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let files = self.0.lock().unwrap();
+        files
+            .get(path)
+            .cloned()
+            .ok_or(io::ErrorKind::NotFound.into())
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), data.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn blocks_survive_between_runs() {
+    // Saves V0 and V1 to block 3, then reads the block back at 0x600.
+    let rom = assemble(&[
+        "LD V0, 0xAB",
+        "LD V1, 0xCD",
+        "LD V2, 3",
+        "LD I, 0x500",
+        "LD [I], V1",
+        "DWRITE V2",
+        "LD I, 0x600",
+        "DREAD V2",
+        "JP 0x210",
+    ]);
+    let files = MemoryFiles::default();
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::none())
+        .disk(Disk::load_from(&files, &rom).unwrap())
+        .rom(&rom)
+        .build()
+        .unwrap();
+    assert!(run_frame(&mut emulator).error.is_none());
+    assert_eq!(&emulator.ram[0x600..0x603], &[0xAB, 0xCD, 0]);
+    assert_eq!(emulator.i_register, 0x600);
+
+    let disk = emulator.disk.as_mut().unwrap();
+    assert!(disk.is_dirty());
+    assert_eq!(&disk.as_bytes()[3 * BLOCK_SIZE..][..2], &[0xAB, 0xCD]);
+    disk.save_to(&files, &rom).unwrap();
+    assert!(!disk.is_dirty());
+
+    let disk = Disk::load_from(&files, &rom).unwrap();
+    assert_eq!(disk.block(3).unwrap()[..2], [0xAB, 0xCD]);
+    // Another ROM gets a blank disk of its own.
+    let other = Disk::load_from(&files, &assemble(&["CLS"])).unwrap();
+    assert_eq!(other, Disk::new());
+}
+
+#[test]
+fn the_disk_has_hard_limits() {
+    let rom = assemble(&["LD V0, 16", "DREAD V0"]);
+    let mut emulator = EmulatorBuilder::new()
+        .disk(Disk::new())
+        .rom(&rom)
+        .build()
+        .unwrap();
+    assert_eq!(
+        run_frame(&mut emulator).error,
+        Some(EmulatorError::DiskBlockOutOfRange(16))
+    );
+
+    let mut emulator = EmulatorBuilder::new().rom(&rom).build().unwrap();
+    assert_eq!(
+        run_frame(&mut emulator).error,
+        Some(EmulatorError::Unimplemented(0xF0F2, "a disk"))
+    );
+
+    assert!(Disk::from_bytes(&[1; DISK_SIZE]).is_ok());
+    assert!(Disk::from_bytes(&[1; DISK_SIZE + 1]).is_err());
+    assert!(Disk::path(&rom).starts_with(chip8_core::config::config_dir().join("disks")));
+}
+
+#[test]
+fn savestates_keep_unsaved_blocks() {
+    let rom = assemble(&["LD V0, 1", "DWRITE V0", "JP 0x204"]);
+    let mut emulator = EmulatorBuilder::new()
+        .disk(Disk::new())
+        .rom(&rom)
+        .memory(0, &[9; 4])
+        .build()
+        .unwrap();
+    run_frame(&mut emulator);
+    let restored = load(&save(&emulator)).unwrap();
+    assert_eq!(restored.disk, emulator.disk);
+    assert!(restored.disk.unwrap().is_dirty());
+}
@@ -0,0 +1,54 @@
+use chip8_core::dump::{debug_pane, disassemble, hexdump, parse_hex_bytes, poke};
+use chip8_core::{EmulatorBuilder, RAM_SIZE};
+
+#[test]
+fn hexdumps_paste_back() {
+    let mut ram = [0; RAM_SIZE];
+    let bytes: Vec<u8> = (0..40).collect();
+    poke(&mut ram, 0xFF0, &bytes);
+    assert_eq!(ram[0x000], 16);
+
+    let text = hexdump(&ram, 0xFF0, bytes.len());
+    assert!(text.starts_with("0xff0: 00 01 02"));
+    assert!(text.contains("\n0x000: 10 11"));
+    assert_eq!(parse_hex_bytes(&text), Ok(bytes));
+}
+
+#[test]
+fn parses_loose_hex() {
+    assert_eq!(
+        parse_hex_bytes("0x6A, 42 f033"),
+        Ok(vec![0x6A, 0x42, 0xF0, 0x33])
+    );
+    assert!(parse_hex_bytes("6A4").is_err());
+    assert!(parse_hex_bytes("zz").is_err());
+}
+
+#[test]
+fn disassembles_ranges() {
+    let mut ram = [0; RAM_SIZE];
+    poke(&mut ram, 0x200, &[0x6A, 0x02, 0x00, 0xE0]);
+    assert_eq!(
+        disassemble(&ram, 0x200, 2),
+        "0x200  6A02  LD VA, 0x02\n0x202  00E0  CLS\n"
+    );
+}
+
+#[test]
+fn debug_panes_follow_the_program_counter() {
+    let emulator = EmulatorBuilder::new()
+        .seed(0)
+        .rom(&[0x60, 0x01, 0xA3, 0x00, 0x12, 0x00])
+        .build()
+        .unwrap();
+    let disassembly = debug_pane(&emulator, "disassembly").unwrap();
+    assert!(disassembly.contains("> 0x200  6001  LD V0, 0x01\n"));
+    assert!(disassembly.contains("  0x202  A300"));
+    assert!(debug_pane(&emulator, "registers")
+        .unwrap()
+        .contains("PC=0x200"));
+    assert!(debug_pane(&emulator, "memory")
+        .unwrap()
+        .starts_with("0x000: "));
+    assert_eq!(debug_pane(&emulator, "stack"), None);
+}
@@ -0,0 +1,69 @@
+use chip8_core::endstate::{Classifier, EndState};
+use chip8_core::{load_rom_to_memory, run_frame, EmulatorBuilder, EmulatorError, Quirks};
+
+fn classify(rom: &[u8], frames: u32, classifier: &mut Classifier) -> EndState {
+    let mut emulator = EmulatorBuilder::new()
+        // One sprite per frame would leave the moving example blank at the end
+        // of every frame, after its CLS.
+        .quirks(Quirks {
+            display_wait: false,
+            ..Quirks::default()
+        })
+        .seed(0)
+        .build()
+        .unwrap();
+    load_rom_to_memory(&mut emulator, rom).unwrap();
+    for _ in 0..frames {
+        let output = run_frame(&mut emulator);
+        classifier.observe(&emulator, &output);
+        if output.error.is_some() {
+            break;
+        }
+    }
+    classifier.classify()
+}
+
+#[test]
+fn classifies_common_end_states() {
+    // LD I, 0x206; DRW V0, V1, 1; JP 0x204; sprite
+    let halts = [0xA2, 0x06, 0xD0, 0x11, 0x12, 0x04, 0xFF];
+    assert_eq!(
+        classify(&halts, 30, &mut Classifier::new()),
+        EndState::Halted
+    );
+
+    // LD I, 0x206; DRW V0, V1, 1; LD V0, K; sprite
+    let waits = [0xA2, 0x06, 0xD0, 0x11, 0xF0, 0x0A, 0xFF];
+    assert_eq!(
+        classify(&waits, 30, &mut Classifier::new()),
+        EndState::StuckAtTitle
+    );
+
+    // LD I, 0x20a; CLS; DRW V0, V1, 1; ADD V0, 1; JP 0x202; sprite
+    let moves = [
+        0xA2, 0x0A, 0x00, 0xE0, 0xD0, 0x11, 0x70, 0x01, 0x12, 0x02, 0xFF,
+    ];
+    assert_eq!(
+        classify(&moves, 30, &mut Classifier::new()),
+        EndState::ReachedGameplay
+    );
+
+    assert_eq!(
+        classify(&[0xFF, 0xFF], 30, &mut Classifier::new()),
+        EndState::Crashed(EmulatorError::UnknownOpcode(0xFFFF))
+    );
+    assert_eq!(
+        classify(&[0x12, 0x00], 30, &mut Classifier::new()),
+        EndState::NeverDrew
+    );
+}
+
+#[test]
+fn hooks_override_the_heuristics() {
+    let moves = [
+        0xA2, 0x0A, 0x00, 0xE0, 0xD0, 0x11, 0x70, 0x01, 0x12, 0x02, 0xFF,
+    ];
+    let mut classifier = Classifier::new();
+    classifier.hook(|emulator| (emulator.v_registers[0] >= 40).then_some(EndState::GameOver));
+    assert_eq!(classify(&moves, 30, &mut classifier), EndState::GameOver);
+}
@@ -0,0 +1,69 @@
+use chip8_core::config::RomConfig;
+use chip8_core::font::{Font, FONT_ADDRESS, LARGE_FONT_ADDRESS, NAMES};
+use chip8_core::host::StdFiles;
+use chip8_core::EmulatorBuilder;
+
+#[test]
+fn the_font_is_in_the_interpreter_area() {
+    let emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    let font = Font::default();
+    let start = FONT_ADDRESS as usize;
+    assert_eq!(
+        &emulator.ram[start..start + 5],
+        [0xF0, 0x90, 0x90, 0x90, 0xF0]
+    );
+    assert_eq!(
+        &emulator.ram[start + 0xF * 5..start + 0x50],
+        font.digit(0xF)
+    );
+    let large = LARGE_FONT_ADDRESS as usize;
+    assert_eq!(&emulator.ram[large..large + 100], font.large);
+}
+
+#[test]
+fn fonts_can_be_replaced() {
+    let vip: Font = "vip".parse().unwrap();
+    let emulator = EmulatorBuilder::new()
+        .seed(0)
+        .font(vip.clone())
+        .build()
+        .unwrap();
+    let start = FONT_ADDRESS as usize;
+    assert_eq!(&emulator.ram[start..start + 80], vip.small);
+    // The VIP's 7 is straight down.
+    assert_eq!(vip.digit(7), [0xF0, 0x10, 0x10, 0x10, 0x10]);
+
+    for name in NAMES {
+        assert!(name.parse::<Font>().is_ok(), "{}", name);
+    }
+    assert!("comic"
+        .parse::<Font>()
+        .unwrap_err()
+        .contains("expected chip48"));
+}
+
+#[test]
+fn reads_font_files() {
+    let small = [0xAA; 80];
+    let font = Font::from_bytes(&small).unwrap();
+    assert_eq!(font.small, small);
+    assert_eq!(font.large, Font::default().large);
+
+    let mut both = vec![0x11; 80];
+    both.extend([0x22; 100]);
+    let font = Font::from_bytes(&both).unwrap();
+    assert_eq!(font.large, [0x22; 100]);
+
+    assert_eq!(
+        Font::from_bytes(&[0; 81]).unwrap_err(),
+        "a font is 80 or 180 bytes, not 81"
+    );
+    assert!(Font::open(&StdFiles, "/nonexistent.font").is_err());
+}
+
+#[test]
+fn roms_remember_their_font() {
+    let config = RomConfig::parse("font = dream6800\n").unwrap();
+    assert_eq!(config.font.as_deref(), Some("dream6800"));
+    assert_eq!(RomConfig::parse(&config.to_text()).unwrap(), config);
+}
@@ -0,0 +1,104 @@
+// Plays the ROMs of tests/golden/corpus.txt with their recorded input and
+// compares the machine state every 60 frames with the checksums recorded
+// when they last played right, so any change in behaviour shows up. Only
+// with `--features rom-tests`. After a deliberate change, run with
+// CHIP8_BLESS=1 to record the checksums again and review their diff.
+#![cfg(feature = "rom-tests")]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chip8_core::checksum::ChecksumLog;
+use chip8_core::input::{InputScript, InputSource};
+use chip8_core::{run_frame, EmulatorBuilder};
+
+const INTERVAL: u64 = 60;
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn rom_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("../../roms/{}.ch8", name))
+}
+
+// The name and frame count of every corpus entry.
+fn corpus() -> Vec<(String, u64)> {
+    let text = fs::read_to_string(golden_dir().join("corpus.txt")).unwrap();
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, frames) = line.split_once(' ').expect("expected `name frames`");
+            (name.to_string(), frames.trim().parse().unwrap())
+        })
+        .collect()
+}
+
+// The checksums of one play through, from a fixed seed.
+fn play(name: &str, frames: u64) -> Result<ChecksumLog, String> {
+    let data = fs::read(rom_path(name)).map_err(|error| error.to_string())?;
+    let input = fs::read_to_string(golden_dir().join(format!("{}.input", name)))
+        .map_err(|error| error.to_string())?;
+    let mut script = InputScript::parse(&input)?;
+    let mut emulator = EmulatorBuilder::new()
+        .seed(0)
+        .rom(&data)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let mut log = ChecksumLog::new(INTERVAL);
+    for frame in 1..=frames {
+        script.poll(&mut emulator.keypad);
+        if let Some(error) = run_frame(&mut emulator).error {
+            return Err(format!("frame {}: {}", frame, error));
+        }
+        log.record(frame, &emulator);
+    }
+    Ok(log)
+}
+
+#[test]
+fn the_corpus_plays_as_recorded() {
+    let bless = env::var_os("CHIP8_BLESS").is_some();
+    let mut failures = Vec::new();
+    for (name, frames) in corpus() {
+        let path = golden_dir().join(format!("{}.checksums", name));
+        let actual = match play(&name, frames) {
+            Ok(log) => log,
+            Err(error) => {
+                failures.push(format!("{}: {}", name, error));
+                continue;
+            }
+        };
+        if bless {
+            fs::write(&path, actual.to_text()).unwrap();
+            continue;
+        }
+        let expected = match fs::read_to_string(&path) {
+            Ok(text) => ChecksumLog::parse(&text).unwrap(),
+            Err(error) => {
+                failures.push(format!("{}: {}: {}", name, path.display(), error));
+                continue;
+            }
+        };
+        // The first frame that differs, later ones only follow from it.
+        let desync = (INTERVAL..=frames)
+            .step_by(INTERVAL as usize)
+            .find_map(|frame| {
+                let actual = actual.get(frame)?;
+                expected.check_checksum(frame, actual).err()
+            });
+        if let Some(desync) = desync {
+            failures.push(format!("{}: {}", name, desync));
+        } else if expected.len() != actual.len() {
+            failures.push(format!(
+                "{}: {} checksums recorded, {} taken",
+                name,
+                expected.len(),
+                actual.len()
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,35 @@
+use chip8_core::heatmap::Heatmap;
+use chip8_core::{load_rom_to_memory, EmulatorBuilder, Quirks};
+
+#[test]
+fn counts_reads_writes_and_executes() {
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks {
+            memory_increments_i: false,
+            display_wait: false,
+            ..Quirks::default()
+        })
+        .seed(0)
+        .build()
+        .unwrap();
+    // LD I, 0x300; LD B, V0; LD V1, [I]; DRW V0, V0, 4
+    load_rom_to_memory(
+        &mut emulator,
+        &[0xA3, 0x00, 0xF0, 0x33, 0xF1, 0x65, 0xD0, 0x04],
+    )
+    .unwrap();
+
+    let mut heatmap = Heatmap::new();
+    for _ in 0..4 {
+        heatmap.step(&mut emulator).unwrap();
+    }
+    assert!(heatmap.executes[0x200..0x208]
+        .iter()
+        .all(|&count| count == 1));
+    assert_eq!(&heatmap.writes[0x300..0x304], &[1, 1, 1, 0]);
+    assert_eq!(&heatmap.reads[0x300..0x305], &[2, 2, 1, 1, 0]);
+
+    let pixels = heatmap.to_rgba();
+    assert_eq!(pixels.len(), 64 * 64 * 4);
+    assert_eq!(&pixels[0x300 * 4..0x300 * 4 + 4], &[0xFF, 0xFF, 0, 0xFF]);
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+
+use chip8_core::{step, Emulator, EmulatorBuilder, EmulatorError, ExecutionHook, Instruction};
+
+#[derive(Default)]
+struct Log(Arc<Mutex<Vec<String>>>);
+
+impl ExecutionHook for Log {
+    fn before_step(&mut self, emulator: &mut Emulator, instruction: Instruction) {
+        let pc = emulator.program_counter - 2;
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{:#x} {}", pc, instruction));
+    }
+
+    fn on_memory_write(&mut self, address: u16, value: u8) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("[{:#x}] = {}", address, value));
+    }
+
+    fn on_draw(&mut self, x: usize, y: usize, sprite: &[u8], collision: bool) {
+        let line = format!("draw {} rows at {},{} {}", sprite.len(), x, y, collision);
+        self.0.lock().unwrap().push(line);
+    }
+}
+
+// Keeps V0 at 99, whatever the ROM does.
+struct Cheat;
+
+impl ExecutionHook for Cheat {
+    fn after_step(
+        &mut self,
+        emulator: &mut Emulator,
+        _: Instruction,
+        _: &Result<(), EmulatorError>,
+    ) {
+        emulator.v_registers[0] = 99;
+    }
+}
+
+fn emulator(rom: &[u8]) -> Emulator {
+    EmulatorBuilder::new().seed(0).rom(rom).build().unwrap()
+}
+
+#[test]
+fn hooks_see_every_instruction_write_and_draw() {
+    // LD V0, 123; LD I, 0x300; LD B, V0; DRW V0, V0, 2
+    let mut emulator = emulator(&[0x60, 0x7B, 0xA3, 0x00, 0xF0, 0x33, 0xD0, 0x02]);
+    emulator.quirks.display_wait = false;
+    let log = Log::default();
+    let lines = log.0.clone();
+    emulator.hooks.add(log);
+    for _ in 0..4 {
+        step(&mut emulator).unwrap();
+    }
+    assert_eq!(
+        *lines.lock().unwrap(),
+        [
+            "0x200 LD V0, 0x7b",
+            "0x202 LD I, 0x300",
+            "0x204 LD B, V0",
+            "[0x300] = 1",
+            "[0x301] = 2",
+            "[0x302] = 3",
+            "0x206 DRW V0, V0, 2",
+            "draw 2 rows at 123,123 false",
+        ]
+    );
+}
+
+#[test]
+fn hooks_can_change_the_machine() {
+    // LD V0, 5; ADD V0, 1
+    let mut emulator = emulator(&[0x60, 0x05, 0x70, 0x01]);
+    emulator.hooks.add(Cheat);
+    step(&mut emulator).unwrap();
+    assert_eq!(emulator.v_registers[0], 99);
+    step(&mut emulator).unwrap();
+    assert_eq!(emulator.v_registers[0], 99);
+    assert_eq!(emulator.hooks.len(), 1);
+
+    // A copy of the machine does not inherit them.
+    assert!(emulator.clone().hooks.is_empty());
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use chip8_core::config::RomConfig;
+use chip8_core::font::Font;
+use chip8_core::host::{Clock, Files, FixedClock, MemoryFiles};
+
+#[test]
+fn runs_without_a_file_system() {
+    let files = MemoryFiles::new();
+    let rom = [0x12, 0x00];
+    assert_eq!(
+        RomConfig::load_from(&files, &rom).unwrap(),
+        RomConfig::default()
+    );
+
+    files
+        .write(&RomConfig::path(&rom), b"font = vip\n")
+        .unwrap();
+    let config = RomConfig::load_from(&files, &rom).unwrap();
+    assert_eq!(config.font.as_deref(), Some("vip"));
+
+    files.write(Path::new("small.font"), &[0x0F; 80]).unwrap();
+    assert_eq!(Font::open(&files, "small.font").unwrap().small, [0x0F; 80]);
+    assert!(Font::open(&files, "missing.font").is_err());
+    assert_eq!(files.paths().len(), 2);
+}
+
+#[test]
+fn clocks_can_be_fixed() {
+    let clock: &dyn Clock = &FixedClock(1_700_000_000);
+    assert_eq!(clock.unix_seconds(), 1_700_000_000);
+}
@@ -0,0 +1,43 @@
+use chip8_core::hotkeys::{Action, Hotkeys};
+
+#[test]
+fn default_bindings_map_to_their_actions() {
+    let hotkeys = Hotkeys::default();
+    let bindings = [
+        ("F5", Action::SaveState(0)),
+        ("F8", Action::SaveState(3)),
+        ("Shift+F5", Action::LoadState(0)),
+        ("Shift+F8", Action::LoadState(3)),
+        ("Tab", Action::ToggleTurbo),
+        ("P", Action::TogglePause),
+        ("N", Action::Step),
+        ("F2", Action::Reset),
+        ("F12", Action::Screenshot),
+        ("Escape", Action::Quit),
+    ];
+    for (chord, action) in bindings {
+        assert_eq!(hotkeys.action_for(chord), Some(action), "{}", chord);
+    }
+    // Any case, modifiers in any order.
+    assert_eq!(hotkeys.action_for("f6+SHIFT"), Some(Action::LoadState(1)));
+}
+
+#[test]
+fn unbound_keys_pass_through() {
+    let mut hotkeys = Hotkeys::default();
+    // The keypad keys are left to the ROM.
+    for chord in ["1", "Q", "V", "Ctrl+F5", "F9"] {
+        assert_eq!(hotkeys.action_for(chord), None, "{}", chord);
+    }
+    hotkeys.unbind("escape");
+    assert_eq!(hotkeys.action_for("Escape"), None);
+}
+
+#[test]
+fn bindings_read_back_from_their_config() {
+    let hotkeys = Hotkeys::parse("# mine\n\nctrl+s = save_state 2\nq = quit").unwrap();
+    assert_eq!(hotkeys.action_for("Ctrl+S"), Some(Action::SaveState(2)));
+    assert_eq!(Hotkeys::parse(&hotkeys.to_config()), Ok(hotkeys));
+    assert!(Hotkeys::parse("f5 = save_state").is_err());
+    assert!(Hotkeys::parse("f5").is_err());
+}
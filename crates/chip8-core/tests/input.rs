@@ -0,0 +1,80 @@
+use chip8_core::input::{ChatVotes, InputScript, InputSource, VoteConfig, VoteRejected};
+use chip8_core::Keypad;
+
+fn run_frames(votes: &mut ChatVotes, keypad: &mut Keypad, frames: u32) {
+    for _ in 0..frames {
+        votes.poll(keypad);
+        // A frame of instructions, so queued events apply.
+        for _ in 0..10 {
+            keypad.tick(1);
+        }
+    }
+}
+
+#[test]
+fn the_most_voted_key_is_pressed_each_window() {
+    let config = VoteConfig {
+        window_frames: 4,
+        hold_frames: 2,
+        cooldown_frames: 4,
+    };
+    let mut votes = ChatVotes::new(config);
+    let mut keypad = Keypad::new();
+    assert_eq!(votes.vote("ana", "!5"), Ok(5));
+    assert_eq!(votes.vote("bo", "a"), Ok(0xA));
+    assert_eq!(votes.vote("cy", " A "), Ok(0xA));
+    assert_eq!(
+        votes.vote("dee", "hello chat"),
+        Err(VoteRejected::NotACommand)
+    );
+    assert_eq!(votes.vote("ana", "a"), Err(VoteRejected::TooSoon));
+    assert_eq!(votes.tally()[0xA], 2);
+
+    run_frames(&mut votes, &mut keypad, 3);
+    assert_eq!(keypad.state(), 0);
+    run_frames(&mut votes, &mut keypad, 1);
+    assert!(keypad.is_pressed(0xA));
+    assert_eq!(votes.tally(), [0; 16]);
+    run_frames(&mut votes, &mut keypad, 2);
+    assert_eq!(keypad.state(), 0);
+
+    // The cooldown is over, and an empty window presses nothing.
+    assert_eq!(votes.vote("ana", "3"), Ok(3));
+    run_frames(&mut votes, &mut keypad, 2);
+    assert!(keypad.is_pressed(3));
+    run_frames(&mut votes, &mut keypad, 4);
+    assert_eq!(keypad.state(), 0);
+}
+
+#[test]
+fn input_scripts_press_and_release_keys_on_their_frames() {
+    let text = "# start\n2 5 down\n3 5 up\n3 a down # and hold\n";
+    let mut script = InputScript::parse(text).unwrap();
+    assert_eq!(script.length(), 3);
+    assert_eq!(
+        InputScript::parse(&script.to_text()).unwrap().to_text(),
+        "2 5 down\n3 5 up\n3 A down\n"
+    );
+
+    let mut keypad = Keypad::new();
+    let mut pressed = Vec::new();
+    for _ in 0..3 {
+        script.poll(&mut keypad);
+        for _ in 0..10 {
+            keypad.tick(1);
+        }
+        pressed.push(keypad.state());
+    }
+    assert_eq!(pressed, [0, 1 << 5, 1 << 0xA]);
+    assert!(script.is_finished());
+
+    script.rewind();
+    assert!(!script.is_finished());
+
+    assert_eq!(
+        InputScript::parse("1 G down"),
+        Err("line 1: the key is a hexadecimal digit".to_string())
+    );
+    assert!(InputScript::parse("1 5 sideways").is_err());
+    assert!(InputScript::parse("5 down").is_err());
+}
@@ -0,0 +1,57 @@
+use chip8_core::instruction::{find, Variant, INSTRUCTIONS};
+use chip8_core::Instruction;
+
+#[test]
+fn every_opcode_round_trips() {
+    for op_code in 0..=u16::MAX {
+        assert_eq!(Instruction::decode(op_code).encode(), op_code);
+    }
+}
+
+#[test]
+fn every_pattern_is_documented_once() {
+    for info in INSTRUCTIONS {
+        let zeroed: String = info
+            .pattern
+            .chars()
+            .map(|c| if "XYNK".contains(c) { '0' } else { c })
+            .collect();
+        let op_code = u16::from_str_radix(&zeroed, 16).unwrap();
+        let instruction = match info.variant {
+            Variant::Chip8x => Instruction::decode_chip8x(op_code),
+            _ => Instruction::decode(op_code),
+        };
+        assert_eq!(instruction.describe(), info, "{}", info.pattern);
+        assert_eq!(find(&info.pattern.to_lowercase()), Some(info));
+    }
+}
+
+#[test]
+fn disassembles_operands() {
+    assert_eq!(Instruction::decode(0x8A36).to_string(), "SHR VA, V3");
+    assert_eq!(Instruction::decode(0xD125).to_string(), "DRW V1, V2, 5");
+    assert_eq!(Instruction::decode(0xA2F0).to_string(), "LD I, 0x2f0");
+    assert_eq!(Instruction::decode(0x5122).to_string(), "DW 0x5122");
+}
+
+#[test]
+fn disassembly_assembles_back() {
+    for op_code in 0..=u16::MAX {
+        let instruction = Instruction::decode(op_code);
+        assert_eq!(instruction.to_string().parse(), Ok(instruction));
+    }
+}
+
+#[test]
+fn assembler_accepts_other_number_forms_and_rejects_bad_operands() {
+    assert_eq!(
+        "ld va, #1f".parse(),
+        Ok(Instruction::LdByte { x: 0xA, kk: 0x1F })
+    );
+    assert_eq!("JP 512".parse(), Ok(Instruction::Jp { nnn: 0x200 }));
+    assert_eq!("SHL V3".parse(), Ok(Instruction::Shl { x: 3, y: 3 }));
+    assert!("LD V0, 256".parse::<Instruction>().is_err());
+    assert!("DRW V0, V1, 16".parse::<Instruction>().is_err());
+    assert!("ADD I, 5".parse::<Instruction>().is_err());
+    assert!("FOO V0".parse::<Instruction>().is_err());
+}
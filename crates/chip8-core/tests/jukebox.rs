@@ -0,0 +1,94 @@
+use chip8_core::audio::{write_wav, PatternVoice, DEFAULT_VOLUME};
+use chip8_core::instruction::Variant;
+use chip8_core::jukebox::{Jukebox, TrackEnd};
+use chip8_core::xochip::{playback_rate, DEFAULT_PITCH};
+use chip8_core::{step, Emulator, EmulatorBuilder, EmulatorError, Instruction};
+
+fn xochip(rom: &[u8]) -> Emulator {
+    EmulatorBuilder::new()
+        .seed(0)
+        .variant(Variant::XoChip)
+        .rom(rom)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn audio_and_pitch_set_the_pattern() {
+    // LD I, 0x300; AUDIO; LD V1, 112; PITCH V1
+    let mut emulator = xochip(&[0xA3, 0x00, 0xF0, 0x02, 0x61, 0x70, 0xF1, 0x3A]);
+    emulator.ram[0x300..0x310].copy_from_slice(&[0xAA; 16]);
+    assert_eq!(emulator.xochip.as_ref().unwrap().pitch, DEFAULT_PITCH);
+    for _ in 0..4 {
+        step(&mut emulator).unwrap();
+    }
+    let xochip = emulator.xochip.as_ref().unwrap();
+    assert_eq!(xochip.pattern, Some([0xAA; 16]));
+    assert_eq!(xochip.pitch, 112);
+    assert_eq!(playback_rate(64), 4000.0);
+    assert_eq!(xochip.playback_rate(), 8000.0);
+
+    assert_eq!(Instruction::decode(0xF002).to_string(), "AUDIO");
+    assert_eq!("PITCH V1".parse(), Ok(Instruction::Pitch { x: 1 }));
+
+    // Other machines have no audio pattern.
+    let mut emulator = EmulatorBuilder::new().rom(&[0xF0, 0x02]).build().unwrap();
+    assert_eq!(
+        step(&mut emulator),
+        Err(EmulatorError::Unimplemented(0xF002, "XO-CHIP"))
+    );
+}
+
+#[test]
+fn pattern_plays_one_bit_per_sample_at_the_pitch_rate() {
+    let mut pattern = [0; 16];
+    pattern[..8].copy_from_slice(&[0xFF; 8]);
+    let mut voice = PatternVoice::new(4000);
+    let mut samples = [0.0; 256];
+    voice.fill(&mut samples, &pattern, DEFAULT_PITCH);
+    assert!(samples[..64].iter().all(|&s| s == DEFAULT_VOLUME));
+    assert!(samples[64..128].iter().all(|&s| s == -DEFAULT_VOLUME));
+    assert_eq!(samples[..128], samples[128..]);
+}
+
+#[test]
+fn wav_files_are_16_bit_mono() {
+    let mut wav = Vec::new();
+    write_wav(&mut wav, 44_100, &[0.0, 1.0, -1.0]).unwrap();
+    assert_eq!(wav.len(), 44 + 6);
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 44_100);
+    assert_eq!(&wav[44..], [0x00, 0x00, 0xFF, 0x7F, 0x01, 0x80]);
+}
+
+#[test]
+fn tracks_end_when_they_go_silent_or_loop() {
+    // LD I, 0x300; AUDIO; LD V0, 30; LD ST, V0; JP 0x208
+    let rom = [0xA3, 0x00, 0xF0, 0x02, 0x60, 0x1E, 0xF0, 0x18, 0x12, 0x08];
+    let mut jukebox = Jukebox::new(xochip(&rom), 6000, 3.0);
+    let mut samples = Vec::new();
+    let end = loop {
+        if let Some(end) = jukebox.play_frame(&mut samples) {
+            break end;
+        }
+    };
+    // Jumping to itself once the timer ran out, the ROM never plays again.
+    // The frame that sets the timer ticks it, 29 frames sound.
+    assert_eq!(end, TrackEnd::Silence);
+    assert_eq!(jukebox.track_seconds(), 29.0 / 60.0);
+    assert_eq!(samples.len(), jukebox.frame() as usize * 100);
+
+    // The same, starting over every time the beep ends.
+    // LD V0, 30; LD ST, V0; LD DT, V0; LD V1, DT; SE V1, 0; JP 0x206; JP 0x200
+    let rom = [
+        0x60, 0x1E, 0xF0, 0x18, 0xF0, 0x15, 0xF1, 0x07, 0x31, 0x00, 0x12, 0x06, 0x12, 0x00,
+    ];
+    let mut jukebox = Jukebox::new(xochip(&rom), 6000, 3.0);
+    let end = loop {
+        if let Some(end) = jukebox.play_frame(&mut Vec::new()) {
+            break end;
+        }
+    };
+    assert!(matches!(end, TrackEnd::Loop { .. }), "{:?}", end);
+}
@@ -0,0 +1,70 @@
+use chip8_core::keypad::{KeyEvent, Keypad};
+use chip8_core::{run_frame, step, EmulatorBuilder};
+
+#[test]
+fn taps_between_frames_last_a_frame() {
+    let mut keypad = Keypad::new();
+    keypad.press(5);
+    keypad.release(5);
+    keypad.tick(10);
+    assert!(keypad.is_pressed(5));
+    for _ in 0..9 {
+        keypad.tick(10);
+    }
+    assert!(keypad.is_pressed(5));
+    keypad.tick(10);
+    assert!(!keypad.is_pressed(5));
+    assert_eq!(keypad.pending(), 0);
+
+    // Later events wait for the release in front of them.
+    keypad.press(1);
+    keypad.release(1);
+    keypad.push(KeyEvent {
+        key: 2,
+        pressed: true,
+        at: 0,
+    });
+    keypad.tick(10);
+    assert_eq!(keypad.state(), 1 << 1);
+}
+
+#[test]
+fn skips_on_keys_and_waits_for_a_release() {
+    // 0x200 SKP V0; 0x202 LD V1, 1; 0x204 LD V2, K; 0x206 JP 0x206
+    let mut emulator = EmulatorBuilder::new()
+        .rom(&[0xE0, 0x9E, 0x61, 0x01, 0xF2, 0x0A, 0x12, 0x06])
+        .build()
+        .unwrap();
+    emulator.keypad.press(0);
+    step(&mut emulator).unwrap();
+    assert_eq!(emulator.program_counter, 0x204);
+
+    // Key 0 was pressed before Fx0A started waiting, so it does not count.
+    run_frame(&mut emulator);
+    assert_eq!(emulator.program_counter, 0x204);
+    emulator.keypad.release(0);
+    emulator.keypad.press(0xB);
+    run_frame(&mut emulator);
+    assert_eq!(emulator.program_counter, 0x204);
+    emulator.keypad.release(0xB);
+    run_frame(&mut emulator);
+    run_frame(&mut emulator);
+    assert_eq!(emulator.v_registers[2], 0xB);
+    assert_eq!(emulator.program_counter, 0x206);
+}
+
+#[test]
+fn frames_tell_when_fx0a_is_waiting() {
+    // 0x200 LD V0, K; 0x202 JP 0x202
+    let mut emulator = EmulatorBuilder::new()
+        .rom(&[0xF0, 0x0A, 0x12, 0x02])
+        .build()
+        .unwrap();
+    assert!(run_frame(&mut emulator).waiting_for_key);
+    emulator.keypad.press(7);
+    emulator.keypad.release(7);
+    assert!(run_frame(&mut emulator).waiting_for_key);
+    assert!(!run_frame(&mut emulator).waiting_for_key);
+    assert_eq!(emulator.v_registers[0], 7);
+    assert!(!run_frame(&mut emulator).waiting_for_key);
+}
@@ -0,0 +1,82 @@
+use chip8_core::config::RomConfig;
+use chip8_core::dump::disassemble_labeled;
+use chip8_core::labels::Labels;
+use chip8_core::trace::Trace;
+use chip8_core::watch::Expression;
+use chip8_core::{step, EmulatorBuilder, Instruction};
+
+#[test]
+fn names_are_checked_and_follow_their_address() {
+    let mut labels = Labels::new();
+    labels.add(0x3A0, "ball_x").unwrap();
+    labels.add(0x3A1, "_y2").unwrap();
+    for name in ["", "2x", "ball-x", "V3", "vf", "pc", "RAM"] {
+        assert!(labels.add(0x300, name).is_err(), "{}", name);
+    }
+    // Naming the address again renames it, reusing the name moves it.
+    labels.add(0x3A0, "paddle").unwrap();
+    assert_eq!(labels.address("ball_x"), None);
+    labels.add(0x3A2, "paddle").unwrap();
+    assert_eq!(labels.name(0x3A0), None);
+    assert_eq!(
+        labels.iter().collect::<Vec<_>>(),
+        [(0x3A1, "_y2"), (0x3A2, "paddle")]
+    );
+    assert_eq!(labels.remove("_y2"), Some(0x3A1));
+    assert_eq!(labels.len(), 1);
+}
+
+#[test]
+fn disassembly_and_traces_show_the_names() {
+    let mut labels = Labels::new();
+    labels.add(0x200, "main").unwrap();
+    labels.add(0x3A0, "ball_x").unwrap();
+    let rom: Vec<u8> = ["LD I, 0x3A0", "ADD V0, 1", "JP 0x200"]
+        .iter()
+        .flat_map(|line| line.parse::<Instruction>().unwrap().encode().to_be_bytes())
+        .collect();
+    let mut emulator = EmulatorBuilder::new().rom(&rom).build().unwrap();
+    assert_eq!(
+        disassemble_labeled(&emulator.ram, 0x200, 3, &labels),
+        "0x200  A3A0  LD I, 0x3a0  ; main, -> ball_x\n\
+         0x202  7001  ADD V0, 0x01\n\
+         0x204  1200  JP 0x200  ; -> main\n"
+    );
+
+    let mut trace = Trace::new(2);
+    for _ in 0..3 {
+        trace.record(&emulator);
+        step(&mut emulator).unwrap();
+    }
+    assert_eq!(
+        trace.to_text_labeled(&labels),
+        "0x202  7001  ADD V0, 0x01\n0x204  1200  JP 0x200  ; -> main\n"
+    );
+}
+
+#[test]
+fn the_rom_config_keeps_labels_for_triggers_and_watches() {
+    let mut labels = Labels::new();
+    labels.add(0x3A0, "score").unwrap();
+    let mut config =
+        RomConfig::parse("label.old = 0x300\ntrigger.win = RAM[score] >= 3 | won").unwrap();
+    config.set_labels(&labels);
+    let text = config.to_text();
+    assert!(text.contains("label.score = 0x3a0\n"));
+    assert!(!text.contains("label.old"));
+
+    let config = RomConfig::parse(&text).unwrap();
+    let labels = config.labels().unwrap();
+    assert_eq!(labels.address("score"), Some(0x3A0));
+    let mut triggers = config.triggers().unwrap();
+    let emulator = EmulatorBuilder::new().memory(0x3A0, &[3]).build().unwrap();
+    assert_eq!(triggers.check(&emulator).len(), 1);
+
+    let expression = Expression::parse_with("RAM[score] * 2", &labels).unwrap();
+    assert_eq!(expression.eval(&emulator), Some(6));
+    assert!("RAM[score]".parse::<Expression>().is_err());
+    assert!(RomConfig::parse("label.x = 0x10000")
+        .unwrap()
+        .labels()
+        .is_err());
+}
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use chip8_core::latency::{probe_emulator, LatencyProbe, Summary};
+use chip8_core::run_frame;
+
+#[test]
+fn the_probe_flashes_the_whole_screen_in_the_frame_a_key_goes_down() {
+    let mut emulator = probe_emulator().unwrap();
+    run_frame(&mut emulator);
+    assert!(!run_frame(&mut emulator).display_changed);
+
+    emulator.keypad.press(0xB);
+    assert!(run_frame(&mut emulator).display_changed);
+    assert!(emulator
+        .display
+        .iter_rows()
+        .all(|row| row.iter().all(|on| on)));
+    // Held down, it stays lit.
+    assert!(!run_frame(&mut emulator).display_changed);
+
+    emulator.keypad.release(0xB);
+    run_frame(&mut emulator);
+    run_frame(&mut emulator);
+    assert!(emulator
+        .display
+        .iter_rows()
+        .all(|row| row.iter().all(|on| !on)));
+}
+
+#[test]
+fn presses_are_split_into_stages() {
+    let start = Instant::now();
+    let ms = |n| start + Duration::from_millis(n);
+    let mut probe = LatencyProbe::new();
+    assert!(probe.key_event(ms(0)));
+    // Another press before the first one was presented does not count.
+    assert!(!probe.key_event(ms(1)));
+    probe.polled(ms(5));
+    probe.emulated(ms(6), false);
+    probe.presented(ms(7));
+    assert!(probe.samples().is_empty());
+    probe.polled(ms(21));
+    probe.emulated(ms(22), true);
+    probe.presented(ms(30));
+
+    let sample = probe.samples()[0];
+    assert_eq!(sample.input, Duration::from_millis(5));
+    assert_eq!(sample.emulation, Duration::from_millis(17));
+    assert_eq!(sample.render, Duration::from_millis(8));
+    assert_eq!(sample.total(), Duration::from_millis(30));
+    assert!(probe.report().starts_with("1 presses\n"));
+    assert!(probe.report().contains("total"));
+}
+
+#[test]
+fn summaries_use_the_nearest_rank() {
+    let durations: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+    let summary = Summary::of(&durations).unwrap();
+    assert_eq!(summary.min, Duration::from_millis(1));
+    assert_eq!(summary.median, Duration::from_millis(10));
+    assert_eq!(summary.p95, Duration::from_millis(19));
+    assert_eq!(summary.max, Duration::from_millis(20));
+    assert_eq!(summary.mean, Duration::from_micros(10_500));
+    assert_eq!(Summary::of(&[]), None);
+}
@@ -0,0 +1,78 @@
+use chip8_core::demo;
+use chip8_core::lint::{lint, to_json, to_sarif, Rule, Severity};
+
+fn rules(rom: &[u8]) -> Vec<(Rule, u16)> {
+    lint(rom)
+        .iter()
+        .map(|lint| (lint.rule, lint.address))
+        .collect()
+}
+
+#[test]
+fn drawing_needs_a_clear_screen_first() {
+    // DRW V0, V1, 5; CLS; JP 0x202
+    let rom = [0xD0, 0x15, 0x00, 0xE0, 0x12, 0x02];
+    assert_eq!(rules(&rom), [(Rule::DrawBeforeClear, 0x200)]);
+
+    // CALL 0x206; DRW V0, V1, 5; JP 0x202; CLS; RET
+    let rom = [0x22, 0x06, 0xD0, 0x15, 0x12, 0x02, 0x00, 0xE0, 0x00, 0xEE];
+    assert_eq!(rules(&rom), []);
+    assert_eq!(rules(demo::ROM), []);
+}
+
+#[test]
+fn memory_writes_are_checked_against_code_and_reads() {
+    // CLS; LD I, 0x206; LD B, V0; JP 0x200
+    let rom = [0x00, 0xE0, 0xA2, 0x06, 0xF0, 0x33, 0x12, 0x00];
+    assert_eq!(rules(&rom), [(Rule::BcdOverCode, 0x204)]);
+
+    // CLS; LD I, 0x300; LD [I], V3; JP 0x200
+    let rom = [0x00, 0xE0, 0xA3, 0x00, 0xF3, 0x55, 0x12, 0x00];
+    assert_eq!(rules(&rom), [(Rule::StoreNeverLoaded, 0x204)]);
+    assert_eq!(lint(&rom)[0].severity(), Severity::Note);
+
+    // The same, then LD I, 0x300; LD V3, [I].
+    let rom = [
+        0x00, 0xE0, 0xA3, 0x00, 0xF3, 0x55, 0xA3, 0x00, 0xF3, 0x65, 0x12, 0x00,
+    ];
+    assert_eq!(rules(&rom), []);
+}
+
+#[test]
+fn jumps_go_to_code() {
+    // CLS; LD I, 0x20A; DRW V0, V1, 4; JP 0x20C; then the sprite.
+    let rom = [
+        0x00, 0xE0, 0xA2, 0x0A, 0xD0, 0x14, 0x12, 0x0C, 0x00, 0x00, 0x3C, 0x42, 0x42, 0x3C,
+    ];
+    let lints = lint(&rom);
+    assert_eq!(lints[0].rule, Rule::JumpIntoSprite);
+    assert_eq!(
+        lints[0].message,
+        "goes to 0x20c, inside the sprite at 0x20a drawn at 0x204"
+    );
+}
+
+#[test]
+fn calls_and_returns_pair_up() {
+    // CLS; RET
+    let rom = [0x00, 0xE0, 0x00, 0xEE];
+    assert_eq!(rules(&rom), [(Rule::RetWithoutCall, 0x202)]);
+
+    // CLS; CALL 0x206; JP 0x200; JP 0x200
+    let rom = [0x00, 0xE0, 0x22, 0x06, 0x12, 0x00, 0x12, 0x00];
+    assert_eq!(rules(&rom), [(Rule::CallNeverReturns, 0x202)]);
+}
+
+#[test]
+fn lints_export_as_json_and_sarif() {
+    let lints = lint(&[0x00, 0xE0, 0x00, 0xEE]);
+    assert_eq!(
+        to_json(&lints),
+        "[{\"rule\":\"ret-without-call\",\"severity\":\"error\",\"address\":514,\
+         \"message\":\"returns outside any subroutine, with nothing on the stack\"}]\n"
+    );
+    let sarif = to_sarif(&lints, "game.ch8");
+    assert!(sarif.contains("\"version\":\"2.1.0\""));
+    assert!(sarif.contains("\"ruleId\":\"ret-without-call\",\"level\":\"error\""));
+    assert!(sarif.contains("{\"uri\":\"game.ch8\"},\"region\":{\"byteOffset\":2,\"byteLength\":2}"));
+}
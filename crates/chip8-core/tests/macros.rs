@@ -0,0 +1,95 @@
+use chip8_core::config::RomConfig;
+use chip8_core::input::{InputScript, InputSource};
+use chip8_core::macros::{MacroRecorder, Macros};
+use chip8_core::{run_frame, EmulatorBuilder};
+
+#[test]
+fn recording_keeps_the_frames_keys_were_pressed_on() {
+    let mut emulator = EmulatorBuilder::new().rom(&[0x12, 0x00]).build().unwrap();
+    let mut recorder = MacroRecorder::start(&emulator.keypad);
+    for frame in 1..=5 {
+        match frame {
+            2 => emulator.keypad.press(0x5),
+            4 => emulator.keypad.release(0x5),
+            5 => emulator.keypad.press(0xA),
+            _ => {}
+        }
+        recorder.before_frame(&emulator.keypad);
+        run_frame(&mut emulator);
+        recorder.after_frame(&emulator.keypad);
+    }
+    // A is let go after the recording ends.
+    let script = recorder.finish();
+    assert_eq!(
+        script.events(),
+        [(2, 5, true), (4, 5, false), (5, 0xA, true), (6, 0xA, false)]
+    );
+}
+
+#[test]
+fn macros_play_on_their_hotkey() {
+    let mut macros = Macros::new();
+    let script = InputScript::parse("1 5 down\n3 5 up\n").unwrap();
+    macros.add("skip_title", script).unwrap();
+    macros.get_mut("skip_title").unwrap().hotkey = Some("ctrl+t".to_string());
+    assert_eq!(macros.for_hotkey("Ctrl+T"), Some("skip_title"));
+    assert_eq!(macros.for_hotkey("ctrl+u"), None);
+    assert!(macros.add("skip title", InputScript::default()).is_err());
+
+    let mut emulator = EmulatorBuilder::new().rom(&[0x12, 0x00]).build().unwrap();
+    assert!(macros.play("skip_title"));
+    let mut pressed = Vec::new();
+    for _ in 0..4 {
+        macros.poll(&mut emulator.keypad);
+        run_frame(&mut emulator);
+        pressed.push(emulator.keypad.is_pressed(0x5));
+    }
+    assert_eq!(pressed, [true, true, false, false]);
+    assert!(!macros.is_playing());
+    assert!(!macros.play("missing"));
+}
+
+#[test]
+fn autoplay_macros_start_together() {
+    let config = RomConfig::parse(
+        "macro.start = 1 5 down, 2 5 up\n\
+         macro.start.autoplay = on\n\
+         macro.menu = 1 1 down, 2 1 up\n",
+    )
+    .unwrap();
+    let mut macros = config.macros().unwrap();
+    assert_eq!(macros.len(), 2);
+    macros.autoplay();
+    let mut emulator = EmulatorBuilder::new().rom(&[0x12, 0x00]).build().unwrap();
+    macros.poll(&mut emulator.keypad);
+    run_frame(&mut emulator);
+    assert!(emulator.keypad.is_pressed(0x5));
+    assert!(!emulator.keypad.is_pressed(0x1));
+}
+
+#[test]
+fn macros_round_trip_through_the_config() {
+    let text = "macro.start = 30 5 down, 34 5 up\n\
+                macro.start.autoplay = on\n\
+                macro.start.hotkey = ctrl+t\n";
+    let config = RomConfig::parse(text).unwrap();
+    let macros = config.macros().unwrap();
+    let found = macros.get("start").unwrap();
+    assert!(found.autoplay);
+    assert_eq!(found.hotkey.as_deref(), Some("ctrl+t"));
+    assert_eq!(found.script.events(), [(30, 5, true), (34, 5, false)]);
+
+    let mut saved = RomConfig::default();
+    saved.set_macros(&macros);
+    assert_eq!(saved.to_text(), text);
+
+    for broken in [
+        "macro.start = 30 5 sideways",
+        "macro.start.hotkey = ctrl+t",
+        "macro.start = 1 5 down\nmacro.start.autoplay = maybe",
+        "macro.start = 1 5 down\nmacro.start.speed = 2",
+    ] {
+        let config = RomConfig::parse(broken).unwrap();
+        assert!(config.macros().is_err(), "{}", broken);
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::HashSet;
+
+use chip8_core::instruction::find;
+use chip8_core::mutants::{all, Mutant};
+use chip8_core::{EmulatorBuilder, Instruction};
+
+#[test]
+fn every_mutant_targets_a_real_instruction_under_its_own_name() {
+    let names: HashSet<String> = all().map(|mutant| mutant.name()).collect();
+    assert_eq!(names.len(), all().count());
+    for mutant in all() {
+        assert!(find(mutant.pattern).is_some(), "{}", mutant);
+        assert_eq!(Mutant::find(&mutant.name()), Some(mutant));
+    }
+    assert_eq!(Mutant::find("flip-vf-8xy4").unwrap().pattern, "8XY4");
+    assert_eq!(Mutant::find("flip-vf-00E0"), None);
+}
+
+#[test]
+fn a_mutant_only_breaks_its_instruction() {
+    let mutant = Mutant::find("flip-vf-8XY4").unwrap();
+    let mut emulator = EmulatorBuilder::new().build().unwrap();
+    mutant.apply(&mut emulator, Instruction::Sub { x: 0, y: 1 });
+    assert_eq!(emulator.v_registers[0xF], 0);
+    mutant.apply(&mut emulator, Instruction::Add { x: 0, y: 1 });
+    assert_eq!(emulator.v_registers[0xF], 1);
+
+    let mutant = Mutant::find("vx-plus-1-7XKK").unwrap();
+    mutant.apply(&mut emulator, Instruction::AddByte { x: 3, kk: 1 });
+    assert_eq!(emulator.v_registers[3], 1);
+}
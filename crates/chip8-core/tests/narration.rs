@@ -0,0 +1,32 @@
+use chip8_core::narration::Narrator;
+use chip8_core::{load_rom_to_memory, EmulatorBuilder, Quirks};
+
+#[test]
+fn narrates_one_line_per_instruction() {
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .build()
+        .unwrap();
+    // LD V0, 5; LD I, 0x300; LD B, V0; DW 0x5122
+    load_rom_to_memory(
+        &mut emulator,
+        &[0x60, 0x05, 0xA3, 0x00, 0xF0, 0x33, 0x51, 0x22],
+    )
+    .unwrap();
+
+    let mut narrator = Narrator::new(Vec::new());
+    for _ in 0..3 {
+        narrator.step(&mut emulator).unwrap();
+    }
+    assert!(narrator.step(&mut emulator).is_err());
+    let text = String::from_utf8(narrator.finish().unwrap()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with(
+        r#"{"step":1,"address":512,"opcode":"6005","pattern":"6XKK","mnemonic":"LD V0, 0x05","#
+    ));
+    assert!(lines[2].contains(r#""memory":[{"address":770,"before":0,"after":5}]"#));
+    assert!(lines[3].ends_with(r#""error":"unknown opcode 0x5122"}"#));
+}
@@ -0,0 +1,76 @@
+use chip8_core::font::{Font, FONT_ADDRESS, LARGE_FONT_ADDRESS};
+use chip8_core::ocr::{read_text, screen_shows, screen_text};
+use chip8_core::{run_frame, EmulatorBuilder, Instruction};
+
+// Draws `sprites` of (address, x, y, rows) and halts.
+fn draw(sprites: &[(u16, u8, u8, u8)]) -> Vec<u8> {
+    let mut lines = Vec::new();
+    for &(address, x, y, rows) in sprites {
+        lines.push(format!("LD I, {:#x}", address));
+        lines.push(format!("LD V0, {}", x));
+        lines.push(format!("LD V1, {}", y));
+        lines.push(format!("DRW V0, V1, {}", rows));
+    }
+    let halt = 0x200 + 2 * lines.len();
+    lines.push(format!("JP {:#x}", halt));
+    lines
+        .iter()
+        .flat_map(|line| line.parse::<Instruction>().unwrap().encode().to_be_bytes())
+        .collect()
+}
+
+fn digit(value: u16) -> u16 {
+    FONT_ADDRESS + 5 * value
+}
+
+#[test]
+fn reads_digits_words_and_lines() {
+    let rom = draw(&[
+        (digit(0xA), 2, 2, 5),
+        (digit(3), 7, 2, 5),
+        (digit(1), 20, 2, 5),
+        (digit(0xF), 10, 20, 5),
+    ]);
+    let mut emulator = EmulatorBuilder::new().seed(0).rom(&rom).build().unwrap();
+    for _ in 0..10 {
+        run_frame(&mut emulator);
+    }
+    assert_eq!(screen_text(&emulator), "A3 1\nF\n");
+    assert!(screen_shows(&emulator, "3 1"));
+    assert!(!screen_shows(&emulator, "A31"));
+}
+
+#[test]
+fn glyphs_touching_other_pixels_are_not_text() {
+    // An 8 from the font with a full block right next to it.
+    let rom = draw(&[(digit(8), 0, 0, 5), (FONT_ADDRESS, 4, 0, 1)]);
+    let mut emulator = EmulatorBuilder::new().seed(0).rom(&rom).build().unwrap();
+    for _ in 0..10 {
+        run_frame(&mut emulator);
+    }
+    assert_eq!(screen_text(&emulator), "");
+}
+
+#[test]
+fn reads_the_large_font_and_other_fonts() {
+    let rom = draw(&[(LARGE_FONT_ADDRESS + 10 * 7, 30, 10, 10)]);
+    let mut emulator = EmulatorBuilder::new().seed(0).rom(&rom).build().unwrap();
+    for _ in 0..10 {
+        run_frame(&mut emulator);
+    }
+    assert_eq!(screen_text(&emulator), "7\n");
+
+    let vip: Font = "vip".parse().unwrap();
+    let rom = draw(&[(digit(0xB), 0, 0, 5)]);
+    let mut emulator = EmulatorBuilder::new()
+        .seed(0)
+        .font(vip.clone())
+        .rom(&rom)
+        .build()
+        .unwrap();
+    for _ in 0..10 {
+        run_frame(&mut emulator);
+    }
+    assert_eq!(read_text(&emulator.display, &vip), "B\n");
+    assert_eq!(screen_text(&emulator), "B\n");
+}
@@ -0,0 +1,43 @@
+use chip8_core::octo::{OctoState, OCTO_TICKRATE};
+use chip8_core::{run_frame, savestate, Quirks};
+
+#[test]
+fn octo_options_become_quirks() {
+    let state = OctoState::parse(
+        r##"{"options": {"tickrate": 500, "shiftQuirks": true, "clipQuirks": true,
+            "fillColor": "#FFCC00", "vfOrderQuirks": true, "screenRotation": 0}}"##,
+    )
+    .unwrap();
+    assert_eq!(state.cycles_per_frame, 500);
+    assert!(!state.quirks.shift_uses_vy);
+    assert!(state.quirks.memory_increments_i);
+    assert!(state.quirks.clipping);
+    assert_eq!(state.flags, None);
+    assert_eq!(state.unsupported, vec!["vfOrderQuirks"]);
+
+    let defaults = OctoState::parse("{}").unwrap();
+    assert_eq!(defaults.cycles_per_frame, OCTO_TICKRATE);
+    assert!(defaults.quirks.shift_uses_vy && !defaults.quirks.vf_reset);
+    assert_ne!(defaults.quirks, Quirks::chip8());
+}
+
+#[test]
+fn octo_flags_survive_into_the_savestate() {
+    let state = OctoState::parse(r#"{"tickrate": 7, "flags": [3, 0, 120]}"#).unwrap();
+    // LD V2, R then halt.
+    let rom = [0xF2, 0x85, 0x12, 0x02];
+    let emulator = state.emulator(&rom).unwrap();
+    let mut loaded = savestate::load(&savestate::save(&emulator)).unwrap();
+    run_frame(&mut loaded);
+    assert_eq!(loaded.v_registers[..3], [3, 0, 120]);
+    assert_eq!(loaded.cycles_per_frame, 7);
+}
+
+#[test]
+fn bad_octo_json_is_reported() {
+    assert!(OctoState::parse("[1, 2]").is_err());
+    assert!(OctoState::parse(r#"{"flags": [256]}"#).is_err());
+    assert!(OctoState::parse(r#"{"tickrate": 10"#)
+        .unwrap_err()
+        .starts_with("invalid JSON"));
+}
@@ -0,0 +1,436 @@
+use chip8_core::testrom::{TestRom, DATA_ADDRESS};
+use chip8_core::{run_frame, Emulator, EmulatorBuilder, EmulatorError, Instruction, Quirks, Rng};
+
+fn emulator(quirks: Quirks) -> Emulator {
+    EmulatorBuilder::new()
+        .quirks(quirks)
+        .seed(0)
+        .build()
+        .unwrap()
+}
+
+fn run(rom: TestRom) {
+    run_with(rom, Quirks::default());
+}
+
+fn run_with(rom: TestRom, quirks: Quirks) {
+    if let Err(failures) = rom.run(&mut emulator(quirks)) {
+        panic!("{}", failures);
+    }
+}
+
+#[test]
+fn nop_does_nothing() {
+    run(TestRom::new()
+        .ops(&[0x6007, 0x0000])
+        .expect_registers(&[(0, 0x07)]));
+}
+
+#[test]
+fn cls_clears_the_screen() {
+    let rom = TestRom::new()
+        .data(&[0xFF])
+        .ops(&[0xA000 | DATA_ADDRESS, 0xD001, 0x00E0]);
+    let mut emulator = emulator(Quirks::default());
+    rom.run(&mut emulator).unwrap();
+    assert!(emulator.display.is_blank());
+}
+
+#[test]
+fn call_and_ret() {
+    // CALL 0x208; LD V0, 1; JP 0x20C; NOP; (0x208) LD V1, 2; RET
+    let rom = TestRom::new().ops(&[0x2208, 0x6001, 0x120C, 0x0000, 0x6102, 0x00EE]);
+    run(rom.expect_registers(&[(0, 1), (1, 2)]));
+}
+
+#[test]
+fn ret_with_an_empty_stack_fails() {
+    let error = TestRom::new()
+        .op(0x00EE)
+        .run(&mut emulator(Quirks::default()))
+        .unwrap_err();
+    assert!(error.contains(&EmulatorError::StackUnderflow.to_string()));
+}
+
+#[test]
+fn jp_skips_code() {
+    run(TestRom::new()
+        .ops(&[0x1204, 0x6001, 0x6102])
+        .expect_registers(&[(0, 0), (1, 2)]));
+}
+
+#[test]
+fn se_vx_byte() {
+    run(TestRom::new()
+        .ops(&[0x6005, 0x3005, 0x6101, 0x6301, 0x3006, 0x6201])
+        .expect_registers(&[(1, 0), (2, 1), (3, 1)]));
+}
+
+#[test]
+fn sne_vx_byte() {
+    run(TestRom::new()
+        .ops(&[0x6005, 0x4005, 0x6101, 0x4006, 0x6201])
+        .expect_registers(&[(1, 1), (2, 0)]));
+}
+
+#[test]
+fn se_vx_vy() {
+    run(TestRom::new()
+        .ops(&[
+            0x6005, 0x6105, 0x6206, 0x5010, 0x6301, 0x6501, 0x5020, 0x6401,
+        ])
+        .expect_registers(&[(3, 0), (4, 1), (5, 1)]));
+}
+
+#[test]
+fn sne_vx_vy() {
+    run(TestRom::new()
+        .ops(&[0x6005, 0x6105, 0x6206, 0x9010, 0x6301, 0x9020, 0x6401])
+        .expect_registers(&[(3, 1), (4, 0)]));
+}
+
+#[test]
+fn ld_vx_byte() {
+    run(TestRom::new()
+        .ops(&[0x6A42])
+        .expect_registers(&[(0xA, 0x42)]));
+}
+
+#[test]
+fn add_vx_byte_wraps_without_touching_vf() {
+    run(TestRom::new()
+        .ops(&[0x60FF, 0x6F07, 0x7002])
+        .expect_registers(&[(0, 0x01), (0xF, 0x07)]));
+}
+
+#[test]
+fn ld_vx_vy() {
+    run(TestRom::new()
+        .ops(&[0x6133, 0x8010])
+        .expect_registers(&[(0, 0x33)]));
+}
+
+#[test]
+fn logic_ops_reset_vf_on_chip8() {
+    run(TestRom::new()
+        .ops(&[0x60F0, 0x610F, 0x6F05, 0x8011])
+        .expect_registers(&[(0, 0xFF), (0xF, 0)])
+        .ops(&[0x60F0, 0x6F05, 0x8012])
+        .expect_registers(&[(0, 0x00), (0xF, 0)])
+        .ops(&[0x60FF, 0x6F05, 0x8013])
+        .expect_registers(&[(0, 0xF0), (0xF, 0)]));
+}
+
+#[test]
+fn logic_ops_keep_vf_without_the_quirk() {
+    run_with(
+        TestRom::new()
+            .ops(&[0x60F0, 0x610F, 0x6F05, 0x8011])
+            .expect_registers(&[(0, 0xFF), (0xF, 5)]),
+        Quirks::schip(),
+    );
+}
+
+#[test]
+fn add_vx_vy_sets_carry() {
+    run(TestRom::new()
+        .ops(&[0x60FF, 0x6102, 0x8014])
+        .expect_registers(&[(0, 0x01), (0xF, 1)])
+        .ops(&[0x6001, 0x6102, 0x6F05, 0x8014])
+        .expect_registers(&[(0, 0x03), (0xF, 0)]));
+}
+
+#[test]
+fn sub_vx_vy_sets_not_borrow() {
+    run(TestRom::new()
+        .ops(&[0x6005, 0x6103, 0x8015])
+        .expect_registers(&[(0, 0x02), (0xF, 1)])
+        .ops(&[0x6003, 0x6105, 0x8015])
+        .expect_registers(&[(0, 0xFE), (0xF, 0)])
+        .ops(&[0x6003, 0x6103, 0x8015])
+        .expect_registers(&[(0, 0x00), (0xF, 1)]));
+}
+
+#[test]
+fn subn_vx_vy_sets_not_borrow() {
+    run(TestRom::new()
+        .ops(&[0x6003, 0x6105, 0x8017])
+        .expect_registers(&[(0, 0x02), (0xF, 1)])
+        .ops(&[0x6005, 0x6103, 0x8017])
+        .expect_registers(&[(0, 0xFE), (0xF, 0)]));
+}
+
+#[test]
+fn flag_wins_when_vf_is_the_destination() {
+    run(TestRom::new()
+        .ops(&[0x6FFF, 0x6102, 0x8F14])
+        .expect_registers(&[(0xF, 1)]));
+}
+
+#[test]
+fn shr_shifts_vy_on_chip8() {
+    run(TestRom::new()
+        .ops(&[0x6000, 0x6105, 0x8016])
+        .expect_registers(&[(0, 0x02), (1, 0x05), (0xF, 1)]));
+}
+
+#[test]
+fn shr_shifts_vx_in_place_on_schip() {
+    run_with(
+        TestRom::new()
+            .ops(&[0x6004, 0x6105, 0x8016])
+            .expect_registers(&[(0, 0x02), (0xF, 0)]),
+        Quirks::schip(),
+    );
+}
+
+#[test]
+fn shl_sets_vf_to_the_shifted_out_bit() {
+    run(TestRom::new()
+        .ops(&[0x6181, 0x801E])
+        .expect_registers(&[(0, 0x02), (0xF, 1)])
+        .ops(&[0x6141, 0x801E])
+        .expect_registers(&[(0, 0x82), (0xF, 0)]));
+}
+
+#[test]
+fn ld_i_addr() {
+    let rom = TestRom::new().op(0xA123);
+    let mut emulator = emulator(Quirks::default());
+    rom.run(&mut emulator).unwrap();
+    assert_eq!(emulator.i_register, 0x123);
+}
+
+#[test]
+fn jp_v0_addr() {
+    // JP V0 + 0x204 with V0 = 2 lands on LD V2, 1.
+    run(TestRom::new()
+        .ops(&[0x6002, 0xB204, 0x6101, 0x6201])
+        .expect_registers(&[(1, 0), (2, 1)]));
+}
+
+#[test]
+fn jp_vx_addr_on_schip() {
+    // B206 reads V2 with the quirk, V0 is left out.
+    run_with(
+        TestRom::new()
+            .ops(&[0x6004, 0x6202, 0xB206, 0x6301, 0x6401, 0x6501])
+            .expect_registers(&[(3, 0), (4, 1)]),
+        Quirks::schip(),
+    );
+}
+
+#[test]
+fn rnd_is_masked() {
+    let rom = TestRom::new().ops(&[0x60FF, 0xC000, 0xC10F]);
+    let mut emulator = emulator(Quirks::default());
+    rom.run(&mut emulator).unwrap();
+    assert_eq!(emulator.v_registers[0], 0);
+    assert_eq!(emulator.v_registers[1] & 0xF0, 0);
+}
+
+#[test]
+fn rnd_follows_the_seed() {
+    let rom = TestRom::new().ops(&[0xC0FF, 0xC1FF, 0xC2FF]);
+    let run = |seed| {
+        let mut emulator = emulator(Quirks::default());
+        emulator.rng = Rng::new(seed);
+        rom.run(&mut emulator).unwrap();
+        emulator.v_registers
+    };
+    assert_eq!(run(7), run(7));
+    assert_ne!(run(7), run(8));
+}
+
+#[test]
+fn drw_draws_and_reports_collisions() {
+    let rom = TestRom::new()
+        .data(&[0xF0, 0x90])
+        .ops(&[0xA000 | DATA_ADDRESS, 0x6002, 0x6103, 0xD012])
+        .expect_registers(&[(0xF, 0)])
+        .ops(&[0xA000 | DATA_ADDRESS, 0xD012])
+        .expect_registers(&[(0xF, 1)])
+        .ops(&[0xA000 | DATA_ADDRESS, 0xD012]);
+    let mut emulator = emulator(Quirks::default());
+    rom.run(&mut emulator).unwrap();
+    let lit = [(2, 3), (3, 3), (4, 3), (5, 3), (2, 4), (5, 4)];
+    for y in 0..emulator.display.height() {
+        for x in 0..emulator.display.width() {
+            assert_eq!(emulator.display.pixel(x, y), lit.contains(&(x, y)));
+        }
+    }
+}
+
+#[test]
+fn drw_clips_or_wraps() {
+    let rom = TestRom::new()
+        .data(&[0xFF])
+        .ops(&[0xA000 | DATA_ADDRESS, 0x603C, 0x611F, 0xD011]);
+    let mut clipped = emulator(Quirks::chip8());
+    rom.run(&mut clipped).unwrap();
+    assert!(clipped.display.pixel(63, 31));
+    assert!(!clipped.display.pixel(0, 31));
+
+    let mut wrapped = emulator(Quirks::xo_chip());
+    rom.run(&mut wrapped).unwrap();
+    assert!(wrapped.display.pixel(63, 31));
+    assert!(wrapped.display.pixel(3, 31));
+}
+
+#[test]
+fn high_and_low_switch_the_resolution() {
+    let rom =
+        TestRom::new()
+            .data(&[0x80])
+            .ops(&[0x00FF, 0xA000 | DATA_ADDRESS, 0x6078, 0x613C, 0xD011]);
+    let mut high = emulator(Quirks::schip());
+    rom.run(&mut high).unwrap();
+    assert_eq!((high.display.width(), high.display.height()), (128, 64));
+    assert!(high.display.pixel(120, 60));
+    assert!(!high.display.pixel(121, 60));
+
+    let rom = TestRom::new().ops(&[0x00FF, 0x00FE]);
+    let mut low = emulator(Quirks::schip());
+    rom.run(&mut low).unwrap();
+    assert_eq!(low.display.width(), 64);
+    assert_eq!("HIGH".parse::<Instruction>(), Ok(Instruction::High));
+    assert_eq!(Instruction::decode(0x00FE).to_string(), "LOW");
+}
+
+#[test]
+fn drw_waits_for_vblank_on_chip8() {
+    // LD I, 0x208; DRW V0, V0, 1; DRW V0, V0, 1; JP 0x206; sprite
+    let rom = [0xA2, 0x08, 0xD0, 0x01, 0xD0, 0x01, 0x12, 0x06, 0x80];
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::chip8())
+        .seed(0)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    run_frame(&mut emulator);
+    assert_eq!(emulator.program_counter, 0x202);
+    assert!(emulator.display.is_blank());
+    run_frame(&mut emulator);
+    assert_eq!(emulator.program_counter, 0x204);
+    assert!(emulator.display.pixel(0, 0));
+    run_frame(&mut emulator);
+    assert!(!emulator.display.pixel(0, 0));
+
+    let mut eager = EmulatorBuilder::new()
+        .quirks(Quirks::schip())
+        .seed(0)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    run_frame(&mut eager);
+    assert_eq!(eager.program_counter, 0x206);
+    assert!(eager.display.is_blank());
+}
+
+#[test]
+fn delay_timer_round_trip() {
+    run(TestRom::new()
+        .ops(&[0x6033, 0xF015, 0xF107])
+        .expect_registers(&[(1, 0x33)]));
+}
+
+#[test]
+fn sound_timer_is_set() {
+    let rom = TestRom::new().ops(&[0x6033, 0xF018]);
+    let mut emulator = emulator(Quirks::default());
+    rom.run(&mut emulator).unwrap();
+    assert_eq!(emulator.sound_timer_registry, 0x33);
+}
+
+#[test]
+fn add_i_vx() {
+    let rom = TestRom::new().ops(&[0xA100, 0x6010, 0xF01E]);
+    let mut emulator = emulator(Quirks::default());
+    rom.run(&mut emulator).unwrap();
+    assert_eq!(emulator.i_register, 0x110);
+}
+
+#[test]
+fn ld_f_points_at_the_digit_of_the_low_nibble() {
+    // B is E0 90 E0 90 E0.
+    run(TestRom::new()
+        .ops(&[0x601B, 0xF029, 0xF165])
+        .expect_registers(&[(0, 0xE0), (1, 0x90)]));
+}
+
+#[test]
+fn bcd() {
+    run(TestRom::new()
+        .ops(&[0x60FE, 0xA300, 0xF033])
+        .expect_memory(0x300, 2)
+        .expect_memory(0x301, 5)
+        .expect_memory(0x302, 4));
+}
+
+#[test]
+fn store_and_load_registers() {
+    run(TestRom::new()
+        .ops(&[0x6011, 0x6122, 0x6233, 0xA300, 0xF255])
+        .expect_memory(0x300, 0x11)
+        .expect_memory(0x301, 0x22)
+        .expect_memory(0x302, 0x33)
+        .ops(&[0x6000, 0x6100, 0x6200, 0xA300, 0xF165])
+        .expect_registers(&[(0, 0x11), (1, 0x22), (2, 0)]));
+}
+
+#[test]
+fn load_registers_moves_i_on_chip8_only() {
+    let rom = TestRom::new().ops(&[0xA300, 0xF265]);
+    let mut chip8 = emulator(Quirks::chip8());
+    rom.run(&mut chip8).unwrap();
+    assert_eq!(chip8.i_register, 0x303);
+
+    let mut schip = emulator(Quirks::schip());
+    rom.run(&mut schip).unwrap();
+    assert_eq!(schip.i_register, 0x300);
+}
+
+#[test]
+fn skp_and_sknp_follow_the_keypad() {
+    // Key 3 is down, key 4 up.
+    let rom = TestRom::new()
+        .ops(&[0x6003, 0x6104])
+        .ops(&[0xE09E, 0x6201, 0x6301, 0xE19E, 0x6401, 0x6501])
+        .ops(&[0xE0A1, 0x6601, 0x6701, 0xE1A1, 0x6801, 0x6901])
+        .expect_registers(&[(2, 0), (3, 1), (4, 1), (5, 1)])
+        .expect_registers(&[(6, 1), (7, 1), (8, 0), (9, 1)]);
+    let mut emulator = emulator(Quirks::default());
+    emulator.keypad.press(3);
+    if let Err(failures) = rom.run(&mut emulator) {
+        panic!("{}", failures);
+    }
+}
+
+#[test]
+fn ld_vx_k_waits_for_a_key_press_and_release() {
+    let mut emulator = EmulatorBuilder::new()
+        .rom(&[0xF5, 0x0A, 0x12, 0x02])
+        .build()
+        .unwrap();
+    run_frame(&mut emulator);
+    assert_eq!(emulator.program_counter, 0x200);
+    emulator.keypad.press(7);
+    emulator.keypad.release(7);
+    for _ in 0..10 {
+        run_frame(&mut emulator);
+    }
+    assert_eq!(emulator.program_counter, 0x202);
+    assert_eq!(emulator.v_registers[5], 7);
+}
+
+#[test]
+fn store_registers_moves_i_on_chip8_only() {
+    let rom = TestRom::new().ops(&[0xA300, 0xF255]);
+    let mut chip8 = emulator(Quirks::chip8());
+    rom.run(&mut chip8).unwrap();
+    assert_eq!(chip8.i_register, 0x303);
+
+    let mut schip = emulator(Quirks::schip());
+    rom.run(&mut schip).unwrap();
+    assert_eq!(schip.i_register, 0x300);
+}
@@ -0,0 +1,73 @@
+use chip8_core::patch::{diff, unified, Edit, Patch, Record};
+
+#[test]
+fn diffs_line_instructions_up_past_an_insertion() {
+    // CLS; LD V0, 5; JP 0x200, with an LD V1, 1 put in after CLS.
+    let old = [0x00, 0xE0, 0x60, 0x05, 0x12, 0x00];
+    let new = [0x00, 0xE0, 0x61, 0x01, 0x60, 0x05, 0x12, 0x00];
+    let edits = diff(&old, &new);
+    let changed: Vec<_> = edits
+        .iter()
+        .filter(|edit| !matches!(edit, Edit::Same { .. }))
+        .collect();
+    assert_eq!(
+        changed,
+        [&Edit::Added {
+            address: 0x202,
+            op_code: 0x6101
+        }]
+    );
+    assert_eq!(
+        unified(&edits, 1),
+        "@@ -0x200 +0x200 @@\n \
+         0x200  0x200  00E0  CLS\n\
+         +       0x202  6101  LD V1, 0x01\n \
+         0x202  0x204  6005  LD V0, 0x05\n"
+    );
+    assert_eq!(unified(&diff(&old, &old), 3), "");
+}
+
+#[test]
+fn patches_round_trip_through_ips() {
+    let old: Vec<u8> = (0..40).collect();
+    let mut new = old.clone();
+    new[3] = 0xFF;
+    new[6] = 0xFF;
+    new[30] = 0xFF;
+    new.truncate(35);
+    let patch = Patch::between(&old, &new);
+    // Two bytes alike between 3 and 6 are cheaper written than a record.
+    assert_eq!(
+        patch.records,
+        [
+            Record {
+                offset: 3,
+                data: vec![0xFF, 4, 5, 0xFF]
+            },
+            Record {
+                offset: 30,
+                data: vec![0xFF]
+            },
+        ]
+    );
+    assert_eq!(patch.truncate, Some(35));
+    assert_eq!(patch.apply(&old), new);
+
+    let ips = patch.to_ips().unwrap();
+    assert!(ips.starts_with(b"PATCH"));
+    assert_eq!(Patch::from_ips(&ips).unwrap(), patch);
+    assert!(Patch::between(&old, &old).is_empty());
+}
+
+#[test]
+fn ips_reads_run_length_records_and_growing_roms() {
+    // Four 0xAA from offset 2, then 0x12 0x00 at 6 past the ROM's end.
+    let ips = b"PATCH\x00\x00\x02\x00\x00\x00\x04\xAA\x00\x00\x06\x00\x02\x12\x00EOF";
+    let patch = Patch::from_ips(ips).unwrap();
+    assert_eq!(
+        patch.apply(&[1, 2, 3]),
+        [1, 2, 0xAA, 0xAA, 0xAA, 0xAA, 0x12, 0x00]
+    );
+    assert!(Patch::from_ips(b"PATCH\x00\x00\x02\x00\x05\x01").is_err());
+    assert!(Patch::from_ips(b"IPS!").is_err());
+}
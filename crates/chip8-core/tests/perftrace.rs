@@ -0,0 +1,100 @@
+use std::time::Instant;
+
+use chip8_core::perftrace::{PerfTrace, Track};
+use chip8_core::{EmulatorBuilder, Instruction, Quirks};
+
+fn assemble(lines: &[&str]) -> Vec<u8> {
+    lines
+        .iter()
+        .flat_map(|line| line.parse::<Instruction>().unwrap().encode().to_be_bytes())
+        .collect()
+}
+
+fn count(json: &str, needle: &str) -> usize {
+    json.matches(needle).count()
+}
+
+#[test]
+fn frames_are_split_into_batches_around_each_draw() {
+    // Four instructions, a draw, two more and the draw again.
+    let rom = assemble(&[
+        "LD V0, 4",
+        "LD V1, 6",
+        "LD I, 0x50",
+        "LD DT, V0",
+        "DRW V0, V1, 5",
+        "ADD V0, 1",
+        "JP 0x208",
+    ]);
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::none())
+        .cycles_per_frame(9)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    let mut trace = PerfTrace::new();
+    trace.run_frame(&mut emulator);
+    let json = trace.to_json();
+
+    assert_eq!(count(&json, "\"name\":\"frame\""), 1);
+    assert!(json.contains("\"args\":{\"frame\":0,\"instructions\":9,\"display_changed\":1}"));
+    assert_eq!(count(&json, "\"name\":\"DRW\""), 2);
+    assert!(json.contains("\"args\":{\"x\":4,\"y\":6,\"height\":5,\"collision\":0}"));
+    assert!(json.contains("\"args\":{\"x\":5,\"y\":6,\"height\":5,\"collision\":1}"));
+    // 4 before the first draw, 2 between the draws, 1 after the second.
+    for batch in [4, 2, 1] {
+        assert!(json.contains(&format!("\"args\":{{\"count\":{}}}", batch)));
+    }
+    assert!(json.contains("\"name\":\"timers\",\"ph\":\"C\""));
+    assert!(json.contains("\"args\":{\"delay\":3,\"sound\":0}"));
+}
+
+#[test]
+fn draws_waiting_for_the_vertical_blank_count_as_instructions() {
+    let rom = assemble(&["DRW V0, V1, 5", "JP 0x200"]);
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks {
+            display_wait: true,
+            ..Quirks::none()
+        })
+        .cycles_per_frame(4)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    let mut trace = PerfTrace::new();
+    trace.run_frame(&mut emulator);
+    let json = trace.to_json();
+    // The draw in the vertical blank, then the jump and three waits.
+    assert_eq!(count(&json, "\"name\":\"DRW\""), 1);
+    assert!(json.contains("\"args\":{\"count\":3}"));
+}
+
+#[test]
+fn audio_and_frontend_spans_go_on_their_own_tracks() {
+    let mut trace = PerfTrace::new();
+    assert!(trace.is_empty());
+    trace.audio_callback(Instant::now(), 512);
+    trace.span(Track::Frontend, "present", Instant::now());
+    trace.instant(Track::Frontend, "key");
+    assert_eq!(trace.len(), 3);
+
+    let json = trace.to_json();
+    assert!(json.starts_with("{\"traceEvents\":["));
+    assert!(json.contains("\"tid\":2,\"args\":{\"name\":\"audio\"}"));
+    assert!(json.contains("\"name\":\"audio callback\",\"ph\":\"X\",\"pid\":1,\"tid\":2"));
+    assert!(json.contains("\"args\":{\"samples\":512}"));
+    assert!(json.contains("\"name\":\"present\",\"ph\":\"X\",\"pid\":1,\"tid\":3"));
+    assert!(json.contains("\"name\":\"key\",\"ph\":\"i\",\"pid\":1,\"tid\":3"));
+    assert!(json.trim_end().ends_with('}'));
+}
+
+#[test]
+fn a_failing_frame_is_marked_instead_of_ticking_the_timers() {
+    let rom = assemble(&["RET"]);
+    let mut emulator = EmulatorBuilder::new().rom(&rom).build().unwrap();
+    let mut trace = PerfTrace::new();
+    assert!(trace.run_frame(&mut emulator).error.is_some());
+    let json = trace.to_json();
+    assert!(json.contains("\"name\":\"error: stack underflow\",\"ph\":\"i\""));
+    assert!(!json.contains("\"name\":\"timers\""));
+}
@@ -0,0 +1,41 @@
+use std::path::{Path, PathBuf};
+
+use chip8_core::host::{Files, MemoryFiles};
+use chip8_core::playlist::Playlist;
+use chip8_core::Quirks;
+
+#[test]
+fn playlists_resolve_paths_next_to_themselves() {
+    let files = MemoryFiles::new();
+    let text = "# the arcade corner\n\
+                pong.ch8 seconds=20 inputs=demos/pong.inputs\n\
+                \n\
+                ../breakout.ch8 quirks=chip8 # no demo\n";
+    files
+        .write(Path::new("kiosk/list.txt"), text.as_bytes())
+        .unwrap();
+    let playlist = Playlist::load_from(&files, Path::new("kiosk/list.txt")).unwrap();
+    assert_eq!(playlist.entries.len(), 2);
+
+    let pong = &playlist.entries[0];
+    assert_eq!(pong.rom, PathBuf::from("kiosk/pong.ch8"));
+    assert_eq!(pong.seconds, Some(20));
+    assert_eq!(pong.inputs, Some(PathBuf::from("kiosk/demos/pong.inputs")));
+    assert_eq!(pong.quirks, None);
+
+    let breakout = &playlist.entries[1];
+    assert_eq!(breakout.rom, PathBuf::from("kiosk/../breakout.ch8"));
+    assert_eq!(breakout.quirks, Some(Quirks::chip8()));
+    assert_eq!(breakout.inputs, None);
+}
+
+#[test]
+fn bad_playlists_say_where() {
+    assert_eq!(
+        Playlist::parse("a.ch8\nb.ch8 seconds=soon"),
+        Err("line 2: invalid seconds".to_string())
+    );
+    assert!(Playlist::parse("a.ch8 speed=2").is_err());
+    assert!(Playlist::parse("a.ch8 inputs").is_err());
+    assert!(Playlist::parse("# nothing\n").is_err());
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "plugins")]
+
+use std::ffi::c_void;
+use std::path::Path;
+
+use chip8_core::plugin::{Chip8Plugin, Plugin, PluginState, PLUGIN_ABI_VERSION};
+use chip8_core::{step, EmulatorBuilder};
+
+// What a C plugin would keep behind `user`.
+#[derive(Default)]
+struct Counts {
+    steps: u32,
+    writes: u32,
+    dropped: bool,
+}
+
+unsafe extern "C" fn after_step(user: *mut c_void, state: *mut PluginState, _: u16, _: i32) {
+    (*(user as *mut Counts)).steps += 1;
+    // Infinite lives in V1.
+    *(*state).v.add(1) = 3;
+}
+
+unsafe extern "C" fn on_memory_write(user: *mut c_void, _: u16, _: u8) {
+    (*(user as *mut Counts)).writes += 1;
+}
+
+unsafe extern "C" fn drop(user: *mut c_void) {
+    (*(user as *mut Counts)).dropped = true;
+}
+
+fn plugin(counts: &mut Counts) -> Chip8Plugin {
+    Chip8Plugin {
+        abi_version: PLUGIN_ABI_VERSION,
+        user: counts as *mut Counts as *mut c_void,
+        before_step: None,
+        after_step: Some(after_step),
+        on_memory_write: Some(on_memory_write),
+        on_draw: None,
+        drop: Some(drop),
+    }
+}
+
+#[test]
+fn plugins_run_as_hooks() {
+    let mut counts = Counts::default();
+    // LD V1, 0; LD I, 0x300; LD B, V1
+    let mut emulator = EmulatorBuilder::new()
+        .seed(0)
+        .rom(&[0x61, 0x00, 0xA3, 0x00, 0xF1, 0x33])
+        .build()
+        .unwrap();
+    emulator
+        .hooks
+        .add(unsafe { Plugin::new(plugin(&mut counts)) }.unwrap());
+    for _ in 0..3 {
+        step(&mut emulator).unwrap();
+    }
+    assert_eq!(emulator.v_registers[1], 3);
+    emulator.hooks.clear();
+    assert_eq!((counts.steps, counts.writes, counts.dropped), (3, 3, true));
+}
+
+#[test]
+fn refuses_other_abi_versions_and_missing_libraries() {
+    let mut counts = Counts::default();
+    let mut old = plugin(&mut counts);
+    old.abi_version = 0;
+    old.drop = None;
+    let error = unsafe { Plugin::new(old) }.err().unwrap();
+    assert_eq!(
+        error,
+        format!("plugin ABI version 0, expected {}", PLUGIN_ABI_VERSION)
+    );
+
+    assert!(Plugin::load(Path::new("/nonexistent/libplugin.so")).is_err());
+}
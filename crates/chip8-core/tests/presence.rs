@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use chip8_core::config::Settings;
+use chip8_core::events::Event;
+use chip8_core::presence::{handshake, Presence};
+use chip8_core::title::rom_title;
+
+#[test]
+fn presence_follows_the_session() {
+    let mut presence = Presence::new(0);
+    assert_eq!(
+        presence.set_activity(7, 1),
+        r#"{"cmd":"SET_ACTIVITY","args":{"pid":7,"activity":null},"nonce":"1"}"#
+    );
+    let loaded = Event::RomLoaded {
+        name: "Space \"Invaders\"".to_string(),
+    };
+    assert!(presence.handle(&loaded, 1_700_000_000));
+    assert_eq!(
+        presence.set_activity(7, 2),
+        r#"{"cmd":"SET_ACTIVITY","args":{"pid":7,"activity":{"details":"Space \"Invaders\"","state":"Playing","timestamps":{"start":1700000000}}},"nonce":"2"}"#
+    );
+    assert!(presence.handle(&Event::Paused, 1_700_000_060));
+    assert!(presence.set_activity(7, 3).contains(r#""state":"Paused"}"#));
+    assert!(!presence.handle(&Event::SpeedChanged { percent: 200 }, 0));
+    presence.handle(&Event::Resumed, 1_700_000_090);
+    presence.restart(1_700_000_120);
+    assert!(presence
+        .set_activity(7, 4)
+        .contains(r#""timestamps":{"start":1700000120}"#));
+    assert_eq!(handshake(42), r#"{"v":1,"client_id":"42"}"#);
+}
+
+#[test]
+fn known_roms_have_proper_titles() {
+    let pong = include_bytes!("../../../roms/pong.ch8");
+    assert_eq!(
+        rom_title(pong, Path::new("roms/pong.ch8")),
+        "Pong (1 player)"
+    );
+    assert_eq!(rom_title(&[0x12, 0x00], Path::new("roms/loop.ch8")), "loop");
+}
+
+#[test]
+fn discord_is_opt_in() {
+    assert!(!Settings::default().discord);
+    let settings = Settings::parse("discord = on\ndiscord_client_id = 1234\nvolume = 3\n").unwrap();
+    assert!(settings.discord);
+    assert_eq!(settings.discord_client_id, Some(1234));
+    assert_eq!(Settings::parse(&settings.to_text()).unwrap(), settings);
+    assert!(Settings::parse("discord = maybe").is_err());
+}
+
+#[test]
+fn the_welcome_is_remembered() {
+    assert!(!Settings::default().welcomed);
+    let settings = Settings::parse("welcomed = on\n").unwrap();
+    assert!(settings.welcomed);
+    assert_eq!(Settings::parse(&settings.to_text()).unwrap(), settings);
+    assert!(Settings::parse("welcomed = yes").is_err());
+}
@@ -0,0 +1,63 @@
+use chip8_core::savestate::{load, save};
+use chip8_core::{run_frame, EmulatorBuilder, EmulatorError, Instruction};
+
+fn assemble(lines: &[&str]) -> Vec<u8> {
+    lines
+        .iter()
+        .flat_map(|line| line.parse::<Instruction>().unwrap().encode().to_be_bytes())
+        .collect()
+}
+
+const HELLO: &[&str] = &[
+    "LD V0, 72",
+    "PRINT V0",
+    "LD V0, 73",
+    "PRINT V0",
+    "LD V0, 10",
+    "PRINT V0",
+    "JP 0x20C",
+];
+
+#[test]
+fn printed_bytes_wait_for_the_frontend() {
+    let mut emulator = EmulatorBuilder::new()
+        .printer(true)
+        .rom(&assemble(HELLO))
+        .build()
+        .unwrap();
+    assert!(run_frame(&mut emulator).error.is_none());
+    let printer = emulator.printer.as_mut().unwrap();
+    assert_eq!(printer.output(), b"HI\n");
+    assert_eq!(printer.take(), b"HI\n");
+    run_frame(&mut emulator);
+    assert!(emulator.printer.unwrap().output().is_empty());
+}
+
+#[test]
+fn the_printer_is_opt_in() {
+    let mut emulator = EmulatorBuilder::new()
+        .rom(&assemble(HELLO))
+        .build()
+        .unwrap();
+    assert_eq!(
+        run_frame(&mut emulator).error,
+        Some(EmulatorError::Unimplemented(0xF0F0, "the printer"))
+    );
+    assert_eq!("PRINT V3".parse::<Instruction>().unwrap().encode(), 0xF3F0);
+    assert_eq!(Instruction::decode(0xF3F0).to_string(), "PRINT V3");
+}
+
+#[test]
+fn output_not_taken_yet_survives_a_savestate() {
+    let mut emulator = EmulatorBuilder::new()
+        .printer(true)
+        .rom(&assemble(HELLO))
+        .build()
+        .unwrap();
+    run_frame(&mut emulator);
+    let restored = load(&save(&emulator)).unwrap();
+    assert_eq!(restored.printer, emulator.printer);
+
+    let without = EmulatorBuilder::new().build().unwrap();
+    assert_eq!(load(&save(&without)).unwrap().printer, None);
+}
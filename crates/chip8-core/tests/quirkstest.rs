@@ -0,0 +1,90 @@
+use chip8_core::instruction::Variant;
+use chip8_core::quirkstest::{format_report, quirks_report, Verdict};
+use chip8_core::{Instruction, Quirks};
+
+// Stands in for the real test: six lines of three pixel rows, the first
+// showing whether 8xy1 reset VF and the last how Bnnn jumped, the others
+// always the same.
+fn fake_quirks_test() -> Vec<u8> {
+    let mut rom = vec![0; 0x130];
+    let mut put = |address: usize, lines: &[&str]| {
+        for (index, line) in lines.iter().enumerate() {
+            let word = line.parse::<Instruction>().unwrap().encode();
+            rom[address - 0x200 + 2 * index..][..2].copy_from_slice(&word.to_be_bytes());
+        }
+    };
+    put(
+        0x200,
+        &[
+            "LD VF, 5",
+            "LD V1, 0",
+            "OR V1, V1",
+            "LD I, 0x320",
+            "ADD I, VF",
+            "LD V2, 0",
+            "LD V3, 0",
+            "DRW V2, V3, 3",
+            "LD I, 0x32A",
+            "LD V3, 5",
+            "DRW V2, V3, 3",
+            "LD V3, 10",
+            "DRW V2, V3, 3",
+            "LD V3, 15",
+            "DRW V2, V3, 3",
+            "LD V3, 20",
+            "DRW V2, V3, 3",
+            "LD V0, 0",
+            "LD V3, 4",
+            "JP V0, 0x300",
+        ],
+    );
+    put(0x300, &["LD V4, 0", "JP 0x308", "LD V4, 5", "JP 0x308"]);
+    put(
+        0x308,
+        &[
+            "LD I, 0x320",
+            "ADD I, V4",
+            "LD V3, 25",
+            "DRW V2, V3, 3",
+            "JP 0x310",
+        ],
+    );
+    let glyphs = [
+        0xF0, 0x90, 0xF0, 0, 0, 0xF0, 0x80, 0xF0, 0, 0, 0x20, 0x60, 0x20,
+    ];
+    rom[0x120..0x120 + glyphs.len()].copy_from_slice(&glyphs);
+    rom
+}
+
+#[test]
+fn verdicts_compare_with_the_platform_and_each_quirk_flipped() {
+    let rom = fake_quirks_test();
+    let profiles: Vec<(String, Quirks)> = Quirks::profiles()
+        .iter()
+        .map(|(name, quirks)| (name.to_string(), *quirks))
+        .collect();
+    let rows = quirks_report(&rom, Variant::Chip8, &profiles, 30).unwrap();
+    use Verdict::*;
+    assert_eq!(
+        rows[0].verdicts,
+        [Pass, Unknown, Unknown, Unknown, Unknown, Pass]
+    );
+    // SUPER-CHIP neither resets VF nor jumps with V0.
+    assert_eq!(
+        rows[1].verdicts,
+        [Fail, Unknown, Unknown, Unknown, Unknown, Fail]
+    );
+    assert_eq!(rows[2].verdicts[0], Fail);
+    assert_eq!(rows[2].verdicts[5], Pass);
+
+    let report = format_report(&rows);
+    assert!(report.starts_with("PROFILE  vf_reset"));
+    assert!(report.contains("\nschip    FAIL      "));
+}
+
+#[test]
+fn other_screens_are_not_read_as_results() {
+    let error = quirks_report(&[0x12, 0x00], Variant::Chip8, &[], 5).unwrap_err();
+    assert!(error.contains("0 lines of text"));
+    assert!(quirks_report(&[0x12, 0x00], Variant::Eti660, &[], 5).is_err());
+}
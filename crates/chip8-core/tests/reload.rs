@@ -0,0 +1,64 @@
+use chip8_core::config::RomConfig;
+use chip8_core::host::{Files, MemoryFiles};
+use chip8_core::reload::{ConfigWatcher, Reload};
+use chip8_core::{EmulatorBuilder, Palette, Quirks};
+
+const ROM: &[u8] = &[0x12, 0x00];
+
+fn edit(files: &MemoryFiles, text: &str) {
+    files.write(&RomConfig::path(ROM), text.as_bytes()).unwrap();
+}
+
+#[test]
+fn edits_apply_while_the_rom_runs() {
+    let files = MemoryFiles::new();
+    edit(&files, "cycles_per_frame = 10\n");
+    let config = RomConfig::load_from(&files, ROM).unwrap();
+    let mut emulator = EmulatorBuilder::new().rom(ROM).build().unwrap();
+    let mut watcher = ConfigWatcher::new(&files, ROM, config);
+    assert_eq!(watcher.poll(&files, &mut emulator), None);
+
+    edit(
+        &files,
+        "cycles_per_frame = 30\nquirks = schip\npalette = 33ff66 002200\nfont = octo\n",
+    );
+    let reload = watcher.poll(&files, &mut emulator).unwrap().unwrap();
+    assert_eq!(reload.applied, ["speed", "quirks", "palette"]);
+    assert_eq!(reload.on_reset, ["font"]);
+    assert_eq!(emulator.cycles_per_frame, 30);
+    assert_eq!(emulator.quirks, Quirks::schip());
+    let palette = watcher.config().palette.unwrap();
+    assert_eq!(palette.foreground, [0x33, 0xFF, 0x66, 0xFF]);
+    assert_eq!(watcher.poll(&files, &mut emulator), None);
+}
+
+#[test]
+fn broken_edits_leave_the_config_alone() {
+    let files = MemoryFiles::new();
+    let mut emulator = EmulatorBuilder::new().rom(ROM).build().unwrap();
+    let cycles = emulator.cycles_per_frame;
+    let mut watcher = ConfigWatcher::new(&files, ROM, RomConfig::default());
+    edit(&files, "cycles_per_frame = 30\npalette = green\n");
+    assert!(watcher.poll(&files, &mut emulator).unwrap().is_err());
+    assert_eq!(emulator.cycles_per_frame, cycles);
+    // Reported once.
+    assert_eq!(watcher.poll(&files, &mut emulator), None);
+
+    edit(&files, "cycles_per_frame = 30\n");
+    assert!(watcher.poll(&files, &mut emulator).unwrap().is_ok());
+    assert_eq!(emulator.cycles_per_frame, 30);
+}
+
+#[test]
+fn taking_an_option_out_waits_for_a_reset() {
+    let old = RomConfig::parse("cycles_per_frame = 30\nmacro.start = 1 5 down\n").unwrap();
+    let new = RomConfig::parse("macro.start = 1 6 down\n").unwrap();
+    let reload = Reload::between(&old, &new);
+    assert_eq!(reload.applied, ["macros"]);
+    assert_eq!(reload.on_reset, ["speed"]);
+    assert!(Reload::between(&new, &new).is_empty());
+
+    let palette: Palette = "#33ff66 002200".parse().unwrap();
+    assert_eq!(palette.to_string(), "33ff66 002200");
+    assert!("33ff66".parse::<Palette>().is_err());
+}
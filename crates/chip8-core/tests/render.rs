@@ -0,0 +1,139 @@
+use chip8_core::render::{PixelStyle, Renderer, Rotation, Viewport};
+use chip8_core::{EmulatorBuilder, Palette};
+
+#[test]
+fn viewports_scale_by_whole_physical_pixels() {
+    let emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    let display = &emulator.display;
+
+    // 800x600 points at 150% are 1200x900 pixels, 18 per CHIP-8 pixel.
+    let viewport = Viewport::fit(display, (800.0, 600.0), 1.5);
+    assert_eq!(viewport.scale, 18);
+    assert_eq!((viewport.width, viewport.height), (1200, 900));
+    assert_eq!((viewport.x, viewport.y), (24, 162));
+
+    // The same window dragged onto a 4K monitor at 200%.
+    let viewport = Viewport::fit(display, (800.0, 600.0), 2.0);
+    assert_eq!(viewport.scale, 25);
+    assert_eq!((viewport.x, viewport.y), (0, 200));
+
+    // Too small a window still shows every pixel.
+    assert_eq!(Viewport::fit(display, (10.0, 10.0), 1.0).scale, 1);
+    assert_eq!(Viewport::logical_size(display, 10, 2.0), (320.0, 160.0));
+}
+
+#[test]
+fn turned_pictures_fit_on_their_side() {
+    let emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    // 32x64 once turned, in a 400x800 window.
+    let viewport = Viewport::fit_turned(&emulator.display, Rotation::Right, (400.0, 800.0), 1.0);
+    assert_eq!(viewport.scale, 12);
+    assert_eq!((viewport.x, viewport.y), (8, 16));
+}
+
+#[test]
+fn physical_points_map_back_to_display_pixels() {
+    let emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    let display = &emulator.display;
+    let viewport = Viewport::fit_physical(display, (660, 340));
+    assert_eq!(viewport.scale, 10);
+    assert_eq!(viewport.display_pixel(display, 9, 9), None);
+    assert_eq!(viewport.display_pixel(display, 10, 10), Some((0, 0)));
+    assert_eq!(viewport.display_pixel(display, 649, 329), Some((63, 31)));
+    assert_eq!(viewport.display_pixel(display, 650, 10), None);
+}
+
+#[test]
+fn rotation_turns_the_picture_and_the_arrows() {
+    let mut emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    emulator.display.draw_sprite(0, 0, &[0x80], false);
+    let mut renderer = Renderer::new(Palette::default(), 2);
+    renderer.rotation = "270".parse().unwrap();
+    assert_eq!(renderer.output_size(&emulator.display), (64, 128));
+    let mut buffer = vec![0; 64 * 128 * 4];
+    renderer.render(&emulator.display, &mut buffer);
+    // The top left corner is now the bottom left one.
+    let lit = |x: usize, y: usize| buffer[(y * 64 + x) * 4..][..4] == Palette::default().foreground;
+    assert!(lit(0, 127) && lit(1, 126));
+    assert!(!lit(0, 0) && !lit(2, 127));
+
+    // Up on the keypad is up on the screen.
+    assert_eq!(Rotation::Right.key(0x6), 0x2);
+    assert_eq!(Rotation::Right.key(0x2), 0x4);
+    assert_eq!(Rotation::Left.key(0x2), 0x6);
+    assert_eq!(Rotation::UpsideDown.key(0x8), 0x2);
+    assert_eq!(Rotation::Left.key(0x5), 0x5);
+    assert!("45".parse::<Rotation>().is_err());
+}
+
+#[test]
+fn blending_averages_pixels_over_the_last_frames() {
+    let mut emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    let mut renderer = Renderer::new(Palette::default(), 1);
+    renderer.set_persistence(2);
+    let mut buffer = vec![0; 64 * 32 * 4];
+    // (0, 0) steady, (1, 0) lit for only the first of the two frames.
+    emulator.display.draw_sprite(0, 0, &[0xC0], false);
+    renderer.render(&emulator.display, &mut buffer);
+    emulator.display.draw_sprite(1, 0, &[0x80], false);
+    renderer.render(&emulator.display, &mut buffer);
+    assert_eq!(buffer[..4], [0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(buffer[4..8], [0x7F, 0x7F, 0x7F, 0xFF]);
+    assert_eq!(buffer[8..12], [0x00, 0x00, 0x00, 0xFF]);
+
+    // Without blending the pixel is simply off.
+    renderer.set_persistence(1);
+    renderer.render(&emulator.display, &mut buffer);
+    assert_eq!(buffer[4..8], [0x00, 0x00, 0x00, 0xFF]);
+}
+
+#[test]
+fn pixel_styles_shape_every_cell() {
+    let mut emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    emulator.display.draw_sprite(0, 0, &[0x80], false);
+    // The 4x4 cell of the lit pixel, '#' for the foreground.
+    let cell = |style: PixelStyle| {
+        let mut renderer = Renderer::new(Palette::default(), 4);
+        renderer.style = style;
+        let mut buffer = vec![0; 256 * 128 * 4];
+        renderer.render(&emulator.display, &mut buffer);
+        let lit = |x: usize, y: usize| buffer[(y * 256 + x) * 4] == 0xFF;
+        (0..4)
+            .map(|y| (0..4).map(|x| if lit(x, y) { '#' } else { '.' }).collect())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(cell(PixelStyle::Solid), ["####", "####", "####", "####"]);
+    assert_eq!(cell(PixelStyle::Grid), ["###.", "###.", "###.", "...."]);
+    assert_eq!(cell(PixelStyle::Led), [".##.", "####", "####", ".##."]);
+    assert_eq!("led".parse(), Ok(PixelStyle::Led));
+    assert!("round".parse::<PixelStyle>().is_err());
+}
+
+#[test]
+fn a_zero_scale_renders_at_one() {
+    let mut emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    emulator.display.draw_sprite(0, 0, &[0x80], false);
+    let mut renderer = Renderer::new(Palette::default(), 0);
+    assert_eq!(renderer.scale(), 1);
+    renderer.set_scale(3);
+    renderer.set_scale(0);
+    assert_eq!(renderer.output_size(&emulator.display), (64, 32));
+    let mut buffer = vec![0; 64 * 32 * 4];
+    renderer.render(&emulator.display, &mut buffer);
+    assert_eq!(buffer[..4], [0xFF; 4]);
+}
+
+#[test]
+fn rgba_buffers_of_the_wrong_size_are_refused() {
+    let mut emulator = EmulatorBuilder::new().seed(0).build().unwrap();
+    emulator.display.draw_sprite(0, 0, &[0x80], false);
+    let display = &emulator.display;
+    let palette = Palette::default();
+    let mut buffer = vec![0; 128 * 64 * 4];
+    assert!(display.render_rgba(&mut buffer, &palette, 0).is_err());
+    assert!(display.render_rgba(&mut buffer[4..], &palette, 2).is_err());
+    assert_eq!(buffer, vec![0; 128 * 64 * 4]);
+    display.render_rgba(&mut buffer, &palette, 2).unwrap();
+    assert_eq!(buffer[..8], [0xFF; 8]);
+    assert_eq!(display.to_rgba(&palette).len(), 64 * 32 * 4);
+}
@@ -0,0 +1,40 @@
+use chip8_core::rom::Rom;
+use chip8_core::{EmulatorError, Instruction};
+
+#[test]
+fn instructions_skip_data() {
+    // 0x200 LD I, 0x20a; 0x202 SE V0, 0; 0x204 CALL 0x208; 0x206 JP 0x206;
+    // 0x208 RET; 0x20a sprite
+    let rom = Rom::new(vec![
+        0xA2, 0x0A, 0x30, 0x00, 0x22, 0x08, 0x12, 0x06, 0x00, 0xEE, 0xF0, 0x90,
+    ])
+    .unwrap();
+    let addresses: Vec<u16> = rom.instructions().map(|(address, _, _)| address).collect();
+    assert_eq!(addresses, [0x200, 0x202, 0x204, 0x206, 0x208]);
+    assert_eq!(
+        rom.instructions().nth(2),
+        Some((0x204, 0x2208, Instruction::Call { nnn: 0x208 }))
+    );
+    assert_eq!(
+        rom.analysis().data_references().collect::<Vec<_>>(),
+        [0x20a]
+    );
+    assert!(!rom.analysis().is_code(0x20a));
+
+    assert_eq!(
+        Rom::new(vec![0; 4000]),
+        Err(EmulatorError::RomTooLarge(4000))
+    );
+}
+
+#[test]
+fn disassembles_code_and_data() {
+    // LD I, 0x204; JP 0x202; sprite
+    let rom = Rom::new(vec![0xA2, 0x04, 0x12, 0x02, 0xF0, 0x90, 0xF0]).unwrap();
+    assert_eq!(
+        chip8_core::dump::disassemble_rom(&rom),
+        "0x200  A204  LD I, 0x204\n\
+         0x202  1202  JP 0x202\n\
+         0x204        DB 0xf0, 0x90, 0xf0\n"
+    );
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "serde")]
+
+use chip8_core::checksum::state_checksum;
+use chip8_core::instruction::Variant;
+use chip8_core::{run_frame, Emulator, EmulatorBuilder};
+
+fn restored(emulator: &Emulator) -> Emulator {
+    let json = serde_json::to_string(emulator).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+fn run(emulator: &mut Emulator, frames: usize) {
+    for _ in 0..frames {
+        assert!(run_frame(emulator).error.is_none());
+    }
+}
+
+#[test]
+fn restored_machines_carry_on_the_same() {
+    // RND V0, 0xFF; RND V1, 0x1F; LD I, 0x20A; DRW V0, V1, 1; JP 0x200; sprite
+    let rom = [
+        0xC0, 0xFF, 0xC1, 0x1F, 0xA2, 0x0A, 0xD0, 0x11, 0x12, 0x00, 0xFF,
+    ];
+    let mut original = EmulatorBuilder::new().seed(7).rom(&rom).build().unwrap();
+    original.keypad.press(0x3);
+    run(&mut original, 10);
+    let mut copy = restored(&original);
+    assert_eq!(state_checksum(&copy), state_checksum(&original));
+
+    run(&mut original, 20);
+    run(&mut copy, 20);
+    assert_eq!(copy.ram, original.ram);
+    assert_eq!(copy.display, original.display);
+    assert_eq!(copy.keypad, original.keypad);
+    assert_eq!(state_checksum(&copy), state_checksum(&original));
+}
+
+#[test]
+fn chip8x_colour_zones_survive() {
+    let mut original = EmulatorBuilder::new()
+        .variant(Variant::Chip8x)
+        .build()
+        .unwrap();
+    let chip8x = original.chip8x.as_mut().unwrap();
+    chip8x.zones[..3].copy_from_slice(&[2, 5, 7]);
+    chip8x.background = 3;
+    let copy = restored(&original);
+    assert_eq!(copy.chip8x, original.chip8x);
+}
@@ -0,0 +1,57 @@
+use chip8_core::session::SessionLog;
+use chip8_core::{run_frame, EmulatorBuilder};
+
+// Waits for a key, then draws a random byte's worth of sprite and calls
+// itself until the stack overflows.
+const ROM: [u8; 10] = [0xF0, 0x0A, 0xC1, 0xFF, 0xA3, 0x00, 0xD0, 0x11, 0x22, 0x06];
+
+fn record() -> SessionLog {
+    let mut emulator = EmulatorBuilder::new().seed(7).rom(&ROM).build().unwrap();
+    let mut log = SessionLog::start("test.ch8", &ROM, &emulator);
+    for frame in 1..=200 {
+        match frame {
+            100 => emulator.keypad.press(5),
+            110 => emulator.keypad.release(5),
+            _ => {}
+        }
+        log.before_frame(&emulator.keypad);
+        let output = run_frame(&mut emulator);
+        log.after_frame(&emulator, &output);
+        if output.error.is_some() {
+            break;
+        }
+    }
+    log
+}
+
+#[test]
+fn sessions_replay_to_the_same_error() {
+    let log = record();
+    assert_eq!(log.events, vec![(100, 5, true), (110, 5, false)]);
+    let error = log.error.clone().expect("the ROM overflows its stack");
+    assert_eq!(log.frames, error.0);
+
+    let parsed = SessionLog::parse(&log.to_text()).unwrap();
+    assert_eq!(parsed.to_text(), log.to_text());
+    assert!(parsed.replay(&ROM).is_ok());
+
+    // Another seed draws another sprite, the first checksum catches it.
+    let mut tampered = parsed.clone();
+    tampered.seed += 1;
+    assert!(tampered.replay(&ROM).unwrap_err().contains("desync"));
+    let mut other = ROM;
+    other[3] = 0x0F;
+    assert!(parsed
+        .replay(&other)
+        .unwrap_err()
+        .contains("does not match"));
+}
+
+#[test]
+fn session_logs_report_bad_lines() {
+    assert_eq!(
+        SessionLog::parse("rom 12 x.ch8\nkey 5 G down\n").unwrap_err(),
+        "line 2: invalid key event"
+    );
+    assert!(SessionLog::parse("quirks chip8\n").is_err());
+}
@@ -0,0 +1,21 @@
+use chip8_core::stats::PlayStatsStore;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn stats_accumulate_and_round_trip() {
+    let mut store = PlayStatsStore::default();
+    let launch = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    store.record_launch(0x9495_733f_6062_4ee6, "pong", launch);
+    store.add_playtime(0x9495_733f_6062_4ee6, Duration::from_secs(65));
+    store.record_launch(0x9495_733f_6062_4ee6, "pong 2", launch);
+    store.add_playtime(0x9495_733f_6062_4ee6, Duration::from_secs(3600));
+
+    let stats = store.get(0x9495_733f_6062_4ee6).unwrap();
+    assert_eq!(stats.launches, 2);
+    assert_eq!(stats.name, "pong 2");
+    assert_eq!(stats.playtime(), "1h 01m");
+    assert_eq!(stats.last_played_date().as_deref(), Some("2023-11-14"));
+
+    assert_eq!(PlayStatsStore::parse(&store.to_text()), Ok(store));
+    assert!(PlayStatsStore::parse("beef = 1 2").is_err());
+}
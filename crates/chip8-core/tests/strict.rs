@@ -0,0 +1,81 @@
+use chip8_core::strict::{Behavior, Findings, Strict};
+use chip8_core::{demo, run_frame, step, Emulator, EmulatorBuilder, EmulatorError};
+
+fn strict(rom: &[u8]) -> (Emulator, Findings) {
+    let mut emulator = EmulatorBuilder::new().seed(0).rom(rom).build().unwrap();
+    emulator.quirks.display_wait = false;
+    let strict = Strict::new(&emulator, rom.len());
+    let findings = strict.findings();
+    emulator.hooks.add(strict);
+    (emulator, findings)
+}
+
+fn behaviors(findings: &Findings) -> Vec<(Behavior, u16)> {
+    findings
+        .list()
+        .iter()
+        .map(|finding| (finding.behavior, finding.address))
+        .collect()
+}
+
+#[test]
+fn flags_what_interpreters_disagree_on() {
+    // LD VF, 1; ADD VF, V0; LD I, 0x300; LD V1, [I]; LD V0, 80; LD I, 0x50;
+    // DRW V0, V0, 5; JP 0x211; at 0x211 LD V0, 60; LD V1, 0; DRW V0, V1, 5
+    let (mut emulator, findings) = strict(&[
+        0x6F, 0x01, 0x8F, 0x04, 0xA3, 0x00, 0xF1, 0x65, 0x60, 0x50, 0xA0, 0x50, 0xD0, 0x05, 0x12,
+        0x11, 0x00, 0x60, 0x3C, 0x61, 0x00, 0xD0, 0x15,
+    ]);
+    for _ in 0..11 {
+        step(&mut emulator).unwrap();
+    }
+    assert_eq!(
+        behaviors(&findings),
+        [
+            (Behavior::FlagOperand, 0x202),
+            (Behavior::Uninitialized, 0x206),
+            (Behavior::OffScreen, 0x20C),
+            (Behavior::OddAddress, 0x211),
+            (Behavior::OddAddress, 0x213),
+            (Behavior::OddAddress, 0x215),
+            (Behavior::AcrossEdge, 0x215),
+        ]
+    );
+    assert_eq!(
+        findings.list()[0].to_string(),
+        "0x202 (8F04): VF is an operand of 8xyN, which also sets it as the flag"
+    );
+}
+
+#[test]
+fn memory_the_program_wrote_is_initialized() {
+    // LD V0, 42; LD I, 0x300; LD B, V0; LD V2, [I], in a loop.
+    let (mut emulator, findings) =
+        strict(&[0x60, 0x2A, 0xA3, 0x00, 0xF0, 0x33, 0xF2, 0x65, 0x12, 0x00]);
+    run_frame(&mut emulator);
+    assert!(findings.is_empty());
+    assert_eq!(emulator.v_registers[..3], [0, 4, 2]);
+    assert_eq!(findings.trap(), None);
+
+    // LD I, 0x300; LD V2, [I], counted once however often it runs.
+    let (mut emulator, findings) = strict(&[0xA3, 0x00, 0xF2, 0x65, 0x12, 0x00]);
+    run_frame(&mut emulator);
+    assert_eq!(findings.len(), 1);
+    assert!(findings.list()[0].count > 1);
+    assert!(matches!(
+        findings.trap(),
+        Some(EmulatorError::UndefinedBehavior(_))
+    ));
+}
+
+#[test]
+fn the_demo_is_portable() {
+    let (mut emulator, findings) = strict(demo::ROM);
+    for frame in 0..300 {
+        if frame == 100 {
+            emulator.keypad.press(0xA);
+        }
+        assert_eq!(run_frame(&mut emulator).error, None);
+    }
+    assert_eq!(behaviors(&findings), []);
+}
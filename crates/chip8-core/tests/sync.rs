@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use chip8_core::audio::Beeper;
+use chip8_core::sync::{Correction, Resync, SyncMonitor};
+
+#[test]
+fn drift_is_the_clock_minus_the_emulated_time() {
+    let mut sync = SyncMonitor::new(1000, Resync::Off);
+    for _ in 0..60 {
+        sync.frame_emulated();
+    }
+    // A second of frames, but the device played 1.05 seconds.
+    sync.audio_played(1050);
+    sync.presented(Duration::from_millis(1000));
+    let stats = sync.stats();
+    assert!((stats.audio_drift - 0.05).abs() < 1e-9);
+    // The 60th frame went up 59 frames after the first.
+    assert!((stats.render_drift - (1.0 - 59.0 / 60.0)).abs() < 1e-9);
+    assert_eq!(sync.correction(), Correction::None);
+}
+
+#[test]
+fn resampling_stretches_audio_until_it_is_back_in_line() {
+    let mut sync = SyncMonitor::new(1000, Resync::Resample);
+    for _ in 0..60 {
+        sync.frame_emulated();
+    }
+    sync.audio_played(1100);
+    let Correction::Resample(rate) = sync.correction() else {
+        panic!("expected resampling");
+    };
+    assert_eq!(rate, 1.005);
+    assert_eq!(sync.stats().rate, 1.005);
+
+    // Once emulation has caught up the rate goes back to 1.
+    for _ in 0..6 {
+        sync.frame_emulated();
+    }
+    assert_eq!(sync.correction(), Correction::None);
+    assert_eq!(sync.stats().rate, 1.0);
+}
+
+#[test]
+fn resampled_beeps_keep_their_pitch_in_emulated_time() {
+    let sign_changes = |resample: f32| {
+        let mut beeper = Beeper::new(1000);
+        beeper.set_frequency(100.0);
+        beeper.set_resample(resample);
+        beeper.set_beeping(true);
+        let mut samples = [0.0; 1200];
+        beeper.fill(&mut samples);
+        samples[1000..]
+            .windows(2)
+            .filter(|pair| pair[0].signum() != pair[1].signum())
+            .count()
+    };
+    // Twice the samples per emulated second, each wave twice as long.
+    // 200 samples of 100 Hz at 1000 Hz are 40 half waves, give or take the
+    // one the window starts in.
+    assert!((39..=40).contains(&sign_changes(1.0)));
+    assert!((19..=20).contains(&sign_changes(2.0)));
+}
+
+#[test]
+fn duplicating_frames_follows_the_audio_or_else_the_display() {
+    let mut sync = SyncMonitor::new(1000, Resync::DuplicateFrames);
+    sync.frame_emulated();
+    sync.frame_emulated();
+    // Presented late, emulation has to catch up.
+    sync.presented(Duration::from_millis(50));
+    assert_eq!(sync.correction(), Correction::SkipFrame);
+
+    // With audio, the audio clock wins. It is behind: show a frame again.
+    sync.audio_played(0);
+    assert_eq!(sync.correction(), Correction::DuplicateFrame);
+    let stats = sync.stats();
+    assert_eq!((stats.skipped_frames, stats.duplicated_frames), (1, 1));
+    assert!(stats
+        .to_string()
+        .starts_with("audio -33.3 ms, video +33.3 ms"));
+}
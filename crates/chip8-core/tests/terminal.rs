@@ -0,0 +1,133 @@
+use chip8_core::cast::CastWriter;
+use chip8_core::display::Resolution;
+use chip8_core::render::Rotation;
+use chip8_core::terminal::{Graphics, TextStyle};
+use chip8_core::{Display, Palette};
+
+fn display() -> Display {
+    let mut display = Display::new();
+    // Two lit pixels stacked, and a lone one below them.
+    display.draw_sprite(0, 0, &[0x80, 0x80, 0x40], false);
+    display
+}
+
+#[test]
+fn renders_text() {
+    let display = display();
+    let ascii = TextStyle::Ascii.render(&display);
+    let lines: Vec<&str> = ascii.lines().collect();
+    assert_eq!(lines.len(), 32);
+    assert_eq!(&lines[2][..3], " # ");
+    assert_eq!(TextStyle::Ascii.size(&display), (64, 32));
+
+    let blocks = TextStyle::Blocks.render(&display);
+    let lines: Vec<&str> = blocks.lines().collect();
+    assert_eq!(lines.len(), 16);
+    assert!(lines[0].starts_with("█ "));
+    assert!(lines[1].starts_with(" ▀"));
+    assert!("sixel".parse::<TextStyle>().is_err());
+}
+
+#[test]
+fn writes_asciinema_recordings() {
+    let mut cast = CastWriter::new(Vec::new(), 64, 16, 1_700_000_000).unwrap();
+    cast.output(0.0, "\x1b[H").unwrap();
+    cast.output(0.5, "█\n").unwrap();
+    let text = String::from_utf8(cast.finish().unwrap()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines[0],
+        r#"{"version":2,"width":64,"height":16,"timestamp":1700000000,"env":{"TERM":"xterm-256color"}}"#
+    );
+    assert_eq!(lines[1], r#"[0.000000,"o","\u001b[H"]"#);
+    assert_eq!(lines[2], r#"[0.500000,"o","█\u000a"]"#);
+}
+
+#[test]
+fn braille_fits_the_hires_screen_in_80x24() {
+    let mut display = Display::new();
+    display.set_resolution(Resolution::Hires);
+    // A 2x4 block in the first cell and one dot, the bottom right, in the
+    // last one.
+    display.draw_sprite(0, 0, &[0xC0, 0xC0, 0xC0, 0xC0], false);
+    display.draw_sprite(127, 63, &[0x80], false);
+    assert_eq!(TextStyle::Braille.size(&display), (64, 16));
+    let text = TextStyle::Braille.render(&display);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 16);
+    assert!(lines[0].starts_with("⣿⠀"));
+    assert!(lines[15].ends_with("⢀"));
+}
+
+#[test]
+fn detects_graphics_terminals() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+    assert_eq!(
+        Graphics::detect(env(&[("TERM", "xterm-kitty")])),
+        Some(Graphics::Kitty)
+    );
+    assert_eq!(
+        Graphics::detect(env(&[("TERM", "xterm"), ("TERM_PROGRAM", "WezTerm")])),
+        Some(Graphics::Kitty)
+    );
+    assert_eq!(
+        Graphics::detect(env(&[("TERM", "foot")])),
+        Some(Graphics::Sixel)
+    );
+    assert_eq!(Graphics::detect(env(&[("TERM", "xterm-256color")])), None);
+}
+
+#[test]
+fn draws_bitmaps_with_sixel_and_kitty() {
+    let mut display = Display::new();
+    display.draw_sprite(0, 0, &[0x80], false);
+    let sixel = Graphics::Sixel.render(&display, &Palette::default(), 1);
+    assert!(sixel.starts_with("\x1bPq\"1;1;64;32#0;2;0;0;0#1;2;100;100;100"));
+    // In the first band only the top left pixel is lit.
+    assert!(sixel.contains("#0}!63~$#1@!63?-"));
+    assert!(sixel.ends_with("\x1b\\"));
+
+    let kitty = Graphics::Kitty.render(&display, &Palette::default(), 2);
+    assert!(kitty.starts_with("\x1b_Ga=T,f=32,i=1,q=2,C=1,s=128,v=64,m=1;//////////8AAAD/"));
+    // 128x64 RGBA in base64 is 43692 bytes, 11 chunks.
+    assert_eq!(kitty.matches("\x1b_G").count(), 11);
+    assert!(kitty.ends_with("=\x1b\\"));
+}
+
+#[test]
+fn turned_screens_stand_on_their_side() {
+    let display = display();
+    let text = TextStyle::Ascii.render_rotated(&display, Rotation::Right);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!((lines[0].len(), lines.len()), (32, 64));
+    // The top left corner is now the top right one.
+    assert!(lines[0].ends_with(" ##"));
+    assert!(lines[1].ends_with("#  "));
+
+    let text = TextStyle::Ascii.render_rotated(&display, Rotation::UpsideDown);
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[31].ends_with(" #"));
+    assert!(lines[29].ends_with("# "));
+}
+
+#[test]
+fn scaled_screens_blow_every_pixel_up() {
+    let display = display();
+    let text = TextStyle::Ascii.render_scaled(&display, Rotation::None, 2);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!((lines[0].len(), lines.len()), (128, 64));
+    assert!(lines[0].starts_with("##  "));
+    assert!(lines[3].starts_with("##  "));
+    assert!(lines[4].starts_with("  ##"));
+    assert!(lines[5].starts_with("  ##"));
+
+    let blocks = TextStyle::Blocks.render_scaled(&display, Rotation::None, 2);
+    assert!(blocks.lines().next().unwrap().starts_with("██  "));
+    assert_eq!(blocks.lines().count(), 32);
+}
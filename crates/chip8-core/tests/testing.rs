@@ -0,0 +1,42 @@
+use chip8_core::font::FONT_ADDRESS;
+use chip8_core::testing::Harness;
+
+// LD V5, K; LD F, V5; DRW V0, V0, 5; JP 0x206
+const ROM: [u8; 8] = [0xF5, 0x0A, 0xF5, 0x29, 0xD0, 0x05, 0x12, 0x06];
+
+#[test]
+fn drives_a_rom_like_a_player() {
+    let mut game = Harness::new(&ROM);
+    game.run_frames(10);
+    assert!(game.emulator.display.is_blank());
+    game.tap(0x5).run_frames(2);
+    game.assert_register(0x5, 5);
+    game.assert_i(FONT_ADDRESS + 25);
+    game.assert_memory(FONT_ADDRESS + 25, &[0xF0, 0x80, 0xF0, 0x10, 0xF0]);
+    game.assert_pixel(0, 1, true);
+    game.assert_pixel(1, 1, false);
+    let top: String = game.screen().lines().take(5).map(|row| &row[..4]).collect();
+    assert_eq!(top, "#####...####...#####");
+    assert!(game.screen_shows("5"));
+}
+
+#[test]
+fn waits_until_the_rom_draws() {
+    let mut game = Harness::new(&ROM);
+    // Fx0A takes a key pressed while it waits, once it is let go.
+    game.run_frames(1).press(0x2).run_frames(3);
+    assert!(game.emulator.display.is_blank());
+    game.release(0x2);
+    // The draw waits for the vertical blank after the frame taking the key.
+    assert_eq!(
+        game.run_until(60, |emulator| !emulator.display.is_blank()),
+        2
+    );
+    game.assert_register(0x5, 2);
+}
+
+#[test]
+#[should_panic(expected = "V5 after 0 frames: expected 0x01, found 0x00")]
+fn failures_say_what_differs() {
+    Harness::new(&ROM).assert_register(0x5, 1);
+}
@@ -0,0 +1,83 @@
+use chip8_core::checksum::state_checksum;
+use chip8_core::timeline::Timeline;
+use chip8_core::{demo, run_frame, EmulatorBuilder, Quirks};
+
+#[test]
+fn keeps_every_nth_frame_and_rewinds() {
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .build()
+        .unwrap();
+    let mut timeline = Timeline::new(10, 3);
+    for frame in 0..=40 {
+        emulator.v_registers[0] = frame as u8;
+        if frame == 30 {
+            emulator.display.draw_sprite(0, 0, &[0x80], true);
+        }
+        timeline.record(frame, &emulator);
+    }
+
+    let frames: Vec<u64> = timeline.moments().map(|moment| moment.frame).collect();
+    assert_eq!(frames, [20, 30, 40]);
+    assert_eq!(timeline.seek(35), Some(1));
+    assert_eq!(timeline.seek(5), None);
+    assert!(timeline.get(1).unwrap().thumbnail.pixel(0, 0));
+    assert!(!timeline.get(0).unwrap().thumbnail.pixel(0, 0));
+
+    assert_eq!(timeline.rewind(0, &mut emulator), Some(20));
+    assert_eq!(emulator.v_registers[0], 20);
+    assert_eq!(timeline.len(), 1);
+}
+
+#[test]
+fn states_come_back_exactly_however_they_were_kept() {
+    let mut emulator = EmulatorBuilder::new()
+        .rom(demo::ROM)
+        .seed(3)
+        .build()
+        .unwrap();
+    let mut timeline = Timeline::new(1, 100);
+    let mut checksums = Vec::new();
+    for frame in 0..150 {
+        emulator.keypad.press((frame / 10 % 16) as u8);
+        timeline.record(frame, &emulator);
+        checksums.push(state_checksum(&emulator));
+        run_frame(&mut emulator);
+    }
+    // The first 50 have gone, the rest against what came before them.
+    assert_eq!(timeline.len(), 100);
+    for index in [0, 1, 31, 32, 33, 99] {
+        let state = timeline.state(index).unwrap();
+        assert_eq!(state_checksum(&state), checksums[50 + index], "{}", index);
+    }
+    assert_eq!(timeline.rewind(40, &mut emulator), Some(90));
+    assert_eq!(state_checksum(&emulator), checksums[90]);
+    timeline.record(91, &emulator);
+    assert_eq!(
+        state_checksum(&timeline.state(41).unwrap()),
+        state_checksum(&emulator)
+    );
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn ten_seconds_of_rewind_take_little_memory() {
+    let mut emulator = EmulatorBuilder::new().rom(demo::ROM).build().unwrap();
+    let mut timeline = Timeline::new(1, 600);
+    for frame in 0..600 {
+        timeline.record(frame, &emulator);
+        run_frame(&mut emulator);
+    }
+    let state = chip8_core::savestate::save(&emulator);
+    let plain = chip8_core::compress::zstd_unpack(&state[4..])
+        .unwrap()
+        .len();
+    let kept = timeline.state_bytes();
+    assert!(
+        kept * 20 < 600 * plain,
+        "{} bytes for {} unpacked",
+        kept,
+        plain
+    );
+}
@@ -0,0 +1,35 @@
+use chip8_core::title::{rom_name, rom_title};
+use std::path::Path;
+
+#[test]
+fn known_roms_go_by_their_proper_title() {
+    let pong = include_bytes!("../../../roms/pong.ch8");
+    assert_eq!(
+        rom_title(pong, Path::new("roms/pong.ch8")),
+        "Pong (1 player)"
+    );
+    // Whatever the file is called.
+    assert_eq!(rom_title(pong, Path::new("x.bin")), "Pong (1 player)");
+}
+
+#[test]
+fn other_roms_go_by_their_file_name() {
+    assert_eq!(
+        rom_title(&[0x12, 0x00], Path::new("/games/Loop.ch8")),
+        "Loop"
+    );
+    assert_eq!(rom_name(Path::new("roms/maze")), "maze");
+    assert_eq!(rom_name(Path::new("tetris.v2.ch8")), "tetris.v2");
+    assert_eq!(rom_name(Path::new(".hidden")), ".hidden");
+    // Nothing to take a stem from, the path as it is.
+    assert_eq!(rom_name(Path::new("..")), "..");
+}
+
+#[cfg(unix)]
+#[test]
+fn names_that_are_not_utf8_are_shown_lossily() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    let path = Path::new(OsStr::from_bytes(b"caf\xe9.ch8"));
+    assert_eq!(rom_name(path), "caf\u{FFFD}");
+}
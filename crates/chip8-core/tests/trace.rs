@@ -0,0 +1,24 @@
+use chip8_core::trace::Trace;
+use chip8_core::{load_rom_to_memory, step, EmulatorBuilder, Quirks};
+
+#[test]
+fn keeps_the_last_instructions() {
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .build()
+        .unwrap();
+    // ADD V0, 1; JP 0x200
+    load_rom_to_memory(&mut emulator, &[0x70, 0x01, 0x12, 0x00]).unwrap();
+
+    let mut trace = Trace::new(3);
+    for _ in 0..5 {
+        trace.record(&emulator);
+        step(&mut emulator).unwrap();
+    }
+    assert_eq!(trace.len(), 3);
+    assert_eq!(
+        trace.to_text(),
+        "0x200  7001  ADD V0, 0x01\n0x202  1200  JP 0x200\n0x200  7001  ADD V0, 0x01\n"
+    );
+}
@@ -0,0 +1,53 @@
+use chip8_core::config::RomConfig;
+use chip8_core::triggers::Condition;
+use chip8_core::{load_rom_to_memory, step, EmulatorBuilder, Quirks};
+
+#[test]
+fn conditions_parse_and_print() {
+    let condition: Condition = "RAM[0x3A0] >= 100 && v0 != #ff".parse().unwrap();
+    assert_eq!(condition.to_string(), "RAM[0x3a0] >= 100 && V0 != 255");
+    // XO-CHIP has 64K.
+    assert!("RAM[0x1000] == 1".parse::<Condition>().is_ok());
+    assert!("RAM[0x10000] == 1".parse::<Condition>().is_err());
+    assert!("V0 = 1".parse::<Condition>().is_err());
+    assert!("VG == 1".parse::<Condition>().is_err());
+}
+
+#[test]
+fn triggers_fire_once_when_their_condition_holds() {
+    let config = RomConfig::parse(
+        "trigger.score = RAM[0x300] >= 3 | Scored three\n\
+         trigger.done = V1 == 1\n",
+    )
+    .unwrap();
+    let mut triggers = config.triggers().unwrap();
+    // V0 counts up and is stored at 0x300 after every increment.
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::default())
+        .seed(0)
+        .build()
+        .unwrap();
+    // LD I, 0x300; ADD V0, 1; LD [I], V0; JP 0x200
+    load_rom_to_memory(
+        &mut emulator,
+        &[0xA3, 0x00, 0x70, 0x01, 0xF0, 0x55, 0x12, 0x00],
+    )
+    .unwrap();
+
+    let mut fired = Vec::new();
+    for _ in 0..40 {
+        step(&mut emulator).unwrap();
+        for trigger in triggers.check(&emulator) {
+            fired.push((trigger.name.clone(), trigger.message.clone()));
+        }
+    }
+    assert_eq!(fired, [("score".to_string(), "Scored three".to_string())]);
+
+    emulator.v_registers[1] = 1;
+    let messages: Vec<String> = triggers
+        .check(&emulator)
+        .iter()
+        .map(|trigger| trigger.message.clone())
+        .collect();
+    assert_eq!(messages, ["done"]);
+}
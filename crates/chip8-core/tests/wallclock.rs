@@ -0,0 +1,46 @@
+use chip8_core::host::FixedClock;
+use chip8_core::savestate::{load, save};
+use chip8_core::{run_frame, EmulatorBuilder, EmulatorError, Instruction};
+
+fn assemble(lines: &[&str]) -> Vec<u8> {
+    lines
+        .iter()
+        .flat_map(|line| line.parse::<Instruction>().unwrap().encode().to_be_bytes())
+        .collect()
+}
+
+// 2024-03-05 13:04:09 UTC.
+const NOW: u64 = 1_709_643_849;
+
+#[test]
+fn the_time_of_day_lands_in_three_registers() {
+    let rom = assemble(&["LD V4, TIME", "LD VE, TIME", "JP 0x204"]);
+    let mut emulator = EmulatorBuilder::new()
+        .wall_clock(-5 * 3600)
+        .rom(&rom)
+        .build()
+        .unwrap();
+    emulator.wall_clock.as_mut().unwrap().sync(&FixedClock(NOW));
+    assert!(run_frame(&mut emulator).error.is_none());
+    assert_eq!(emulator.v_registers[4..7], [9, 4, 8]);
+    // Past VF it wraps to V0.
+    assert_eq!(emulator.v_registers[14..], [9, 4]);
+    assert_eq!(emulator.v_registers[0], 8);
+}
+
+#[test]
+fn the_wall_clock_is_opt_in_and_kept_in_savestates() {
+    let rom = assemble(&["LD V0, TIME"]);
+    let mut emulator = EmulatorBuilder::new().rom(&rom).build().unwrap();
+    assert_eq!(
+        run_frame(&mut emulator).error,
+        Some(EmulatorError::Unimplemented(0xF0F1, "the wall clock"))
+    );
+    assert_eq!(Instruction::decode(0xF2F1).to_string(), "LD V2, TIME");
+
+    let mut emulator = EmulatorBuilder::new().wall_clock(3600).build().unwrap();
+    emulator.wall_clock.as_mut().unwrap().set(NOW);
+    let restored = load(&save(&emulator)).unwrap().wall_clock.unwrap();
+    assert_eq!(restored.time_of_day(), [9, 4, 14]);
+    assert_eq!(restored.utc_offset(), 3600);
+}
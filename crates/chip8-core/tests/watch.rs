@@ -0,0 +1,50 @@
+use chip8_core::watch::{Expression, Watches};
+use chip8_core::EmulatorBuilder;
+
+#[test]
+fn expressions_read_registers_memory_and_do_arithmetic() {
+    let emulator = EmulatorBuilder::new()
+        .register(2, 3)
+        .register(3, 10)
+        .i_register(0x300)
+        .memory(0x300, &[0x12, 0x34, 0x56])
+        .build()
+        .unwrap();
+    for (text, value) in [
+        ("V3", Some(10)),
+        ("v3 - v2", Some(7)),
+        ("V2 - V3", Some(0xFFF9)),
+        ("1 + V3 * 2", Some(21)),
+        ("(1 + V3) * 2", Some(22)),
+        ("V3 / V2", Some(3)),
+        ("V3 / (V2 - 3)", None),
+        ("RAM[0x300]", Some(0x12)),
+        ("RAM[I + V2 - 1]", Some(0x56)),
+        ("WORD[I]", Some(0x1234)),
+        ("word[#301]", Some(0x3456)),
+        ("PC + DT", Some(0x200)),
+    ] {
+        let expression: Expression = text.parse().unwrap();
+        assert_eq!(expression.eval(&emulator), value, "{}", text);
+    }
+    for text in ["", "V3 +", "(V3", "RAM 3", "VG", "V3 % 2", "0xFFFFF"] {
+        assert!(text.parse::<Expression>().is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn the_panel_lines_up_the_values() {
+    let emulator = EmulatorBuilder::new().register(0, 255).build().unwrap();
+    let mut watches = Watches::new();
+    watches.add("V0").unwrap();
+    watches.add("V0 / V1").unwrap();
+    watches.add("V0 + 1").unwrap();
+    assert!(watches.add("V0 +").is_err());
+    assert_eq!(
+        watches.panel(&emulator),
+        "V0         255  0x00ff\nV0 / V1  division by zero\nV0 + 1     256  0x0100\n"
+    );
+    assert_eq!(watches.remove(1).unwrap().to_string(), "V0 / V1");
+    assert!(watches.remove(2).is_none());
+    assert_eq!(watches.len(), 2);
+}
@@ -0,0 +1,35 @@
+use chip8_core::watchdog::Watchdog;
+use chip8_core::{run_frame, Emulator, EmulatorBuilder};
+
+// The frame the watchdog first reports a stall at, if it does within
+// `frames`.
+fn stalls_at(emulator: &mut Emulator, watchdog: &mut Watchdog, frames: u32) -> Option<u32> {
+    (1..=frames).find(|_| {
+        run_frame(emulator);
+        watchdog.observe(emulator).is_err()
+    })
+}
+
+#[test]
+fn a_rom_jumping_to_itself_stalls() {
+    // JP 0x200, the keypad clock still moves on every instruction.
+    let mut emulator = EmulatorBuilder::new().rom(&[0x12, 0x00]).build().unwrap();
+    let mut watchdog = Watchdog::new(30);
+    // The first frame is what the next 30 are compared with.
+    assert_eq!(stalls_at(&mut emulator, &mut watchdog, 100), Some(31));
+    let stall = watchdog.observe(&emulator).unwrap_err();
+    assert_eq!((stall.program_counter, stall.op_code), (0x200, 0x1200));
+    assert!(stall.frames >= 30);
+    assert!(emulator.keypad.clock() > 100);
+}
+
+#[test]
+fn a_rom_changing_state_never_stalls() {
+    // ADD V0, 1 and back, V0 differs from one frame to the next.
+    let mut emulator = EmulatorBuilder::new()
+        .rom(&[0x70, 0x01, 0x12, 0x00])
+        .build()
+        .unwrap();
+    let mut watchdog = Watchdog::new(30);
+    assert_eq!(stalls_at(&mut emulator, &mut watchdog, 1000), None);
+}
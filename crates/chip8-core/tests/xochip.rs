@@ -0,0 +1,131 @@
+use chip8_core::analyzer::analyze;
+use chip8_core::dump::{disassemble, parse_hex_bytes};
+use chip8_core::instruction::Variant;
+use chip8_core::{run_frame, EmulatorBuilder, EmulatorError, Instruction, Quirks};
+
+// Source lines in the assembler's syntax, `DB` lines as bytes.
+fn assemble(lines: &[&str]) -> Vec<u8> {
+    lines
+        .iter()
+        .flat_map(|line| match line.strip_prefix("DB ") {
+            Some(bytes) => parse_hex_bytes(bytes).unwrap(),
+            None => line
+                .parse::<Instruction>()
+                .unwrap()
+                .encode()
+                .to_be_bytes()
+                .to_vec(),
+        })
+        .collect()
+}
+
+#[test]
+fn long_i_reaches_all_of_memory() {
+    let rom = assemble(&[
+        "LD I, LONG",
+        "DB 20 00",
+        "LD V0, 0x55",
+        "LD V1, 0x66",
+        "LD [I], V1",
+        "LD I, LONG",
+        "DB 30 00",
+        "DRW V2, V2, 1",
+        "LD I, LONG",
+        "DB FF FF",
+        "LD V4, [I]",
+        "JP 0x216",
+    ]);
+    let mut emulator = EmulatorBuilder::new()
+        .variant(Variant::XoChip)
+        .quirks(Quirks::none())
+        .rom(&rom)
+        .memory(0x3000, &[0xF0])
+        .memory(0xFFFF, &[1])
+        .memory(0, &[2, 3, 4, 5])
+        .build()
+        .unwrap();
+    run_frame(&mut emulator);
+    assert!(run_frame(&mut emulator).error.is_none());
+
+    assert_eq!(&emulator.ram[0x2000..0x2002], &[0x55, 0x66]);
+    assert!((0..4).all(|x| emulator.display.pixel(x, 0)));
+    assert!(!emulator.display.pixel(4, 0));
+    // Fx65 wraps from 0xFFFF to 0.
+    assert_eq!(emulator.v_registers[..5], [1, 2, 3, 4, 5]);
+    assert_eq!(emulator.i_register, 0xFFFF);
+    assert_eq!(emulator.program_counter, 0x216);
+}
+
+#[test]
+fn classic_machines_have_no_long_i_and_wrap_at_4k() {
+    let rom = assemble(&["LD I, LONG", "DB 20 00"]);
+    let mut emulator = EmulatorBuilder::new().rom(&rom).build().unwrap();
+    assert_eq!(
+        run_frame(&mut emulator).error,
+        Some(EmulatorError::Unimplemented(0xF000, "XO-CHIP"))
+    );
+
+    // An I past 0xFFF, e.g. from Fx1E, reads from the bottom of memory.
+    let rom = assemble(&["ADD I, V0", "LD V1, [I]", "JP 0x204"]);
+    let mut emulator = EmulatorBuilder::new()
+        .quirks(Quirks::none())
+        .rom(&rom)
+        .i_register(0xFFF)
+        .register(0, 2)
+        .memory(0x001, &[7, 8])
+        .build()
+        .unwrap();
+    run_frame(&mut emulator);
+    assert_eq!(emulator.i_register, 0x1001);
+    assert_eq!(emulator.v_registers[..2], [7, 8]);
+}
+
+#[test]
+fn the_long_address_is_data_to_tools() {
+    let rom = assemble(&["LD I, LONG", "DB 02 06", "JP 0x200", "DB F0 90"]);
+    let mut ram = vec![0; 0x1000];
+    ram[0x200..0x200 + rom.len()].copy_from_slice(&rom);
+    assert_eq!(
+        disassemble(&ram, 0x200, 3),
+        "0x200  F000  LD I, LONG\n0x202  0206  DB 02 06\n0x204  1200  JP 0x200\n"
+    );
+
+    let analysis = analyze(&rom);
+    assert_eq!(analysis.code().collect::<Vec<_>>(), [0x200, 0x204]);
+    assert_eq!(analysis.data_references().collect::<Vec<_>>(), [0x206]);
+
+    // Skipping it goes past the address too.
+    let rom = assemble(&["SE V0, 0", "LD I, LONG", "DB 02 08", "JP 0x200"]);
+    let analysis = analyze(&rom);
+    assert_eq!(analysis.code().collect::<Vec<_>>(), [0x200, 0x202, 0x206]);
+}
+
+#[test]
+fn skips_step_over_the_whole_long_i() {
+    for (skip, taken) in [
+        ("SE V0, 0", true),
+        ("SE V0, 1", false),
+        ("SNE V0, 1", true),
+        ("SE V0, V1", true),
+        ("SNE V0, V2", true),
+        ("SNE V0, V1", false),
+        ("SKP V3", true),
+        ("SKNP V3", false),
+        ("SKNP V4", true),
+    ] {
+        let rom = assemble(&[skip, "LD I, LONG", "DB 12 34", "LD V5, 1", "JP 0x208"]);
+        let mut emulator = EmulatorBuilder::new()
+            .variant(Variant::XoChip)
+            .rom(&rom)
+            .register(2, 5)
+            .register(3, 3)
+            .register(4, 4)
+            .build()
+            .unwrap();
+        emulator.keypad.press(3);
+        assert!(run_frame(&mut emulator).error.is_none(), "{}", skip);
+        assert_eq!(emulator.v_registers[5], 1, "{}", skip);
+        let expected = if taken { 0 } else { 0x1234 };
+        assert_eq!(emulator.i_register, expected, "{}", skip);
+    }
+}
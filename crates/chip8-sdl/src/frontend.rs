@@ -0,0 +1,530 @@
+use chip8_core::audio::{Beeper, AUDIBLE_BEEP_SECONDS, DEFAULT_VOLUME};
+use chip8_core::config::Settings;
+use chip8_core::diagnosis::Diagnosis;
+use chip8_core::discord::{self, Discord};
+use chip8_core::events::Event as SessionEvent;
+use chip8_core::host::{Clock as _, StdFiles, SystemClock};
+use chip8_core::hotkeys::{Action, Hotkeys};
+use chip8_core::instruction::Variant;
+use chip8_core::render::{PixelStyle, Renderer, Rotation, Viewport};
+use chip8_core::title::{self, WindowTitle};
+use chip8_core::{
+    demo, run_frame, savestate, sound_time_left, Display, EmulatorBuilder, Palette, Quirks,
+};
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+use sdl2::messagebox::{show_simple_message_box, MessageBoxFlag};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+use sdl2::surface::Surface;
+use sdl2::AudioSubsystem;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::options;
+
+const DEFAULT_SCALE: u32 = 10;
+const FRAME: Duration = Duration::from_micros(16_667);
+const SAMPLE_RATE: i32 = 44_100;
+// About 12 ms, less than a frame, so a beep starts with the frame that
+// set the sound timer.
+const AUDIO_BUFFER: u16 = 512;
+// Behind by more than this, after the window was dragged or the machine
+// slept, the game carries on from now rather than racing to catch up.
+const MAX_BEHIND: u32 = 4;
+// Frames run for every one shown while turbo is on.
+const TURBO_FRAMES: u32 = 4;
+const STATE_SLOTS: usize = 4;
+// The keypad on the left of a QWERTY keyboard, by position so it is the
+// same block on other layouts:
+//
+//     1 2 3 4        1 2 3 C
+//     Q W E R   ->   4 5 6 D
+//     A S D F        7 8 9 E
+//     Z X C V        A 0 B F
+const KEY_LAYOUT: [Scancode; 16] = [
+    Scancode::X,
+    Scancode::Num1,
+    Scancode::Num2,
+    Scancode::Num3,
+    Scancode::Q,
+    Scancode::W,
+    Scancode::E,
+    Scancode::A,
+    Scancode::S,
+    Scancode::D,
+    Scancode::Z,
+    Scancode::C,
+    Scancode::Num4,
+    Scancode::R,
+    Scancode::F,
+    Scancode::V,
+];
+
+// How the picture is drawn, from the options.
+struct Look {
+    scale: u32,
+    palette: Palette,
+    style: PixelStyle,
+    persistence: usize,
+    rotation: Rotation,
+}
+
+// `chip8-sdl [rom]`: plays the ROM, or the demo without one, in a window
+// `--scale` times the size of the screen, 10 by default. The window can be
+// resized, the picture is scaled by whole pixels of the screen it is on
+// and centered, so it stays sharp on HiDPI monitors too. `--palette`,
+// `--pixel-style`, `--blend` and `--rotate` draw it like every other
+// frontend, see `render`, the arrow keys 2, 4, 6 and 8 turn with it.
+// Emulation runs at 60 frames a second whatever the display's refresh
+// rate, presented on its vertical blank so the picture does not tear.
+// `--cpu-hz` sets the speed in instructions a second. The sound timer
+// beeps, `--volume` from 0 to 1 sets how loud, 0.25 by default, and
+// `--mute` keeps it quiet. The hotkeys are the defaults of `hotkeys`, or
+// those of `--hotkeys FILE`; the title shows the game, its speed and when
+// it is paused, and so does Discord when the settings say so, counting
+// from the last reset. Closing the window quits. When the ROM does not load or
+// the game stops, the reason and what to try show in a message box, the
+// console it was started from may be nowhere in sight.
+pub fn run(args: &[String]) -> i32 {
+    let options = options::command().get_matches_from(args);
+    let path = options.get_one::<String>("rom").map(Path::new);
+    let (name, data) = match path {
+        None => (demo::NAME.to_string(), demo::ROM.to_vec()),
+        Some(path) => match fs::read(path) {
+            Ok(data) => (title::rom_name(path), data),
+            Err(error) => {
+                eprintln!("error: cannot read {}: {}", path.display(), error);
+                return 1;
+            }
+        },
+    };
+    let hotkeys = match options.get_one::<String>("hotkeys") {
+        None => Hotkeys::default(),
+        Some(file) => {
+            let text = fs::read_to_string(file).map_err(|error| error.to_string());
+            match text.and_then(|text| Hotkeys::parse(&text)) {
+                Ok(hotkeys) => hotkeys,
+                Err(error) => {
+                    eprintln!("error: cannot read the hotkeys in {}: {}", file, error);
+                    return 1;
+                }
+            }
+        }
+    };
+    let volume = match options.get_flag("mute") {
+        true => 0.0,
+        false => options
+            .get_one::<f32>("volume")
+            .copied()
+            .unwrap_or(DEFAULT_VOLUME),
+    };
+    let look = Look {
+        scale: options
+            .get_one::<u32>("scale")
+            .copied()
+            .unwrap_or(DEFAULT_SCALE),
+        palette: options
+            .get_one::<Palette>("palette")
+            .copied()
+            .unwrap_or_default(),
+        style: options
+            .get_one::<PixelStyle>("pixel-style")
+            .copied()
+            .unwrap_or_default(),
+        persistence: options
+            .get_one::<u8>("blend")
+            .map_or(1, |&frames| frames as usize),
+        rotation: options
+            .get_one::<Rotation>("rotate")
+            .copied()
+            .unwrap_or_default(),
+    };
+    let mut builder = EmulatorBuilder::new()
+        .variant(
+            options
+                .get_one::<Variant>("variant")
+                .copied()
+                .unwrap_or(Variant::Chip8),
+        )
+        .rom(&data);
+    if let Some(quirks) = options.get_one::<Quirks>("quirks") {
+        builder = builder.quirks(*quirks);
+    }
+    if let Some(&hz) = options.get_one::<u32>("cpu-hz") {
+        builder = builder.clock_hz(hz);
+    }
+    let settings = Settings::load_from(&StdFiles).unwrap_or_else(|error| {
+        eprintln!("error: cannot read the settings: {}", error);
+        Default::default()
+    });
+    let rom_title = title::rom_title(&data, path.unwrap_or(Path::new(demo::NAME)));
+    let discord = discord::publish(&settings, &rom_title, SystemClock.unix_seconds());
+    match play(&name, &data, builder, &hotkeys, &look, volume, discord) {
+        Ok(()) => 0,
+        Err(diagnosis) => {
+            let message = format!("{}\n\n{}", diagnosis.error, diagnosis.fixes.join("\n"));
+            eprintln!("error: {}", diagnosis.error);
+            let _ = show_simple_message_box(
+                MessageBoxFlag::ERROR,
+                &diagnosis.title,
+                message.trim_end(),
+                None,
+            );
+            1
+        }
+    }
+}
+
+fn play(
+    name: &str,
+    data: &[u8],
+    builder: EmulatorBuilder,
+    hotkeys: &Hotkeys,
+    look: &Look,
+    volume: f32,
+    mut discord: Option<Discord>,
+) -> Result<(), Diagnosis> {
+    let mut emulator = builder
+        .clone()
+        .build()
+        .map_err(|error| Diagnosis::rom(name, data, &error))?;
+    let failed = |error: String| Diagnosis::new("The window stopped working", error);
+    let context = sdl2::init().map_err(failed)?;
+    let video = context.video().map_err(failed)?;
+    let mut window_title = WindowTitle::new();
+    let (width, height) = look
+        .rotation
+        .size(emulator.display.width(), emulator.display.height());
+    let window = video
+        .window(
+            "chip8-rs",
+            width as u32 * look.scale,
+            height as u32 * look.scale,
+        )
+        .position_centered()
+        .resizable()
+        .allow_highdpi()
+        .build()
+        .map_err(|error| failed(error.to_string()))?;
+    let mut canvas = window
+        .into_canvas()
+        .present_vsync()
+        .build()
+        .map_err(|error| failed(error.to_string()))?;
+    let textures = canvas.texture_creator();
+    let mut events = context.event_pump().map_err(failed)?;
+    // Without sound, from `--mute` or no audio device, the game still plays.
+    let mut beep = match context.audio() {
+        Ok(audio) if volume > 0.0 => open_audio(&audio, volume)
+            .map_err(|error| eprintln!("warning: no sound: {}", error))
+            .ok(),
+        Err(error) => {
+            eprintln!("warning: no sound: {}", error);
+            None
+        }
+        Ok(_) => None,
+    };
+    let loaded = SessionEvent::RomLoaded {
+        name: name.to_string(),
+    };
+    // Discord already shows the ROM's proper name, see `title::rom_title`.
+    announce(
+        &loaded,
+        &mut canvas,
+        &mut window_title,
+        &mut beep,
+        &mut None,
+    );
+
+    let mut renderer = Renderer::new(look.palette, 1);
+    renderer.style = look.style;
+    renderer.rotation = look.rotation;
+    renderer.set_persistence(look.persistence);
+    // At the size the picture is drawn, made again when the window is
+    // resized or SUPER-CHIP switches resolution.
+    let mut pixels = Vec::new();
+    let mut viewport = render(&canvas, &mut renderer, &emulator.display, &mut pixels)?;
+    let mut size = (0, 0);
+    let mut texture = None;
+    let mut states: [Option<Vec<u8>>; STATE_SLOTS] = Default::default();
+    let mut paused = false;
+    let mut turbo = false;
+    let mut steps = 0;
+    let mut screenshots = 0;
+    let mut next_frame = Instant::now();
+    let mut redraw = true;
+    loop {
+        for event in events.poll_iter() {
+            let action = match event {
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    keymod,
+                    repeat: false,
+                    ..
+                } => hotkeys.action_for(&chord(keycode, keymod)),
+                _ => None,
+            };
+            if let Some(action) = action {
+                match action {
+                    Action::Quit => return Ok(()),
+                    Action::TogglePause => {
+                        paused = !paused;
+                        let event = match paused {
+                            true => SessionEvent::Paused,
+                            false => SessionEvent::Resumed,
+                        };
+                        announce(
+                            &event,
+                            &mut canvas,
+                            &mut window_title,
+                            &mut beep,
+                            &mut discord,
+                        );
+                    }
+                    Action::Step if paused => steps += 1,
+                    Action::Step => (),
+                    Action::ToggleTurbo => {
+                        turbo = !turbo;
+                        let percent = if turbo { TURBO_FRAMES * 100 } else { 100 };
+                        let event = SessionEvent::SpeedChanged { percent };
+                        announce(
+                            &event,
+                            &mut canvas,
+                            &mut window_title,
+                            &mut beep,
+                            &mut discord,
+                        );
+                    }
+                    Action::SaveState(slot) => {
+                        if let Some(state) = states.get_mut(slot as usize) {
+                            *state = Some(savestate::save(&emulator));
+                        }
+                    }
+                    Action::LoadState(slot) => {
+                        let state = states.get(slot as usize).and_then(Option::as_ref);
+                        match state.map(|state| savestate::load(state)) {
+                            Some(Ok(loaded)) => emulator = loaded,
+                            Some(Err(error)) => eprintln!("warning: {}", error),
+                            None => (),
+                        }
+                        viewport = render(&canvas, &mut renderer, &emulator.display, &mut pixels)?;
+                        redraw = true;
+                    }
+                    Action::Reset => {
+                        emulator = builder
+                            .clone()
+                            .build()
+                            .map_err(|error| Diagnosis::rom(name, data, &error))?;
+                        if let Some(discord) = &mut discord {
+                            discord.restart(SystemClock.unix_seconds());
+                        }
+                        viewport = render(&canvas, &mut renderer, &emulator.display, &mut pixels)?;
+                        redraw = true;
+                    }
+                    Action::Screenshot => {
+                        screenshots += 1;
+                        let file = format!("{}-{}.bmp", name, screenshots);
+                        let (width, height) = renderer.output_size(&emulator.display);
+                        let saved = Surface::from_data(
+                            &mut pixels,
+                            width as u32,
+                            height as u32,
+                            width as u32 * 4,
+                            PixelFormatEnum::RGBA32,
+                        )
+                        .and_then(|surface| surface.save_bmp(&file));
+                        match saved {
+                            Ok(()) => eprintln!("saved {}", file),
+                            Err(error) => eprintln!("warning: cannot save {}: {}", file, error),
+                        }
+                    }
+                }
+                continue;
+            }
+            match event {
+                Event::Quit { .. } => return Ok(()),
+                Event::Window {
+                    win_event: WindowEvent::SizeChanged(..),
+                    ..
+                } => {
+                    // Also what a window dragged onto a monitor with another
+                    // DPI gets, its drawable changes size.
+                    viewport = render(&canvas, &mut renderer, &emulator.display, &mut pixels)?;
+                    redraw = true;
+                }
+                Event::KeyDown {
+                    scancode: Some(scancode),
+                    repeat: false,
+                    ..
+                } => {
+                    if let Some(key) = KEY_LAYOUT.iter().position(|&k| k == scancode) {
+                        emulator.keypad.press(look.rotation.key(key as u8));
+                    }
+                }
+                Event::KeyUp {
+                    scancode: Some(scancode),
+                    ..
+                } => {
+                    if let Some(key) = KEY_LAYOUT.iter().position(|&k| k == scancode) {
+                        emulator.keypad.release(look.rotation.key(key as u8));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let now = Instant::now();
+        if now.duration_since(next_frame) > FRAME * MAX_BEHIND {
+            next_frame = now;
+        }
+        while next_frame <= now {
+            let frames = match (paused, turbo) {
+                (true, _) => std::mem::take(&mut steps),
+                (false, true) => TURBO_FRAMES,
+                (false, false) => 1,
+            };
+            for _ in 0..frames {
+                let frame = run_frame(&mut emulator);
+                if let Some(error) = frame.error {
+                    return Err(Diagnosis::crash(name, data, &error));
+                }
+                // Blending averages every frame, shown or not.
+                viewport = render(&canvas, &mut renderer, &emulator.display, &mut pixels)?;
+                redraw = true;
+            }
+            next_frame += FRAME;
+        }
+        if let Some(device) = &mut beep {
+            device.lock().0.set_time_left(sound_time_left(&emulator));
+        }
+
+        if redraw {
+            let resolution = renderer.output_size(&emulator.display);
+            if size != resolution || texture.is_none() {
+                let made = textures
+                    .create_texture_streaming(
+                        PixelFormatEnum::RGBA32,
+                        resolution.0 as u32,
+                        resolution.1 as u32,
+                    )
+                    .map_err(|error| failed(error.to_string()))?;
+                texture = Some(made);
+                size = resolution;
+            }
+            let texture = texture.as_mut().unwrap();
+            texture
+                .update(None, &pixels, size.0 * 4)
+                .map_err(|error| failed(error.to_string()))?;
+            redraw = false;
+        }
+        // The picture is drawn again for every present, SDL does not keep
+        // what was there. Waits for the vertical blank, a display faster
+        // than 60 Hz shows some frames twice.
+        let [r, g, b, _] = look.palette.background;
+        canvas.set_draw_color(Color::RGB(r, g, b));
+        canvas.clear();
+        if let Some(texture) = &texture {
+            let target = Rect::new(
+                viewport.x as i32,
+                viewport.y as i32,
+                size.0 as u32,
+                size.1 as u32,
+            );
+            canvas.copy(texture, None, target).map_err(failed)?;
+        }
+        canvas.present();
+        // Without vsync, as under some drivers, present returns at once.
+        thread::sleep(next_frame.saturating_duration_since(Instant::now()));
+    }
+}
+
+// Tells the beeper, the window title and Discord what happened.
+fn announce(
+    event: &SessionEvent,
+    canvas: &mut WindowCanvas,
+    title: &mut WindowTitle,
+    beep: &mut Option<AudioDevice<Beep>>,
+    discord: &mut Option<Discord>,
+) {
+    if let Some(device) = beep {
+        device.lock().0.handle(event);
+    }
+    if let Some(discord) = discord {
+        discord.handle(event, SystemClock.unix_seconds());
+    }
+    if title.handle(event) {
+        let _ = canvas.window_mut().set_title(&title.to_string());
+    }
+}
+
+// Draws the display into `pixels`, scaled to fit the window, which is
+// measured again every time: it may have been resized, or SUPER-CHIP
+// switched resolution. Returns where the picture goes.
+fn render(
+    canvas: &WindowCanvas,
+    renderer: &mut Renderer,
+    display: &Display,
+    pixels: &mut Vec<u8>,
+) -> Result<Viewport, Diagnosis> {
+    let (points_width, points_height) = canvas.window().size();
+    let (pixels_width, _) = canvas
+        .output_size()
+        .map_err(|error| Diagnosis::new("The window stopped working", error))?;
+    // Pixels per point of the screen the window is on.
+    let scale_factor = pixels_width as f64 / points_width.max(1) as f64;
+    let logical = (points_width as f64, points_height as f64);
+    let viewport = Viewport::fit_turned(display, renderer.rotation, logical, scale_factor);
+    renderer.set_scale(viewport.scale);
+    let (width, height) = renderer.output_size(display);
+    pixels.resize(width * height * 4, 0);
+    renderer.render(display, pixels);
+    Ok(viewport)
+}
+
+// The hotkey chord of a key going down, like "shift+f5", see `Hotkeys`.
+fn chord(keycode: Keycode, keymod: Mod) -> String {
+    let mut chord = String::new();
+    for (modifiers, name) in [
+        (Mod::LCTRLMOD | Mod::RCTRLMOD, "ctrl+"),
+        (Mod::LALTMOD | Mod::RALTMOD, "alt+"),
+        (Mod::LSHIFTMOD | Mod::RSHIFTMOD, "shift+"),
+    ] {
+        if keymod.intersects(modifiers) {
+            chord.push_str(name);
+        }
+    }
+    chord.push_str(&keycode.name());
+    chord
+}
+
+// The beeper on the audio thread, SDL asks it for samples as it needs them.
+struct Beep(Beeper);
+
+impl AudioCallback for Beep {
+    type Channel = f32;
+
+    fn callback(&mut self, samples: &mut [f32]) {
+        self.0.fill(samples);
+    }
+}
+
+fn open_audio(audio: &AudioSubsystem, volume: f32) -> Result<AudioDevice<Beep>, String> {
+    let desired = AudioSpecDesired {
+        freq: Some(SAMPLE_RATE),
+        channels: Some(1),
+        samples: Some(AUDIO_BUFFER),
+    };
+    let device = audio.open_playback(None, &desired, |spec| {
+        let mut beeper = Beeper::new(spec.freq as u32);
+        beeper.set_volume(volume);
+        beeper.set_min_duration(AUDIBLE_BEEP_SECONDS);
+        Beep(beeper)
+    })?;
+    device.resume();
+    Ok(device)
+}
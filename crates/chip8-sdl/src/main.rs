@@ -0,0 +1,20 @@
+#[cfg(feature = "sdl")]
+mod frontend;
+#[cfg(feature = "sdl")]
+mod options;
+
+use std::process;
+
+// The SDL2 frontend is behind the `sdl` feature, so the workspace builds
+// without the SDL2 libraries. Without it this binary only says so.
+#[cfg(feature = "sdl")]
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    process::exit(frontend::run(&args));
+}
+
+#[cfg(not(feature = "sdl"))]
+fn main() {
+    eprintln!("error: chip8-sdl was built without the `sdl` feature");
+    process::exit(1);
+}
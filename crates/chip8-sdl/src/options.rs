@@ -0,0 +1,89 @@
+// Options of `chip8-sdl [rom]`, parsed like the `chip8` player's.
+use chip8_core::instruction::Variant;
+use chip8_core::render::{PixelStyle, Rotation, MAX_PERSISTENCE};
+use chip8_core::{Palette, Quirks};
+use clap::{value_parser, Arg, ArgAction, Command};
+
+pub fn command() -> Command {
+    let value = |name: &'static str, value_name: &'static str, help: &'static str| {
+        Arg::new(name).long(name).value_name(value_name).help(help)
+    };
+    Command::new("chip8-sdl")
+        .about("Plays a CHIP-8 ROM in a window")
+        .arg(
+            Arg::new("rom")
+                .value_name("ROM")
+                .help("The ROM file to play, the demo without one"),
+        )
+        .arg(
+            value(
+                "scale",
+                "N",
+                "Window pixels per pixel on the screen when it opens, 10 by default",
+            )
+            .value_parser(value_parser!(u32).range(1..=32)),
+        )
+        .arg(
+            value("variant", "NAME", "The machine, chip8 by default")
+                .value_parser(|text: &str| text.parse::<Variant>()),
+        )
+        .arg(
+            value("quirks", "PROFILE", "A quirks profile, or quirks by name")
+                .value_parser(|text: &str| text.parse::<Quirks>()),
+        )
+        .arg(
+            value("cpu-hz", "N", "Instructions a second, 600 by default")
+                .value_parser(value_parser!(u32).range(1..)),
+        )
+        .arg(
+            value(
+                "palette",
+                "\"FG BG\"",
+                "Colours as RRGGBB, like \"33ff66 002200\"",
+            )
+            .value_parser(|text: &str| text.parse::<Palette>()),
+        )
+        .arg(
+            value(
+                "pixel-style",
+                "STYLE",
+                "solid, grid or led, solid by default",
+            )
+            .value_parser(|text: &str| text.parse::<PixelStyle>()),
+        )
+        .arg(
+            value(
+                "blend",
+                "FRAMES",
+                "Frames averaged together against flicker, 1 by default",
+            )
+            .value_parser(value_parser!(u8).range(1..=MAX_PERSISTENCE as i64)),
+        )
+        .arg(
+            value(
+                "rotate",
+                "DEGREES",
+                "Turns the picture clockwise: 90, 180 or 270",
+            )
+            .value_parser(|text: &str| text.parse::<Rotation>()),
+        )
+        .arg(
+            value("volume", "0-1", "How loud the beep is, 0.25 by default").value_parser(
+                |text: &str| match text.parse::<f32>() {
+                    Ok(volume) if (0.0..=1.0).contains(&volume) => Ok(volume),
+                    _ => Err("the volume is from 0 to 1".to_string()),
+                },
+            ),
+        )
+        .arg(
+            Arg::new("mute")
+                .long("mute")
+                .help("Keeps the beep quiet")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(value(
+            "hotkeys",
+            "FILE",
+            "`chord = action` lines replacing the default hotkeys",
+        ))
+}
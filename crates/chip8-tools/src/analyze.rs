@@ -0,0 +1,96 @@
+use chip8_core::lint::{self, Severity};
+use chip8_core::rom::Rom;
+use std::fs;
+
+const USAGE: &str = "usage: analyze <rom> [--lint] [--format text|json|sarif]";
+
+// `analyze <rom>`: what the static analyzer knows about a ROM, as ranges of
+// reachable code, data referenced by `LD I` and unresolved jumps.
+// `--lint` adds the bugs it found, see `chip8_core::lint`, and fails if
+// one of them is an error. `--format json` or `sarif` prints only those,
+// for editors and code scanning.
+pub fn run(args: &[String]) -> i32 {
+    let mut path = None;
+    let mut lints = false;
+    let mut format = "text";
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match (arg.as_str(), path) {
+            ("--lint", _) => lints = true,
+            ("--format", _) => match rest.next().map(String::as_str) {
+                Some(name @ ("text" | "json" | "sarif")) => format = name,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return 1;
+                }
+            },
+            (_, None) if !arg.starts_with("--") => path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return 1;
+            }
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let rom = match fs::read(path) {
+        Ok(data) => Rom::new(data),
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    let rom = match rom {
+        Ok(rom) => rom,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+    let found = lint::lint(rom.data());
+    let failed =
+        (lints || format != "text") && found.iter().any(|lint| lint.severity() == Severity::Error);
+    match format {
+        "json" => {
+            print!("{}", lint::to_json(&found));
+            return failed as i32;
+        }
+        "sarif" => {
+            print!("{}", lint::to_sarif(&found, path));
+            return failed as i32;
+        }
+        _ => {}
+    }
+    let analysis = rom.analysis();
+
+    let mut ranges: Vec<(u16, u16)> = Vec::new();
+    for address in analysis.code() {
+        match ranges.last_mut() {
+            Some((_, end)) if *end == address => *end = address + 2,
+            _ => ranges.push((address, address + 2)),
+        }
+    }
+    let code_bytes: u16 = ranges.iter().map(|(start, end)| end - start).sum();
+    println!(
+        "{} bytes, {} of them reachable code",
+        rom.data().len(),
+        code_bytes
+    );
+    for (start, end) in ranges {
+        println!("code  {:#05x}..{:#05x}", start, end);
+    }
+    for address in analysis.data_references() {
+        println!("data  {:#05x}", address);
+    }
+    for address in analysis.indirect_jumps() {
+        println!("jump  {:#05x}  JP V0 target unknown", address);
+    }
+    if lints {
+        for lint in &found {
+            println!("lint  {}", lint);
+        }
+    }
+    failed as i32
+}
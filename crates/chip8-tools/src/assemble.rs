@@ -0,0 +1,41 @@
+use chip8_core::assembler::assemble;
+use std::fs;
+
+const USAGE: &str = "usage: assemble <source> <rom>";
+
+// `assemble <source> <rom>`: turns one instruction per line into a ROM,
+// see `chip8_core::assembler`.
+pub fn run(args: &[String]) -> i32 {
+    let [source, output] = args else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let text = match fs::read_to_string(source) {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", source, error);
+            return 1;
+        }
+    };
+    let rom = match assemble(&text) {
+        Ok(rom) => rom,
+        Err(diagnostics) => {
+            for diagnostic in &diagnostics {
+                eprintln!("{}", diagnostic.render(source, &text));
+            }
+            let plural = if diagnostics.len() == 1 { "" } else { "s" };
+            eprintln!(
+                "error: {} not assembled, {} error{}",
+                source,
+                diagnostics.len(),
+                plural
+            );
+            return 1;
+        }
+    };
+    if let Err(error) = fs::write(output, &rom) {
+        eprintln!("error: cannot write {}: {}", output, error);
+        return 1;
+    }
+    0
+}
@@ -0,0 +1,92 @@
+use chip8_core::patch::{self, Patch};
+use chip8_core::INITIAL_ADDRESS;
+use std::fs;
+
+const USAGE: &str = "usage: diff <old> <new> [--bytes] [--ips FILE]";
+// Unchanged instructions shown around a change.
+const CONTEXT: usize = 3;
+
+// `diff <old> <new>`: the instructions that differ between two ROMs, lined
+// up so an insertion does not shift everything after it, see
+// `chip8_core::patch`. `--bytes` lists the bytes that differ instead, and
+// `--ips` also writes them as an IPS patch turning old into new.
+pub fn run(args: &[String]) -> i32 {
+    let mut paths = Vec::new();
+    let mut bytes = false;
+    let mut ips = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--bytes" => bytes = true,
+            "--ips" => match rest.next() {
+                Some(path) => ips = Some(path),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return 1;
+                }
+            },
+            _ if !arg.starts_with("--") && paths.len() < 2 => paths.push(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                return 1;
+            }
+        }
+    }
+    let [old_path, new_path] = paths[..] else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let mut roms = Vec::new();
+    for path in [old_path, new_path] {
+        match fs::read(path) {
+            Ok(data) => roms.push(data),
+            Err(error) => {
+                eprintln!("error: cannot read {}: {}", path, error);
+                return 1;
+            }
+        }
+    }
+    let (old, new) = (&roms[0], &roms[1]);
+
+    let patch = Patch::between(old, new);
+    if bytes {
+        for record in &patch.records {
+            let end = (record.offset + record.data.len()).min(old.len());
+            let before = old.get(record.offset..end).unwrap_or_default();
+            println!(
+                "{:#05x}  {} -> {}",
+                INITIAL_ADDRESS as usize + record.offset,
+                hex(before),
+                hex(&record.data)
+            );
+        }
+        if let Some(len) = patch.truncate {
+            println!(
+                "{:#05x}  {} bytes cut",
+                INITIAL_ADDRESS as usize + len,
+                old.len() - len
+            );
+        }
+    } else {
+        print!("{}", patch::unified(&patch::diff(old, new), CONTEXT));
+    }
+
+    if let Some(path) = ips {
+        let written = patch
+            .to_ips()
+            .and_then(|data| fs::write(path, data).map_err(|error| error.to_string()));
+        if let Err(error) = written {
+            eprintln!("error: cannot write {}: {}", path, error);
+            return 1;
+        }
+    }
+    0
+}
+
+fn hex(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return "nothing".to_string();
+    }
+    let bytes: Vec<String> = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+    bytes.join(" ")
+}
@@ -0,0 +1,30 @@
+use chip8_core::dump;
+use chip8_core::rom::Rom;
+use std::fs;
+
+const USAGE: &str = "usage: disassemble <rom>";
+
+// `disassemble <rom>`: prints the ROM as assembly, without running it.
+pub fn run(args: &[String]) -> i32 {
+    let Some(path) = args.first() else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let rom = match fs::read(path) {
+        Ok(data) => Rom::new(data),
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            return 1;
+        }
+    };
+    match rom {
+        Ok(rom) => {
+            print!("{}", dump::disassemble_rom(&rom));
+            0
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            1
+        }
+    }
+}
@@ -0,0 +1,28 @@
+mod analyze;
+mod assemble;
+mod diff;
+mod disassemble;
+mod mutants;
+mod patch;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage: chip8-tools <assemble|disassemble|analyze|mutants|diff|patch> ...";
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let code = match args.get(1).map(String::as_str) {
+        Some("assemble") => assemble::run(&args[2..]),
+        Some("disassemble") => disassemble::run(&args[2..]),
+        Some("analyze") => analyze::run(&args[2..]),
+        Some("mutants") => mutants::run(&args[2..]),
+        Some("diff") => diff::run(&args[2..]),
+        Some("patch") => patch::run(&args[2..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            1
+        }
+    };
+    process::exit(code);
+}
@@ -0,0 +1,116 @@
+use chip8_core::mutants::{self, Mutant, ENV_VAR};
+use std::env;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const USAGE: &str = "usage: mutants [--only TEXT] [--test NAME]... [--fail-under PERCENT]";
+
+// `mutants`: mutation testing of the opcode tests. Runs chip8-core's test
+// suite once per deliberate executor bug, see `chip8_core::mutants`, and
+// lists the bugs no test failed on. `--only` keeps the mutants whose name
+// contains TEXT, `--test` runs only those test files, and `--fail-under`
+// fails when fewer than PERCENT of the mutants were caught, for CI.
+pub fn run(args: &[String]) -> i32 {
+    let mut only = None;
+    let mut tests = Vec::new();
+    let mut fail_under = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let value = rest.next();
+        match (arg.as_str(), value) {
+            ("--only", Some(text)) => only = Some(text.to_ascii_uppercase()),
+            ("--test", Some(name)) => tests.push(name.clone()),
+            ("--fail-under", Some(percent)) => match percent.parse::<f64>() {
+                Ok(percent) => fail_under = Some(percent),
+                Err(_) => {
+                    eprintln!("error: --fail-under {:?} is not a number", percent);
+                    return 1;
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                return 1;
+            }
+        }
+    }
+    let selected: Vec<Mutant> = mutants::all()
+        .filter(|mutant| {
+            only.as_ref()
+                .is_none_or(|text| mutant.name().to_ascii_uppercase().contains(text))
+        })
+        .collect();
+    if selected.is_empty() {
+        eprintln!("error: no mutant matches");
+        return 1;
+    }
+
+    match test_suite(&tests, None) {
+        Ok(true) => (),
+        Ok(false) => {
+            eprintln!("error: the tests fail without any mutant, fix them first");
+            return 1;
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    }
+    let mut survivors = Vec::new();
+    for mutant in &selected {
+        match test_suite(&tests, Some(mutant)) {
+            Ok(true) => {
+                println!("SURVIVED  {}", mutant);
+                survivors.push(mutant);
+            }
+            Ok(false) => println!("caught    {}", mutant),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                return 1;
+            }
+        }
+    }
+
+    let caught = selected.len() - survivors.len();
+    let score = 100.0 * caught as f64 / selected.len() as f64;
+    println!(
+        "caught {} of {} mutants ({:.0}%)",
+        caught,
+        selected.len(),
+        score
+    );
+    match fail_under {
+        Some(percent) if score < percent => 1,
+        _ => 0,
+    }
+}
+
+// Whether chip8-core's tests pass with `mutant` in the executor. Their
+// output is dropped, only the verdict matters.
+fn test_suite(tests: &[String], mutant: Option<&Mutant>) -> Result<bool, String> {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let cargo = env::var_os("CARGO").unwrap_or("cargo".into());
+    let mut command = Command::new(cargo);
+    command
+        .current_dir(workspace)
+        .args([
+            "test",
+            "--quiet",
+            "-p",
+            "chip8-core",
+            "--features",
+            "mutants",
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .env_remove(ENV_VAR);
+    for test in tests {
+        command.args(["--test", test]);
+    }
+    if let Some(mutant) = mutant {
+        command.env(ENV_VAR, mutant.name());
+    }
+    let status = command
+        .status()
+        .map_err(|error| format!("cannot run cargo test: {}", error))?;
+    Ok(status.success())
+}
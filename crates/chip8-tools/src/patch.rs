@@ -0,0 +1,35 @@
+use chip8_core::patch::Patch;
+use std::fs;
+
+const USAGE: &str = "usage: patch <rom> <patch.ips> <output>";
+
+// `patch <rom> <patch.ips> <output>`: applies an IPS patch, such as one
+// `diff --ips` wrote, and writes the patched ROM.
+pub fn run(args: &[String]) -> i32 {
+    let [rom, ips, output] = args else {
+        eprintln!("{}", USAGE);
+        return 1;
+    };
+    let mut read = Vec::new();
+    for path in [rom, ips] {
+        match fs::read(path) {
+            Ok(data) => read.push(data),
+            Err(error) => {
+                eprintln!("error: cannot read {}: {}", path, error);
+                return 1;
+            }
+        }
+    }
+    let patch = match Patch::from_ips(&read[1]) {
+        Ok(patch) => patch,
+        Err(error) => {
+            eprintln!("error: {}: {}", ips, error);
+            return 1;
+        }
+    };
+    if let Err(error) = fs::write(output, patch.apply(&read[0])) {
+        eprintln!("error: cannot write {}: {}", output, error);
+        return 1;
+    }
+    0
+}
@@ -0,0 +1,151 @@
+// A C ABI over `chip8_core` for browsers and other embedders, built as a
+// `cdylib` for wasm32-unknown-unknown. JavaScript owns a `Machine` through
+// the pointer `chip8_new` returns and hands it back to every other call.
+//
+// Safety: every `machine` argument must come from `chip8_new`, be non-null
+// and not have been passed to `chip8_free`, and `rom` must point to `len`
+// readable bytes.
+#![allow(clippy::missing_safety_doc)]
+
+use chip8_core::demo;
+use chip8_core::instruction::Variant;
+use chip8_core::render::Viewport;
+use chip8_core::{run_frame, Emulator, EmulatorBuilder, Palette};
+
+// The `variant` numbers `chip8_new` takes.
+const VARIANTS: [Variant; 5] = [
+    Variant::Chip8,
+    Variant::Schip,
+    Variant::XoChip,
+    Variant::Eti660,
+    Variant::Chip8x,
+];
+
+pub struct Machine {
+    emulator: Emulator,
+    // What every ROM loaded afterwards runs on.
+    variant: Variant,
+    seed: u64,
+    // RGBA at the logical resolution, refreshed by `chip8_frame`.
+    pixels: Vec<u8>,
+}
+
+// Runs the demo until a ROM is loaded. `variant` is 0 for CHIP-8, 1
+// SUPER-CHIP, 2 XO-CHIP, 3 ETI-660 and 4 CHIP-8X, any other number gives
+// null.
+#[no_mangle]
+pub extern "C" fn chip8_new(seed: u64, variant: u32) -> *mut Machine {
+    let Some(&variant) = VARIANTS.get(variant as usize) else {
+        return std::ptr::null_mut();
+    };
+    let built = EmulatorBuilder::new()
+        .variant(variant)
+        .seed(seed)
+        .rom(demo::ROM)
+        .build();
+    let Ok(emulator) = built else {
+        return std::ptr::null_mut();
+    };
+    let pixels = render(&emulator);
+    Box::into_raw(Box::new(Machine {
+        emulator,
+        variant,
+        seed,
+        pixels,
+    }))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chip8_free(machine: *mut Machine) {
+    if !machine.is_null() {
+        drop(Box::from_raw(machine));
+    }
+}
+
+// 0 on success, 1 if the ROM does not fit in memory. The machine keeps its
+// variant, quirks and seed.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_rom(machine: *mut Machine, rom: *const u8, len: usize) -> i32 {
+    let machine = &mut *machine;
+    let rom = std::slice::from_raw_parts(rom, len);
+    match EmulatorBuilder::new()
+        .variant(machine.variant)
+        .quirks(machine.emulator.quirks)
+        .seed(machine.seed)
+        .rom(rom)
+        .build()
+    {
+        Ok(emulator) => {
+            machine.emulator = emulator;
+            0
+        }
+        Err(_) => 1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_key(machine: *mut Machine, key: u8, pressed: bool) {
+    let keypad = &mut (*machine).emulator.keypad;
+    if pressed {
+        keypad.press(key);
+    } else {
+        keypad.release(key);
+    }
+}
+
+// Runs one 60 Hz frame. Bit 0 of the result is set while beeping, bit 1
+// when the ROM stopped with an error.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_frame(machine: *mut Machine) -> u32 {
+    let machine = &mut *machine;
+    let output = run_frame(&mut machine.emulator);
+    // CHIP-8X colours change without the screen changing.
+    let colored = machine.emulator.chip8x.is_some();
+    if output.display_changed || colored || machine.pixels.len() != pixel_len(&machine.emulator) {
+        machine.pixels = render(&machine.emulator);
+    }
+    chip8_core::is_beeping(&machine.emulator) as u32 | (output.error.is_some() as u32) << 1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chip8_width(machine: *const Machine) -> usize {
+    (*machine).emulator.display.width()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chip8_height(machine: *const Machine) -> usize {
+    (*machine).emulator.display.height()
+}
+
+// `width * height * 4` bytes, valid until the next `chip8_frame`.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_pixels(machine: *const Machine) -> *const u8 {
+    (*machine).pixels.as_ptr()
+}
+
+// Device pixels per CHIP-8 pixel to fill a canvas box of `css_width` x
+// `css_height` CSS pixels at `device_pixel_ratio` without blurring: set the
+// canvas to `width * scale` by `height * scale` device pixels and its CSS
+// size to that divided by the ratio. Ask again on resize and whenever the
+// ratio changes, as it does when the window moves to another monitor.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_device_scale(
+    machine: *const Machine,
+    css_width: f64,
+    css_height: f64,
+    device_pixel_ratio: f64,
+) -> usize {
+    let display = &(*machine).emulator.display;
+    Viewport::fit(display, (css_width, css_height), device_pixel_ratio).scale
+}
+
+fn render(emulator: &Emulator) -> Vec<u8> {
+    match &emulator.chip8x {
+        Some(chip8x) => chip8x.to_rgba(&emulator.display),
+        None => emulator.display.to_rgba(&Palette::default()),
+    }
+}
+
+fn pixel_len(emulator: &Emulator) -> usize {
+    emulator.display.width() * emulator.display.height() * 4
+}
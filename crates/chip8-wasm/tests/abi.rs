@@ -0,0 +1,40 @@
+use chip8_wasm::*;
+use std::slice;
+
+// RND V0, 0xFF; RND V1, 0x1F; LD I, 0x20A; DRW V0, V1, 1; JP 0x200; sprite
+const RANDOM_DOTS: [u8; 11] = [
+    0xC0, 0xFF, 0xC1, 0x1F, 0xA2, 0x0A, 0xD0, 0x11, 0x12, 0x00, 0xFF,
+];
+
+// The screen after `frames` frames of `rom`.
+unsafe fn screen(seed: u64, variant: u32, rom: &[u8], frames: usize) -> Vec<u8> {
+    let machine = chip8_new(seed, variant);
+    assert_eq!(chip8_load_rom(machine, rom.as_ptr(), rom.len()), 0);
+    for _ in 0..frames {
+        chip8_frame(machine);
+    }
+    let len = chip8_width(machine) * chip8_height(machine) * 4;
+    let pixels = slice::from_raw_parts(chip8_pixels(machine), len).to_vec();
+    chip8_free(machine);
+    pixels
+}
+
+#[test]
+fn loading_a_rom_keeps_the_variant_and_seed() {
+    unsafe {
+        // The ETI-660's 64x48 screen outlives the demo.
+        let machine = chip8_new(0, 3);
+        assert_eq!(chip8_load_rom(machine, [0x16, 0x00].as_ptr(), 2), 0);
+        assert_eq!((chip8_width(machine), chip8_height(machine)), (64, 48));
+        chip8_free(machine);
+
+        assert_eq!(screen(1, 0, &RANDOM_DOTS, 5), screen(1, 0, &RANDOM_DOTS, 5));
+        assert_ne!(screen(1, 0, &RANDOM_DOTS, 5), screen(2, 0, &RANDOM_DOTS, 5));
+    }
+}
+
+#[test]
+fn unknown_variants_give_null() {
+    assert!(chip8_new(0, 5).is_null());
+    unsafe { chip8_free(chip8_new(0, u32::MAX)) };
+}
@@ -0,0 +1,29 @@
+; A ball bouncing off the edges of the screen, key 5 sends it left.
+; Written for chip8-rs's golden tests, under the same license.
+0x200  LD V0, 10        ; x
+0x202  LD V1, 5         ; y
+0x204  LD V2, 1         ; x step
+0x206  LD V3, 1         ; y step
+0x208  LD V5, 5         ; the key
+0x20A  LD I, 0x50       ; the font's 0 as the ball
+0x20C  DRW V0, V1, 5
+0x20E  LD V4, 2         ; wait two frames
+0x210  LD DT, V4
+0x212  LD V4, DT
+0x214  SE V4, 0
+0x216  JP 0x212
+0x218  DRW V0, V1, 5    ; erase
+0x21A  ADD V0, V2
+0x21C  ADD V1, V3
+0x21E  SNE V0, 0
+0x220  LD V2, 1
+0x222  SNE V0, 59
+0x224  LD V2, 255
+0x226  SNE V1, 0
+0x228  LD V3, 1
+0x22A  SNE V1, 26
+0x22C  LD V3, 255
+0x22E  SKNP V5
+0x230  LD V2, 255
+0x232  RND V7, 0xFF     ; keeps the generator in the checksums
+0x234  JP 0x20C
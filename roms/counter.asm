@@ -0,0 +1,21 @@
+; Counts key presses and shows the last digit of the count, beeping each
+; time. Written for chip8-rs's golden tests, under the same license.
+0x200  LD V3, 0         ; presses
+0x202  LD V0, K
+0x204  ADD V3, 1
+0x206  LD I, 0x300
+0x208  LD B, V3
+0x20A  LD I, 0x300
+0x20C  LD V2, [I]       ; V2 is the ones digit
+0x20E  CLS
+0x210  LD V4, V2        ; times 5, the size of a font digit
+0x212  ADD V4, V4
+0x214  ADD V4, V4
+0x216  ADD V4, V2
+0x218  LD I, 0x50
+0x21A  ADD I, V4
+0x21C  LD V6, 30
+0x21E  LD V7, 12
+0x220  DRW V6, V7, 5
+0x222  LD ST, V7
+0x224  JP 0x202
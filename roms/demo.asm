@@ -0,0 +1,48 @@
+; The splash screen frontends run without a ROM: the title, a blinking
+; cursor, and the key held shown in hex with a beep. Written for chip8-rs
+; and in the public domain. `chip8-tools assemble roms/demo.asm
+; roms/demo.ch8` after a change, tests/demo.rs checks the two agree.
+0x200  CLS
+0x202  LD V0, 26        ; the title, the font's C and 8
+0x204  LD V1, 6
+0x206  LD I, 0x8C
+0x208  DRW V0, V1, 5
+0x20A  LD V0, 32
+0x20C  LD I, 0x78
+0x20E  DRW V0, V1, 5
+0x210  LD V8, 255       ; the key shown, none yet
+0x212  LD VB, 30        ; x of the key and the cursor
+0x214  LD VC, 14        ; y of the key
+0x216  LD VD, 21        ; y of the cursor
+0x218  LD V4, DT        ; the cursor blinks twice a second
+0x21A  SE V4, 0
+0x21C  JP 0x226
+0x21E  LD V4, 15
+0x220  LD DT, V4
+0x222  LD I, 0x50       ; the top of the font's 0
+0x224  DRW VB, VD, 1
+0x226  LD V6, 0         ; the first key held, if any
+0x228  SKNP V6
+0x22A  JP 0x234
+0x22C  ADD V6, 1
+0x22E  SE V6, 16
+0x230  JP 0x228
+0x232  JP 0x218
+0x234  SNE V6, V8
+0x236  JP 0x218         ; already shown
+0x238  SE V8, 255
+0x23A  CALL 0x246       ; erase the last one
+0x23C  LD V8, V6
+0x23E  CALL 0x246
+0x240  LD V4, 4         ; and beep
+0x242  LD ST, V4
+0x244  JP 0x218
+0x246  LD V7, V8        ; draws key V8, its font digit is 5 bytes
+0x248  ADD V7, V8       ; at 0x50 + 5 * V8
+0x24A  ADD V7, V8
+0x24C  ADD V7, V8
+0x24E  ADD V7, V8
+0x250  LD I, 0x50
+0x252  ADD I, V7
+0x254  DRW VB, VC, 5
+0x256  RET